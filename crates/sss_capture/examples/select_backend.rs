@@ -1,24 +1,33 @@
-//! `cargo run -p sss_capture --example select_backend -- [wayland|portal|x11|windows|macos|auto]`
+//! `cargo run -p sss_capture --example select_backend -- [wayland|portal|x11|framebuffer|windows|macos|vnc://host|auto]`
 //!
 //! Build a [`Capturer`] forcing a specific backend, then print which one was
 //! chosen and capture all monitors.
 
-use sss_capture::{BackendKind, Capturer, Result};
+use sss_capture::{BackendKind, Capturer, Result, VncOptions};
 
 fn main() -> Result<()> {
     let arg = std::env::args()
         .nth(1)
         .unwrap_or_else(|| "auto".to_string());
+    let mut builder = Capturer::builder();
     let kind = match arg.to_lowercase().as_str() {
         "wayland" => BackendKind::Wayland,
         "portal" | "wayland-portal" => BackendKind::WaylandPortal,
         "x11" => BackendKind::X11,
+        "framebuffer" | "fb" => BackendKind::Framebuffer,
         "windows" | "windows-gdi" => BackendKind::WindowsGdi,
         "macos" => BackendKind::MacOS,
+        other if other.starts_with("vnc://") => {
+            builder = builder.vnc(VncOptions {
+                addr: arg["vnc://".len()..].to_string(),
+                password: std::env::var("SSS_VNC_PASSWORD").ok(),
+            });
+            BackendKind::Vnc
+        }
         _ => BackendKind::Auto,
     };
 
-    let cap = Capturer::builder().backend(kind).build()?;
+    let cap = builder.backend(kind).build()?;
     println!("backend: {}", cap.backend_name());
     let img = cap.capture_all()?;
     let out = std::env::args()
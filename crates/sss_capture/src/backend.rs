@@ -16,6 +16,7 @@ pub(crate) mod macos;
 pub(crate) mod windows;
 
 pub(crate) mod compose;
+pub(crate) mod vnc;
 
 pub(crate) trait Backend: Send {
     fn name(&self) -> &'static str;
@@ -24,6 +25,12 @@ pub(crate) trait Backend: Send {
 
     fn windows(&self) -> Result<Vec<Window>>;
 
+    /// Whether [`Backend::windows`] reflects what is on screen. Backends
+    /// with no way to enumerate windows return an empty list and `false`.
+    fn lists_windows(&self) -> bool {
+        true
+    }
+
     fn capture_monitor(&self, id: MonitorId, opts: &CaptureOptions) -> Result<RgbaImage>;
 
     fn capture_window(&self, id: WindowId, opts: &CaptureOptions) -> Result<RgbaImage>;
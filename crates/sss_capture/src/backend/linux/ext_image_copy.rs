@@ -435,6 +435,10 @@ impl Backend for ExtImageCopyBackend {
         Ok(Vec::new())
     }
 
+    fn lists_windows(&self) -> bool {
+        false
+    }
+
     fn capture_monitor(&self, id: MonitorId, opts: &CaptureOptions) -> Result<RgbaImage> {
         let output = {
             let inner = self.inner.lock().unwrap();
@@ -0,0 +1,711 @@
+//! Linux framebuffer (`/dev/fb*`) backend.
+//!
+//! Works without any display server: bare TTYs, kiosks and embedded boards
+//! that scan out straight from fbdev. Every `/dev/fbN` node is exposed as
+//! one [`Monitor`]; geometry and pixel layout come from
+//! `FBIOGET_VSCREENINFO`, the row stride from sysfs.
+
+use std::fs::{self, File};
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use image::imageops::{overlay, rotate180, rotate270, rotate90};
+use image::{Rgba, RgbaImage};
+
+use crate::backend::Backend;
+use crate::error::{CaptureError, Result};
+use crate::geometry::{Point, Rect, Rotation};
+use crate::monitor::{Monitor, MonitorId};
+use crate::options::CaptureOptions;
+use crate::window::{Window, WindowId};
+
+const BACKEND: &str = "framebuffer";
+
+/// `linux/fb.h` — `FBIOGET_VSCREENINFO`.
+const FBIOGET_VSCREENINFO: u32 = 0x4600;
+
+nix::ioctl_read_bad!(fbioget_vscreeninfo, FBIOGET_VSCREENINFO, VarScreenInfo);
+
+/// `struct fb_bitfield` from `linux/fb.h`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Bitfield {
+    pub offset: u32,
+    pub length: u32,
+    pub msb_right: u32,
+}
+
+/// `struct fb_var_screeninfo` from `linux/fb.h`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct VarScreenInfo {
+    pub xres: u32,
+    pub yres: u32,
+    pub xres_virtual: u32,
+    pub yres_virtual: u32,
+    pub xoffset: u32,
+    pub yoffset: u32,
+    pub bits_per_pixel: u32,
+    pub grayscale: u32,
+    pub red: Bitfield,
+    pub green: Bitfield,
+    pub blue: Bitfield,
+    pub transp: Bitfield,
+    pub nonstd: u32,
+    pub activate: u32,
+    pub height: u32,
+    pub width: u32,
+    pub accel_flags: u32,
+    pub pixclock: u32,
+    pub left_margin: u32,
+    pub right_margin: u32,
+    pub upper_margin: u32,
+    pub lower_margin: u32,
+    pub hsync_len: u32,
+    pub vsync_len: u32,
+    pub sync: u32,
+    pub vmode: u32,
+    pub rotate: u32,
+    pub colorspace: u32,
+    pub reserved: [u32; 4],
+}
+
+impl VarScreenInfo {
+    fn bytes_per_pixel(&self) -> u32 {
+        self.bits_per_pixel.div_ceil(8)
+    }
+
+    /// Upright size of the visible mode.
+    fn upright_size(&self) -> (u32, u32) {
+        match self.rotation() {
+            Rotation::Rotate90 | Rotation::Rotate270 => (self.yres, self.xres),
+            _ => (self.xres, self.yres),
+        }
+    }
+
+    fn rotation(&self) -> Rotation {
+        // FB_ROTATE_UR / CW / UD / CCW
+        match self.rotate {
+            1 => Rotation::Rotate90,
+            2 => Rotation::Rotate180,
+            3 => Rotation::Rotate270,
+            _ => Rotation::Normal,
+        }
+    }
+
+    /// Vertical refresh derived from the pixel clock (picoseconds per pixel).
+    fn refresh_rate(&self) -> Option<f32> {
+        if self.pixclock == 0 {
+            return None;
+        }
+        let htotal = self.xres + self.left_margin + self.right_margin + self.hsync_len;
+        let vtotal = self.yres + self.upper_margin + self.lower_margin + self.vsync_len;
+        if htotal == 0 || vtotal == 0 {
+            return None;
+        }
+        let hz = 1e12 / (self.pixclock as f64 * htotal as f64 * vtotal as f64);
+        Some(hz as f32)
+    }
+}
+
+/// Where a device's screeninfo comes from.
+enum InfoSource {
+    /// Query the kernel on every capture, so panning / page flips are honoured.
+    Ioctl,
+    /// A fixed description — used when the "device" is a regular file.
+    #[cfg_attr(not(test), allow(dead_code))]
+    Fixed { info: VarScreenInfo, stride: u32 },
+}
+
+struct FbDevice {
+    index: u32,
+    path: PathBuf,
+    file: File,
+    source: InfoSource,
+}
+
+impl FbDevice {
+    fn open(index: u32, path: PathBuf) -> Result<Self> {
+        let file = File::open(&path).map_err(|e| {
+            CaptureError::backend(
+                BACKEND,
+                format!(
+                    "cannot open {}: {e} (is the user in the `video` group?)",
+                    path.display()
+                ),
+            )
+        })?;
+        let dev = Self {
+            index,
+            path,
+            file,
+            source: InfoSource::Ioctl,
+        };
+        // Fail early on nodes that are not framebuffers at all.
+        dev.screeninfo()?;
+        Ok(dev)
+    }
+
+    fn screeninfo(&self) -> Result<(VarScreenInfo, u32)> {
+        match &self.source {
+            InfoSource::Fixed { info, stride } => Ok((*info, *stride)),
+            InfoSource::Ioctl => {
+                let mut info = VarScreenInfo::default();
+                // SAFETY: `info` is a `#[repr(C)]` mirror of
+                // `fb_var_screeninfo`, which is exactly what the kernel writes.
+                unsafe { fbioget_vscreeninfo(self.file.as_raw_fd(), &mut info) }.map_err(|e| {
+                    CaptureError::backend(
+                        BACKEND,
+                        format!("FBIOGET_VSCREENINFO on {}: {e}", self.path.display()),
+                    )
+                })?;
+                let stride = sysfs_stride(self.index)
+                    .or_else(|| info.xres_virtual.checked_mul(info.bytes_per_pixel()))
+                    .ok_or_else(|| {
+                        CaptureError::backend(
+                            BACKEND,
+                            format!(
+                                "{}: virtual width {} overflows the row stride",
+                                self.path.display(),
+                                info.xres_virtual
+                            ),
+                        )
+                    })?;
+                Ok((info, stride))
+            }
+        }
+    }
+
+    fn name(&self) -> String {
+        let driver = fs::read_to_string(format!("/sys/class/graphics/fb{}/name", self.index))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        match driver {
+            Some(d) => format!("fb{} ({d})", self.index),
+            None => format!("fb{}", self.index),
+        }
+    }
+
+    /// The visible mode, turned upright so it matches the monitor bounds.
+    /// fbcon draws a rotated console turned clockwise by `rotate`, so the
+    /// frame is turned back the other way.
+    fn read_frame(&self) -> Result<RgbaImage> {
+        let (info, stride) = self.screeninfo()?;
+        let panel = self.read_panel(&info, stride)?;
+        Ok(match info.rotation() {
+            Rotation::Rotate90 => rotate270(&panel),
+            Rotation::Rotate180 => rotate180(&panel),
+            Rotation::Rotate270 => rotate90(&panel),
+            _ => panel,
+        })
+    }
+
+    /// The visible mode as scanned out, in panel orientation.
+    fn read_panel(&self, info: &VarScreenInfo, stride: u32) -> Result<RgbaImage> {
+        let bpp = info.bytes_per_pixel();
+        if info.xres == 0 || info.yres == 0 {
+            return Err(CaptureError::backend(
+                BACKEND,
+                format!("{} reports an empty mode", self.path.display()),
+            ));
+        }
+        // The values come straight from the kernel; don't trust them not to
+        // overflow.
+        let Some(row_bytes) = info
+            .xoffset
+            .checked_add(info.xres)
+            .and_then(|w| w.checked_mul(bpp))
+        else {
+            return Err(CaptureError::backend(
+                BACKEND,
+                format!(
+                    "{}: x offset {} plus width {} overflows the row size",
+                    self.path.display(),
+                    info.xoffset,
+                    info.xres
+                ),
+            ));
+        };
+        if stride < row_bytes {
+            return Err(CaptureError::backend(
+                BACKEND,
+                format!(
+                    "{}: stride {stride} is too small for {}x{} at {} bpp",
+                    self.path.display(),
+                    info.xres,
+                    info.yres,
+                    info.bits_per_pixel
+                ),
+            ));
+        }
+        let mut raw = vec![0u8; stride as usize * info.yres as usize];
+        self.file
+            .read_exact_at(&mut raw, info.yoffset as u64 * stride as u64)?;
+        decode(&raw, info, stride)
+    }
+}
+
+pub(crate) struct FramebufferBackend {
+    devices: Vec<FbDevice>,
+}
+
+impl FramebufferBackend {
+    /// Open `$FRAMEBUFFER` when set, otherwise every `/dev/fbN` node.
+    pub fn try_new() -> Result<Self> {
+        let paths = match std::env::var_os("FRAMEBUFFER") {
+            Some(p) if !p.is_empty() => {
+                let path = PathBuf::from(p);
+                vec![(device_index(&path).unwrap_or(0), path)]
+            }
+            _ => enumerate_devices(),
+        };
+        if paths.is_empty() {
+            return Err(CaptureError::backend(BACKEND, "no /dev/fb* devices found"));
+        }
+
+        let mut devices = Vec::with_capacity(paths.len());
+        let mut last_err = None;
+        for (index, path) in paths {
+            match FbDevice::open(index, path) {
+                Ok(d) => devices.push(d),
+                Err(e) => {
+                    tracing::debug!(error = %e, "skipping framebuffer device");
+                    last_err = Some(e);
+                }
+            }
+        }
+        if devices.is_empty() {
+            return Err(last_err.unwrap_or(CaptureError::NoMonitors));
+        }
+        Ok(Self { devices })
+    }
+
+    /// Back the backend with a regular file laid out like `/dev/fb0`.
+    #[cfg(test)]
+    fn from_file(path: &Path, info: VarScreenInfo, stride: u32) -> Result<Self> {
+        Ok(Self {
+            devices: vec![FbDevice {
+                index: 0,
+                path: path.to_path_buf(),
+                file: File::open(path)?,
+                source: InfoSource::Fixed { info, stride },
+            }],
+        })
+    }
+
+    fn device(&self, id: MonitorId) -> Result<&FbDevice> {
+        self.devices
+            .iter()
+            .find(|d| d.index as u64 == id.raw())
+            .ok_or(CaptureError::MonitorNotFound(id))
+    }
+
+    /// The part of the desktop under `area`, black where no device is.
+    /// Frames are already upright, so this stands in for
+    /// `compose`, which would rotate them a second time.
+    fn stitch(&self, area: Rect) -> Result<RgbaImage> {
+        let mut out = RgbaImage::from_pixel(area.width(), area.height(), Rgba([0, 0, 0, 255]));
+        for (dev, monitor) in self.devices.iter().zip(self.monitors()?) {
+            if monitor.bounds.intersection(&area).is_none() {
+                continue;
+            }
+            match dev.read_frame() {
+                Ok(frame) => overlay(
+                    &mut out,
+                    &frame,
+                    (monitor.bounds.x() - area.x()) as i64,
+                    (monitor.bounds.y() - area.y()) as i64,
+                ),
+                Err(e) => tracing::warn!(
+                    monitor = ?monitor.id,
+                    error = %e,
+                    "monitor capture failed; skipping"
+                ),
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl Backend for FramebufferBackend {
+    fn name(&self) -> &'static str {
+        BACKEND
+    }
+
+    fn monitors(&self) -> Result<Vec<Monitor>> {
+        // Framebuffers carry no layout information; place them left to right.
+        let mut x = 0i32;
+        let mut out = Vec::with_capacity(self.devices.len());
+        for dev in &self.devices {
+            let (info, _) = dev.screeninfo()?;
+            let rotation = info.rotation();
+            let (w, h) = info.upright_size();
+            out.push(Monitor {
+                id: MonitorId(dev.index as u64),
+                name: dev.name(),
+                bounds: Rect::from_xywh(x, 0, w, h),
+                physical_size: (info.xres, info.yres),
+                scale_factor: 1.0,
+                rotation,
+                refresh_rate: info.refresh_rate(),
+                is_primary: out.is_empty(),
+            });
+            x += w as i32;
+        }
+        if out.is_empty() {
+            return Err(CaptureError::NoMonitors);
+        }
+        Ok(out)
+    }
+
+    fn windows(&self) -> Result<Vec<Window>> {
+        Ok(Vec::new())
+    }
+
+    fn lists_windows(&self) -> bool {
+        false
+    }
+
+    fn capture_monitor(&self, id: MonitorId, _opts: &CaptureOptions) -> Result<RgbaImage> {
+        self.device(id)?.read_frame()
+    }
+
+    fn capture_window(&self, id: WindowId, _opts: &CaptureOptions) -> Result<RgbaImage> {
+        Err(CaptureError::WindowNotFound(id))
+    }
+
+    fn capture_all(&self, _opts: &CaptureOptions) -> Result<RgbaImage> {
+        let monitors = self.monitors()?;
+        let bounds = Rect::bounding(&monitors.iter().map(|m| m.bounds).collect::<Vec<_>>())
+            .ok_or(CaptureError::NoMonitors)?;
+        self.stitch(bounds)
+    }
+
+    fn capture_region(&self, region: Rect, _opts: &CaptureOptions) -> Result<RgbaImage> {
+        if region.size.is_empty() {
+            return Err(CaptureError::EmptyRegion(region));
+        }
+        if !self
+            .monitors()?
+            .iter()
+            .any(|m| m.bounds.intersection(&region).is_some())
+        {
+            return Err(CaptureError::RegionOutsideDesktop(region));
+        }
+        self.stitch(region)
+    }
+
+    fn cursor_position(&self) -> Result<Point> {
+        Err(CaptureError::CursorUnavailable(
+            "the framebuffer has no pointer".into(),
+        ))
+    }
+}
+
+fn enumerate_devices() -> Vec<(u32, PathBuf)> {
+    let mut out: Vec<(u32, PathBuf)> = fs::read_dir("/dev")
+        .map(|dir| {
+            dir.filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let path = e.path();
+                    device_index(&path).map(|i| (i, path))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    out.sort_by_key(|(i, _)| *i);
+    out
+}
+
+/// `/dev/fb3` → `3`.
+fn device_index(path: &Path) -> Option<u32> {
+    path.file_name()?.to_str()?.strip_prefix("fb")?.parse().ok()
+}
+
+fn sysfs_stride(index: u32) -> Option<u32> {
+    fs::read_to_string(format!("/sys/class/graphics/fb{index}/stride"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|s| *s > 0)
+}
+
+/// Decode the visible `xres × yres` window of a framebuffer dump.
+fn decode(raw: &[u8], info: &VarScreenInfo, stride: u32) -> Result<RgbaImage> {
+    let bpp = info.bytes_per_pixel() as usize;
+    if !matches!(bpp, 1..=4) {
+        return Err(CaptureError::backend(
+            BACKEND,
+            format!("unsupported pixel depth: {} bpp", info.bits_per_pixel),
+        ));
+    }
+    let gray = info.grayscale != 0 || info.red.length == 0;
+    if !gray {
+        for (name, field) in [
+            ("red", &info.red),
+            ("green", &info.green),
+            ("blue", &info.blue),
+        ] {
+            check_bitfield(name, field, info.bits_per_pixel)?;
+        }
+    }
+    let (w, h) = (info.xres, info.yres);
+    let mut out = RgbaImage::new(w, h);
+    for y in 0..h {
+        let row = y as usize * stride as usize + info.xoffset as usize * bpp;
+        for x in 0..w {
+            let at = row + x as usize * bpp;
+            let mut px = [0u8; 4];
+            px[..bpp].copy_from_slice(&raw[at..at + bpp]);
+            let v = u32::from_ne_bytes(px);
+            let rgba = if gray {
+                let g = (v & 0xff) as u8;
+                [g, g, g, 255]
+            } else {
+                [
+                    channel(v, &info.red),
+                    channel(v, &info.green),
+                    channel(v, &info.blue),
+                    255,
+                ]
+            };
+            out.put_pixel(x, y, image::Rgba(rgba));
+        }
+    }
+    Ok(out)
+}
+
+/// Reject channel layouts [`channel`] can't read: fields past the pixel
+/// and bit-reversed (`msb_right`) ones, which no current driver reports.
+fn check_bitfield(name: &str, field: &Bitfield, bits_per_pixel: u32) -> Result<()> {
+    let bad = |why: &str| {
+        Err(CaptureError::unsupported(
+            BACKEND,
+            format!(
+                "{name} channel at bit {} of length {}: {why}",
+                field.offset, field.length
+            ),
+        ))
+    };
+    if field.msb_right != 0 {
+        return bad("bit-reversed channels are not supported");
+    }
+    match field.offset.checked_add(field.length) {
+        Some(end) if end <= bits_per_pixel.min(32) => Ok(()),
+        _ => bad(&format!("does not fit a {bits_per_pixel} bpp pixel")),
+    }
+}
+
+/// Extract one colour channel and widen it to 8 bits. The field must
+/// have passed [`check_bitfield`].
+fn channel(pixel: u32, field: &Bitfield) -> u8 {
+    if field.length == 0 {
+        return 0;
+    }
+    let len = field.length.min(8);
+    let max = u32::MAX >> (32 - field.length);
+    let v = (pixel >> field.offset) & max;
+    let v = v >> (field.length - len);
+    let max8 = (1u32 << len) - 1;
+    (v * 255 / max8) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn xrgb8888(xres: u32, yres: u32) -> VarScreenInfo {
+        VarScreenInfo {
+            xres,
+            yres,
+            xres_virtual: xres,
+            yres_virtual: yres * 2,
+            bits_per_pixel: 32,
+            red: Bitfield {
+                offset: 16,
+                length: 8,
+                msb_right: 0,
+            },
+            green: Bitfield {
+                offset: 8,
+                length: 8,
+                msb_right: 0,
+            },
+            blue: Bitfield {
+                offset: 0,
+                length: 8,
+                msb_right: 0,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn fake_device(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("sss-fb-{}-{name}", std::process::id()));
+        File::create(&path).unwrap().write_all(bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn screeninfo_matches_kernel_layout() {
+        assert_eq!(std::mem::size_of::<VarScreenInfo>(), 160);
+    }
+
+    #[test]
+    fn captures_xrgb8888_with_panning() {
+        let info = VarScreenInfo {
+            yoffset: 2,
+            ..xrgb8888(2, 2)
+        };
+        let stride = 2 * 4;
+        let mut bytes = vec![0u8; stride * 4];
+        // Second page (yoffset = 2): red, green / blue, white.
+        let page = [0x00ff0000u32, 0x0000ff00, 0x000000ff, 0x00ffffff];
+        for (i, px) in page.iter().enumerate() {
+            let at = stride * 2 + i * 4;
+            bytes[at..at + 4].copy_from_slice(&px.to_ne_bytes());
+        }
+        let path = fake_device("xrgb", &bytes);
+        let backend = FramebufferBackend::from_file(&path, info, stride as u32).unwrap();
+
+        let mons = backend.monitors().unwrap();
+        assert_eq!(mons.len(), 1);
+        assert_eq!(mons[0].bounds(), Rect::from_xywh(0, 0, 2, 2));
+
+        let img = backend
+            .capture_monitor(mons[0].id(), &CaptureOptions::default())
+            .unwrap();
+        assert_eq!(img.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(img.get_pixel(1, 0).0, [0, 255, 0, 255]);
+        assert_eq!(img.get_pixel(0, 1).0, [0, 0, 255, 255]);
+        assert_eq!(img.get_pixel(1, 1).0, [255, 255, 255, 255]);
+
+        let region = backend
+            .capture_region(Rect::from_xywh(1, 1, 1, 1), &CaptureOptions::default())
+            .unwrap();
+        assert_eq!(region.dimensions(), (1, 1));
+        assert_eq!(region.get_pixel(0, 0).0, [255, 255, 255, 255]);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn captures_rgb565() {
+        let info = VarScreenInfo {
+            bits_per_pixel: 16,
+            red: Bitfield {
+                offset: 11,
+                length: 5,
+                msb_right: 0,
+            },
+            green: Bitfield {
+                offset: 5,
+                length: 6,
+                msb_right: 0,
+            },
+            blue: Bitfield {
+                offset: 0,
+                length: 5,
+                msb_right: 0,
+            },
+            ..xrgb8888(2, 1)
+        };
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&0xf800u16.to_ne_bytes());
+        bytes.extend_from_slice(&0x07e0u16.to_ne_bytes());
+        let path = fake_device("rgb565", &bytes);
+        let backend = FramebufferBackend::from_file(&path, info, 4).unwrap();
+        let img = backend.capture_all(&CaptureOptions::default()).unwrap();
+        assert_eq!(img.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(img.get_pixel(1, 0).0, [0, 255, 0, 255]);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn rotated_console_is_captured_upright() {
+        // Panel rows: red, green / blue, white. fbcon turned clockwise
+        // (FB_ROTATE_CW) shows it as a 2×2 turned back counter-clockwise.
+        let info = VarScreenInfo {
+            rotate: 1,
+            ..xrgb8888(2, 1)
+        };
+        let bytes: Vec<u8> = [0x00ff0000u32, 0x0000ff00]
+            .iter()
+            .flat_map(|px| px.to_ne_bytes())
+            .collect();
+        let path = fake_device("rotated", &bytes);
+        let backend = FramebufferBackend::from_file(&path, info, 8).unwrap();
+
+        let mons = backend.monitors().unwrap();
+        assert_eq!(mons[0].bounds(), Rect::from_xywh(0, 0, 1, 2));
+        assert_eq!(mons[0].rotation(), Rotation::Rotate90);
+        let opts = CaptureOptions::default();
+        let img = backend.capture_monitor(mons[0].id(), &opts).unwrap();
+        assert_eq!(img.dimensions(), (1, 2));
+        assert_eq!(img.get_pixel(0, 0).0, [0, 255, 0, 255]);
+        assert_eq!(img.get_pixel(0, 1).0, [255, 0, 0, 255]);
+
+        let all = backend.capture_all(&opts).unwrap();
+        assert_eq!(all, img);
+        let region = backend
+            .capture_region(Rect::from_xywh(0, 1, 1, 1), &opts)
+            .unwrap();
+        assert_eq!(region.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn rejects_impossible_bitfields() {
+        let field = |offset, length, msb_right| Bitfield {
+            offset,
+            length,
+            msb_right,
+        };
+        assert!(check_bitfield("red", &field(16, 8, 0), 32).is_ok());
+        assert!(check_bitfield("red", &field(0, 32, 0), 32).is_ok());
+        assert!(check_bitfield("red", &field(11, 5, 0), 16).is_ok());
+        assert!(check_bitfield("red", &field(12, 5, 0), 16).is_err());
+        assert!(check_bitfield("red", &field(0, 33, 0), 32).is_err());
+        assert!(check_bitfield("red", &field(u32::MAX, 8, 0), 32).is_err());
+        assert!(check_bitfield("red", &field(16, 8, 1), 32).is_err());
+        assert_eq!(channel(0xffff_ffff, &field(0, 32, 0)), 255);
+
+        let info = VarScreenInfo {
+            red: field(30, 8, 0),
+            ..xrgb8888(1, 1)
+        };
+        assert!(decode(&[0; 4], &info, 4).is_err());
+    }
+
+    #[test]
+    fn overflowing_row_size_is_an_error() {
+        let path = fake_device("overflow", &[0; 8]);
+        let opts = CaptureOptions::default();
+        for info in [
+            VarScreenInfo {
+                xoffset: u32::MAX,
+                ..xrgb8888(2, 1)
+            },
+            VarScreenInfo {
+                xoffset: u32::MAX / 4,
+                ..xrgb8888(2, 1)
+            },
+        ] {
+            let backend = FramebufferBackend::from_file(&path, info, 8).unwrap();
+            let mons = backend.monitors().unwrap();
+            assert!(backend.capture_monitor(mons[0].id(), &opts).is_err());
+        }
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn device_index_parses_dev_nodes() {
+        assert_eq!(device_index(Path::new("/dev/fb0")), Some(0));
+        assert_eq!(device_index(Path::new("/dev/fb12")), Some(12));
+        assert_eq!(device_index(Path::new("/dev/fbdev")), None);
+        assert_eq!(device_index(Path::new("/dev/tty1")), None);
+    }
+}
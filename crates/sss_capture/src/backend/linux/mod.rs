@@ -1,9 +1,10 @@
 //! Linux backends: native Wayland (ext-image-copy-capture / wlr-screencopy),
-//! xdg-desktop-portal, X11.
+//! xdg-desktop-portal, X11, fbdev.
 
 use std::env;
 
 pub(crate) mod ext_image_copy;
+pub(crate) mod framebuffer;
 pub(crate) mod portal;
 pub(crate) mod wayland;
 pub(crate) mod x11;
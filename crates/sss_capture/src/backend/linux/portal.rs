@@ -190,6 +190,10 @@ impl Backend for PortalBackend {
         Ok(Vec::new())
     }
 
+    fn lists_windows(&self) -> bool {
+        false
+    }
+
     fn capture_monitor(&self, _id: MonitorId, _opts: &CaptureOptions) -> Result<RgbaImage> {
         self.screenshot()
     }
@@ -0,0 +1,875 @@
+//! VNC / RFB remote-framebuffer backend.
+//!
+//! Speaks RFB 3.3 – 3.8 over plain TCP to grab the desktop of a VNC server
+//! (x11vnc, wayvnc, QEMU, TigerVNC, …) as a single [`Monitor`]. Handles the
+//! `None` and `VNC Authentication` security types and the Raw and ZRLE
+//! encodings. Cross-platform: nothing here touches the local display.
+
+mod des;
+mod zrle;
+
+use std::io::{BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use flate2::{Decompress, FlushDecompress, Status};
+use image::{Rgba, RgbaImage};
+
+use crate::backend::Backend;
+use crate::error::{CaptureError, Result};
+use crate::geometry::{Point, Rect};
+use crate::monitor::{Monitor, MonitorId};
+use crate::options::CaptureOptions;
+use crate::window::{Window, WindowId};
+
+const BACKEND: &str = "vnc";
+const DEFAULT_PORT: u16 = 5900;
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+const MONITOR_ID: MonitorId = MonitorId(0);
+/// Updates read for one grab before giving up on a server that never
+/// paints the whole requested area.
+const MAX_UPDATES: usize = 64;
+
+const SECURITY_NONE: u8 = 1;
+const SECURITY_VNC_AUTH: u8 = 2;
+
+const ENCODING_RAW: i32 = 0;
+const ENCODING_ZRLE: i32 = 16;
+
+/// Client → server message types.
+const MSG_SET_PIXEL_FORMAT: u8 = 0;
+const MSG_SET_ENCODINGS: u8 = 2;
+const MSG_FRAMEBUFFER_UPDATE_REQUEST: u8 = 3;
+
+/// Server → client message types.
+const MSG_FRAMEBUFFER_UPDATE: u8 = 0;
+const MSG_SET_COLOUR_MAP_ENTRIES: u8 = 1;
+const MSG_BELL: u8 = 2;
+const MSG_SERVER_CUT_TEXT: u8 = 3;
+
+/// 32 bpp, depth 24, little-endian true colour, `0x00RRGGBB`.
+const PIXEL_FORMAT: [u8; 16] = [32, 24, 0, 1, 0, 255, 0, 255, 0, 255, 16, 8, 0, 0, 0, 0];
+
+pub(crate) struct VncBackend {
+    addr: String,
+    password: Option<String>,
+    session: Mutex<Option<Session>>,
+}
+
+impl VncBackend {
+    /// Connect and authenticate up front so a bad address or password is
+    /// reported at build time, not on the first capture.
+    pub fn try_new(addr: &str, password: Option<&str>) -> Result<Self> {
+        let session = Session::connect(addr, password)?;
+        tracing::info!(
+            server = %addr,
+            name = %session.name,
+            width = session.frame.width(),
+            height = session.frame.height(),
+            "vnc: connected"
+        );
+        Ok(Self {
+            addr: addr.to_string(),
+            password: password.map(str::to_string),
+            session: Mutex::new(Some(session)),
+        })
+    }
+
+    /// Run `f` on the live session, reconnecting once when the link dropped.
+    fn with_session<T>(
+        &self,
+        retry: bool,
+        mut f: impl FnMut(&mut Session) -> Result<T>,
+    ) -> Result<T> {
+        let mut guard = self.session.lock().unwrap();
+        if guard.is_none() {
+            *guard = Some(Session::connect(&self.addr, self.password.as_deref())?);
+        }
+        match f(guard.as_mut().expect("session just set")) {
+            Ok(v) => Ok(v),
+            Err(e) if retry => {
+                tracing::debug!(error = %e, "vnc: request failed; reconnecting");
+                *guard = None;
+                let mut fresh = Session::connect(&self.addr, self.password.as_deref())?;
+                let out = f(&mut fresh);
+                *guard = Some(fresh);
+                out
+            }
+            Err(e) => {
+                *guard = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Backend for VncBackend {
+    fn name(&self) -> &'static str {
+        BACKEND
+    }
+
+    fn monitors(&self) -> Result<Vec<Monitor>> {
+        let (name, w, h) = self.with_session(true, |s| {
+            Ok((s.name.clone(), s.frame.width(), s.frame.height()))
+        })?;
+        Ok(vec![Monitor {
+            id: MONITOR_ID,
+            name: if name.is_empty() {
+                self.addr.clone()
+            } else {
+                name
+            },
+            bounds: Rect::from_xywh(0, 0, w, h),
+            physical_size: (w, h),
+            scale_factor: 1.0,
+            rotation: Default::default(),
+            refresh_rate: None,
+            is_primary: true,
+        }])
+    }
+
+    fn windows(&self) -> Result<Vec<Window>> {
+        Ok(Vec::new())
+    }
+
+    fn lists_windows(&self) -> bool {
+        false
+    }
+
+    fn capture_monitor(&self, id: MonitorId, opts: &CaptureOptions) -> Result<RgbaImage> {
+        if id != MONITOR_ID {
+            return Err(CaptureError::MonitorNotFound(id));
+        }
+        self.capture_all(opts)
+    }
+
+    fn capture_window(&self, id: WindowId, _opts: &CaptureOptions) -> Result<RgbaImage> {
+        Err(CaptureError::WindowNotFound(id))
+    }
+
+    fn capture_all(&self, opts: &CaptureOptions) -> Result<RgbaImage> {
+        self.with_session(opts.retry_on_failure, |s| {
+            let full = Rect::from_xywh(0, 0, s.frame.width(), s.frame.height());
+            s.grab(full)
+        })
+    }
+
+    fn capture_region(&self, region: Rect, opts: &CaptureOptions) -> Result<RgbaImage> {
+        if region.size.is_empty() {
+            return Err(CaptureError::EmptyRegion(region));
+        }
+        self.with_session(opts.retry_on_failure, |s| {
+            let full = Rect::from_xywh(0, 0, s.frame.width(), s.frame.height());
+            let visible = full
+                .intersection(&region)
+                .ok_or(CaptureError::RegionOutsideDesktop(region))?;
+            let grabbed = s.grab(visible)?;
+            if visible == region {
+                return Ok(grabbed);
+            }
+            // Pad the off-screen part black, like `compose::region` does.
+            let mut out =
+                RgbaImage::from_pixel(region.width(), region.height(), Rgba([0, 0, 0, 255]));
+            image::imageops::overlay(
+                &mut out,
+                &grabbed,
+                (visible.x() - region.x()) as i64,
+                (visible.y() - region.y()) as i64,
+            );
+            Ok(out)
+        })
+    }
+
+    fn cursor_position(&self) -> Result<Point> {
+        Err(CaptureError::CursorUnavailable(
+            "RFB does not report the remote pointer position to clients".into(),
+        ))
+    }
+}
+
+/// One authenticated RFB connection plus the client-side copy of the
+/// remote framebuffer.
+struct Session {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    name: String,
+    /// ZRLE uses one zlib stream for the whole connection.
+    zlib: Decompress,
+    frame: RgbaImage,
+}
+
+impl Session {
+    fn connect(addr: &str, password: Option<&str>) -> Result<Self> {
+        let stream = open_stream(addr)?;
+        let writer = stream.try_clone()?;
+        let mut s = Self {
+            reader: BufReader::new(stream),
+            writer,
+            name: String::new(),
+            zlib: Decompress::new(true),
+            frame: RgbaImage::new(0, 0),
+        };
+        s.handshake(password)?;
+        Ok(s)
+    }
+
+    fn handshake(&mut self, password: Option<&str>) -> Result<()> {
+        // ProtocolVersion: "RFB 003.008\n"
+        let mut version = [0u8; 12];
+        self.read_exact(&mut version)?;
+        let minor = parse_version(&version)?;
+        self.write(format!("RFB 003.{minor:03}\n").as_bytes())?;
+
+        let security = if minor >= 7 {
+            let count = self.u8()?;
+            if count == 0 {
+                return Err(self.failure("server refused the connection"));
+            }
+            let mut offered = vec![0u8; count as usize];
+            self.read_exact(&mut offered)?;
+            let chosen = choose_security(&offered, password.is_some())?;
+            self.write(&[chosen])?;
+            chosen
+        } else {
+            match self.u32()? {
+                0 => return Err(self.failure("server refused the connection")),
+                t if t == SECURITY_NONE as u32 || t == SECURITY_VNC_AUTH as u32 => t as u8,
+                t => {
+                    return Err(CaptureError::unsupported(
+                        BACKEND,
+                        format!("security type {t}"),
+                    ))
+                }
+            }
+        };
+
+        if security == SECURITY_VNC_AUTH {
+            let password = password
+                .ok_or_else(|| CaptureError::backend(BACKEND, "server requires a password"))?;
+            let mut challenge = [0u8; 16];
+            self.read_exact(&mut challenge)?;
+            self.write(&des::vnc_auth_response(password, &challenge))?;
+        }
+        // RFB 3.3 / 3.7 skip SecurityResult for the None type.
+        if (security == SECURITY_VNC_AUTH || minor >= 8) && self.u32()? != 0 {
+            return Err(if minor >= 8 {
+                self.failure("authentication failed")
+            } else {
+                CaptureError::backend(BACKEND, "authentication failed")
+            });
+        }
+
+        // ClientInit: shared session, so we don't kick other viewers.
+        self.write(&[1])?;
+
+        // ServerInit.
+        let width = self.u16()? as u32;
+        let height = self.u16()? as u32;
+        let mut server_format = [0u8; 16];
+        self.read_exact(&mut server_format)?;
+        let name_len = self.u32()? as usize;
+        let mut name = vec![0u8; name_len];
+        self.read_exact(&mut name)?;
+        self.name = String::from_utf8_lossy(&name).into_owned();
+        self.frame = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255]));
+
+        let mut msg = vec![MSG_SET_PIXEL_FORMAT, 0, 0, 0];
+        msg.extend_from_slice(&PIXEL_FORMAT);
+        self.write(&msg)?;
+
+        let encodings = [ENCODING_ZRLE, ENCODING_RAW];
+        let mut msg = vec![MSG_SET_ENCODINGS, 0];
+        msg.extend_from_slice(&(encodings.len() as u16).to_be_bytes());
+        for e in encodings {
+            msg.extend_from_slice(&e.to_be_bytes());
+        }
+        self.write(&msg)
+    }
+
+    /// Request a full (non-incremental) refresh of `area` and return it.
+    ///
+    /// Servers are free to split a refresh over several updates or to
+    /// answer with only part of it, so updates are read until every pixel
+    /// of `area` has been painted, asking again for whatever is missing.
+    fn grab(&mut self, area: Rect) -> Result<RgbaImage> {
+        self.request_update(area)?;
+        let mut coverage = Coverage::new(area);
+        let mut updates = 0;
+        loop {
+            match self.u8()? {
+                MSG_FRAMEBUFFER_UPDATE => {
+                    for rect in self.read_update()? {
+                        coverage.paint(rect);
+                    }
+                    let Some(missing) = coverage.missing() else {
+                        break;
+                    };
+                    updates += 1;
+                    if updates == MAX_UPDATES {
+                        return Err(CaptureError::backend(
+                            BACKEND,
+                            format!("server never painted {missing} after {updates} updates"),
+                        ));
+                    }
+                    self.request_update(missing)?;
+                }
+                MSG_SET_COLOUR_MAP_ENTRIES => {
+                    self.skip(1)?;
+                    let _first = self.u16()?;
+                    let count = self.u16()? as usize;
+                    self.skip(count * 6)?;
+                }
+                MSG_BELL => {}
+                MSG_SERVER_CUT_TEXT => {
+                    self.skip(3)?;
+                    let len = self.u32()? as usize;
+                    self.skip(len)?;
+                }
+                other => {
+                    return Err(CaptureError::backend(
+                        BACKEND,
+                        format!("unexpected server message type {other}"),
+                    ))
+                }
+            }
+        }
+
+        Ok(image::imageops::crop_imm(
+            &self.frame,
+            area.x() as u32,
+            area.y() as u32,
+            area.width(),
+            area.height(),
+        )
+        .to_image())
+    }
+
+    /// Ask for a non-incremental refresh of `area`.
+    fn request_update(&mut self, area: Rect) -> Result<()> {
+        let mut msg = vec![MSG_FRAMEBUFFER_UPDATE_REQUEST, 0];
+        for v in [
+            area.x(),
+            area.y(),
+            area.width() as i32,
+            area.height() as i32,
+        ] {
+            msg.extend_from_slice(&(v as u16).to_be_bytes());
+        }
+        self.write(&msg)
+    }
+
+    /// Decode one FramebufferUpdate into `frame`, returning the rectangles
+    /// it painted.
+    fn read_update(&mut self) -> Result<Vec<Rect>> {
+        self.skip(1)?;
+        let rects = self.u16()?;
+        let mut painted = Vec::with_capacity(rects as usize);
+        for _ in 0..rects {
+            let x = self.u16()? as u32;
+            let y = self.u16()? as u32;
+            let w = self.u16()? as u32;
+            let h = self.u16()? as u32;
+            let pixels = rect_pixels(&self.frame, x, y, w, h)?;
+            match self.u32()? as i32 {
+                ENCODING_RAW => {
+                    let mut raw = vec![0u8; pixels * 4];
+                    self.read_exact(&mut raw)?;
+                    for (i, px) in raw.chunks_exact(4).enumerate() {
+                        let (px_x, px_y) = (x + i as u32 % w, y + i as u32 / w);
+                        self.frame
+                            .put_pixel(px_x, px_y, Rgba([px[2], px[1], px[0], 255]));
+                    }
+                }
+                ENCODING_ZRLE => {
+                    let len = self.u32()? as usize;
+                    let mut compressed = vec![0u8; len];
+                    self.read_exact(&mut compressed)?;
+                    let data = inflate(&mut self.zlib, &compressed, zrle_limit(w, h, pixels))?;
+                    zrle::decode(&data, x, y, w, h, &mut self.frame)
+                        .map_err(|e| CaptureError::backend(BACKEND, e))?;
+                }
+                other => {
+                    return Err(CaptureError::unsupported(
+                        BACKEND,
+                        format!("server sent encoding {other}, which was not negotiated"),
+                    ))
+                }
+            }
+            painted.push(Rect::from_xywh(x as i32, y as i32, w, h));
+        }
+        Ok(painted)
+    }
+
+    /// Read the reason string that follows a failed handshake step.
+    fn failure(&mut self, what: &str) -> CaptureError {
+        let reason = self.u32().ok().and_then(|len| {
+            let mut buf = vec![0u8; len.min(4096) as usize];
+            self.read_exact(&mut buf).ok()?;
+            Some(String::from_utf8_lossy(&buf).into_owned())
+        });
+        match reason {
+            Some(r) if !r.is_empty() => CaptureError::backend(BACKEND, format!("{what}: {r}")),
+            _ => CaptureError::backend(BACKEND, what.to_string()),
+        }
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.reader.read_exact(buf).map_err(io_error)
+    }
+
+    fn skip(&mut self, n: usize) -> Result<()> {
+        let copied = std::io::copy(&mut (&mut self.reader).take(n as u64), &mut std::io::sink())
+            .map_err(io_error)?;
+        if copied as usize != n {
+            return Err(io_error(std::io::ErrorKind::UnexpectedEof.into()));
+        }
+        Ok(())
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        let mut b = [0u8; 1];
+        self.read_exact(&mut b)?;
+        Ok(b[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let mut b = [0u8; 2];
+        self.read_exact(&mut b)?;
+        Ok(u16::from_be_bytes(b))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let mut b = [0u8; 4];
+        self.read_exact(&mut b)?;
+        Ok(u32::from_be_bytes(b))
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.writer.write_all(bytes).map_err(io_error)
+    }
+}
+
+/// Which pixels of a requested area the server has painted so far.
+struct Coverage {
+    area: Rect,
+    painted: Vec<bool>,
+    missing: usize,
+}
+
+impl Coverage {
+    fn new(area: Rect) -> Self {
+        let len = area.width() as usize * area.height() as usize;
+        Self {
+            area,
+            painted: vec![false; len],
+            missing: len,
+        }
+    }
+
+    fn paint(&mut self, rect: Rect) {
+        let Some(hit) = self.area.intersection(&rect) else {
+            return;
+        };
+        let w = self.area.width() as usize;
+        for y in hit.y()..hit.bottom() {
+            let row = (y - self.area.y()) as usize * w;
+            for x in hit.x()..hit.right() {
+                let px = &mut self.painted[row + (x - self.area.x()) as usize];
+                if !*px {
+                    *px = true;
+                    self.missing -= 1;
+                }
+            }
+        }
+    }
+
+    /// Bounding box of the pixels still unpainted, `None` once complete.
+    fn missing(&self) -> Option<Rect> {
+        if self.missing == 0 {
+            return None;
+        }
+        let w = self.area.width() as usize;
+        let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+        for (i, _) in self.painted.iter().enumerate().filter(|(_, p)| !**p) {
+            let (x, y) = (i % w, i / w);
+            x0 = x0.min(x);
+            y0 = y0.min(y);
+            x1 = x1.max(x + 1);
+            y1 = y1.max(y + 1);
+        }
+        Some(Rect::from_xywh(
+            self.area.x() + x0 as i32,
+            self.area.y() + y0 as i32,
+            (x1 - x0) as u32,
+            (y1 - y0) as u32,
+        ))
+    }
+}
+
+/// Resolve `host[:port]` and connect. Ports below 100 are VNC display
+/// numbers (`host:1` → 5901), like every VNC viewer accepts.
+fn open_stream(addr: &str) -> Result<TcpStream> {
+    let (host, port) = match addr.rsplit_once(':') {
+        Some((h, p)) if !h.ends_with(':') => {
+            let p: u16 = p
+                .parse()
+                .map_err(|_| CaptureError::backend(BACKEND, format!("bad port in {addr:?}")))?;
+            (h.trim_start_matches('[').trim_end_matches(']'), p)
+        }
+        _ => (addr, DEFAULT_PORT),
+    };
+    let port = if port < 100 {
+        DEFAULT_PORT + port
+    } else {
+        port
+    };
+
+    let targets = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| CaptureError::backend(BACKEND, format!("cannot resolve {host}: {e}")))?;
+    let mut last_err = None;
+    for target in targets {
+        match TcpStream::connect_timeout(&target, IO_TIMEOUT) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(IO_TIMEOUT))?;
+                stream.set_write_timeout(Some(IO_TIMEOUT))?;
+                stream.set_nodelay(true)?;
+                return Ok(stream);
+            }
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(CaptureError::backend(
+        BACKEND,
+        match last_err {
+            Some(e) => format!("cannot connect to {host}:{port}: {e}"),
+            None => format!("{host} did not resolve to any address"),
+        },
+    ))
+}
+
+fn parse_version(raw: &[u8; 12]) -> Result<u32> {
+    let text = std::str::from_utf8(raw).unwrap_or_default();
+    let bad = || CaptureError::backend(BACKEND, format!("not an RFB server (got {text:?})"));
+    let rest = text.strip_prefix("RFB ").ok_or_else(bad)?;
+    let (major, minor) = rest.trim_end().split_once('.').ok_or_else(bad)?;
+    let major: u32 = major.parse().map_err(|_| bad())?;
+    let minor: u32 = minor.parse().map_err(|_| bad())?;
+    if major != 3 {
+        return Err(CaptureError::unsupported(
+            BACKEND,
+            format!("RFB protocol {major}.{minor}"),
+        ));
+    }
+    // 3.3, 3.7 and 3.8 are the only published versions; anything newer
+    // speaks 3.8 and anything in between (e.g. Apple's 3.889) falls back.
+    Ok(match minor {
+        8.. => 8,
+        7 => 7,
+        _ => 3,
+    })
+}
+
+fn choose_security(offered: &[u8], have_password: bool) -> Result<u8> {
+    let none = offered.contains(&SECURITY_NONE);
+    let vnc = offered.contains(&SECURITY_VNC_AUTH);
+    match (none, vnc) {
+        (true, false) => Ok(SECURITY_NONE),
+        (true, true) if !have_password => Ok(SECURITY_NONE),
+        (_, true) => Ok(SECURITY_VNC_AUTH),
+        _ => Err(CaptureError::unsupported(
+            BACKEND,
+            format!(
+                "none of the offered security types {offered:?} (need None or VNC Authentication)"
+            ),
+        )),
+    }
+}
+
+/// Pixel count of an update rectangle, which must lie inside the
+/// framebuffer negotiated in ServerInit.
+fn rect_pixels(frame: &RgbaImage, x: u32, y: u32, w: u32, h: u32) -> Result<usize> {
+    if x + w > frame.width() || y + h > frame.height() {
+        return Err(CaptureError::backend(
+            BACKEND,
+            format!(
+                "update rectangle {w}x{h}+{x}+{y} lies outside the {}x{} framebuffer",
+                frame.width(),
+                frame.height()
+            ),
+        ));
+    }
+    (w as usize)
+        .checked_mul(h as usize)
+        .ok_or_else(|| CaptureError::backend(BACKEND, "update rectangle too large"))
+}
+
+/// Most bytes a ZRLE rectangle can decode to: a subencoding byte per
+/// 64×64 tile, then at worst plain RLE with a one-byte run per CPIXEL.
+fn zrle_limit(w: u32, h: u32, pixels: usize) -> usize {
+    let tiles = w.div_ceil(64) as usize * h.div_ceil(64) as usize;
+    pixels * 4 + tiles
+}
+
+/// Inflate one ZRLE rectangle, continuing the connection-wide zlib stream.
+/// Fails once the output passes `limit` or the stream stops making
+/// progress with input left over.
+fn inflate(z: &mut Decompress, input: &[u8], limit: usize) -> Result<Vec<u8>> {
+    let fail = |what: &str| CaptureError::backend(BACKEND, format!("ZRLE inflate: {what}"));
+    let mut out = Vec::with_capacity((input.len() * 4 + 64).min(limit + 1));
+    let mut consumed = 0usize;
+    loop {
+        if out.len() == out.capacity() {
+            out.reserve(out.capacity().min(limit + 1 - out.len()).max(1));
+        }
+        let (in_before, out_before) = (z.total_in(), z.total_out());
+        let status = z
+            .decompress_vec(&input[consumed..], &mut out, FlushDecompress::Sync)
+            .map_err(|e| fail(&e.to_string()))?;
+        consumed += (z.total_in() - in_before) as usize;
+        if out.len() > limit {
+            return Err(fail("rectangle decodes past its size"));
+        }
+        if consumed >= input.len() && out.len() < out.capacity() {
+            return Ok(out);
+        }
+        let stalled = z.total_in() == in_before && z.total_out() == out_before;
+        if status == Status::StreamEnd || (stalled && out.len() < out.capacity()) {
+            return Err(fail("stream stopped with input left over"));
+        }
+    }
+}
+
+fn io_error(e: std::io::Error) -> CaptureError {
+    match e.kind() {
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => {
+            CaptureError::Timeout(IO_TIMEOUT)
+        }
+        _ => CaptureError::backend(BACKEND, format!("connection: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    use flate2::{Compress, Compression, FlushCompress};
+
+    /// A 4×2 desktop: left half red, right half blue.
+    fn desktop(x: u32) -> [u8; 3] {
+        if x < 2 {
+            [255, 0, 0]
+        } else {
+            [0, 0, 255]
+        }
+    }
+
+    fn read_n(s: &mut TcpStream, n: usize) -> Vec<u8> {
+        let mut buf = vec![0u8; n];
+        s.read_exact(&mut buf).unwrap();
+        buf
+    }
+
+    /// Minimal RFB 3.8 server answering one update request per encoding.
+    /// With `partial`, each request is answered with only its first row.
+    fn serve(password: Option<&'static str>, encoding: i32, partial: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            let (mut s, _) = listener.accept().unwrap();
+            s.write_all(b"RFB 003.008\n").unwrap();
+            assert_eq!(read_n(&mut s, 12), b"RFB 003.008\n");
+            let challenge = [7u8; 16];
+            match password {
+                Some(pw) => {
+                    s.write_all(&[1, SECURITY_VNC_AUTH]).unwrap();
+                    assert_eq!(read_n(&mut s, 1), [SECURITY_VNC_AUTH]);
+                    s.write_all(&challenge).unwrap();
+                    let ok = read_n(&mut s, 16) == des::vnc_auth_response(pw, &challenge);
+                    s.write_all(&(!ok as u32).to_be_bytes()).unwrap();
+                    if !ok {
+                        s.write_all(&6u32.to_be_bytes()).unwrap();
+                        s.write_all(b"denied").unwrap();
+                        return;
+                    }
+                }
+                None => {
+                    s.write_all(&[1, SECURITY_NONE]).unwrap();
+                    assert_eq!(read_n(&mut s, 1), [SECURITY_NONE]);
+                    s.write_all(&0u32.to_be_bytes()).unwrap();
+                }
+            }
+            assert_eq!(read_n(&mut s, 1), [1]);
+            let mut init = vec![0, 4, 0, 2];
+            init.extend_from_slice(&PIXEL_FORMAT);
+            init.extend_from_slice(&4u32.to_be_bytes());
+            init.extend_from_slice(b"test");
+            s.write_all(&init).unwrap();
+            read_n(&mut s, 20); // SetPixelFormat
+            read_n(&mut s, 4 + 2 * 4); // SetEncodings
+
+            let mut zlib = Compress::new(Compression::default(), true);
+            let mut req = [0u8; 10];
+            while s.read_exact(&mut req).is_ok() {
+                let be = |i: usize| u16::from_be_bytes([req[i], req[i + 1]]) as u32;
+                let (x, y, w) = (be(2), be(4), be(6));
+                let h = if partial { 1 } else { be(8) };
+                let mut msg = vec![MSG_FRAMEBUFFER_UPDATE, 0, 0, 1];
+                for v in [x, y, w, h] {
+                    msg.extend_from_slice(&(v as u16).to_be_bytes());
+                }
+                msg.extend_from_slice(&encoding.to_be_bytes());
+                if encoding == ENCODING_RAW {
+                    for _ in y..y + h {
+                        for px in x..x + w {
+                            let [r, g, b] = desktop(px);
+                            msg.extend_from_slice(&[b, g, r, 0]);
+                        }
+                    }
+                } else {
+                    // One raw tile (subencoding 0) of CPIXELs.
+                    let mut tile = vec![0u8];
+                    for _ in y..y + h {
+                        for px in x..x + w {
+                            let [r, g, b] = desktop(px);
+                            tile.extend_from_slice(&[b, g, r]);
+                        }
+                    }
+                    let mut packed = Vec::with_capacity(tile.len() + 64);
+                    zlib.compress_vec(&tile, &mut packed, FlushCompress::Sync)
+                        .unwrap();
+                    msg.extend_from_slice(&(packed.len() as u32).to_be_bytes());
+                    msg.extend_from_slice(&packed);
+                }
+                s.write_all(&msg).unwrap();
+            }
+        });
+        addr
+    }
+
+    fn assert_desktop(img: &RgbaImage, x0: u32) {
+        for (x, _, px) in img.enumerate_pixels() {
+            let [r, g, b] = desktop(x0 + x);
+            assert_eq!(px.0, [r, g, b, 255], "pixel at x={}", x0 + x);
+        }
+    }
+
+    #[test]
+    fn raw_capture_without_auth() {
+        let backend = VncBackend::try_new(&serve(None, ENCODING_RAW, false), None).unwrap();
+        let mons = backend.monitors().unwrap();
+        assert_eq!(mons.len(), 1);
+        assert_eq!(mons[0].name(), "test");
+        assert_eq!(mons[0].bounds(), Rect::from_xywh(0, 0, 4, 2));
+
+        let opts = CaptureOptions::default();
+        assert_desktop(&backend.capture_all(&opts).unwrap(), 0);
+        let region = backend
+            .capture_region(Rect::from_xywh(1, 0, 2, 2), &opts)
+            .unwrap();
+        assert_eq!(region.dimensions(), (2, 2));
+        assert_desktop(&region, 1);
+    }
+
+    #[test]
+    fn zrle_capture_with_password() {
+        let backend = VncBackend::try_new(
+            &serve(Some("hunter2"), ENCODING_ZRLE, false),
+            Some("hunter2"),
+        )
+        .unwrap();
+        let opts = CaptureOptions::default();
+        assert_desktop(&backend.capture_all(&opts).unwrap(), 0);
+        // Second update continues the same zlib stream.
+        assert_desktop(&backend.capture_all(&opts).unwrap(), 0);
+    }
+
+    #[test]
+    fn partial_updates_are_requested_until_the_frame_is_painted() {
+        let backend = VncBackend::try_new(&serve(None, ENCODING_RAW, true), None).unwrap();
+        let opts = CaptureOptions::default();
+        assert_desktop(&backend.capture_all(&opts).unwrap(), 0);
+        let region = backend
+            .capture_region(Rect::from_xywh(2, 0, 2, 2), &opts)
+            .unwrap();
+        assert_desktop(&region, 2);
+    }
+
+    #[test]
+    fn coverage_tracks_the_unpainted_area() {
+        let mut c = Coverage::new(Rect::from_xywh(10, 10, 4, 3));
+        assert_eq!(c.missing(), Some(Rect::from_xywh(10, 10, 4, 3)));
+        c.paint(Rect::from_xywh(0, 0, 12, 11));
+        assert_eq!(c.missing(), Some(Rect::from_xywh(10, 10, 4, 3)));
+        c.paint(Rect::from_xywh(10, 10, 4, 1));
+        assert_eq!(c.missing(), Some(Rect::from_xywh(10, 11, 4, 2)));
+        c.paint(Rect::from_xywh(12, 11, 9, 9));
+        assert_eq!(c.missing(), Some(Rect::from_xywh(10, 11, 2, 2)));
+        c.paint(Rect::from_xywh(10, 11, 2, 2));
+        assert_eq!(c.missing(), None);
+        // Rectangles outside the area don't count.
+        let mut c = Coverage::new(Rect::from_xywh(0, 0, 2, 2));
+        c.paint(Rect::from_xywh(2, 0, 5, 5));
+        assert_eq!(c.missing(), Some(Rect::from_xywh(0, 0, 2, 2)));
+    }
+
+    #[test]
+    fn wrong_password_is_reported() {
+        let err = VncBackend::try_new(&serve(Some("hunter2"), ENCODING_RAW, false), Some("nope"))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("denied"), "{err}");
+    }
+
+    #[test]
+    fn zrle_palette_rle_tile() {
+        // 3×1 tile, palette [red, blue]: a run of two blue, then one red.
+        let data = [130, 0, 0, 255, 255, 0, 0, 0x81, 1, 0];
+        let mut frame = RgbaImage::new(3, 1);
+        zrle::decode(&data, 0, 0, 3, 1, &mut frame).unwrap();
+        assert_eq!(frame.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(frame.get_pixel(1, 0).0, [0, 0, 255, 255]);
+        assert_eq!(frame.get_pixel(2, 0).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn rejects_rectangles_outside_the_framebuffer() {
+        let frame = RgbaImage::new(4, 2);
+        assert_eq!(rect_pixels(&frame, 1, 0, 3, 2).unwrap(), 6);
+        assert!(rect_pixels(&frame, 2, 0, 3, 1).is_err());
+        assert!(rect_pixels(&frame, 0, 0, 65535, 65535).is_err());
+    }
+
+    #[test]
+    fn inflate_stops_on_bad_streams() {
+        let pack = |data: &[u8], flush| {
+            let mut zlib = Compress::new(Compression::default(), true);
+            let mut packed = Vec::with_capacity(data.len() + 64);
+            zlib.compress_vec(data, &mut packed, flush).unwrap();
+            packed
+        };
+        let data = [9u8; 1000];
+        let packed = pack(&data, FlushCompress::Sync);
+        assert_eq!(
+            inflate(&mut Decompress::new(true), &packed, 1000).unwrap(),
+            data
+        );
+        // More than the rectangle can hold.
+        assert!(inflate(&mut Decompress::new(true), &packed, 999).is_err());
+        // Input left after the end of the stream.
+        let mut ended = pack(&data, FlushCompress::Finish);
+        ended.extend_from_slice(&[1, 2, 3]);
+        assert!(inflate(&mut Decompress::new(true), &ended, 4000).is_err());
+    }
+
+    #[test]
+    fn parses_protocol_versions() {
+        assert_eq!(parse_version(b"RFB 003.008\n").unwrap(), 8);
+        assert_eq!(parse_version(b"RFB 003.889\n").unwrap(), 8);
+        assert_eq!(parse_version(b"RFB 003.003\n").unwrap(), 3);
+        assert!(parse_version(b"HTTP/1.1 200").is_err());
+    }
+}
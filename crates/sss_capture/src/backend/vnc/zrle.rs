@@ -0,0 +1,155 @@
+//! ZRLE (RFB encoding 16) tile decoder.
+//!
+//! Input is the already-inflated payload of one rectangle. The session
+//! negotiates 32-bpp little-endian true colour, so every CPIXEL on the wire
+//! is three bytes in `b, g, r` order.
+
+use image::{Rgba, RgbaImage};
+
+const TILE: u32 = 64;
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn u8(&mut self) -> Result<u8, String> {
+        let b = *self
+            .data
+            .get(self.pos)
+            .ok_or_else(|| "ZRLE payload truncated".to_string())?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn cpixel(&mut self) -> Result<Rgba<u8>, String> {
+        let b = self.u8()?;
+        let g = self.u8()?;
+        let r = self.u8()?;
+        Ok(Rgba([r, g, b, 255]))
+    }
+
+    /// RLE run length: a chain of bytes summed until one is not 255, plus one.
+    fn run_length(&mut self) -> Result<u32, String> {
+        let mut len = 1u32;
+        loop {
+            let b = self.u8()?;
+            len += b as u32;
+            if b != 255 {
+                return Ok(len);
+            }
+        }
+    }
+}
+
+/// Decode a `w × h` ZRLE rectangle at `(x, y)` into `frame`.
+pub(super) fn decode(
+    data: &[u8],
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+    frame: &mut RgbaImage,
+) -> Result<(), String> {
+    let mut cur = Cursor { data, pos: 0 };
+
+    for ty in (0..h).step_by(TILE as usize) {
+        let th = TILE.min(h - ty);
+        for tx in (0..w).step_by(TILE as usize) {
+            let tw = TILE.min(w - tx);
+            let (ox, oy) = (x + tx, y + ty);
+            let sub = cur.u8()?;
+            match sub {
+                // Raw CPIXELs.
+                0 => {
+                    for j in 0..th {
+                        for i in 0..tw {
+                            let c = cur.cpixel()?;
+                            put(frame, ox + i, oy + j, c);
+                        }
+                    }
+                }
+                // Solid tile.
+                1 => {
+                    let c = cur.cpixel()?;
+                    for j in 0..th {
+                        for i in 0..tw {
+                            put(frame, ox + i, oy + j, c);
+                        }
+                    }
+                }
+                // Packed palette; rows are padded to a byte boundary.
+                2..=16 => {
+                    let palette = (0..sub)
+                        .map(|_| cur.cpixel())
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let bits = match sub {
+                        2 => 1,
+                        3 | 4 => 2,
+                        _ => 4,
+                    };
+                    let mask = (1u8 << bits) - 1;
+                    for j in 0..th {
+                        let mut byte = 0u8;
+                        let mut left = 0u32;
+                        for i in 0..tw {
+                            if left == 0 {
+                                byte = cur.u8()?;
+                                left = 8;
+                            }
+                            left -= bits;
+                            let idx = (byte >> left) & mask;
+                            let c = *palette
+                                .get(idx as usize)
+                                .ok_or_else(|| format!("ZRLE palette index {idx} out of range"))?;
+                            put(frame, ox + i, oy + j, c);
+                        }
+                    }
+                }
+                // Plain RLE and palette RLE.
+                128 | 130..=255 => {
+                    let palette = if sub == 128 {
+                        Vec::new()
+                    } else {
+                        (0..sub - 128)
+                            .map(|_| cur.cpixel())
+                            .collect::<Result<Vec<_>, _>>()?
+                    };
+                    let total = tw * th;
+                    let mut n = 0u32;
+                    while n < total {
+                        let (c, len) = if sub == 128 {
+                            let c = cur.cpixel()?;
+                            (c, cur.run_length()?)
+                        } else {
+                            let idx = cur.u8()?;
+                            let c = *palette.get((idx & 0x7f) as usize).ok_or_else(|| {
+                                format!("ZRLE palette index {} out of range", idx & 0x7f)
+                            })?;
+                            let len = if idx & 0x80 != 0 {
+                                cur.run_length()?
+                            } else {
+                                1
+                            };
+                            (c, len)
+                        };
+                        for k in n..(n + len).min(total) {
+                            put(frame, ox + k % tw, oy + k / tw, c);
+                        }
+                        n += len;
+                    }
+                }
+                other => return Err(format!("invalid ZRLE subencoding {other}")),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Servers may send rectangles that poke past a freshly resized framebuffer.
+fn put(frame: &mut RgbaImage, x: u32, y: u32, c: Rgba<u8>) {
+    if x < frame.width() && y < frame.height() {
+        frame.put_pixel(x, y, c);
+    }
+}
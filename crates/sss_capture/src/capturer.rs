@@ -1,17 +1,21 @@
 //! The public capture entry point.
 
+use image::RgbaImage;
+
 use crate::backend::Backend;
 use crate::error::{CaptureError, Result};
 use crate::frame::Image;
 use crate::geometry::{Point, Rect};
 use crate::monitor::{Monitor, MonitorId};
-use crate::options::{BackendKind, CaptureOptions};
+use crate::options::{BackendKind, CaptureOptions, VncOptions};
+use crate::privacy::PrivacyMask;
 use crate::window::{Window, WindowId, WindowSearch};
 
 /// Cross-platform screen capture.
 pub struct Capturer {
     backend: Box<dyn Backend>,
     default_options: CaptureOptions,
+    privacy_mask: PrivacyMask,
 }
 
 unsafe impl Send for Capturer {}
@@ -22,6 +26,7 @@ impl std::fmt::Debug for Capturer {
         f.debug_struct("Capturer")
             .field("backend", &self.backend.name())
             .field("default_options", &self.default_options)
+            .field("privacy_mask", &self.privacy_mask)
             .finish()
     }
 }
@@ -49,6 +54,14 @@ impl Capturer {
         self.default_options = opts;
     }
 
+    pub fn privacy_mask(&self) -> &PrivacyMask {
+        &self.privacy_mask
+    }
+
+    pub fn set_privacy_mask(&mut self, mask: PrivacyMask) {
+        self.privacy_mask = mask;
+    }
+
     pub fn monitors(&self) -> Result<Vec<Monitor>> {
         self.backend.monitors()
     }
@@ -124,7 +137,19 @@ impl Capturer {
     }
 
     pub fn capture_all_with(&self, opts: CaptureOptions) -> Result<Image> {
-        self.backend.capture_all(&opts).map(Image::from)
+        let img = self.backend.capture_all(&opts)?;
+        if self.privacy_mask.is_empty() {
+            return Ok(img.into());
+        }
+        let bounds = Rect::bounding(
+            &self
+                .monitors()?
+                .iter()
+                .map(|m| m.bounds())
+                .collect::<Vec<_>>(),
+        )
+        .ok_or(CaptureError::NoMonitors)?;
+        self.masked(img, bounds)
     }
 
     pub fn capture_monitor(&self, monitor: &Monitor) -> Result<Image> {
@@ -134,7 +159,7 @@ impl Capturer {
     pub fn capture_monitor_with(&self, monitor: &Monitor, opts: CaptureOptions) -> Result<Image> {
         self.backend
             .capture_monitor(monitor.id(), &opts)
-            .map(Image::from)
+            .and_then(|img| self.masked(img, monitor.bounds()))
     }
 
     pub fn capture_window(&self, window: &Window) -> Result<Image> {
@@ -152,7 +177,9 @@ impl Capturer {
     }
 
     pub fn capture_region_with(&self, region: Rect, opts: CaptureOptions) -> Result<Image> {
-        self.backend.capture_region(region, &opts).map(Image::from)
+        self.backend
+            .capture_region(region, &opts)
+            .and_then(|img| self.masked(img, region))
     }
 
     pub fn capture_at(&self, point: Point) -> Result<Image> {
@@ -168,12 +195,34 @@ impl Capturer {
     pub fn cursor_position(&self) -> Result<Point> {
         self.backend.cursor_position()
     }
+
+    /// Apply the privacy mask to a capture of the desktop rectangle `area`.
+    /// Fails closed: when the windows to hide cannot be located the capture
+    /// is dropped, never returned unmasked.
+    fn masked(&self, mut img: RgbaImage, area: Rect) -> Result<Image> {
+        if self.privacy_mask.is_empty() {
+            return Ok(img.into());
+        }
+        if !self.backend.lists_windows() {
+            return Err(CaptureError::PrivacyMask(format!(
+                "the {} backend cannot list windows",
+                self.backend.name()
+            )));
+        }
+        let windows = self
+            .windows()
+            .map_err(|e| CaptureError::PrivacyMask(format!("cannot list windows: {e}")))?;
+        self.privacy_mask.apply(&mut img, area, &windows)?;
+        Ok(img.into())
+    }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct CapturerBuilder {
     backend: BackendKind,
     options: CaptureOptions,
+    privacy_mask: PrivacyMask,
+    vnc: Option<VncOptions>,
 }
 
 impl CapturerBuilder {
@@ -182,6 +231,13 @@ impl CapturerBuilder {
         self
     }
 
+    /// Capture the desktop of a VNC server; selects [`BackendKind::Vnc`].
+    pub fn vnc(mut self, server: VncOptions) -> Self {
+        self.backend = BackendKind::Vnc;
+        self.vnc = Some(server);
+        self
+    }
+
     pub fn show_cursor(mut self, show: bool) -> Self {
         self.options.show_cursor = show;
         self
@@ -192,17 +248,30 @@ impl CapturerBuilder {
         self
     }
 
+    /// Hide matching windows in every desktop, monitor and region capture.
+    pub fn privacy_mask(mut self, mask: PrivacyMask) -> Self {
+        self.privacy_mask = mask;
+        self
+    }
+
     pub fn build(self) -> Result<Capturer> {
-        let backend = select_backend(self.backend)?;
+        if self.backend == BackendKind::Vnc && self.vnc.is_none() {
+            return Err(CaptureError::backend(
+                "vnc",
+                "BackendKind::Vnc needs a server; set one with CapturerBuilder::vnc",
+            ));
+        }
+        let backend = select_backend(self.backend, self.vnc.as_ref())?;
         tracing::info!(backend = backend.name(), "sss_capture: backend selected");
         Ok(Capturer {
             backend,
             default_options: self.options,
+            privacy_mask: self.privacy_mask,
         })
     }
 }
 
-fn select_backend(kind: BackendKind) -> Result<Box<dyn Backend>> {
+fn select_backend(kind: BackendKind, vnc: Option<&VncOptions>) -> Result<Box<dyn Backend>> {
     let mut errors: Vec<String> = Vec::new();
     match kind {
         BackendKind::Auto => auto_select(&mut errors),
@@ -210,8 +279,10 @@ fn select_backend(kind: BackendKind) -> Result<Box<dyn Backend>> {
         BackendKind::Wayland => try_wayland(&mut errors),
         BackendKind::WaylandPortal => try_portal(&mut errors),
         BackendKind::X11 => try_x11(&mut errors),
+        BackendKind::Framebuffer => try_framebuffer(&mut errors),
         BackendKind::WindowsGdi | BackendKind::WindowsDxgi => try_windows(&mut errors),
         BackendKind::MacOS => try_macos(&mut errors),
+        BackendKind::Vnc => try_vnc(vnc.expect("checked in build"), &mut errors),
     }
     .ok_or(CaptureError::NoBackend(errors))
 }
@@ -248,7 +319,8 @@ fn auto_select(errors: &mut Vec<String>) -> Option<Box<dyn Backend>> {
             return Some(b);
         }
     }
-    None
+    // No display server at all: bare TTY, kiosk or embedded board.
+    try_framebuffer(errors)
 }
 
 #[cfg(target_os = "windows")]
@@ -335,6 +407,34 @@ fn try_x11(errors: &mut Vec<String>) -> Option<Box<dyn Backend>> {
     None
 }
 
+#[cfg(target_os = "linux")]
+fn try_framebuffer(errors: &mut Vec<String>) -> Option<Box<dyn Backend>> {
+    match crate::backend::linux::framebuffer::FramebufferBackend::try_new() {
+        Ok(b) => Some(Box::new(b)),
+        Err(e) => {
+            tracing::warn!(backend = "framebuffer", error = %e, "backend unavailable");
+            errors.push(format!("framebuffer: {e}"));
+            None
+        }
+    }
+}
+#[cfg(not(target_os = "linux"))]
+fn try_framebuffer(errors: &mut Vec<String>) -> Option<Box<dyn Backend>> {
+    errors.push("framebuffer: Linux-only".to_string());
+    None
+}
+
+fn try_vnc(server: &VncOptions, errors: &mut Vec<String>) -> Option<Box<dyn Backend>> {
+    match crate::backend::vnc::VncBackend::try_new(&server.addr, server.password.as_deref()) {
+        Ok(b) => Some(Box::new(b)),
+        Err(e) => {
+            tracing::warn!(backend = "vnc", error = %e, "backend unavailable");
+            errors.push(format!("vnc: {e}"));
+            None
+        }
+    }
+}
+
 #[cfg(target_os = "windows")]
 fn try_windows(errors: &mut Vec<String>) -> Option<Box<dyn Backend>> {
     match crate::backend::windows::WindowsBackend::try_new() {
@@ -366,3 +466,124 @@ fn try_macos(errors: &mut Vec<String>) -> Option<Box<dyn Backend>> {
     errors.push("macos: macOS-only".to_string());
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+
+    use super::*;
+    use crate::privacy::WindowMatcher;
+
+    /// One 4×4 white monitor showing `windows`, or no window list at all.
+    struct Fake {
+        windows: Option<Vec<Window>>,
+    }
+
+    impl Backend for Fake {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        fn monitors(&self) -> Result<Vec<Monitor>> {
+            Ok(vec![Monitor {
+                id: MonitorId(0),
+                name: "fake".into(),
+                bounds: Rect::from_xywh(0, 0, 4, 4),
+                physical_size: (4, 4),
+                scale_factor: 1.0,
+                rotation: Default::default(),
+                refresh_rate: None,
+                is_primary: true,
+            }])
+        }
+
+        fn windows(&self) -> Result<Vec<Window>> {
+            Ok(self.windows.clone().unwrap_or_default())
+        }
+
+        fn lists_windows(&self) -> bool {
+            self.windows.is_some()
+        }
+
+        fn capture_monitor(&self, _id: MonitorId, opts: &CaptureOptions) -> Result<RgbaImage> {
+            self.capture_all(opts)
+        }
+
+        fn capture_window(&self, id: WindowId, _opts: &CaptureOptions) -> Result<RgbaImage> {
+            Err(CaptureError::WindowNotFound(id))
+        }
+
+        fn capture_all(&self, _opts: &CaptureOptions) -> Result<RgbaImage> {
+            Ok(RgbaImage::from_pixel(4, 4, Rgba([255; 4])))
+        }
+
+        fn capture_region(&self, region: Rect, _opts: &CaptureOptions) -> Result<RgbaImage> {
+            Ok(RgbaImage::from_pixel(
+                region.width(),
+                region.height(),
+                Rgba([255; 4]),
+            ))
+        }
+
+        fn cursor_position(&self) -> Result<Point> {
+            Err(CaptureError::CursorUnavailable("fake".into()))
+        }
+    }
+
+    fn capturer(windows: Option<Vec<Window>>) -> Capturer {
+        Capturer {
+            backend: Box::new(Fake { windows }),
+            default_options: CaptureOptions::default(),
+            privacy_mask: PrivacyMask::new(vec![WindowMatcher::by_app("pass")], Default::default()),
+        }
+    }
+
+    fn window(bounds: Rect) -> Window {
+        Window {
+            id: WindowId(1),
+            title: "vault".into(),
+            app_name: "pass".into(),
+            bounds,
+            monitor: None,
+            is_minimized: false,
+            is_maximized: false,
+            is_focused: false,
+        }
+    }
+
+    #[test]
+    fn privacy_mask_fails_closed_without_window_geometry() {
+        let withheld = |cap: &Capturer| {
+            let mon = cap.primary_monitor().unwrap();
+            for res in [
+                cap.capture_all(),
+                cap.capture_monitor(&mon),
+                cap.capture_region(Rect::from_xywh(1, 1, 2, 2)),
+            ] {
+                assert!(matches!(res, Err(CaptureError::PrivacyMask(_))));
+            }
+        };
+        // The backend cannot enumerate windows at all.
+        withheld(&capturer(None));
+        // The window is listed, but without bounds.
+        withheld(&capturer(Some(vec![window(Rect::default())])));
+
+        let cap = capturer(Some(vec![window(Rect::from_xywh(0, 0, 2, 4))]));
+        let img = cap.capture_all().unwrap().into_rgba();
+        assert_eq!(img.get_pixel(1, 1).0, [0, 0, 0, 255]);
+        assert_eq!(img.get_pixel(2, 1).0, [255; 4]);
+
+        let mut open = capturer(None);
+        open.set_privacy_mask(PrivacyMask::default());
+        assert!(open.capture_all().is_ok());
+    }
+
+    #[test]
+    fn vnc_backend_without_a_server_fails_to_build() {
+        let err = Capturer::builder()
+            .backend(BackendKind::Vnc)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("CapturerBuilder::vnc"), "{err}");
+    }
+}
@@ -52,6 +52,11 @@ pub enum CaptureError {
     #[error("user cancelled the capture")]
     Cancelled,
 
+    /// A privacy mask is set but the windows it hides cannot be located,
+    /// so the capture is withheld rather than returned unmasked.
+    #[error("privacy mask cannot be applied, capture withheld: {0}")]
+    PrivacyMask(String),
+
     #[error("{backend}: {detail}")]
     Backend {
         backend: &'static str,
@@ -4,6 +4,7 @@ use std::fmt;
 
 /// A 2D point.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub x: i32,
     pub y: i32,
@@ -26,6 +27,7 @@ impl From<(i32, i32)> for Point {
 
 /// A 2D size in pixels.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Size {
     pub width: u32,
     pub height: u32,
@@ -56,6 +58,7 @@ impl From<(u32, u32)> for Size {
 
 /// An axis-aligned rectangle.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rect {
     pub origin: Point,
     pub size: Size,
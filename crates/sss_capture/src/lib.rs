@@ -22,14 +22,17 @@ mod frame;
 mod geometry;
 mod monitor;
 mod options;
+mod privacy;
 mod window;
 
 pub use ::image;
+pub use ::regex;
 
 pub use capturer::{Capturer, CapturerBuilder};
 pub use error::{CaptureError, Result};
 pub use frame::Image;
 pub use geometry::{Area, Point, Rect, Rotation, Size};
 pub use monitor::{Monitor, MonitorId};
-pub use options::{BackendKind, CaptureOptions};
+pub use options::{BackendKind, CaptureOptions, VncOptions};
+pub use privacy::{MaskStyle, PrivacyMask, WindowMatcher};
 pub use window::{Window, WindowId, WindowSearch};
@@ -39,6 +39,21 @@ pub struct Monitor {
 }
 
 impl Monitor {
+    /// A monitor that stands for no real output: 1× scale, no rotation.
+    /// Frontends use it to lay out a surface over an image that did not
+    /// come from the desktop.
+    pub fn detached(name: impl Into<String>, bounds: Rect) -> Self {
+        Self {
+            id: MonitorId(0),
+            name: name.into(),
+            bounds,
+            physical_size: (bounds.width(), bounds.height()),
+            scale_factor: 1.0,
+            rotation: Rotation::default(),
+            refresh_rate: None,
+            is_primary: true,
+        }
+    }
     #[inline]
     pub fn id(&self) -> MonitorId {
         self.id
@@ -1,5 +1,7 @@
 //! Capture-time options.
 
+use std::fmt;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct CaptureOptions {
     /// Composite the mouse cursor into the captured frame when supported.
@@ -37,7 +39,32 @@ pub enum BackendKind {
     Wayland,
     WaylandPortal,
     X11,
+    /// Linux fbdev (`/dev/fb*`) — bare TTYs, kiosks, embedded boards.
+    Framebuffer,
     WindowsGdi,
     WindowsDxgi,
     MacOS,
+    /// Remote framebuffer of a VNC server, exposed as a single monitor.
+    /// The server lives in [`VncOptions`], passed to
+    /// [`crate::CapturerBuilder::vnc`], so this enum stays `Copy`; building
+    /// with `Vnc` but no server fails before any connection is attempted.
+    Vnc,
+}
+
+/// The server [`BackendKind::Vnc`] connects to.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct VncOptions {
+    /// `host[:port]`; ports below 100 are display numbers (`host:1` → 5901).
+    pub addr: String,
+    pub password: Option<String>,
+}
+
+// Hand-written so the VNC password never ends up in logs.
+impl fmt::Debug for VncOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VncOptions")
+            .field("addr", &self.addr)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .finish()
+    }
 }
@@ -0,0 +1,265 @@
+//! Privacy mask: hide listed windows in desktop-wide captures.
+//!
+//! [`crate::Capturer`] applies the mask to `capture_all`, `capture_monitor`
+//! and `capture_region` (and everything built on them). Window captures are
+//! left alone — asking for a window by name is an explicit choice.
+
+use image::{imageops, Rgba, RgbaImage};
+use regex::Regex;
+
+use crate::error::{CaptureError, Result};
+use crate::geometry::Rect;
+use crate::window::Window;
+
+/// Selects the windows a [`PrivacyMask`] hides.
+///
+/// With both fields set a window must satisfy both; an empty matcher never
+/// matches anything.
+#[derive(Clone, Debug, Default)]
+pub struct WindowMatcher {
+    /// Application id (Wayland `app_id`, X11 `WM_CLASS`, macOS owner name),
+    /// compared case-insensitively against [`Window::app_name`].
+    pub app_id: Option<String>,
+    /// Pattern searched for in [`Window::title`].
+    pub title: Option<Regex>,
+}
+
+impl WindowMatcher {
+    pub fn by_app(id: impl Into<String>) -> Self {
+        Self {
+            app_id: Some(id.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn by_title(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            title: Some(Regex::new(pattern)?),
+            ..Default::default()
+        })
+    }
+
+    pub fn matches(&self, w: &Window) -> bool {
+        if let Some(app) = &self.app_id {
+            if !w.app_name.eq_ignore_ascii_case(app) {
+                return false;
+            }
+        }
+        if let Some(re) = &self.title {
+            if !re.is_match(&w.title) {
+                return false;
+            }
+        }
+        self.app_id.is_some() || self.title.is_some()
+    }
+}
+
+/// How a masked window is painted over.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MaskStyle {
+    /// Solid colour. The only style that is guaranteed not to leak anything.
+    Fill(Rgba<u8>),
+    /// Gaussian blur with the given sigma, in image pixels.
+    Blur { sigma: f32 },
+    /// Mosaic of `block`×`block` squares.
+    Pixelate { block: u32 },
+}
+
+impl Default for MaskStyle {
+    fn default() -> Self {
+        Self::Fill(Rgba([0, 0, 0, 255]))
+    }
+}
+
+/// Windows to hide and how to hide them.
+#[derive(Clone, Debug, Default)]
+pub struct PrivacyMask {
+    pub windows: Vec<WindowMatcher>,
+    pub style: MaskStyle,
+}
+
+impl PrivacyMask {
+    pub fn new(windows: Vec<WindowMatcher>, style: MaskStyle) -> Self {
+        Self { windows, style }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Paint over every matching window in `image`, a capture of the
+    /// desktop rectangle `area`. The image may be larger than `area` on
+    /// HiDPI outputs; window bounds are scaled to match.
+    ///
+    /// Fails when a matching window has no geometry: it might be anywhere
+    /// in `image`, so the caller must not hand the image out.
+    pub(crate) fn apply(
+        &self,
+        image: &mut RgbaImage,
+        area: Rect,
+        windows: &[Window],
+    ) -> Result<()> {
+        if area.size.is_empty() {
+            return Ok(());
+        }
+        let sx = image.width() as f64 / area.width() as f64;
+        let sy = image.height() as f64 / area.height() as f64;
+        for w in windows {
+            if w.is_minimized || !self.windows.iter().any(|m| m.matches(w)) {
+                continue;
+            }
+            if w.bounds.size.is_empty() {
+                // wlr foreign-toplevel lists windows without geometry.
+                return Err(CaptureError::PrivacyMask(format!(
+                    "the backend reports no geometry for window {w}"
+                )));
+            }
+            let Some(hit) = w.bounds.intersection(&area) else {
+                continue;
+            };
+            // Round outwards so a fractional scale never leaves a sliver.
+            let x0 = ((hit.x() - area.x()) as f64 * sx).floor() as u32;
+            let y0 = ((hit.y() - area.y()) as f64 * sy).floor() as u32;
+            let x1 = (((hit.right() - area.x()) as f64 * sx).ceil() as u32).min(image.width());
+            let y1 = (((hit.bottom() - area.y()) as f64 * sy).ceil() as u32).min(image.height());
+            if x1 <= x0 || y1 <= y0 {
+                continue;
+            }
+            tracing::debug!(window = %w, "privacy mask: hiding window");
+            self.paint(image, x0, y0, x1 - x0, y1 - y0);
+        }
+        Ok(())
+    }
+
+    fn paint(&self, image: &mut RgbaImage, x: u32, y: u32, w: u32, h: u32) {
+        match self.style {
+            MaskStyle::Fill(color) => {
+                for py in y..y + h {
+                    for px in x..x + w {
+                        image.put_pixel(px, py, color);
+                    }
+                }
+            }
+            MaskStyle::Blur { sigma } => {
+                let crop = imageops::crop_imm(image, x, y, w, h).to_image();
+                imageops::replace(
+                    image,
+                    &imageops::fast_blur(&crop, sigma),
+                    x as i64,
+                    y as i64,
+                );
+            }
+            MaskStyle::Pixelate { block } => {
+                let block = block.max(1);
+                for by in (y..y + h).step_by(block as usize) {
+                    for bx in (x..x + w).step_by(block as usize) {
+                        let bw = block.min(x + w - bx);
+                        let bh = block.min(y + h - by);
+                        let mut sum = [0u64; 4];
+                        for py in by..by + bh {
+                            for px in bx..bx + bw {
+                                for (s, c) in sum.iter_mut().zip(image.get_pixel(px, py).0) {
+                                    *s += c as u64;
+                                }
+                            }
+                        }
+                        let n = (bw * bh) as u64;
+                        let avg = Rgba(sum.map(|s| (s / n) as u8));
+                        for py in by..by + bh {
+                            for px in bx..bx + bw {
+                                image.put_pixel(px, py, avg);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::window::WindowId;
+
+    fn window(app: &str, title: &str, bounds: Rect) -> Window {
+        Window {
+            id: WindowId(1),
+            title: title.to_string(),
+            app_name: app.to_string(),
+            bounds,
+            monitor: None,
+            is_minimized: false,
+            is_maximized: false,
+            is_focused: false,
+        }
+    }
+
+    #[test]
+    fn matcher_requires_every_field() {
+        let w = window("Slack", "general | Acme", Rect::from_xywh(0, 0, 10, 10));
+        assert!(WindowMatcher::by_app("slack").matches(&w));
+        assert!(WindowMatcher::by_title(r"\| Acme$").unwrap().matches(&w));
+        let both = WindowMatcher {
+            title: Some(Regex::new("direct message").unwrap()),
+            ..WindowMatcher::by_app("slack")
+        };
+        assert!(!both.matches(&w));
+        assert!(!WindowMatcher::default().matches(&w));
+    }
+
+    #[test]
+    fn fill_scales_window_bounds_into_image() {
+        let mask = PrivacyMask::new(vec![WindowMatcher::by_app("pass")], MaskStyle::default());
+        // A 2x HiDPI capture of the desktop slice at (100, 100) 20x10.
+        let area = Rect::from_xywh(100, 100, 20, 10);
+        let mut img = RgbaImage::from_pixel(40, 20, Rgba([255, 255, 255, 255]));
+        let windows = [
+            window("pass", "vault", Rect::from_xywh(110, 95, 50, 10)),
+            window("term", "vault", Rect::from_xywh(100, 100, 5, 5)),
+        ];
+        mask.apply(&mut img, area, &windows).unwrap();
+        assert_eq!(img.get_pixel(20, 0).0, [0, 0, 0, 255]);
+        assert_eq!(img.get_pixel(39, 9).0, [0, 0, 0, 255]);
+        assert_eq!(img.get_pixel(19, 0).0, [255; 4]);
+        assert_eq!(img.get_pixel(20, 10).0, [255; 4]);
+        assert_eq!(img.get_pixel(0, 0).0, [255; 4]);
+    }
+
+    #[test]
+    fn pixelate_averages_blocks() {
+        let mask = PrivacyMask::new(
+            vec![WindowMatcher::by_app("chat")],
+            MaskStyle::Pixelate { block: 2 },
+        );
+        let mut img = RgbaImage::from_fn(4, 1, |x, _| {
+            Rgba(if x % 2 == 0 {
+                [0, 0, 0, 255]
+            } else {
+                [200, 100, 50, 255]
+            })
+        });
+        let windows = [window("chat", "", Rect::from_xywh(0, 0, 3, 1))];
+        mask.apply(&mut img, Rect::from_xywh(0, 0, 4, 1), &windows)
+            .unwrap();
+        assert_eq!(img.get_pixel(0, 0).0, [100, 50, 25, 255]);
+        assert_eq!(img.get_pixel(1, 0).0, [100, 50, 25, 255]);
+        assert_eq!(img.get_pixel(2, 0).0, [0, 0, 0, 255]);
+        assert_eq!(img.get_pixel(3, 0).0, [200, 100, 50, 255]);
+    }
+
+    #[test]
+    fn matching_window_without_geometry_withholds_the_capture() {
+        let mask = PrivacyMask::new(vec![WindowMatcher::by_app("pass")], MaskStyle::default());
+        let area = Rect::from_xywh(0, 0, 4, 4);
+        let mut img = RgbaImage::new(4, 4);
+        let other = [window("term", "", Rect::default())];
+        assert!(mask.apply(&mut img, area, &other).is_ok());
+        let hidden = [window("pass", "vault", Rect::default())];
+        assert!(matches!(
+            mask.apply(&mut img, area, &hidden),
+            Err(CaptureError::PrivacyMask(_))
+        ));
+    }
+}
@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 use std::process::ExitCode;
 
+use sss_capture::{Image, Rect};
 use sss_capture_ui::{Outcome, SelectorBuilder, SelectorMode};
 
 fn main() -> ExitCode {
@@ -48,24 +49,28 @@ fn main() -> ExitCode {
         }
     };
 
-    let rect = match &result.outcome {
-        Outcome::Region { rect, .. }
-        | Outcome::Monitor { rect, .. }
-        | Outcome::Window { rect, .. } => *rect,
+    // One `(rect, image)` per selected region; a multi-region selection
+    // prints a line per region.
+    let picks: Vec<(Rect, Option<Image>)> = match result.outcome {
+        Outcome::Region { rect, image }
+        | Outcome::Monitor { rect, image, .. }
+        | Outcome::Window { rect, image, .. } => vec![(rect, image)],
+        Outcome::Regions { regions } => regions,
         Outcome::Cancelled => {
             return ExitCode::from(1);
         }
     };
-    println!("{rect}");
+    for (rect, _) in &picks {
+        println!("{rect}");
+    }
 
     if let Some(path) = save {
-        let image = match &result.outcome {
-            Outcome::Region { image, .. }
-            | Outcome::Monitor { image, .. }
-            | Outcome::Window { image, .. } => image.clone(),
-            Outcome::Cancelled => None,
-        };
-        if let Some(img) = image {
+        // Naming one file per region is up to the host (`sss` numbers them).
+        if picks.len() > 1 {
+            tracing::error!("sss-select: --save takes a single region");
+            return ExitCode::FAILURE;
+        }
+        if let Some((_, Some(img))) = picks.into_iter().next() {
             if let Err(e) = img.save(&path) {
                 tracing::error!("sss-select: saving {}: {e}", path.display());
                 return ExitCode::FAILURE;
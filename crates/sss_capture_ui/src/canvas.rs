@@ -1,10 +1,13 @@
 //! Canvas state — the editable model behind the overlay.
 
+use std::sync::Arc;
+
 use sss_capture::Rect;
 use sss_core::ocr::TextBox;
 
-use crate::geometry::{FPoint, FRect};
-use crate::shape::{Shape, ShapeId, ShapeKind, Style};
+use crate::edges::EdgeLines;
+use crate::geometry::{ratio_side, Align, Distribute, FPoint, FRect, RegionPreset};
+use crate::shape::{ImagePixels, LineStyle, Shape, ShapeId, ShapeKind, Style};
 use crate::tool::{StepSettings, Tool};
 
 #[derive(Clone, Debug)]
@@ -22,22 +25,61 @@ pub enum CanvasEvent {
     Delete,
 }
 
+/// Serializes as its document — the selection region and the committed
+/// shapes. Tool, drag, undo history and OCR state are session-only and
+/// start fresh on deserialization.
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "CanvasDocument", into = "CanvasDocument")
+)]
 pub struct Canvas {
     next_id: u64,
     shapes: Vec<Shape>,
     region: Option<FRect>,
     pub active_tool: Tool,
     drag: Option<Drag>,
-    selected: Option<ShapeId>,
+    /// Selected shapes in the order they were picked; the last one is the
+    /// primary selection.
+    selection: Vec<ShapeId>,
     next_step: u32,
     pending_text: Option<PendingText>,
+    /// Callout receiving typed text; set when one is drawn or clicked
+    /// with the Callout tool.
+    editing_callout: Option<ShapeId>,
     history: History,
     fill_mode: bool,
     fill_color: Option<crate::color::Color>,
+    /// Opacity, dashes, caps / joins and arrowheads given to new strokes.
+    line_style: LineStyle,
     pending_polygon: Option<Vec<FPoint>>,
     /// Hold-Shift constrain: snap lines to 45° and rect/ellipse to square.
     constrain: bool,
+    /// Ratio or size the region keeps while it is drawn and resized.
+    region_preset: Option<RegionPreset>,
+    /// UI edges region drags snap to, while the snap modifier is held.
+    edge_snap: Option<Arc<EdgeLines>>,
+    /// Earlier regions of a multi-region selection, in drawing order; the
+    /// one being edited stays in `region`.
+    extra_regions: Vec<Rect>,
+    /// Shift held: a drag outside the region adds another instead of
+    /// replacing it.
+    adding_region: bool,
+    /// Shift held: a click on a shape adds it to the selection, or takes
+    /// it out again.
+    extend_selection: bool,
+    /// Alt held: a Pointer drag over empty space selects the shapes it
+    /// encloses.
+    band_select: bool,
+    /// Shapes copied with [`Canvas::copy_selection`], kept for the session.
+    clipboard: Vec<Shape>,
+    /// Physical pixels per unit of new measurements: 1, or the monitor's
+    /// scale factor when measuring logical pixels.
+    measure_scale: f32,
+    /// Touch pressure (0–1) of the pointer events that follow;
+    /// `None` for a mouse, whose strokes keep a uniform width.
+    pressure: Option<f32>,
     /// OCR detections in the captured image's pixel coordinate space.
     /// Empty when OCR is disabled or still downloading models.
     text_boxes: Vec<TextBox>,
@@ -55,14 +97,25 @@ impl Default for Canvas {
             region: None,
             active_tool: Tool::Pointer,
             drag: None,
-            selected: None,
+            selection: Vec::new(),
             next_step: 1,
             pending_text: None,
+            editing_callout: None,
             history: History::default(),
             fill_mode: false,
             fill_color: None,
+            line_style: LineStyle::default(),
             pending_polygon: None,
             constrain: false,
+            region_preset: None,
+            edge_snap: None,
+            extra_regions: Vec::new(),
+            adding_region: false,
+            extend_selection: false,
+            band_select: false,
+            clipboard: Vec::new(),
+            measure_scale: 1.0,
+            pressure: None,
             text_boxes: Vec::new(),
             selected_text_boxes: Vec::new(),
         };
@@ -72,6 +125,49 @@ impl Default for Canvas {
     }
 }
 
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CanvasDocument {
+    #[serde(default)]
+    region: Option<Rect>,
+    #[serde(default)]
+    shapes: Vec<Shape>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Canvas> for CanvasDocument {
+    fn from(c: Canvas) -> Self {
+        Self {
+            region: c.region(),
+            shapes: c.shapes,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<CanvasDocument> for Canvas {
+    fn from(doc: CanvasDocument) -> Self {
+        let mut c = Canvas::default();
+        c.set_region(doc.region);
+        c.next_id = doc.shapes.iter().map(|s| s.id.0 + 1).max().unwrap_or(1);
+        c.next_step = doc
+            .shapes
+            .iter()
+            .filter_map(|s| match s.kind {
+                ShapeKind::Step { number, .. } => Some(number + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(1);
+        c.shapes = doc.shapes;
+        // The loaded shapes are the undo baseline, not an edit on top of
+        // an empty canvas.
+        c.history = History::default();
+        c.history.snapshot(&c.shapes);
+        c
+    }
+}
+
 impl Canvas {
     pub fn fill_mode(&self) -> bool {
         self.fill_mode
@@ -91,6 +187,14 @@ impl Canvas {
         self.fill_color
     }
 
+    pub fn set_line_style(&mut self, line: LineStyle) {
+        self.line_style = line;
+    }
+
+    pub fn line_style(&self) -> LineStyle {
+        self.line_style
+    }
+
     /// OCR detections that should be drawn / hit-tested. Populated
     /// asynchronously by the OCR worker once the recogniser finishes.
     pub fn text_boxes(&self) -> &[TextBox] {
@@ -183,6 +287,158 @@ impl Canvas {
         self.region = r.map(FRect::from);
     }
 
+    /// Every region of the selection in drawing order, the active one
+    /// last; slivers under 2 px are left out.
+    pub fn regions(&self) -> Vec<Rect> {
+        self.extra_regions
+            .iter()
+            .copied()
+            .chain(self.region())
+            .filter(|r| r.width() >= 2 && r.height() >= 2)
+            .collect()
+    }
+
+    /// Regions set aside by [`Canvas::set_adding_region`] drags.
+    pub fn extra_regions(&self) -> &[Rect] {
+        &self.extra_regions
+    }
+
+    /// While on, a Pointer drag outside the region keeps it and starts
+    /// another; otherwise such a drag starts over with a single region.
+    pub fn set_adding_region(&mut self, on: bool) {
+        self.adding_region = on;
+    }
+
+    /// Start a fresh region drag at `p`, keeping the current region when
+    /// adding one.
+    fn begin_region(&mut self, p: FPoint) {
+        if !self.adding_region {
+            self.extra_regions.clear();
+        } else if let Some(r) = self.region().filter(|r| r.width() >= 2 && r.height() >= 2) {
+            self.extra_regions.push(r);
+        }
+        self.region = None;
+        self.drag = Some(Drag::Region { from: p, to: p });
+    }
+
+    pub fn region_preset(&self) -> Option<RegionPreset> {
+        self.region_preset
+    }
+
+    /// Hold region drags to `preset` (`None` frees them) and reshape the
+    /// current region to match.
+    pub fn set_region_preset(&mut self, preset: Option<RegionPreset>) {
+        self.region_preset = preset;
+        if let (Some(preset), Some(r)) = (preset, self.region()) {
+            self.set_region(Some(preset.conform(r)));
+        }
+    }
+
+    /// Region spanned by a drag from `from` to `to`, held to the preset.
+    fn dragged_region(&self, from: FPoint, to: FPoint) -> FRect {
+        match self.region_preset {
+            Some(preset) => preset.drag(from, to),
+            None => FRect::from_corners(self.snap_to_edges(from), self.snap_to_edges(to)),
+        }
+    }
+
+    /// Physical pixels per unit for measurements drawn from now on; see
+    /// [`ShapeKind::Measure`].
+    pub fn set_measure_scale(&mut self, scale: f32) {
+        self.measure_scale = if scale > 0.0 { scale } else { 1.0 };
+    }
+
+    pub fn measure_scale(&self) -> f32 {
+        self.measure_scale
+    }
+
+    /// Pressure (0–1) of the pointer events that follow, for brush
+    /// strokes that thin out under a light touch; `None` when the device
+    /// reports none.
+    pub fn set_pressure(&mut self, pressure: Option<f32>) {
+        self.pressure = pressure;
+    }
+
+    /// Snap region drags to `edges` (`None` turns it off). Presets win
+    /// over snapping when drawing or resizing; moves always snap.
+    pub fn set_edge_snap(&mut self, edges: Option<Arc<EdgeLines>>) {
+        self.edge_snap = edges;
+    }
+
+    /// `p` pulled onto the nearest vertical / horizontal UI edge.
+    fn snap_to_edges(&self, p: FPoint) -> FPoint {
+        let Some(edges) = &self.edge_snap else {
+            return p;
+        };
+        FPoint::new(
+            edges.snap_x(p.x).map_or(p.x, |x| x as f32),
+            edges.snap_y(p.y).map_or(p.y, |y| y as f32),
+        )
+    }
+
+    /// `r` shifted so whichever of its edges is closest to a UI edge lands
+    /// on it, per axis.
+    fn snap_moved_region(&self, r: Rect) -> Rect {
+        let Some(edges) = &self.edge_snap else {
+            return r;
+        };
+        let shift = |a: i32, b: i32, snap: &dyn Fn(f32) -> Option<i32>| {
+            [a, b]
+                .into_iter()
+                .filter_map(|v| snap(v as f32).map(|e| e - v))
+                .min_by_key(|d| d.abs())
+                .unwrap_or(0)
+        };
+        let dx = shift(r.x(), r.right(), &|x| edges.snap_x(x));
+        let dy = shift(r.y(), r.bottom(), &|y| edges.snap_y(y));
+        Rect::from_xywh(r.x() + dx, r.y() + dy, r.width(), r.height())
+    }
+
+    /// Region edges lying on a UI edge while a snapping region drag is in
+    /// progress, as `(xs, ys)`, for drawing guide lines.
+    pub fn edge_guides(&self) -> (Vec<i32>, Vec<i32>) {
+        let dragging_region = matches!(
+            self.drag,
+            Some(Drag::Region { .. } | Drag::RegionMove { .. } | Drag::RegionResize { .. })
+        );
+        let (Some(edges), Some(r), true) = (&self.edge_snap, self.region(), dragging_region) else {
+            return (Vec::new(), Vec::new());
+        };
+        let on = |lines: &[i32], v: i32| lines.binary_search(&v).is_ok();
+        (
+            [r.x(), r.right()]
+                .into_iter()
+                .filter(|&x| on(&edges.xs, x))
+                .collect(),
+            [r.y(), r.bottom()]
+                .into_iter()
+                .filter(|&y| on(&edges.ys, y))
+                .collect(),
+        )
+    }
+
+    /// Move the region and every shape by `(dx, dy)`, e.g. to rebase a
+    /// desktop-space canvas onto the pixels of its cropped region. Undo
+    /// history is rebased too, so this is not itself an undo step.
+    pub fn translate(&mut self, dx: i32, dy: i32) {
+        let History { undo, redo } = &mut self.history;
+        for shape in self
+            .shapes
+            .iter_mut()
+            .chain(undo.iter_mut().chain(redo.iter_mut()).flatten())
+        {
+            translate_shape(shape, dx, dy);
+        }
+        if let Some(r) = self.region() {
+            self.set_region(Some(Rect::from_xywh(
+                r.x() + dx,
+                r.y() + dy,
+                r.width(),
+                r.height(),
+            )));
+        }
+    }
+
     pub fn is_drag_active(&self) -> bool {
         self.drag.is_some()
     }
@@ -195,13 +451,36 @@ impl Canvas {
             Drag::Region { from, .. } => Some(*from),
             Drag::RegionMove { start, .. } => Some(*start),
             Drag::Move { start, .. } => Some(*start),
-            Drag::Stroke { points } => points.first().copied(),
-            Drag::RegionResize { .. } | Drag::Erase { .. } => None,
+            Drag::Band { from, .. } => Some(*from),
+            Drag::Stroke { points, .. } => points.first().copied(),
+            Drag::RegionResize { .. } | Drag::Erase { .. } | Drag::Bend { .. } => None,
         }
     }
 
     pub fn is_typing_text(&self) -> bool {
-        self.pending_text.is_some()
+        self.pending_text.is_some() || self.editing_callout.is_some()
+    }
+
+    /// Callout currently receiving typed text.
+    pub fn editing_callout(&self) -> Option<ShapeId> {
+        self.editing_callout
+    }
+
+    /// Edit the Text / Callout tool's text style; text being typed follows
+    /// along.
+    pub fn update_text_style(&mut self, f: impl Fn(&mut crate::shape::TextStyle)) {
+        match &mut self.active_tool {
+            Tool::Text(style) | Tool::Callout { text: style, .. } => f(style),
+            _ => {}
+        }
+        if let Some(pt) = self.pending_text.as_mut() {
+            f(&mut pt.style);
+        }
+        if let Some(shape) = self.editing_callout_mut() {
+            if let ShapeKind::Callout { text_style, .. } = &mut shape.kind {
+                f(text_style);
+            }
+        }
     }
 
     pub fn set_tool(&mut self, t: Tool) {
@@ -210,18 +489,235 @@ impl Canvas {
         if !matches!(t, Tool::Text(_)) {
             self.commit_pending_text();
         }
+        self.finish_callout();
         if !matches!(t, Tool::Polygon(_)) && self.is_drawing_polygon() {
             self.commit_polygon();
         }
         self.active_tool = t;
     }
 
+    /// The shape picked last; the arrow bend handle and single-shape
+    /// edits act on it.
     pub fn selected(&self) -> Option<ShapeId> {
-        self.selected
+        self.selection.last().copied()
+    }
+
+    /// Every selected shape, in the order they were picked.
+    pub fn selection(&self) -> &[ShapeId] {
+        &self.selection
     }
 
+    /// Select `id` together with the rest of its group, or nothing.
     pub fn select(&mut self, id: Option<ShapeId>) {
-        self.selected = id;
+        self.selection = id.map(|id| self.group_of(id)).unwrap_or_default();
+    }
+
+    /// Select every shape.
+    pub fn select_all(&mut self) {
+        self.selection = self.shapes.iter().map(|s| s.id).collect();
+    }
+
+    pub fn set_extend_selection(&mut self, on: bool) {
+        self.extend_selection = on;
+    }
+
+    pub fn set_band_select(&mut self, on: bool) {
+        self.band_select = on;
+    }
+
+    /// Rubber band being dragged out to select shapes.
+    pub fn selection_band(&self) -> Option<FRect> {
+        match self.drag.as_ref()? {
+            Drag::Band { from, to } => Some(FRect::from_corners(*from, *to)),
+            _ => None,
+        }
+    }
+
+    /// Joint bounds of the selected shapes.
+    pub fn selection_bounds(&self) -> Option<Rect> {
+        let bounds: Vec<Rect> = self
+            .shapes
+            .iter()
+            .filter(|s| self.selection.contains(&s.id))
+            .map(Shape::bounds)
+            .collect();
+        Rect::bounding(&bounds)
+    }
+
+    /// How many independent parts the selection has; a group counts once.
+    pub fn selection_parts(&self) -> usize {
+        self.selected_parts().len()
+    }
+
+    /// Is the selection exactly one group?
+    pub fn selection_is_group(&self) -> bool {
+        let mut groups = self
+            .shapes
+            .iter()
+            .filter(|s| self.selection.contains(&s.id))
+            .map(|s| s.group);
+        match groups.next() {
+            Some(Some(first)) => groups.all(|g| g == Some(first)),
+            _ => false,
+        }
+    }
+
+    /// Group the selected shapes, or break the group up when the selection
+    /// is exactly one group.
+    pub fn toggle_group(&mut self) {
+        let group = if self.selection_is_group() {
+            None
+        } else if self.selected_parts().len() > 1 {
+            self.selection.first().copied()
+        } else {
+            return;
+        };
+        for shape in self.shapes.iter_mut() {
+            if self.selection.contains(&shape.id) {
+                shape.group = group;
+            }
+        }
+        self.history.snapshot(&self.shapes);
+    }
+
+    /// Line the selected parts up on an edge or centre line of their
+    /// joint bounds.
+    pub fn align_selection(&mut self, align: Align) {
+        let parts = self.selected_parts();
+        let bounds: Vec<Rect> = parts.iter().map(|p| self.part_bounds(p)).collect();
+        self.move_parts(&parts, &crate::geometry::align_offsets(&bounds, align));
+    }
+
+    /// Space the selected parts evenly between the outermost two.
+    pub fn distribute_selection(&mut self, axis: Distribute) {
+        let parts = self.selected_parts();
+        let bounds: Vec<Rect> = parts.iter().map(|p| self.part_bounds(p)).collect();
+        self.move_parts(&parts, &crate::geometry::distribute_offsets(&bounds, axis));
+    }
+
+    /// Put copies of the selected shapes on top, offset a little, and
+    /// select them.
+    pub fn duplicate_selection(&mut self) {
+        let copies = self.selected_shapes();
+        self.insert_copies(copies, PASTE_OFFSET);
+    }
+
+    /// Keep copies of the selected shapes for [`Self::paste_shapes`].
+    /// Returns `false` when nothing is selected.
+    pub fn copy_selection(&mut self) -> bool {
+        let copied = self.selected_shapes();
+        if copied.is_empty() {
+            return false;
+        }
+        self.clipboard = copied;
+        true
+    }
+
+    /// Add the copied shapes again, each paste a little further along, and
+    /// select them. Returns `false` when nothing was copied.
+    pub fn paste_shapes(&mut self) -> bool {
+        if self.clipboard.is_empty() {
+            return false;
+        }
+        for shape in &mut self.clipboard {
+            translate_shape(shape, PASTE_OFFSET, PASTE_OFFSET);
+        }
+        self.set_tool(Tool::Pointer);
+        self.insert_copies(self.clipboard.clone(), 0);
+        true
+    }
+
+    /// `id` and the other members of its group, in z-order with `id` last.
+    fn group_of(&self, id: ShapeId) -> Vec<ShapeId> {
+        let group = self
+            .shapes
+            .iter()
+            .find(|s| s.id == id)
+            .and_then(|s| s.group);
+        let mut ids: Vec<ShapeId> = self
+            .shapes
+            .iter()
+            .filter(|s| s.id != id && group.is_some() && s.group == group)
+            .map(|s| s.id)
+            .collect();
+        ids.push(id);
+        ids
+    }
+
+    /// Clones of the selected shapes in z-order.
+    fn selected_shapes(&self) -> Vec<Shape> {
+        self.shapes
+            .iter()
+            .filter(|s| self.selection.contains(&s.id))
+            .cloned()
+            .collect()
+    }
+
+    /// Indices of the selected shapes, bundled per group; ungrouped shapes
+    /// stand alone.
+    fn selected_parts(&self) -> Vec<Vec<usize>> {
+        let mut parts: Vec<(Option<ShapeId>, Vec<usize>)> = Vec::new();
+        for (i, shape) in self.shapes.iter().enumerate() {
+            if !self.selection.contains(&shape.id) {
+                continue;
+            }
+            match parts
+                .iter_mut()
+                .find(|(g, _)| shape.group.is_some() && *g == shape.group)
+            {
+                Some((_, part)) => part.push(i),
+                None => parts.push((shape.group, vec![i])),
+            }
+        }
+        parts.into_iter().map(|(_, part)| part).collect()
+    }
+
+    fn part_bounds(&self, part: &[usize]) -> Rect {
+        let bounds: Vec<Rect> = part.iter().map(|&i| self.shapes[i].bounds()).collect();
+        Rect::bounding(&bounds).unwrap_or_default()
+    }
+
+    fn move_parts(&mut self, parts: &[Vec<usize>], offsets: &[(i32, i32)]) {
+        let mut moved = false;
+        for (part, &(dx, dy)) in parts.iter().zip(offsets) {
+            if (dx, dy) == (0, 0) {
+                continue;
+            }
+            for &i in part {
+                translate_shape(&mut self.shapes[i], dx, dy);
+            }
+            moved = true;
+        }
+        if moved {
+            self.history.snapshot(&self.shapes);
+        }
+    }
+
+    /// Add `shapes` under fresh ids, moved by `offset` on both axes, keeping
+    /// their groups apart from the originals, and select them.
+    fn insert_copies(&mut self, shapes: Vec<Shape>, offset: i32) {
+        if shapes.is_empty() {
+            return;
+        }
+        let mut groups: Vec<(ShapeId, ShapeId)> = Vec::new();
+        self.selection.clear();
+        for mut shape in shapes {
+            shape.id = self.alloc_id();
+            if let Some(old) = shape.group {
+                let new = match groups.iter().find(|(g, _)| *g == old) {
+                    Some(&(_, new)) => new,
+                    None => {
+                        groups.push((old, shape.id));
+                        shape.id
+                    }
+                };
+                shape.group = Some(new);
+            }
+            translate_shape(&mut shape, offset, offset);
+            self.selection.push(shape.id);
+            self.shapes.push(shape);
+        }
+        self.history.snapshot(&self.shapes);
     }
 
     /// Render-time view of the in-flight drag as a shape.
@@ -231,7 +727,11 @@ impl Canvas {
         let id = ShapeId(0);
         let style = current_style_for_canvas(self);
         let kind = match (&self.active_tool, drag) {
-            (Tool::Brush(_), Drag::Stroke { points, .. }) => ShapeKind::FreehandStroke {
+            (Tool::Brush(_), Drag::Stroke { points, widths }) => ShapeKind::FreehandStroke {
+                points: points.clone(),
+                widths: widths.clone(),
+            },
+            (Tool::Highlighter(_), Drag::Stroke { points, .. }) => ShapeKind::Highlight {
                 points: points.clone(),
             },
             (Tool::Line(_), Drag::TwoPoint { from, to, .. }) => ShapeKind::Line {
@@ -241,6 +741,7 @@ impl Canvas {
             (Tool::Arrow(_), Drag::TwoPoint { from, to, .. }) => ShapeKind::Arrow {
                 from: *from,
                 to: *to,
+                bend: None,
             },
             (Tool::Rectangle(_), Drag::TwoPoint { from, to, .. }) => ShapeKind::Rectangle {
                 rect: FRect::from_corners(*from, *to).to_int(),
@@ -252,6 +753,40 @@ impl Canvas {
                 rect: FRect::from_corners(*from, *to).to_int(),
                 radius: *radius,
             },
+            (Tool::Pixelate { block_size }, Drag::TwoPoint { from, to, .. }) => {
+                ShapeKind::Pixelate {
+                    rect: FRect::from_corners(*from, *to).to_int(),
+                    block_size: *block_size,
+                }
+            }
+            (
+                Tool::Spotlight {
+                    shape,
+                    amount,
+                    desaturate,
+                },
+                Drag::TwoPoint { from, to, .. },
+            ) => ShapeKind::Spotlight {
+                rect: FRect::from_corners(*from, *to).to_int(),
+                shape: *shape,
+                amount: *amount,
+                desaturate: *desaturate,
+            },
+            (Tool::Magnify { zoom, .. }, Drag::TwoPoint { from, to, .. }) => {
+                self.magnify_kind(FRect::from_corners(*from, *to).to_int(), *zoom)
+            }
+            (Tool::Callout { text, .. }, Drag::TwoPoint { from, to, .. }) => {
+                callout_kind(*from, *to, text)
+            }
+            (Tool::Measure { boxed, bake, .. }, Drag::TwoPoint { from, to, .. }) => {
+                ShapeKind::Measure {
+                    from: *from,
+                    to: *to,
+                    boxed: *boxed,
+                    scale: self.measure_scale,
+                    baked: *bake,
+                }
+            }
             _ => return None,
         };
         Some(Shape {
@@ -259,6 +794,7 @@ impl Canvas {
             kind,
             style,
             rotation: 0.0,
+            group: None,
         })
     }
 
@@ -275,8 +811,10 @@ impl Canvas {
                 stroke: p.style.color,
                 stroke_width: 1.0,
                 fill: None,
+                ..Style::default()
             },
             rotation: 0.0,
+            group: None,
         })
     }
 
@@ -288,9 +826,13 @@ impl Canvas {
             CanvasEvent::PointerCancel => self.cancel_drag(),
             CanvasEvent::TextInput(c) => self.on_text_char(c),
             CanvasEvent::TextBackspace => self.on_text_backspace(),
-            CanvasEvent::TextCommit => self.commit_pending_text(),
+            CanvasEvent::TextCommit => {
+                self.commit_pending_text();
+                self.finish_callout();
+            }
             CanvasEvent::TextCancel => {
                 self.pending_text = None;
+                self.finish_callout();
             }
             CanvasEvent::Undo => self.undo(),
             CanvasEvent::Redo => self.redo(),
@@ -299,33 +841,48 @@ impl Canvas {
     }
 
     fn on_down(&mut self, p: FPoint) {
+        self.finish_callout();
         match &self.active_tool {
             Tool::Pointer => {
+                // The selected arrow's middle handle bends it.
+                let bend_handle = self.selection.len() == 1
+                    && self.shapes.iter().any(|s| {
+                        Some(s.id) == self.selected()
+                            && matches!(s.kind, ShapeKind::Arrow { from, to, bend }
+                                if crate::shape::arrow_mid(from, to, bend).distance(p) <= BEND_GRAB)
+                    });
+                if let (true, Some(id)) = (bend_handle, self.selected()) {
+                    self.drag = Some(Drag::Bend { id });
+                    return;
+                }
                 // Top-to-bottom: last shape wins (it's on top in z-order).
-                self.selected = self
+                let hit = self
                     .shapes
                     .iter()
                     .rev()
                     .find(|s| s.contains(p))
                     .map(|s| s.id);
-                if let Some(sel) = self.selected {
-                    let original = self
-                        .shapes
-                        .iter()
-                        .find(|s| s.id == sel)
-                        .cloned()
-                        .unwrap_or_else(|| Shape {
-                            id: sel,
-                            kind: ShapeKind::FreehandStroke { points: vec![] },
-                            style: Style::default(),
-                            rotation: 0.0,
-                        });
+                if let Some(id) = hit {
+                    let members = self.group_of(id);
+                    if self.extend_selection && self.selection.contains(&id) {
+                        self.selection.retain(|s| !members.contains(s));
+                        return;
+                    } else if self.extend_selection {
+                        self.selection.extend(members);
+                    } else if !self.selection.contains(&id) {
+                        self.selection = members;
+                    }
                     self.drag = Some(Drag::Move {
-                        id: sel,
                         start: p,
-                        original_shape: Box::new(original),
+                        originals: self.selected_shapes(),
                     });
+                } else if self.band_select {
+                    if !self.extend_selection {
+                        self.selection.clear();
+                    }
+                    self.drag = Some(Drag::Band { from: p, to: p });
                 } else if let Some(region) = self.region.map(FRect::to_int) {
+                    self.selection.clear();
                     let handle = pointer_handle(&region, p);
                     match handle {
                         Some(h) => {
@@ -340,24 +897,46 @@ impl Canvas {
                                 original: region,
                             });
                         }
-                        None => {
-                            self.region = None;
-                            self.drag = Some(Drag::Region { from: p, to: p });
-                        }
+                        None => self.begin_region(p),
                     }
                 } else {
-                    self.drag = Some(Drag::Region { from: p, to: p });
+                    self.selection.clear();
+                    self.begin_region(p);
                 }
             }
-            Tool::Brush(_) => {
-                self.drag = Some(Drag::Stroke { points: vec![p] });
+            Tool::Brush(_) | Tool::Highlighter(_) => {
+                // Only the brush thins out; the highlighter is a marker.
+                let widths = match (&self.active_tool, self.pressure) {
+                    (Tool::Brush(_), Some(pressure)) => {
+                        vec![crate::shape::pressure_width(pressure)]
+                    }
+                    _ => Vec::new(),
+                };
+                self.drag = Some(Drag::Stroke {
+                    points: vec![p],
+                    widths,
+                });
             }
             Tool::Line(_) | Tool::Arrow(_) | Tool::Rectangle(_) | Tool::Ellipse(_) => {
                 self.drag = Some(Drag::TwoPoint { from: p, to: p });
             }
-            Tool::BlurRect { .. } => {
+            Tool::BlurRect { .. }
+            | Tool::Pixelate { .. }
+            | Tool::Spotlight { .. }
+            | Tool::Magnify { .. }
+            | Tool::Measure { .. } => {
                 self.drag = Some(Drag::TwoPoint { from: p, to: p });
             }
+            Tool::Callout { .. } => {
+                // Clicking into an existing bubble resumes typing there.
+                let hit = self.shapes.iter().rev().find(|s| {
+                    matches!(&s.kind, ShapeKind::Callout { body, .. } if region_contains(body, p))
+                });
+                match hit {
+                    Some(shape) => self.editing_callout = Some(shape.id),
+                    None => self.drag = Some(Drag::TwoPoint { from: p, to: p }),
+                }
+            }
             Tool::Eraser { radius } => {
                 let r = *radius;
                 self.erase_at(p, r);
@@ -403,6 +982,7 @@ impl Canvas {
             },
             style,
             rotation: 0.0,
+            group: None,
         });
     }
 
@@ -425,8 +1005,9 @@ impl Canvas {
     /// Wipe every committed shape; the region rectangle is preserved.
     pub fn clear_shapes(&mut self) {
         self.shapes.clear();
-        self.selected = None;
+        self.selection.clear();
         self.pending_text = None;
+        self.editing_callout = None;
         self.pending_polygon = None;
         self.history.snapshot(&self.shapes);
     }
@@ -435,82 +1016,161 @@ impl Canvas {
         self.constrain = on;
     }
 
+    /// Move the selected shapes one step up the z-order, past the
+    /// shape above them.
     pub fn raise_selected(&mut self) {
-        let Some(id) = self.selected else {
-            return;
-        };
-        let Some(idx) = self.shapes.iter().position(|s| s.id == id) else {
-            return;
-        };
-        if idx + 1 < self.shapes.len() {
-            self.shapes.swap(idx, idx + 1);
+        let mut moved = false;
+        for i in (0..self.shapes.len().saturating_sub(1)).rev() {
+            if self.is_selected(i) && !self.is_selected(i + 1) {
+                self.shapes.swap(i, i + 1);
+                moved = true;
+            }
+        }
+        if moved {
             self.history.snapshot(&self.shapes);
         }
     }
 
+    /// Move the selected shapes one step down the z-order, past the
+    /// shape below them.
     pub fn lower_selected(&mut self) {
-        let Some(id) = self.selected else {
-            return;
-        };
-        let Some(idx) = self.shapes.iter().position(|s| s.id == id) else {
-            return;
-        };
-        if idx > 0 {
-            self.shapes.swap(idx, idx - 1);
+        let mut moved = false;
+        for i in 1..self.shapes.len() {
+            if self.is_selected(i) && !self.is_selected(i - 1) {
+                self.shapes.swap(i, i - 1);
+                moved = true;
+            }
+        }
+        if moved {
             self.history.snapshot(&self.shapes);
         }
     }
 
     pub fn raise_to_top(&mut self) {
-        let Some(id) = self.selected else {
+        if self.selection.is_empty() {
             return;
-        };
-        if let Some(idx) = self.shapes.iter().position(|s| s.id == id) {
-            let shape = self.shapes.remove(idx);
-            self.shapes.push(shape);
-            self.history.snapshot(&self.shapes);
         }
+        let (selected, rest): (Vec<Shape>, Vec<Shape>) = std::mem::take(&mut self.shapes)
+            .into_iter()
+            .partition(|s| self.selection.contains(&s.id));
+        self.shapes = rest.into_iter().chain(selected).collect();
+        self.history.snapshot(&self.shapes);
     }
 
-    /// Uniformly scale the selected shape about its bounds centre.
+    /// Uniformly scale the selected shapes about the centre of their
+    /// joint bounds.
     pub fn scale_selected(&mut self, factor: f32) {
         if !factor.is_finite() || (factor - 1.0).abs() < f32::EPSILON {
             return;
         }
-        let id = match self.selected {
-            Some(id) => id,
-            None => return,
-        };
-        let Some(idx) = self.shapes.iter().position(|s| s.id == id) else {
+        let Some(bounds) = self.selection_bounds() else {
             return;
         };
-        let shape = &mut self.shapes[idx];
-        let bounds = shape.bounds();
         let cx = bounds.x() as f32 + bounds.width() as f32 / 2.0;
         let cy = bounds.y() as f32 + bounds.height() as f32 / 2.0;
-        scale_shape(shape, cx, cy, factor);
+        for shape in self.shapes.iter_mut() {
+            if self.selection.contains(&shape.id) {
+                scale_shape(shape, cx, cy, factor);
+            }
+        }
         self.history.snapshot(&self.shapes);
     }
 
-    /// Rotate the selected shape by `radians` about its bounds centre.
+    /// Rotate the selected shapes by `radians` about the centre of their
+    /// joint bounds.
     pub fn rotate_selected(&mut self, radians: f32) {
         if !radians.is_finite() || radians.abs() < 1e-4 {
             return;
         }
-        let Some(id) = self.selected else {
+        let Some(bounds) = self.selection_bounds() else {
             return;
         };
-        let Some(idx) = self.shapes.iter().position(|s| s.id == id) else {
-            return;
-        };
-        let shape = &mut self.shapes[idx];
-        let bounds = shape.bounds();
         let cx = bounds.x() as f32 + bounds.width() as f32 / 2.0;
         let cy = bounds.y() as f32 + bounds.height() as f32 / 2.0;
-        rotate_shape(shape, cx, cy, radians);
+        for shape in self.shapes.iter_mut() {
+            if self.selection.contains(&shape.id) {
+                rotate_shape(shape, cx, cy, radians);
+            }
+        }
         self.history.snapshot(&self.shapes);
     }
 
+    /// Move the selected shapes by `(dx, dy)`, or the region when no
+    /// shape is selected.
+    pub fn nudge_selection(&mut self, dx: i32, dy: i32) {
+        if !self.selection.is_empty() {
+            for shape in self.shapes.iter_mut() {
+                if self.selection.contains(&shape.id) {
+                    translate_shape(shape, dx, dy);
+                }
+            }
+            self.history.snapshot(&self.shapes);
+        } else if let Some(r) = self.region() {
+            self.set_region(Some(Rect::from_xywh(
+                r.x() + dx,
+                r.y() + dy,
+                r.width(),
+                r.height(),
+            )));
+        }
+    }
+
+    /// Grow the region's right / bottom edge by positive `dw` / `dh` and
+    /// pull it in by negative ones; the top-left corner stays put and the
+    /// region keeps at least one pixel across. A ratio preset carries the
+    /// other side along; a fixed size can't be resized.
+    pub fn resize_region(&mut self, dw: i32, dh: i32) {
+        let Some(r) = self.region() else {
+            return;
+        };
+        let w = (r.width() as i32 + dw).max(1) as u32;
+        let h = (r.height() as i32 + dh).max(1) as u32;
+        let (w, h) = match self.region_preset {
+            Some(RegionPreset::Size(..)) => return,
+            Some(RegionPreset::Ratio(rw, rh)) if dw == 0 => (ratio_side(h, rw, rh), h),
+            Some(RegionPreset::Ratio(rw, rh)) => (w, ratio_side(w, rh, rw)),
+            None => (w, h),
+        };
+        self.set_region(Some(Rect::from_xywh(r.x(), r.y(), w, h)));
+    }
+
+    /// Add `pixels` as an image shape centred on `center`, shrunk to fit
+    /// `max_size` if larger, and select it with the pointer tool so it can
+    /// be moved straight away. Returns the new shape's id.
+    pub fn insert_image(
+        &mut self,
+        pixels: impl Into<ImagePixels>,
+        center: FPoint,
+        max_size: (u32, u32),
+    ) -> ShapeId {
+        let pixels = pixels.into();
+        let (w, h) = pixels.image().dimensions();
+        let fit = (max_size.0 as f32 / w.max(1) as f32)
+            .min(max_size.1 as f32 / h.max(1) as f32)
+            .min(1.0);
+        let (w, h) = (
+            (w as f32 * fit).round().max(1.0),
+            (h as f32 * fit).round().max(1.0),
+        );
+        let rect = Rect::from_xywh(
+            (center.x - w / 2.0).round() as i32,
+            (center.y - h / 2.0).round() as i32,
+            w as u32,
+            h as u32,
+        );
+        self.set_tool(Tool::Pointer);
+        let id = self.alloc_id();
+        self.push_shape(Shape {
+            id,
+            kind: ShapeKind::Image { rect, pixels },
+            style: Style::default(),
+            rotation: 0.0,
+            group: None,
+        });
+        self.selection = vec![id];
+        id
+    }
+
     /// Overwrite the shape with the given id. Does NOT snapshot history.
     pub fn replace_shape(&mut self, id: ShapeId, new_shape: Shape) {
         if let Some(s) = self.shapes.iter_mut().find(|s| s.id == id) {
@@ -523,19 +1183,28 @@ impl Canvas {
     }
 
     pub fn lower_to_bottom(&mut self) {
-        let Some(id) = self.selected else {
+        if self.selection.is_empty() {
             return;
-        };
-        if let Some(idx) = self.shapes.iter().position(|s| s.id == id) {
-            let shape = self.shapes.remove(idx);
-            self.shapes.insert(0, shape);
-            self.history.snapshot(&self.shapes);
         }
+        let (selected, rest): (Vec<Shape>, Vec<Shape>) = std::mem::take(&mut self.shapes)
+            .into_iter()
+            .partition(|s| self.selection.contains(&s.id));
+        self.shapes = selected.into_iter().chain(rest).collect();
+        self.history.snapshot(&self.shapes);
+    }
+
+    fn is_selected(&self, idx: usize) -> bool {
+        self.selection.contains(&self.shapes[idx].id)
     }
 
     fn on_move(&mut self, p: FPoint) {
         match self.drag.as_mut() {
-            Some(Drag::Stroke { points }) => {
+            Some(Drag::Stroke { points, .. })
+                if self.constrain && matches!(self.active_tool, Tool::Highlighter(_)) =>
+            {
+                *points = horizontal_bar(points, p);
+            }
+            Some(Drag::Stroke { points, widths }) => {
                 // Drop clustered pointer events (>1px from last) so wobble
                 // doesn't pile up. Smoothing at render time also de-noises,
                 // but trimming inputs keeps the stored point list compact.
@@ -545,6 +1214,10 @@ impl Canvas {
                     .unwrap_or(true);
                 if push {
                     points.push(p);
+                    if !widths.is_empty() {
+                        let last = widths[widths.len() - 1];
+                        widths.push(self.pressure.map_or(last, crate::shape::pressure_width));
+                    }
                 }
             }
             Some(Drag::TwoPoint { from, to }) => {
@@ -556,7 +1229,8 @@ impl Canvas {
             }
             Some(Drag::Region { from, to }) => {
                 *to = p;
-                self.region = Some(FRect::from_corners(*from, *to));
+                let (from, to) = (*from, *to);
+                self.region = Some(self.dragged_region(from, to));
             }
             Some(Drag::RegionMove { start, original }) => {
                 let dx = (p.x - start.x).round() as i32;
@@ -568,31 +1242,81 @@ impl Canvas {
                     original.width(),
                     original.height(),
                 );
-                self.region = Some(FRect::from(moved));
+                self.region = Some(FRect::from(self.snap_moved_region(moved)));
             }
             Some(Drag::RegionResize { handle, original }) => {
-                let original = *original;
-                let dx = (p.x - (original.x() as f32 + handle_pivot_x(*handle, &original))).round()
+                let (handle, original) = (*handle, *original);
+                let p = match self.region_preset {
+                    Some(_) => p,
+                    None => self.snap_to_edges(p),
+                };
+                let dx = (p.x - (original.x() as f32 + handle_pivot_x(handle, &original))).round()
                     as i32;
-                let dy = (p.y - (original.y() as f32 + handle_pivot_y(*handle, &original))).round()
+                let dy = (p.y - (original.y() as f32 + handle_pivot_y(handle, &original))).round()
                     as i32;
-                let resized = resize_region(*handle, original, dx, dy);
+                let resized = match self.region_preset {
+                    Some(preset) => constrain_resize(preset, handle, original, dx, dy, p),
+                    None => resize_region(handle, original, dx, dy),
+                };
                 self.region = Some(FRect::from(resized));
             }
-            Some(Drag::Move {
-                id,
-                start,
-                original_shape,
-            }) => {
+            Some(Drag::Move { start, originals }) => {
                 let dx = (p.x - start.x).round() as i32;
                 let dy = (p.y - start.y).round() as i32;
-                let id = *id;
-                let baseline: Shape = (**original_shape).clone();
-                if let Some(shape) = self.shapes.iter_mut().find(|s| s.id == id) {
+                // The handles below only apply to a shape moved on its own.
+                let alone = originals.len() == 1;
+                for baseline in originals.iter() {
+                    let Some(shape) = self.shapes.iter_mut().find(|s| s.id == baseline.id) else {
+                        continue;
+                    };
                     // Reset to the drag-start baseline before translating so
                     // each PointerMove is idempotent against accumulated drift.
-                    *shape = baseline;
-                    translate_shape(shape, dx, dy);
+                    *shape = baseline.clone();
+                    match &mut shape.kind {
+                        // Grabbing the enlarged copy moves only the copy.
+                        ShapeKind::Magnify {
+                            source,
+                            target,
+                            zoom,
+                        } if alone
+                            && region_contains(
+                                &crate::shape::magnified_rect(*source, *target, *zoom),
+                                *start,
+                            ) =>
+                        {
+                            target.x += dx as f32;
+                            target.y += dy as f32;
+                        }
+                        // Grabbing the tail tip re-aims it; grabbing the
+                        // body moves the bubble while the tip stays put.
+                        ShapeKind::Callout { anchor, .. }
+                            if alone && anchor.distance(*start) <= CALLOUT_TIP_GRAB =>
+                        {
+                            anchor.x += dx as f32;
+                            anchor.y += dy as f32;
+                        }
+                        ShapeKind::Callout { body, .. } if alone => {
+                            *body = Rect::from_xywh(
+                                body.x() + dx,
+                                body.y() + dy,
+                                body.width(),
+                                body.height(),
+                            );
+                        }
+                        _ => translate_shape(shape, dx, dy),
+                    }
+                }
+            }
+            Some(Drag::Band { to, .. }) => *to = p,
+            Some(Drag::Bend { id }) => {
+                let id = *id;
+                if let Some(ShapeKind::Arrow { from, to, bend }) = self
+                    .shapes
+                    .iter_mut()
+                    .find(|s| s.id == id)
+                    .map(|s| &mut s.kind)
+                {
+                    *bend = Some(crate::shape::bend_through(*from, *to, p));
                 }
             }
             Some(Drag::Erase { radius }) => {
@@ -603,31 +1327,88 @@ impl Canvas {
         }
     }
 
+    /// A magnifier for `source` with its copy beside it: right, left,
+    /// below or above, whichever first fits inside the region.
+    fn magnify_kind(&self, source: Rect, zoom: f32) -> ShapeKind {
+        const GAP: f32 = 24.0;
+        let copy = crate::shape::magnified_rect(source, FPoint::default(), zoom);
+        let (w, h) = (copy.width() as f32, copy.height() as f32);
+        let cx = source.x() as f32 + source.width() as f32 / 2.0;
+        let cy = source.y() as f32 + source.height() as f32 / 2.0;
+        let candidates = [
+            FPoint::new(source.right() as f32 + GAP + w / 2.0, cy),
+            FPoint::new(source.x() as f32 - GAP - w / 2.0, cy),
+            FPoint::new(cx, source.bottom() as f32 + GAP + h / 2.0),
+            FPoint::new(cx, source.y() as f32 - GAP - h / 2.0),
+        ];
+        let fits = |target: FPoint| {
+            let r = crate::shape::magnified_rect(source, target, zoom);
+            self.region.map(FRect::to_int).is_none_or(|region| {
+                r.x() >= region.x()
+                    && r.y() >= region.y()
+                    && r.right() <= region.right()
+                    && r.bottom() <= region.bottom()
+            })
+        };
+        let target = candidates
+            .into_iter()
+            .find(|t| fits(*t))
+            .unwrap_or(candidates[0]);
+        ShapeKind::Magnify {
+            source,
+            target,
+            zoom,
+        }
+    }
+
     fn on_up(&mut self, p: FPoint) {
         let drag = match self.drag.take() {
             Some(d) => d,
             None => return,
         };
         match drag {
-            Drag::Stroke { mut points } => {
+            Drag::Stroke {
+                mut points,
+                mut widths,
+            } => {
                 if points.len() < 2 {
                     return;
                 }
-                points.push(p);
+                let kind = if matches!(self.active_tool, Tool::Highlighter(_)) {
+                    if self.constrain {
+                        points = horizontal_bar(&points, p);
+                    } else {
+                        points.push(p);
+                    }
+                    ShapeKind::Highlight { points }
+                } else {
+                    points.push(p);
+                    // Lifting the finger reports no pressure; keep the end
+                    // as wide as the last sample.
+                    if let Some(&last) = widths.last() {
+                        widths.push(last);
+                    }
+                    ShapeKind::FreehandStroke { points, widths }
+                };
                 let style = current_style_for_canvas(self);
                 let id = self.alloc_id();
                 self.push_shape(Shape {
                     id,
-                    kind: ShapeKind::FreehandStroke { points },
+                    kind,
                     style,
                     rotation: 0.0,
+                    group: None,
                 });
             }
             Drag::TwoPoint { from, to } => {
                 let style = current_style_for_canvas(self);
                 let kind = match &self.active_tool {
                     Tool::Line(_) => ShapeKind::Line { from, to },
-                    Tool::Arrow(_) => ShapeKind::Arrow { from, to },
+                    Tool::Arrow(_) => ShapeKind::Arrow {
+                        from,
+                        to,
+                        bend: None,
+                    },
                     Tool::Rectangle(_) => ShapeKind::Rectangle {
                         rect: FRect::from_corners(from, to).to_int(),
                     },
@@ -638,6 +1419,40 @@ impl Canvas {
                         rect: FRect::from_corners(from, to).to_int(),
                         radius: *radius,
                     },
+                    Tool::Pixelate { block_size } => ShapeKind::Pixelate {
+                        rect: FRect::from_corners(from, to).to_int(),
+                        block_size: *block_size,
+                    },
+                    Tool::Spotlight {
+                        shape,
+                        amount,
+                        desaturate,
+                    } => ShapeKind::Spotlight {
+                        rect: FRect::from_corners(from, to).to_int(),
+                        shape: *shape,
+                        amount: *amount,
+                        desaturate: *desaturate,
+                    },
+                    Tool::Magnify { zoom, .. } => {
+                        let source = FRect::from_corners(from, to).to_int();
+                        if source.width() < 2 || source.height() < 2 {
+                            return;
+                        }
+                        self.magnify_kind(source, *zoom)
+                    }
+                    Tool::Callout { text, .. } => callout_kind(from, to, text),
+                    Tool::Measure { boxed, bake, .. } => {
+                        if from.distance(to) < 2.0 {
+                            return;
+                        }
+                        ShapeKind::Measure {
+                            from,
+                            to,
+                            boxed: *boxed,
+                            scale: self.measure_scale,
+                            baked: *bake,
+                        }
+                    }
                     _ => return,
                 };
                 let id = self.alloc_id();
@@ -646,11 +1461,33 @@ impl Canvas {
                     kind,
                     style,
                     rotation: 0.0,
+                    group: None,
                 });
+                if matches!(self.active_tool, Tool::Callout { .. }) {
+                    self.editing_callout = Some(id);
+                }
             }
             Drag::Region { from, to } => {
-                self.region = Some(FRect::from_corners(from, to));
+                self.region = Some(self.dragged_region(from, to));
             }
+            Drag::Band { from, to } => {
+                // Enclosed shapes join the selection with their groups.
+                let band = FRect::from_corners(from, to).to_int();
+                let hits: Vec<ShapeId> = self
+                    .shapes
+                    .iter()
+                    .filter(|s| band.intersection(&s.bounds()) == Some(s.bounds()))
+                    .map(|s| s.id)
+                    .collect();
+                for id in hits {
+                    for member in self.group_of(id) {
+                        if !self.selection.contains(&member) {
+                            self.selection.push(member);
+                        }
+                    }
+                }
+            }
+            Drag::Bend { .. } => self.history.snapshot(&self.shapes),
             Drag::RegionMove { .. }
             | Drag::RegionResize { .. }
             | Drag::Move { .. }
@@ -663,16 +1500,38 @@ impl Canvas {
     }
 
     fn on_text_char(&mut self, c: char) {
+        if c != '\n' && c.is_control() {
+            return;
+        }
         if let Some(pt) = self.pending_text.as_mut() {
-            if !c.is_control() {
-                pt.text.push(c);
-            }
+            pt.text.push(c);
+        } else if let Some(ShapeKind::Callout { text, .. }) =
+            self.editing_callout_mut().map(|s| &mut s.kind)
+        {
+            text.push(c);
         }
     }
 
     fn on_text_backspace(&mut self) {
         if let Some(pt) = self.pending_text.as_mut() {
             pt.text.pop();
+        } else if let Some(ShapeKind::Callout { text, .. }) =
+            self.editing_callout_mut().map(|s| &mut s.kind)
+        {
+            text.pop();
+        }
+    }
+
+    fn editing_callout_mut(&mut self) -> Option<&mut Shape> {
+        let id = self.editing_callout?;
+        self.shapes.iter_mut().find(|s| s.id == id)
+    }
+
+    /// Stop typing into the edited callout, recording its text as one
+    /// undo step.
+    fn finish_callout(&mut self) {
+        if self.editing_callout.take().is_some() {
+            self.history.snapshot(&self.shapes);
         }
     }
 
@@ -685,6 +1544,7 @@ impl Canvas {
                 stroke: pt.style.color,
                 stroke_width: 1.0,
                 fill: None,
+                ..Style::default()
             };
             let id = self.alloc_id();
             self.push_shape(Shape {
@@ -696,6 +1556,7 @@ impl Canvas {
                 },
                 style,
                 rotation: 0.0,
+                group: None,
             });
         }
     }
@@ -714,6 +1575,7 @@ impl Canvas {
             },
             style,
             rotation: 0.0,
+            group: None,
         });
     }
 
@@ -738,10 +1600,12 @@ impl Canvas {
     }
 
     fn delete_selected(&mut self) {
-        if let Some(id) = self.selected.take() {
-            self.shapes.retain(|s| s.id != id);
-            self.history.snapshot(&self.shapes);
+        if self.selection.is_empty() {
+            return;
         }
+        let selection = std::mem::take(&mut self.selection);
+        self.shapes.retain(|s| !selection.contains(&s.id));
+        self.history.snapshot(&self.shapes);
     }
 
     fn undo(&mut self) {
@@ -872,10 +1736,53 @@ fn resize_region(handle: RegionHandle, original: Rect, dx: i32, dy: i32) -> Rect
     Rect::from_xywh(x0, y0, (x1 - x0).max(1) as u32, (y1 - y0).max(1) as u32)
 }
 
+/// [`resize_region`] held to `preset`: corners drag from the opposite
+/// corner, edges carry the other side along about the centre. A fixed
+/// size doesn't resize.
+fn constrain_resize(
+    preset: RegionPreset,
+    handle: RegionHandle,
+    original: Rect,
+    dx: i32,
+    dy: i32,
+    p: FPoint,
+) -> Rect {
+    let RegionPreset::Ratio(rw, rh) = preset else {
+        return original;
+    };
+    let (x0, y0) = (original.x() as f32, original.y() as f32);
+    let (x1, y1) = (original.right() as f32, original.bottom() as f32);
+    use RegionHandle::*;
+    let corner = match handle {
+        NW => Some(FPoint::new(x1, y1)),
+        NE => Some(FPoint::new(x0, y1)),
+        SE => Some(FPoint::new(x0, y0)),
+        SW => Some(FPoint::new(x1, y0)),
+        N | E | S | W => None,
+    };
+    if let Some(anchor) = corner {
+        return preset.drag(anchor, p).to_int();
+    }
+    let free = resize_region(handle, original, dx, dy);
+    let (w, h) = if matches!(handle, E | W) {
+        (free.width(), ratio_side(free.width(), rh, rw))
+    } else {
+        (ratio_side(free.height(), rw, rh), free.height())
+    };
+    let cx = original.x() + original.width() as i32 / 2;
+    let cy = original.y() + original.height() as i32 / 2;
+    match handle {
+        E | W => Rect::from_xywh(free.x(), cy - h as i32 / 2, w, h),
+        _ => Rect::from_xywh(cx - w as i32 / 2, free.y(), w, h),
+    }
+}
+
 #[derive(Clone, Debug)]
 enum Drag {
     Stroke {
         points: Vec<FPoint>,
+        /// Per-point width fractions; empty without pressure.
+        widths: Vec<f32>,
     },
     TwoPoint {
         from: FPoint,
@@ -894,11 +1801,20 @@ enum Drag {
         original: Rect,
     },
     Move {
-        id: ShapeId,
         start: FPoint,
-        /// Drag-start snapshot; motion events translate from this baseline
-        /// so the move is idempotent against accumulated drift.
-        original_shape: Box<Shape>,
+        /// Drag-start snapshot of every moved shape; motion events
+        /// translate from this baseline so the move is idempotent against
+        /// accumulated drift.
+        originals: Vec<Shape>,
+    },
+    /// Rubber band selecting the shapes it encloses.
+    Band {
+        from: FPoint,
+        to: FPoint,
+    },
+    /// Dragging the middle handle of a selected arrow.
+    Bend {
+        id: ShapeId,
     },
     Erase {
         radius: f32,
@@ -942,15 +1858,39 @@ impl History {
 fn current_style(tool: &Tool) -> Style {
     match tool {
         Tool::Brush(b)
+        | Tool::Highlighter(b)
         | Tool::Line(b)
         | Tool::Arrow(b)
         | Tool::Rectangle(b)
         | Tool::Ellipse(b)
         | Tool::Polygon(b) => Style::from(*b),
+        Tool::Magnify { border, .. } => Style {
+            fill: None,
+            ..Style::from(*border)
+        },
+        // Without a fill colour the bubble takes whichever of black or
+        // white reads best behind the text.
+        Tool::Callout { brush, text } => Style {
+            fill: Some(brush.fill.unwrap_or(text.color.contrasting())),
+            ..Style::from(*brush)
+        },
         Tool::BlurRect { .. } => Style {
             stroke: crate::color::Color::ACCENT,
             stroke_width: 1.0,
             fill: Some(crate::color::Color::SHADOW),
+            ..Style::default()
+        },
+        Tool::Measure { color, .. } => Style {
+            stroke: *color,
+            stroke_width: 1.0,
+            fill: None,
+            ..Style::default()
+        },
+        Tool::Pixelate { .. } | Tool::Spotlight { .. } => Style {
+            stroke: crate::color::Color::ACCENT,
+            stroke_width: 1.0,
+            fill: None,
+            ..Style::default()
         },
         Tool::Step(s) => Style::from(*s),
         Tool::Text(_) | Tool::Pointer | Tool::Eraser { .. } => Style::default(),
@@ -967,6 +1907,10 @@ fn current_style_with_fill(tool: &Tool, fill_mode: bool) -> Style {
     {
         s.fill = Some(s.stroke);
     }
+    // Fill mode turns the mosaic into a solid redaction box.
+    if fill_mode && matches!(tool, Tool::Pixelate { .. }) {
+        s.fill = Some(crate::color::Color::BLACK);
+    }
     s
 }
 
@@ -974,7 +1918,7 @@ fn apply_constraint(tool: &Tool, from: FPoint, to: FPoint) -> FPoint {
     let dx = to.x - from.x;
     let dy = to.y - from.y;
     match tool {
-        Tool::Line(_) | Tool::Arrow(_) => {
+        Tool::Line(_) | Tool::Arrow(_) | Tool::Measure { boxed: false, .. } => {
             let len = (dx * dx + dy * dy).sqrt();
             if len < f32::EPSILON {
                 return from;
@@ -984,7 +1928,13 @@ fn apply_constraint(tool: &Tool, from: FPoint, to: FPoint) -> FPoint {
             let snapped = (angle / step).round() * step;
             FPoint::new(from.x + len * snapped.cos(), from.y + len * snapped.sin())
         }
-        Tool::Rectangle(_) | Tool::Ellipse(_) | Tool::BlurRect { .. } => {
+        Tool::Rectangle(_)
+        | Tool::Ellipse(_)
+        | Tool::BlurRect { .. }
+        | Tool::Pixelate { .. }
+        | Tool::Spotlight { .. }
+        | Tool::Magnify { .. }
+        | Tool::Callout { .. } => {
             let side = dx.abs().max(dy.abs());
             let sx = if dx >= 0.0 { 1.0 } else { -1.0 };
             let sy = if dy >= 0.0 { 1.0 } else { -1.0 };
@@ -994,6 +1944,49 @@ fn apply_constraint(tool: &Tool, from: FPoint, to: FPoint) -> FPoint {
     }
 }
 
+/// How far duplicated and pasted shapes land from the ones they copy.
+const PASTE_OFFSET: i32 = 16;
+
+/// How close to a selected arrow's middle a Pointer drag must start to
+/// bend it instead of moving it; the overlay draws the handle there.
+pub(crate) const BEND_GRAB: f32 = 8.0;
+
+/// How close to a callout's tail tip a Pointer drag must start to move
+/// the tip instead of the bubble.
+const CALLOUT_TIP_GRAB: f32 = 10.0;
+
+/// A callout whose body spans the drag. A click without a drag gets a
+/// bubble sized for a line or two of text. The tail starts out pointing
+/// down-left of the body.
+fn callout_kind(from: FPoint, to: FPoint, style: &crate::shape::TextStyle) -> ShapeKind {
+    let mut body = FRect::from_corners(from, to).to_int();
+    if body.width() < 8 || body.height() < 8 {
+        body = Rect::from_xywh(
+            from.x.round() as i32,
+            from.y.round() as i32,
+            (style.size * 12.0).round() as u32,
+            (style.size * 3.0).round() as u32,
+        );
+    }
+    let (w, h) = (body.width() as f32, body.height() as f32);
+    ShapeKind::Callout {
+        body,
+        anchor: FPoint::new(
+            body.x() as f32 + w * 0.25,
+            body.bottom() as f32 + (h * 0.6).max(24.0),
+        ),
+        text: String::new(),
+        text_style: style.clone(),
+    }
+}
+
+/// Shift-drag with the highlighter: a straight bar at the height of the
+/// first point, as wide as the drag.
+fn horizontal_bar(points: &[FPoint], p: FPoint) -> Vec<FPoint> {
+    let first = points.first().copied().unwrap_or(p);
+    vec![first, FPoint::new(p.x, first.y)]
+}
+
 fn current_style_for_canvas(canvas: &Canvas) -> Style {
     let mut s = current_style_with_fill(&canvas.active_tool, canvas.fill_mode);
     if canvas.fill_mode {
@@ -1001,6 +1994,9 @@ fn current_style_for_canvas(canvas: &Canvas) -> Style {
             s.fill = Some(c);
         }
     }
+    if canvas.active_tool.uses_line_style() {
+        s.line = canvas.line_style;
+    }
     s
 }
 
@@ -1018,18 +2014,30 @@ fn scale_shape(shape: &mut Shape, cx: f32, cy: f32, factor: f32) {
         p.y = cy + (p.y - cy) * factor;
     };
     match &mut shape.kind {
-        ShapeKind::FreehandStroke { points } | ShapeKind::Polygon { points, .. } => {
+        ShapeKind::FreehandStroke { points, .. }
+        | ShapeKind::Highlight { points }
+        | ShapeKind::Polygon { points, .. } => {
             for p in points.iter_mut() {
                 s(p);
             }
         }
-        ShapeKind::Line { from, to } | ShapeKind::Arrow { from, to } => {
+        ShapeKind::Line { from, to } | ShapeKind::Measure { from, to, .. } => {
             s(from);
             s(to);
         }
+        ShapeKind::Arrow { from, to, bend } => {
+            s(from);
+            s(to);
+            if let Some(c) = bend {
+                s(c);
+            }
+        }
         ShapeKind::Rectangle { rect }
         | ShapeKind::Ellipse { rect }
-        | ShapeKind::BlurRect { rect, .. } => {
+        | ShapeKind::BlurRect { rect, .. }
+        | ShapeKind::Pixelate { rect, .. }
+        | ShapeKind::Spotlight { rect, .. }
+        | ShapeKind::Image { rect, .. } => {
             let mut tl = FPoint::new(rect.x() as f32, rect.y() as f32);
             let mut br = FPoint::new(
                 rect.x() as f32 + rect.width() as f32,
@@ -1043,13 +2051,35 @@ fn scale_shape(shape: &mut Shape, cx: f32, cy: f32, factor: f32) {
             let nh = (br.y - tl.y).abs().max(1.0) as u32;
             *rect = sss_capture::Rect::from_xywh(nx, ny, nw, nh);
         }
+        ShapeKind::Magnify { source, target, .. } => {
+            let mut tl = FPoint::new(source.x() as f32, source.y() as f32);
+            let mut br = FPoint::new(source.right() as f32, source.bottom() as f32);
+            s(&mut tl);
+            s(&mut br);
+            *source = FRect::from_corners(tl, br).to_int();
+            s(target);
+        }
+        ShapeKind::Callout {
+            body,
+            anchor,
+            text_style,
+            ..
+        } => {
+            let mut tl = FPoint::new(body.x() as f32, body.y() as f32);
+            let mut br = FPoint::new(body.right() as f32, body.bottom() as f32);
+            s(&mut tl);
+            s(&mut br);
+            *body = FRect::from_corners(tl, br).to_int();
+            s(anchor);
+            text_style.scale(factor);
+        }
         ShapeKind::Step { center, radius, .. } => {
             s(center);
             *radius = (*radius * factor).max(2.0);
         }
         ShapeKind::Text { origin, style, .. } => {
             s(origin);
-            style.size = (style.size * factor).max(6.0);
+            style.scale(factor);
         }
     }
     shape.style.stroke_width = (shape.style.stroke_width * factor).max(0.5);
@@ -1064,15 +2094,41 @@ fn rotate_shape(shape: &mut Shape, cx: f32, cy: f32, radians: f32) {
         p.y = cy + dx * sn + dy * cs;
     };
     match &mut shape.kind {
-        ShapeKind::FreehandStroke { points } | ShapeKind::Polygon { points, .. } => {
+        ShapeKind::FreehandStroke { points, .. }
+        | ShapeKind::Highlight { points }
+        | ShapeKind::Polygon { points, .. } => {
             for p in points.iter_mut() {
                 r(p);
             }
         }
-        ShapeKind::Line { from, to } | ShapeKind::Arrow { from, to } => {
+        ShapeKind::Line { from, to } => {
             r(from);
             r(to);
         }
+        // A box stays upright about its rotated centre.
+        ShapeKind::Measure {
+            from,
+            to,
+            boxed: true,
+            ..
+        } => {
+            let mut c = FPoint::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0);
+            let (hx, hy) = ((to.x - from.x) / 2.0, (to.y - from.y) / 2.0);
+            r(&mut c);
+            *from = FPoint::new(c.x - hx, c.y - hy);
+            *to = FPoint::new(c.x + hx, c.y + hy);
+        }
+        ShapeKind::Measure { from, to, .. } => {
+            r(from);
+            r(to);
+        }
+        ShapeKind::Arrow { from, to, bend } => {
+            r(from);
+            r(to);
+            if let Some(c) = bend {
+                r(c);
+            }
+        }
         ShapeKind::Rectangle { rect } => {
             // Convert to a 4-point polygon so the rotation is visible at
             // intermediate angles (not just multiples of 90°).
@@ -1108,8 +2164,10 @@ fn rotate_shape(shape: &mut Shape, cx: f32, cy: f32, radians: f32) {
                 closed: true,
             };
         }
-        ShapeKind::BlurRect { rect, .. } => {
-            // BlurRect requires an axis-aligned rect, so re-bound after rotation.
+        ShapeKind::BlurRect { rect, .. }
+        | ShapeKind::Pixelate { rect, .. }
+        | ShapeKind::Spotlight { rect, .. } => {
+            // Redactions require an axis-aligned rect, so re-bound after rotation.
             let pts = rect_corners(*rect);
             let mut rotated = pts;
             for p in rotated.iter_mut() {
@@ -1128,6 +2186,53 @@ fn rotate_shape(shape: &mut Shape, cx: f32, cy: f32, radians: f32) {
                 (my - ny).max(1) as u32,
             );
         }
+        ShapeKind::Magnify { source, target, .. } => {
+            // The copy stays axis-aligned; only the two anchors move.
+            let mut c = FPoint::new(
+                source.x() as f32 + source.width() as f32 / 2.0,
+                source.y() as f32 + source.height() as f32 / 2.0,
+            );
+            r(&mut c);
+            *source = sss_capture::Rect::from_xywh(
+                (c.x - source.width() as f32 / 2.0).round() as i32,
+                (c.y - source.height() as f32 / 2.0).round() as i32,
+                source.width(),
+                source.height(),
+            );
+            r(target);
+        }
+        ShapeKind::Callout { body, anchor, .. } => {
+            // The bubble stays upright so its text stays level; the body
+            // orbits the pivot and the tail follows the anchor.
+            let mut c = FPoint::new(
+                body.x() as f32 + body.width() as f32 / 2.0,
+                body.y() as f32 + body.height() as f32 / 2.0,
+            );
+            r(&mut c);
+            *body = sss_capture::Rect::from_xywh(
+                (c.x - body.width() as f32 / 2.0).round() as i32,
+                (c.y - body.height() as f32 / 2.0).round() as i32,
+                body.width(),
+                body.height(),
+            );
+            r(anchor);
+        }
+        ShapeKind::Image { rect, .. } => {
+            // The pixels turn with the shape's rotation; the rect stays
+            // upright around the orbiting centre.
+            let mut c = FPoint::new(
+                rect.x() as f32 + rect.width() as f32 / 2.0,
+                rect.y() as f32 + rect.height() as f32 / 2.0,
+            );
+            r(&mut c);
+            *rect = sss_capture::Rect::from_xywh(
+                (c.x - rect.width() as f32 / 2.0).round() as i32,
+                (c.y - rect.height() as f32 / 2.0).round() as i32,
+                rect.width(),
+                rect.height(),
+            );
+            shape.rotation = (shape.rotation + radians) % std::f32::consts::TAU;
+        }
         ShapeKind::Step { center, .. } => r(center),
         ShapeKind::Text { origin, .. } => r(origin),
     }
@@ -1150,21 +2255,32 @@ fn translate_shape(shape: &mut Shape, dx: i32, dy: i32) {
     let dx_f = dx as f32;
     let dy_f = dy as f32;
     match &mut shape.kind {
-        ShapeKind::FreehandStroke { points } | ShapeKind::Polygon { points, .. } => {
+        ShapeKind::FreehandStroke { points, .. }
+        | ShapeKind::Highlight { points }
+        | ShapeKind::Polygon { points, .. } => {
             for p in points.iter_mut() {
                 p.x += dx_f;
                 p.y += dy_f;
             }
         }
-        ShapeKind::Line { from, to } | ShapeKind::Arrow { from, to } => {
+        ShapeKind::Line { from, to } | ShapeKind::Measure { from, to, .. } => {
             from.x += dx_f;
             from.y += dy_f;
             to.x += dx_f;
             to.y += dy_f;
         }
+        ShapeKind::Arrow { from, to, bend } => {
+            for p in [Some(from), Some(to), bend.as_mut()].into_iter().flatten() {
+                p.x += dx_f;
+                p.y += dy_f;
+            }
+        }
         ShapeKind::Rectangle { rect }
         | ShapeKind::Ellipse { rect }
-        | ShapeKind::BlurRect { rect, .. } => {
+        | ShapeKind::BlurRect { rect, .. }
+        | ShapeKind::Pixelate { rect, .. }
+        | ShapeKind::Spotlight { rect, .. }
+        | ShapeKind::Image { rect, .. } => {
             *rect = sss_capture::Rect::from_xywh(
                 rect.x() + dx,
                 rect.y() + dy,
@@ -1172,6 +2288,26 @@ fn translate_shape(shape: &mut Shape, dx: i32, dy: i32) {
                 rect.height(),
             );
         }
+        ShapeKind::Magnify { source, target, .. } => {
+            *source = sss_capture::Rect::from_xywh(
+                source.x() + dx,
+                source.y() + dy,
+                source.width(),
+                source.height(),
+            );
+            target.x += dx_f;
+            target.y += dy_f;
+        }
+        ShapeKind::Callout { body, anchor, .. } => {
+            *body = sss_capture::Rect::from_xywh(
+                body.x() + dx,
+                body.y() + dy,
+                body.width(),
+                body.height(),
+            );
+            anchor.x += dx_f;
+            anchor.y += dy_f;
+        }
         ShapeKind::Step { center, .. } => {
             center.x += dx_f;
             center.y += dy_f;
@@ -1182,3 +2318,85 @@ fn translate_shape(shape: &mut Shape, dx: i32, dy: i32) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::{pressure_width, MIN_PRESSURE_WIDTH};
+    use crate::tool::BrushSettings;
+
+    fn brush() -> Tool {
+        Tool::Brush(BrushSettings::default())
+    }
+
+    #[test]
+    fn pressure_stroke_keeps_a_width_per_point() {
+        let mut c = Canvas::default();
+        c.set_tool(brush());
+        c.set_pressure(Some(1.0));
+        c.handle(CanvasEvent::PointerDown(FPoint::new(0.0, 0.0)));
+        c.set_pressure(Some(0.5));
+        c.handle(CanvasEvent::PointerMove(FPoint::new(10.0, 0.0)));
+        // Too close to the last point: dropped along with its pressure.
+        c.handle(CanvasEvent::PointerMove(FPoint::new(10.5, 0.0)));
+        c.set_pressure(Some(0.0));
+        c.handle(CanvasEvent::PointerMove(FPoint::new(20.0, 0.0)));
+        c.handle(CanvasEvent::PointerUp(FPoint::new(30.0, 0.0)));
+        let ShapeKind::FreehandStroke { points, widths } = &c.shapes()[0].kind else {
+            panic!("expected a freehand stroke");
+        };
+        assert_eq!(points.len(), 4);
+        assert_eq!(
+            widths,
+            &[
+                1.0,
+                pressure_width(0.5),
+                MIN_PRESSURE_WIDTH,
+                MIN_PRESSURE_WIDTH
+            ]
+        );
+    }
+
+    #[test]
+    fn mouse_stroke_has_uniform_width() {
+        let mut c = Canvas::default();
+        c.set_tool(brush());
+        c.handle(CanvasEvent::PointerDown(FPoint::new(0.0, 0.0)));
+        c.set_pressure(Some(0.5));
+        c.handle(CanvasEvent::PointerMove(FPoint::new(10.0, 0.0)));
+        c.handle(CanvasEvent::PointerUp(FPoint::new(20.0, 0.0)));
+        assert!(matches!(
+            &c.shapes()[0].kind,
+            ShapeKind::FreehandStroke { widths, .. } if widths.is_empty()
+        ));
+    }
+
+    #[test]
+    fn keyboard_resize_moves_the_bottom_right_edge() {
+        let mut c = Canvas::default();
+        c.set_region(Some(Rect::from_xywh(10, 20, 100, 50)));
+        c.resize_region(5, 0);
+        assert_eq!(c.region(), Some(Rect::from_xywh(10, 20, 105, 50)));
+        c.resize_region(0, -10);
+        assert_eq!(c.region(), Some(Rect::from_xywh(10, 20, 105, 40)));
+        c.resize_region(-500, 0);
+        assert_eq!(c.region(), Some(Rect::from_xywh(10, 20, 1, 40)));
+    }
+
+    #[test]
+    fn keyboard_resize_keeps_a_large_ratio_region_in_shape() {
+        let mut c = Canvas::default();
+        c.set_region(Some(Rect::from_xywh(0, 0, 60_000, 59_999)));
+        c.set_region_preset(Some(RegionPreset::Ratio(100_000, 99_999)));
+        c.resize_region(1, 0);
+        assert_eq!(c.region(), Some(Rect::from_xywh(0, 0, 60_001, 60_000)));
+        // Growing the height derives the width from it.
+        c.resize_region(0, 2);
+        assert_eq!(c.region(), Some(Rect::from_xywh(0, 0, 60_002, 60_002)));
+
+        c.set_region_preset(Some(RegionPreset::Size(640, 480)));
+        let fixed = c.region();
+        c.resize_region(10, 10);
+        assert_eq!(c.region(), fixed);
+    }
+}
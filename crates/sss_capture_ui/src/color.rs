@@ -49,6 +49,17 @@ impl Color {
         self
     }
 
+    /// Black or white, whichever reads better on top of `self`.
+    pub fn contrasting(self) -> Self {
+        let [r, g, b, _] = self.0;
+        let lum = 0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32;
+        if lum > 140.0 {
+            Self::BLACK
+        } else {
+            Self::WHITE
+        }
+    }
+
     /// Parse `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA` (`#` is optional).
     pub fn parse_hex(s: &str) -> Result<Self, String> {
         sss_core::color::parse_hex(s)
@@ -1,6 +1,8 @@
 //! User-facing configuration for the interactive overlay.
 
 use crate::color::Color;
+use crate::geometry::RegionPreset;
+use crate::keymap::Keymap;
 use crate::tool::{BrushSettings, StepSettings, Tool, ToolPalette};
 
 /// Serializable identifier for a `Tool` variant.
@@ -10,15 +12,21 @@ use crate::tool::{BrushSettings, StepSettings, Tool, ToolPalette};
 pub enum ToolKind {
     Pointer,
     Brush,
+    Highlighter,
     Line,
     Arrow,
     Rectangle,
     Ellipse,
     Polygon,
     BlurRect,
+    Pixelate,
+    Spotlight,
+    Magnify,
     Eraser,
     Step,
     Text,
+    Callout,
+    Measure,
 }
 
 impl ToolKind {
@@ -26,14 +34,21 @@ impl ToolKind {
         vec![
             ToolKind::Pointer,
             ToolKind::Brush,
+            ToolKind::Highlighter,
             ToolKind::Line,
             ToolKind::Arrow,
             ToolKind::Rectangle,
             ToolKind::Ellipse,
             ToolKind::Polygon,
             ToolKind::BlurRect,
+            ToolKind::Pixelate,
+            ToolKind::Spotlight,
+            ToolKind::Magnify,
             ToolKind::Eraser,
             ToolKind::Step,
+            ToolKind::Text,
+            ToolKind::Callout,
+            ToolKind::Measure,
         ]
     }
 
@@ -46,6 +61,12 @@ impl ToolKind {
         match self {
             ToolKind::Pointer => Tool::Pointer,
             ToolKind::Brush => Tool::Brush(brush),
+            // Same scale the width picker applies; see `set_active_tool_width`.
+            ToolKind::Highlighter => Tool::Highlighter(BrushSettings {
+                width: brush.width * 6.0,
+                fill: None,
+                ..brush
+            }),
             ToolKind::Line => Tool::Line(brush),
             ToolKind::Arrow => Tool::Arrow(brush),
             ToolKind::Rectangle => Tool::Rectangle(brush),
@@ -54,6 +75,21 @@ impl ToolKind {
             ToolKind::BlurRect => Tool::BlurRect {
                 radius: ui.default_blur_radius,
             },
+            ToolKind::Pixelate => Tool::Pixelate {
+                block_size: ui.default_pixelate_block.max(2),
+            },
+            ToolKind::Spotlight => Tool::Spotlight {
+                shape: Default::default(),
+                amount: ui.spotlight_dim,
+                desaturate: ui.spotlight_desaturate,
+            },
+            ToolKind::Magnify => Tool::Magnify {
+                zoom: ui.default_magnify_zoom.max(1.0),
+                border: BrushSettings {
+                    fill: None,
+                    ..brush
+                },
+            },
             ToolKind::Eraser => Tool::Eraser {
                 radius: ui.default_eraser_radius,
             },
@@ -63,11 +99,19 @@ impl ToolKind {
                 radius: ui.default_step_radius,
                 next_number: 1,
             }),
-            ToolKind::Text => Tool::Text(crate::shape::TextStyle {
+            ToolKind::Text => Tool::Text(ui.default_text_style()),
+            ToolKind::Callout => Tool::Callout {
+                brush: BrushSettings {
+                    fill: None,
+                    ..brush
+                },
+                text: ui.default_text_style(),
+            },
+            ToolKind::Measure => Tool::Measure {
                 color: ui.default_stroke_color,
-                size: ui.default_text_size,
-                bold: false,
-            }),
+                boxed: false,
+                bake: ui.bake_measurements,
+            },
         }
     }
 }
@@ -113,13 +157,43 @@ pub struct UiConfig {
     pub default_stroke_width: f32,
     pub default_fill: Option<Color>,
     pub default_blur_radius: f32,
+    /// Mosaic cell size of the Pixelate tool, in pixels.
+    pub default_pixelate_block: u32,
+    /// Enlargement factor of the Magnify tool.
+    pub default_magnify_zoom: f32,
     pub default_eraser_radius: f32,
     pub default_step_radius: f32,
     pub default_text_size: f32,
+    /// Font families the text toolbar cycles through; the first is the
+    /// default. System family names, `sans-serif` / `serif` / `monospace`,
+    /// or `Hack` for the embedded font.
+    pub text_fonts: Vec<String>,
     pub snap_step: f32,
+    /// Ratios and fixed sizes the region chip of the selection toolbar
+    /// cycles through; an empty list hides the chip.
+    pub region_presets: Vec<RegionPreset>,
     pub region_outline_color: Color,
     /// Darken applied to pixels outside the active region (0..=255).
     pub background_dim: u8,
+    /// Darken the Spotlight tool applies outside its areas (0..=255).
+    pub spotlight_dim: u8,
+    /// Turn the area outside spotlights grey before darkening it.
+    pub spotlight_desaturate: bool,
+    /// Count measurements and the cursor readout in logical pixels, i.e.
+    /// physical ones divided by the monitor's scale factor.
+    pub measure_logical: bool,
+    /// Keep measurements in the saved image instead of only showing them
+    /// in the overlay.
+    pub bake_measurements: bool,
+    /// Show the cursor position and the selection size next to the
+    /// pointer. Off by default.
+    pub show_readout: bool,
+    /// Directory of PNG / SVG stickers offered by the toolbar's sticker
+    /// picker. `None` hides the picker; `~/` expands to the home directory.
+    pub sticker_dir: Option<std::path::PathBuf>,
+    /// Key chords of the overlay, read from a chord → action table that
+    /// is laid over the defaults; see [`Keymap`].
+    pub keybindings: Keymap,
     pub chrome: ChromeColors,
     /// Initial state of the output-border toggle in the side action
     /// toolbar. Default `true` — the host passes `false` here when the
@@ -139,12 +213,33 @@ impl Default for UiConfig {
             default_stroke_width: 3.0,
             default_fill: None,
             default_blur_radius: 12.0,
+            default_pixelate_block: 12,
+            default_magnify_zoom: 3.0,
             default_eraser_radius: 18.0,
             default_step_radius: 14.0,
             default_text_size: 18.0,
+            text_fonts: vec![
+                crate::font::DEFAULT_FAMILY.to_owned(),
+                "sans-serif".to_owned(),
+                "serif".to_owned(),
+            ],
             snap_step: 10.0,
+            region_presets: vec![
+                RegionPreset::Ratio(16, 9),
+                RegionPreset::Ratio(4, 3),
+                RegionPreset::Ratio(1, 1),
+                RegionPreset::Size(1280, 720),
+                RegionPreset::Size(1200, 630),
+            ],
             region_outline_color: Color::WHITE,
             background_dim: 80,
+            spotlight_dim: 140,
+            spotlight_desaturate: false,
+            measure_logical: false,
+            bake_measurements: false,
+            show_readout: false,
+            sticker_dir: None,
+            keybindings: Keymap::default(),
             chrome: ChromeColors::default(),
             border_enabled: true,
         }
@@ -170,6 +265,15 @@ impl UiConfig {
         }
     }
 
+    fn default_text_style(&self) -> crate::shape::TextStyle {
+        crate::shape::TextStyle {
+            color: self.default_stroke_color,
+            size: self.default_text_size,
+            family: self.text_fonts.first().cloned().unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
     pub fn has_editor_tools(&self) -> bool {
         self.tools.iter().any(|k| *k != ToolKind::Pointer)
     }
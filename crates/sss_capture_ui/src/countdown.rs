@@ -0,0 +1,96 @@
+//! Countdown shown before a [`crate::CaptureTrigger::Delayed`] capture.
+//!
+//! On Linux the countdown is a single notification whose body is updated
+//! every second and closed shortly before the capture, so it never ends up
+//! in the frame. Where no notification can be shown and retracted in time
+//! (macOS, Windows, Linux without a notification daemon) a small
+//! always-on-top window counts down instead and is closed the same way.
+//! Builds without the `editor` feature have no renderer for that window;
+//! there the delay runs silently.
+
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use winit::event_loop::EventLoop;
+
+/// Head start the notification daemon gets to fade the popup out before
+/// the capture happens.
+#[cfg(target_os = "linux")]
+const CLOSE_MARGIN: Duration = Duration::from_millis(400);
+
+/// Block for `delay`, counting down on screen. Used by the selector for
+/// [`crate::CaptureTrigger::Delayed`]; callers that capture without the
+/// overlay (direct targets) can call it themselves.
+///
+/// The countdown window needs an event loop and winit allows only one per
+/// process, so don't call this before running a [`crate::Selector`]; use
+/// [`crate::CaptureTrigger::Delayed`] there instead.
+pub fn countdown(delay: Duration) {
+    run(delay, None);
+}
+
+/// [`countdown`] on the selector's own event loop.
+pub(crate) fn countdown_in(event_loop: &mut EventLoop, delay: Duration) {
+    run(delay, Some(event_loop));
+}
+
+fn run(delay: Duration, event_loop: Option<&mut EventLoop>) {
+    if delay.is_zero() {
+        return;
+    }
+    tracing::info!(?delay, "delaying capture");
+    let deadline = Instant::now() + delay;
+    #[cfg(target_os = "linux")]
+    let notified = notify_countdown(delay);
+    #[cfg(not(target_os = "linux"))]
+    let notified = false;
+    #[cfg(feature = "editor")]
+    if !notified {
+        crate::platform::countdown_window(event_loop, deadline);
+    }
+    #[cfg(not(feature = "editor"))]
+    let _ = (notified, event_loop);
+    sleep(deadline.saturating_duration_since(Instant::now()));
+}
+
+/// Returns `false` when no notification daemon answered; the caller then
+/// falls back to the countdown window.
+#[cfg(target_os = "linux")]
+fn notify_countdown(delay: Duration) -> bool {
+    use notify_rust::{Hint, Notification, Timeout};
+
+    let remaining = |left: Duration| format!("Capturing in {}…", left.as_secs_f32().ceil());
+    let mut handle = match Notification::new()
+        .summary("Screenshot")
+        .body(&remaining(delay))
+        .hint(Hint::Transient(true))
+        .timeout(Timeout::Never)
+        .show()
+    {
+        Ok(h) => h,
+        Err(e) => {
+            tracing::debug!(error = %e, "countdown notification unavailable");
+            return false;
+        }
+    };
+
+    let mut left = delay;
+    while left > CLOSE_MARGIN {
+        // Tick on whole seconds so the number changes once per second
+        // even for fractional delays.
+        let step = match left.as_secs_f32().fract() {
+            f if f > 0.0 => Duration::from_secs_f32(f),
+            _ => Duration::from_secs(1),
+        }
+        .min(left - CLOSE_MARGIN);
+        sleep(step);
+        left -= step;
+        if left > CLOSE_MARGIN {
+            handle.body(&remaining(left));
+            handle.update();
+        }
+    }
+    handle.close();
+    sleep(left);
+    true
+}
@@ -0,0 +1,162 @@
+//! Strong horizontal and vertical edges of the captured frame — panel
+//! borders, buttons, window frames — that the area selection snaps to.
+//!
+//! A Sobel gradient across every pixel boundary marks strong pixels; a
+//! boundary column (row) is an edge line once it holds an unbroken run of
+//! [`MIN_RUN`] of them, which keeps text and noise out.
+
+use image::RgbaImage;
+
+/// Sobel response (0..=1020) a boundary pixel needs to count as strong;
+/// roughly a 40-level step in luma.
+const STRENGTH: i32 = 160;
+/// Shortest unbroken run of strong pixels that makes an edge line.
+const MIN_RUN: u32 = 24;
+/// Distance, in pixels, within which an edge pulls a region edge onto it.
+pub(crate) const SNAP_TOL: f32 = 8.0;
+
+/// Edge lines in desktop coordinates, each the position of a pixel
+/// boundary: `x` lies between columns `x - 1` and `x`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EdgeLines {
+    /// Vertical edges, ascending.
+    pub xs: Vec<i32>,
+    /// Horizontal edges, ascending.
+    pub ys: Vec<i32>,
+}
+
+impl EdgeLines {
+    /// Detect edges in `img`, whose top-left pixel sits at `origin`.
+    pub fn detect(img: &RgbaImage, origin: (i32, i32)) -> Self {
+        let (w, h) = (img.width() as usize, img.height() as usize);
+        if w < 2 || h < 2 {
+            return Self::default();
+        }
+        let luma: Vec<i32> = img
+            .pixels()
+            .map(|p| (p[0] as i32 * 299 + p[1] as i32 * 587 + p[2] as i32 * 114) / 1000)
+            .collect();
+        let row = |y: usize| &luma[y * w..(y + 1) * w];
+
+        // Vertical edges: per boundary column, the current and longest
+        // run of strong pixels, filled a row at a time.
+        let mut run = vec![0u32; w];
+        let mut best = vec![0u32; w];
+        for y in 0..h {
+            let (up, mid, down) = (row(y.saturating_sub(1)), row(y), row((y + 1).min(h - 1)));
+            for x in 1..w {
+                let g = (up[x] - up[x - 1]) + 2 * (mid[x] - mid[x - 1]) + (down[x] - down[x - 1]);
+                run[x] = if g.abs() >= STRENGTH { run[x] + 1 } else { 0 };
+                best[x] = best[x].max(run[x]);
+            }
+        }
+        let xs = (1..w)
+            .filter(|&x| best[x] >= MIN_RUN)
+            .map(|x| origin.0 + x as i32)
+            .collect();
+
+        // Horizontal edges: boundary `y` between rows `y - 1` and `y`.
+        let mut ys = Vec::new();
+        for y in 1..h {
+            let (above, below) = (row(y - 1), row(y));
+            let (mut run, mut best) = (0u32, 0u32);
+            for x in 0..w {
+                let (l, r) = (x.saturating_sub(1), (x + 1).min(w - 1));
+                let g = (below[l] - above[l]) + 2 * (below[x] - above[x]) + (below[r] - above[r]);
+                run = if g.abs() >= STRENGTH { run + 1 } else { 0 };
+                best = best.max(run);
+            }
+            if best >= MIN_RUN {
+                ys.push(origin.1 + y as i32);
+            }
+        }
+        Self { xs, ys }
+    }
+
+    /// The vertical edge nearest `x`, if within [`SNAP_TOL`].
+    pub fn snap_x(&self, x: f32) -> Option<i32> {
+        nearest(&self.xs, x)
+    }
+
+    /// The horizontal edge nearest `y`, if within [`SNAP_TOL`].
+    pub fn snap_y(&self, y: f32) -> Option<i32> {
+        nearest(&self.ys, y)
+    }
+}
+
+fn nearest(edges: &[i32], v: f32) -> Option<i32> {
+    let i = edges.partition_point(|&e| (e as f32) < v);
+    [i.checked_sub(1), Some(i)]
+        .into_iter()
+        .flatten()
+        .filter_map(|i| edges.get(i).copied())
+        .map(|e| (e, (e as f32 - v).abs()))
+        .filter(|&(_, d)| d <= SNAP_TOL)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(e, _)| e)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+
+    use super::*;
+
+    /// A light 100×70 panel at (40, 30) on a dark 200×150 frame, plus a
+    /// bar too short to be an edge, like a text stroke.
+    fn panel() -> RgbaImage {
+        RgbaImage::from_fn(200, 150, |x, y| {
+            let in_panel = (40..140).contains(&x) && (30..100).contains(&y);
+            let in_bar = (170..172).contains(&x) && (10..20).contains(&y);
+            if in_panel || in_bar {
+                Rgba([230, 230, 230, 255])
+            } else {
+                Rgba([30, 30, 30, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn detects_the_panel_border_and_ignores_short_strokes() {
+        let edges = EdgeLines::detect(&panel(), (0, 0));
+        assert_eq!(edges.xs, [40, 140]);
+        assert_eq!(edges.ys, [30, 100]);
+    }
+
+    #[test]
+    fn edges_are_in_desktop_coordinates() {
+        let edges = EdgeLines::detect(&panel(), (1920, -40));
+        assert_eq!(edges.xs, [1960, 2060]);
+        assert_eq!(edges.ys, [-10, 60]);
+    }
+
+    #[test]
+    fn flat_and_tiny_frames_have_no_edges() {
+        let flat = RgbaImage::from_pixel(64, 64, Rgba([90, 90, 90, 255]));
+        assert_eq!(EdgeLines::detect(&flat, (0, 0)), EdgeLines::default());
+        let tiny = RgbaImage::from_pixel(1, 64, Rgba([255; 4]));
+        assert_eq!(EdgeLines::detect(&tiny, (0, 0)), EdgeLines::default());
+    }
+
+    #[test]
+    fn snaps_within_the_tolerance_only() {
+        let edges = EdgeLines::detect(&panel(), (0, 0));
+        assert_eq!(edges.snap_x(40.0), Some(40));
+        assert_eq!(edges.snap_x(40.0 + SNAP_TOL), Some(40));
+        assert_eq!(edges.snap_x(40.0 - SNAP_TOL), Some(40));
+        assert_eq!(edges.snap_x(40.5 + SNAP_TOL), None);
+        assert_eq!(edges.snap_x(90.0), None);
+        assert_eq!(edges.snap_y(97.5), Some(100));
+        assert_eq!(edges.snap_y(20.0), None);
+    }
+
+    #[test]
+    fn nearest_prefers_the_closer_of_two_edges() {
+        let edges = [40, 50, 200];
+        assert_eq!(nearest(&edges, 44.0), Some(40));
+        assert_eq!(nearest(&edges, 46.0), Some(50));
+        assert_eq!(nearest(&edges, 120.0), None);
+        assert_eq!(nearest(&edges, 205.0), Some(200));
+        assert_eq!(nearest(&[], 0.0), None);
+    }
+}
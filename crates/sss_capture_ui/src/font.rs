@@ -1,5 +1,9 @@
 //! Cached TTF rasteriser shared by the wayland-layer-shell overlay and the
 //! CPU compositor that bakes annotations into the captured image.
+//!
+//! Text shapes pick a family by name: the embedded Hack, or any system
+//! family resolved through `font-kit`. Faces are loaded once and live for
+//! the rest of the process, like the glyph cache.
 
 use std::collections::HashMap;
 use std::sync::{Mutex, OnceLock};
@@ -7,11 +11,125 @@ use std::sync::{Mutex, OnceLock};
 use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
 use image::{Rgba, RgbaImage};
 
-const FONT_BYTES: &[u8] = include_bytes!("../assets/fonts/Hack-Regular.ttf");
+use crate::shape::TextStyle;
 
-fn font() -> &'static FontRef<'static> {
-    static FONT: OnceLock<FontRef<'static>> = OnceLock::new();
-    FONT.get_or_init(|| FontRef::try_from_slice(FONT_BYTES).expect("Hack-Regular.ttf is malformed"))
+/// Embedded family; used for step numbers and whenever a requested family
+/// can't be found.
+pub(crate) const DEFAULT_FAMILY: &str = "Hack";
+
+const HACK_REGULAR: &[u8] = include_bytes!("../assets/fonts/Hack-Regular.ttf");
+const HACK_BOLD: &[u8] = include_bytes!("../assets/fonts/Hack-Bold.ttf");
+const HACK_ITALIC: &[u8] = include_bytes!("../assets/fonts/Hack-Italic.ttf");
+const HACK_BOLD_ITALIC: &[u8] = include_bytes!("../assets/fonts/Hack-BoldItalic.ttf");
+
+/// Slot in the loaded-face table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct FaceId(usize);
+
+/// Regular Hack; always the first slot.
+const HACK: FaceId = FaceId(0);
+
+/// Lower-cased family name, bold, italic.
+type FaceKey = (String, bool, bool);
+type FaceTable = Vec<(FaceKey, &'static FontRef<'static>)>;
+
+fn faces() -> &'static Mutex<FaceTable> {
+    static FACES: OnceLock<Mutex<FaceTable>> = OnceLock::new();
+    FACES.get_or_init(|| {
+        let key = (DEFAULT_FAMILY.to_ascii_lowercase(), false, false);
+        Mutex::new(vec![(key, load_face(DEFAULT_FAMILY, false, false))])
+    })
+}
+
+fn font_of(id: FaceId) -> &'static FontRef<'static> {
+    faces().lock().unwrap()[id.0].1
+}
+
+/// Face for `family` in the requested weight and slant. An empty name
+/// means Hack; unknown families fall back to it too.
+pub(crate) fn face(family: &str, bold: bool, italic: bool) -> FaceId {
+    let family = match family.trim() {
+        "" => DEFAULT_FAMILY,
+        f => f,
+    };
+    let key = (family.to_ascii_lowercase(), bold, italic);
+    let mut faces = faces().lock().unwrap();
+    if let Some(i) = faces.iter().position(|(k, _)| *k == key) {
+        return FaceId(i);
+    }
+    faces.push((key, load_face(family, bold, italic)));
+    FaceId(faces.len() - 1)
+}
+
+fn style_face(style: &TextStyle) -> FaceId {
+    face(&style.family, style.bold, style.italic)
+}
+
+/// `face`, or Hack when `face` has no glyph for `ch`.
+fn face_for(face: FaceId, ch: char) -> FaceId {
+    if face != HACK && font_of(face).glyph_id(ch).0 == 0 {
+        HACK
+    } else {
+        face
+    }
+}
+
+fn load_face(family: &str, bold: bool, italic: bool) -> &'static FontRef<'static> {
+    let system = if family.eq_ignore_ascii_case(DEFAULT_FAMILY) {
+        None
+    } else {
+        system_face(family, bold, italic)
+    };
+    let font = system.unwrap_or_else(|| {
+        let bytes = match (bold, italic) {
+            (false, false) => HACK_REGULAR,
+            (true, false) => HACK_BOLD,
+            (false, true) => HACK_ITALIC,
+            (true, true) => HACK_BOLD_ITALIC,
+        };
+        FontRef::try_from_slice(bytes).expect("embedded Hack face is malformed")
+    });
+    Box::leak(Box::new(font))
+}
+
+/// Best system match for `family`. CSS generic names (`sans-serif`,
+/// `serif`, `monospace`, …) map to the platform defaults.
+fn system_face(family: &str, bold: bool, italic: bool) -> Option<FontRef<'static>> {
+    use font_kit::family_name::FamilyName;
+    use font_kit::handle::Handle;
+    use font_kit::properties::{Properties, Style, Weight};
+    use font_kit::source::SystemSource;
+
+    let name = match family.to_ascii_lowercase().as_str() {
+        "serif" => FamilyName::Serif,
+        "sans-serif" | "sans" => FamilyName::SansSerif,
+        "monospace" => FamilyName::Monospace,
+        "cursive" => FamilyName::Cursive,
+        "fantasy" => FamilyName::Fantasy,
+        _ => FamilyName::Title(family.to_owned()),
+    };
+    let mut props = Properties::new();
+    props.weight = if bold { Weight::BOLD } else { Weight::NORMAL };
+    props.style = if italic { Style::Italic } else { Style::Normal };
+    let handle = match SystemSource::new().select_best_match(&[name], &props) {
+        Ok(handle) => handle,
+        Err(e) => {
+            tracing::warn!("font family {family:?} not found ({e}); using {DEFAULT_FAMILY}");
+            return None;
+        }
+    };
+    let (bytes, index) = match handle {
+        Handle::Path { path, font_index } => (std::fs::read(path).ok()?, font_index),
+        Handle::Memory { bytes, font_index } => (bytes.to_vec(), font_index),
+    };
+    let bytes: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+    match FontRef::try_from_slice_and_index(bytes, index) {
+        Ok(font) => Some(font),
+        Err(e) => {
+            tracing::warn!("font family {family:?} is unreadable ({e}); using {DEFAULT_FAMILY}");
+            None
+        }
+    }
 }
 
 /// Row-major coverage bitmap (0 = transparent, 255 = fully covered).
@@ -24,29 +142,28 @@ pub(crate) struct GlyphBitmap {
     pub pixels: Vec<u8>,
 }
 
-pub(crate) fn glyph_for(ch: char, px: f32) -> Option<&'static GlyphBitmap> {
-    let key = (ch, (px * 16.0) as u32);
+pub(crate) fn glyph_for(face: FaceId, ch: char, px: f32) -> Option<&'static GlyphBitmap> {
+    let key = (face, ch, (px * 16.0) as u32);
     let guard = cache().lock().unwrap();
     // SAFETY: cache entries are never removed; addresses are stable forever.
     if let Some(g) = guard.get(&key) {
         return g.as_ref().map(|g| unsafe { &*(g as *const _) });
     }
     drop(guard);
-    let raster = rasterise(ch, px);
+    let raster = rasterise(font_of(face), ch, px);
     let mut guard = cache().lock().unwrap();
     let inserted = guard.entry(key).or_insert(raster);
     inserted.as_ref().map(|g| unsafe { &*(g as *const _) })
 }
 
-type CharGlyphCache = HashMap<(char, u32), Option<GlyphBitmap>>;
+type CharGlyphCache = HashMap<(FaceId, char, u32), Option<GlyphBitmap>>;
 
 fn cache() -> &'static Mutex<CharGlyphCache> {
     static CACHE: OnceLock<Mutex<CharGlyphCache>> = OnceLock::new();
     CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-fn rasterise(ch: char, px: f32) -> Option<GlyphBitmap> {
-    let font = font();
+fn rasterise(font: &FontRef<'static>, ch: char, px: f32) -> Option<GlyphBitmap> {
     let scaled = font.as_scaled(PxScale::from(px));
     let glyph_id = font.glyph_id(ch);
     let glyph = glyph_id.with_scale(PxScale::from(px));
@@ -71,18 +188,144 @@ fn rasterise(ch: char, px: f32) -> Option<GlyphBitmap> {
     })
 }
 
+fn advance(face: FaceId, ch: char, px: f32) -> f32 {
+    let font = font_of(face);
+    font.as_scaled(PxScale::from(px))
+        .h_advance(font.glyph_id(ch))
+}
+
 pub(crate) fn measure(text: &str, px: f32) -> f32 {
-    let font = font();
-    let scaled = font.as_scaled(PxScale::from(px));
-    text.chars()
-        .map(|c| scaled.h_advance(font.glyph_id(c)))
-        .sum()
+    text.chars().map(|c| advance(HACK, c, px)).sum()
 }
 
 pub(crate) fn ascent(px: f32) -> f32 {
-    let font = font();
-    let scaled = font.as_scaled(PxScale::from(px));
-    scaled.ascent()
+    font_of(HACK).as_scaled(PxScale::from(px)).ascent()
+}
+
+/// Ascent and baseline-to-baseline advance of `style`'s face, in pixels.
+pub(crate) fn line_metrics(style: &TextStyle) -> (f32, f32) {
+    let scaled = font_of(style_face(style)).as_scaled(PxScale::from(style.size));
+    (scaled.ascent(), scaled.height() + scaled.line_gap())
+}
+
+/// Advance width of a single line of `text` in `style`.
+pub(crate) fn line_width(line: &str, style: &TextStyle) -> f32 {
+    let face = style_face(style);
+    line.chars()
+        .map(|c| advance(face_for(face, c), c, style.size))
+        .sum()
+}
+
+/// Width and height of the glyph block of `text`, one line per `\n`.
+pub(crate) fn text_size(text: &str, style: &TextStyle) -> (f32, f32) {
+    let scaled = font_of(style_face(style)).as_scaled(PxScale::from(style.size));
+    let (_, line) = line_metrics(style);
+    let width = text
+        .split('\n')
+        .map(|l| line_width(l, style))
+        .fold(0.0, f32::max);
+    let lines = text.split('\n').count().max(1);
+    (width, (lines - 1) as f32 * line + scaled.height())
+}
+
+/// Greedy word wrap of `text` to `max_width` pixels. Existing line breaks
+/// stay; a word wider than a line is split between characters. Trailing
+/// spaces of the last line are kept so a caret lands after them.
+pub(crate) fn wrap(text: &str, style: &TextStyle, max_width: f32) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, paragraph) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let mut line = String::new();
+        for word in paragraph.split_inclusive(' ') {
+            let candidate = format!("{line}{word}");
+            if line.is_empty() || line_width(candidate.trim_end(), style) <= max_width {
+                line = candidate;
+            } else {
+                out.push_str(line.trim_end());
+                out.push('\n');
+                line = word.to_owned();
+            }
+            while line_width(line.trim_end(), style) > max_width && line.chars().count() > 1 {
+                let mut cut = line.chars().next().map_or(0, char::len_utf8);
+                for (at, _) in line.char_indices().skip(2) {
+                    if line_width(&line[..at], style) > max_width {
+                        break;
+                    }
+                    cut = at;
+                }
+                out.push_str(&line[..cut]);
+                out.push('\n');
+                line = line[cut..].to_owned();
+            }
+        }
+        out.push_str(&line);
+    }
+    out
+}
+
+/// Coverage of a laid-out text block, glyphs and underline, with `margin`
+/// empty pixels on every side. The block's top-left sits at
+/// `(margin, margin)`.
+pub(crate) struct TextMask {
+    pub width: u32,
+    pub height: u32,
+    pub margin: u32,
+    pub pixels: Vec<u8>,
+}
+
+pub(crate) fn text_mask(text: &str, style: &TextStyle, margin: u32) -> TextMask {
+    let (w, h) = text_size(text, style);
+    let (ascent, line) = line_metrics(style);
+    let width = w.ceil() as u32 + margin * 2;
+    let height = h.ceil() as u32 + margin * 2;
+    let mut pixels = vec![0u8; (width * height) as usize];
+    let mut plot = |x: i32, y: i32, c: u8| {
+        if x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height {
+            let idx = (y as u32 * width + x as u32) as usize;
+            pixels[idx] = pixels[idx].max(c);
+        }
+    };
+    let face = style_face(style);
+    let m = margin as f32;
+    for (row, text_line) in text.split('\n').enumerate() {
+        let baseline = m + ascent + row as f32 * line;
+        let mut pen_x = m;
+        for ch in text_line.chars() {
+            let f = face_for(face, ch);
+            if let Some(glyph) = glyph_for(f, ch, style.size) {
+                let gx0 = pen_x + glyph.bearing_x as f32;
+                let gy0 = baseline + glyph.bearing_y as f32;
+                for gy in 0..glyph.height {
+                    for gx in 0..glyph.width {
+                        let coverage = glyph.pixels[(gy * glyph.width + gx) as usize];
+                        if coverage > 0 {
+                            let dx = (gx0 + gx as f32).round() as i32;
+                            let dy = (gy0 + gy as f32).round() as i32;
+                            plot(dx, dy, coverage);
+                        }
+                    }
+                }
+            }
+            pen_x += advance(f, ch, style.size);
+        }
+        if style.underline && pen_x > m {
+            let top = (baseline + style.size * 0.1).round() as i32;
+            let thickness = (style.size / 16.0).round().max(1.0) as i32;
+            for y in top..top + thickness {
+                for x in m.round() as i32..pen_x.round() as i32 {
+                    plot(x, y, 255);
+                }
+            }
+        }
+    }
+    TextMask {
+        width,
+        height,
+        margin,
+        pixels,
+    }
 }
 
 /// Alpha-blend `text` onto an `RgbaImage` at top-left `(x, y)`, modulating the
@@ -101,10 +344,10 @@ pub(crate) fn draw_text_rgba(
     let (iw, ih) = img.dimensions();
     let mut pen_x = x as f32;
     for ch in text.chars() {
-        let glyph = match glyph_for(ch, px) {
+        let glyph = match glyph_for(HACK, ch, px) {
             Some(g) => g,
             None => {
-                pen_x += measure(&ch.to_string(), px);
+                pen_x += advance(HACK, ch, px);
                 continue;
             }
         };
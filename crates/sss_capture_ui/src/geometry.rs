@@ -1,8 +1,12 @@
 //! Floating-point geometry helpers for interactive editing.
 
+use std::fmt;
+use std::str::FromStr;
+
 use sss_capture::{Point, Rect};
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPoint {
     pub x: f32,
     pub y: f32,
@@ -83,3 +87,435 @@ impl From<Rect> for FRect {
         )
     }
 }
+
+/// Move `r` along `(dx, dy)` (only the signs matter) until its leading edge
+/// lands on the nearest of `xs` / `ys` beyond it; with `stretch` only that
+/// edge moves. Unchanged when there is no edge in that direction.
+pub fn snap_to_edge(r: Rect, xs: &[i32], ys: &[i32], (dx, dy): (i32, i32), stretch: bool) -> Rect {
+    let (mut x0, mut y0, mut x1, mut y1) = (r.x(), r.y(), r.right(), r.bottom());
+    let snap = |lo: &mut i32, hi: &mut i32, edges: &[i32], sign: i32| {
+        let lead = if sign > 0 { *hi } else { *lo };
+        let Some(target) = edges
+            .iter()
+            .copied()
+            .filter(|&e| (e - lead) * sign > 0)
+            .min_by_key(|&e| (e - lead).abs())
+        else {
+            return;
+        };
+        let delta = target - lead;
+        match (sign > 0, stretch) {
+            (true, true) => *hi += delta,
+            (false, true) => *lo += delta,
+            _ => {
+                *lo += delta;
+                *hi += delta;
+            }
+        }
+    };
+    if dx != 0 {
+        snap(&mut x0, &mut x1, xs, dx.signum());
+    }
+    if dy != 0 {
+        snap(&mut y0, &mut y1, ys, dy.signum());
+    }
+    Rect::from_xywh(x0, y0, (x1 - x0) as u32, (y1 - y0) as u32)
+}
+
+/// Parse a region written `x,y WxH`, e.g. `100,40 800x600`. Width and
+/// height must be non-zero.
+pub fn parse_xywh(s: &str) -> Option<Rect> {
+    let (pos, size) = s.trim().rsplit_once(char::is_whitespace)?;
+    let (x, y) = pos.split_once(',')?;
+    let (w, h) = size.trim().split_once(['x', 'X'])?;
+    let (w, h): (u32, u32) = (w.trim().parse().ok()?, h.trim().parse().ok()?);
+    if w == 0 || h == 0 {
+        return None;
+    }
+    Some(Rect::from_xywh(
+        x.trim().parse().ok()?,
+        y.trim().parse().ok()?,
+        w,
+        h,
+    ))
+}
+
+/// Shape the area selection is held to, written `16:9` for a ratio or
+/// `1280x720` for an exact size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RegionPreset {
+    /// Width : height.
+    Ratio(u32, u32),
+    /// Width × height in pixels.
+    Size(u32, u32),
+}
+
+impl RegionPreset {
+    /// Rectangle dragged from `anchor` towards `to`: a ratio grows with
+    /// whichever axis leads, a fixed size hangs off the anchor on the
+    /// pointer's side.
+    pub fn drag(self, anchor: FPoint, to: FPoint) -> FRect {
+        let (dx, dy) = (to.x - anchor.x, to.y - anchor.y);
+        let (w, h) = match self {
+            RegionPreset::Ratio(rw, rh) => {
+                let ratio = rw as f32 / rh as f32;
+                let w = dx.abs().max(dy.abs() * ratio);
+                (w, w / ratio)
+            }
+            RegionPreset::Size(w, h) => (w as f32, h as f32),
+        };
+        let x = if dx < 0.0 { anchor.x - w } else { anchor.x };
+        let y = if dy < 0.0 { anchor.y - h } else { anchor.y };
+        FRect::new(x, y, w, h)
+    }
+
+    /// `r` reshaped about its centre; a ratio keeps the width.
+    pub fn conform(self, r: Rect) -> Rect {
+        let (w, h) = match self {
+            RegionPreset::Ratio(rw, rh) => (r.width(), ratio_side(r.width(), rh, rw)),
+            RegionPreset::Size(w, h) => (w, h),
+        };
+        let cx = r.x() + r.width() as i32 / 2;
+        let cy = r.y() + r.height() as i32 / 2;
+        Rect::from_xywh(cx - w as i32 / 2, cy - h as i32 / 2, w, h)
+    }
+}
+
+/// `len * num / den`, at least 1, computed wide so large regions and
+/// ratios don't overflow.
+pub(crate) fn ratio_side(len: u32, num: u32, den: u32) -> u32 {
+    (len as u64 * num as u64 / den.max(1) as u64).clamp(1, u32::MAX as u64) as u32
+}
+
+impl fmt::Display for RegionPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionPreset::Ratio(w, h) => write!(f, "{w}:{h}"),
+            RegionPreset::Size(w, h) => write!(f, "{w}x{h}"),
+        }
+    }
+}
+
+impl FromStr for RegionPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |a: &str, b: &str| -> Option<(u32, u32)> {
+            let (a, b) = (a.trim().parse().ok()?, b.trim().parse().ok()?);
+            (a > 0 && b > 0).then_some((a, b))
+        };
+        let preset = if let Some((w, h)) = s.split_once(':') {
+            parse(w, h).map(|(w, h)| RegionPreset::Ratio(w, h))
+        } else {
+            s.split_once(['x', 'X'])
+                .and_then(|(w, h)| parse(w, h))
+                .map(|(w, h)| RegionPreset::Size(w, h))
+        };
+        preset.ok_or_else(|| format!("invalid region preset {s:?} (expected W:H or WxH)"))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RegionPreset {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RegionPreset {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let s: String = serde::Deserialize::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// Edge or centre line that [`align_offsets`] lines shapes up on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Align {
+    Left,
+    /// Horizontal centre.
+    Center,
+    Right,
+    Top,
+    /// Vertical centre.
+    Middle,
+    Bottom,
+}
+
+/// Axis along which [`distribute_offsets`] spaces shapes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Distribute {
+    Horizontal,
+    Vertical,
+}
+
+/// How far to move each of `items` so it sits on the `align` line of
+/// their joint bounds.
+pub fn align_offsets(items: &[Rect], align: Align) -> Vec<(i32, i32)> {
+    let Some(all) = Rect::bounding(items) else {
+        return Vec::new();
+    };
+    let mid = |lo: i32, len: u32| lo + len as i32 / 2;
+    items
+        .iter()
+        .map(|r| match align {
+            Align::Left => (all.x() - r.x(), 0),
+            Align::Center => (mid(all.x(), all.width()) - mid(r.x(), r.width()), 0),
+            Align::Right => (all.right() - r.right(), 0),
+            Align::Top => (0, all.y() - r.y()),
+            Align::Middle => (0, mid(all.y(), all.height()) - mid(r.y(), r.height())),
+            Align::Bottom => (0, all.bottom() - r.bottom()),
+        })
+        .collect()
+}
+
+/// How far to move each of `items` so equal gaps separate neighbours along
+/// `axis`. The first and last item stay put; with fewer than three nothing
+/// moves.
+pub fn distribute_offsets(items: &[Rect], axis: Distribute) -> Vec<(i32, i32)> {
+    let mut offsets = vec![(0, 0); items.len()];
+    if items.len() < 3 {
+        return offsets;
+    }
+    let span = |r: &Rect| match axis {
+        Distribute::Horizontal => (r.x(), r.width() as i32),
+        Distribute::Vertical => (r.y(), r.height() as i32),
+    };
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by_key(|&i| span(&items[i]).0);
+    let (start, _) = span(&items[order[0]]);
+    let (last, last_len) = span(&items[order[order.len() - 1]]);
+    let filled: i32 = order.iter().map(|&i| span(&items[i]).1).sum();
+    let gap = (last + last_len - start - filled) as f32 / (items.len() - 1) as f32;
+    let mut pos = start as f32;
+    for &i in &order {
+        let (at, len) = span(&items[i]);
+        let delta = pos.round() as i32 - at;
+        offsets[i] = match axis {
+            Distribute::Horizontal => (delta, 0),
+            Distribute::Vertical => (0, delta),
+        };
+        pos += len as f32 + gap;
+    }
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(x: i32, y: i32, w: u32, h: u32) -> Rect {
+        Rect::from_xywh(x, y, w, h)
+    }
+
+    #[test]
+    fn parse_xywh_reads_position_and_size() {
+        assert_eq!(parse_xywh("100,40 800x600"), Some(r(100, 40, 800, 600)));
+        assert_eq!(
+            parse_xywh("  -1920,-10   1920X1080 "),
+            Some(r(-1920, -10, 1920, 1080))
+        );
+        assert_eq!(parse_xywh("5, 6 7x8"), Some(r(5, 6, 7, 8)));
+    }
+
+    #[test]
+    fn parse_xywh_rejects_empty_sizes_and_junk() {
+        for junk in [
+            "",
+            "0,0 0x10",
+            "0,0 10x0",
+            "100,40",
+            "800x600",
+            "1,2 -3x4",
+            "1,2 3x",
+            "1;2 3x4",
+            "a,b 1x1",
+            "1,2 3 x 4",
+            "1,2,3 4x5",
+        ] {
+            assert_eq!(parse_xywh(junk), None, "{junk:?}");
+        }
+    }
+
+    #[test]
+    fn region_presets_parse_and_display() {
+        assert_eq!("16:9".parse(), Ok(RegionPreset::Ratio(16, 9)));
+        assert_eq!(" 4 : 3 ".parse(), Ok(RegionPreset::Ratio(4, 3)));
+        assert_eq!("1280x720".parse(), Ok(RegionPreset::Size(1280, 720)));
+        assert_eq!("1280X720".parse(), Ok(RegionPreset::Size(1280, 720)));
+        for preset in [RegionPreset::Ratio(21, 9), RegionPreset::Size(800, 600)] {
+            assert_eq!(preset.to_string().parse(), Ok(preset));
+        }
+        assert_eq!(RegionPreset::Size(800, 600).to_string(), "800x600");
+        for junk in [
+            "", "16", "0:9", "16:0", "-1:2", "0x720", "1280x", "a:b", "4:3:2",
+        ] {
+            assert!(junk.parse::<RegionPreset>().is_err(), "{junk:?}");
+        }
+    }
+
+    #[test]
+    fn ratio_drag_follows_the_leading_axis_on_the_pointer_side() {
+        let ratio = RegionPreset::Ratio(2, 1);
+        let anchor = FPoint::new(100.0, 100.0);
+        assert_eq!(
+            ratio.drag(anchor, FPoint::new(260.0, 120.0)),
+            FRect::new(100.0, 100.0, 160.0, 80.0)
+        );
+        assert_eq!(
+            ratio.drag(anchor, FPoint::new(40.0, 0.0)),
+            FRect::new(-100.0, 0.0, 200.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn size_drag_hangs_off_the_anchor() {
+        let size = RegionPreset::Size(300, 200);
+        let anchor = FPoint::new(50.0, 50.0);
+        assert_eq!(
+            size.drag(anchor, FPoint::new(51.0, 51.0)),
+            FRect::new(50.0, 50.0, 300.0, 200.0)
+        );
+        assert_eq!(
+            size.drag(anchor, FPoint::new(10.0, 60.0)),
+            FRect::new(-250.0, 50.0, 300.0, 200.0)
+        );
+    }
+
+    #[test]
+    fn conform_reshapes_about_the_centre() {
+        assert_eq!(
+            RegionPreset::Ratio(16, 9).conform(r(0, 0, 1600, 100)),
+            r(0, -400, 1600, 900)
+        );
+        assert_eq!(
+            RegionPreset::Size(100, 50).conform(r(0, 0, 200, 200)),
+            r(50, 75, 100, 50)
+        );
+        // Wide enough that width × 99999 no longer fits a u32.
+        assert_eq!(
+            RegionPreset::Ratio(100_000, 99_999).conform(r(0, 0, 60_000, 10)),
+            r(0, -29_994, 60_000, 59_999)
+        );
+    }
+
+    #[test]
+    fn ratio_side_saturates_instead_of_overflowing() {
+        assert_eq!(ratio_side(1920, 9, 16), 1080);
+        assert_eq!(ratio_side(1, 1, 16), 1);
+        assert_eq!(ratio_side(u32::MAX, u32::MAX, 1), u32::MAX);
+    }
+
+    const XS: [i32; 4] = [0, 120, 200, 300];
+    const YS: [i32; 3] = [0, 90, 180];
+
+    #[test]
+    fn snap_moves_the_region_to_the_next_edge() {
+        let region = r(100, 100, 50, 50);
+        let snap = |dir| snap_to_edge(region, &XS, &YS, dir, false);
+        assert_eq!(snap((1, 0)), r(150, 100, 50, 50));
+        assert_eq!(snap((-1, 0)), r(0, 100, 50, 50));
+        assert_eq!(snap((0, -1)), r(100, 90, 50, 50));
+        assert_eq!(snap((0, 1)), r(100, 130, 50, 50));
+        // Only the signs count, and both axes snap at once.
+        assert_eq!(snap((7, -3)), r(150, 90, 50, 50));
+    }
+
+    #[test]
+    fn stretch_moves_only_the_leading_edge() {
+        let region = r(100, 100, 50, 50);
+        let stretch = |dir| snap_to_edge(region, &XS, &YS, dir, true);
+        assert_eq!(stretch((1, 0)), r(100, 100, 100, 50));
+        assert_eq!(stretch((-1, 0)), r(0, 100, 150, 50));
+        assert_eq!(stretch((0, -1)), r(100, 90, 50, 60));
+        assert_eq!(stretch((0, 1)), r(100, 100, 50, 80));
+    }
+
+    #[test]
+    fn snap_skips_edges_already_reached_and_stops_at_the_last() {
+        // The right edge sits on 200, so the next stop is 300.
+        let region = r(150, 100, 50, 50);
+        assert_eq!(
+            snap_to_edge(region, &XS, &YS, (1, 0), false),
+            r(250, 100, 50, 50)
+        );
+        let region = r(250, 0, 50, 50);
+        assert_eq!(snap_to_edge(region, &XS, &YS, (1, 0), true), region);
+        assert_eq!(snap_to_edge(region, &XS, &YS, (0, -1), false), region);
+        assert_eq!(snap_to_edge(region, &[], &[], (1, 1), false), region);
+    }
+
+    #[test]
+    fn align_moves_every_item_onto_the_shared_edge() {
+        let items = [r(10, 0, 20, 10), r(40, 30, 50, 10), r(0, 60, 5, 10)];
+        assert_eq!(
+            align_offsets(&items, Align::Left),
+            [(-10, 0), (-40, 0), (0, 0)]
+        );
+        assert_eq!(
+            align_offsets(&items, Align::Right),
+            [(60, 0), (0, 0), (85, 0)]
+        );
+        assert_eq!(
+            align_offsets(&items, Align::Top),
+            [(0, 0), (0, -30), (0, -60)]
+        );
+        assert_eq!(
+            align_offsets(&items, Align::Bottom),
+            [(0, 60), (0, 30), (0, 0)]
+        );
+    }
+
+    #[test]
+    fn align_centre_rounds_odd_widths_down() {
+        // Bounds are 0..11 wide, centre 5; the 4 px item centres at 2.
+        let items = [r(0, 0, 11, 11), r(0, 20, 4, 4)];
+        assert_eq!(align_offsets(&items, Align::Center), [(0, 0), (3, 0)]);
+        // Bounds are 0..24 tall, centre 12; the items centre at 5 and 22.
+        assert_eq!(align_offsets(&items, Align::Middle), [(0, 7), (0, -10)]);
+    }
+
+    #[test]
+    fn align_leaves_a_single_item_alone() {
+        assert!(align_offsets(&[], Align::Left).is_empty());
+        assert_eq!(align_offsets(&[r(5, 5, 10, 10)], Align::Center), [(0, 0)]);
+    }
+
+    #[test]
+    fn distribute_needs_three_items() {
+        assert!(distribute_offsets(&[], Distribute::Horizontal).is_empty());
+        let two = [r(0, 0, 10, 10), r(100, 0, 10, 10)];
+        assert_eq!(
+            distribute_offsets(&two, Distribute::Horizontal),
+            [(0, 0), (0, 0)]
+        );
+    }
+
+    #[test]
+    fn distribute_evens_the_gaps_between_the_outer_items() {
+        // Given out of order: the outer items stay, the middle one lands
+        // with 20 px on either side.
+        let items = [r(0, 100, 10, 10), r(0, 0, 10, 20), r(0, 15, 10, 40)];
+        assert_eq!(
+            distribute_offsets(&items, Distribute::Vertical),
+            [(0, 0), (0, 0), (0, 25)]
+        );
+        let items = [r(0, 0, 10, 10), r(12, 0, 10, 10), r(100, 0, 10, 10)];
+        assert_eq!(
+            distribute_offsets(&items, Distribute::Horizontal),
+            [(0, 0), (38, 0), (0, 0)]
+        );
+    }
+
+    #[test]
+    fn distribute_spreads_overlapping_items_with_a_negative_gap() {
+        // 120 px of items in a 70 px span overlap by 25 px each time.
+        let items = [r(0, 0, 40, 10), r(20, 0, 40, 10), r(30, 0, 40, 10)];
+        assert_eq!(
+            distribute_offsets(&items, Distribute::Horizontal),
+            [(0, 0), (-5, 0), (0, 0)]
+        );
+    }
+}
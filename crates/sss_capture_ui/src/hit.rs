@@ -1,7 +1,7 @@
 //! Pointer hit-testing against shapes.
 
 use crate::geometry::FPoint;
-use crate::shape::{Shape, ShapeKind};
+use crate::shape::{Shape, ShapeKind, SpotlightShape};
 
 /// Click-tolerance in pixels around thin shapes.
 const STROKE_PAD: f32 = 5.0;
@@ -10,10 +10,13 @@ pub fn shape_hit(shape: &Shape, p: FPoint) -> bool {
     use ShapeKind::*;
     let pad = shape.style.stroke_width.max(STROKE_PAD);
     match &shape.kind {
-        FreehandStroke { points } => points
+        FreehandStroke { points, .. } | Highlight { points } => points
+            .windows(2)
+            .any(|s| dist_point_to_segment(p, s[0], s[1]) <= pad),
+        Line { from, to } => dist_point_to_segment(p, *from, *to) <= pad,
+        Arrow { from, to, bend } => crate::shape::arrow_path(*from, *to, *bend)
             .windows(2)
             .any(|s| dist_point_to_segment(p, s[0], s[1]) <= pad),
-        Line { from, to } | Arrow { from, to } => dist_point_to_segment(p, *from, *to) <= pad,
         Rectangle { rect } | BlurRect { rect, .. } => {
             if shape.style.fill.is_some() {
                 rect_contains(rect, p)
@@ -21,6 +24,28 @@ pub fn shape_hit(shape: &Shape, p: FPoint) -> bool {
                 rect_outline_hit(rect, p, pad)
             }
         }
+        // A redaction is solid either way; grab it anywhere inside.
+        Pixelate { rect, .. } => rect_contains(rect, p),
+        Spotlight {
+            rect,
+            shape: SpotlightShape::Rect,
+            ..
+        } => rect_contains(rect, p),
+        Spotlight { rect, .. } => ellipse_contains(rect, p),
+        Magnify {
+            source,
+            target,
+            zoom,
+        } => {
+            rect_contains(source, p)
+                || rect_contains(&crate::shape::magnified_rect(*source, *target, *zoom), p)
+        }
+        // Grabbed by its lines; a line's label sits on it anyway.
+        Measure {
+            from, to, boxed, ..
+        } => crate::shape::measure_segments(*from, *to, *boxed)
+            .iter()
+            .any(|&(a, b)| dist_point_to_segment(p, a, b) <= pad),
         Ellipse { rect } => {
             if shape.style.fill.is_some() {
                 ellipse_contains(rect, p)
@@ -29,14 +54,14 @@ pub fn shape_hit(shape: &Shape, p: FPoint) -> bool {
             }
         }
         Step { center, radius, .. } => p.distance(*center) <= *radius + pad,
-        Text {
-            origin,
-            content,
-            style,
-        } => {
-            let w = content.chars().count() as f32 * style.size * 0.55;
-            let h = style.size;
-            p.x >= origin.x && p.x <= origin.x + w && p.y >= origin.y && p.y <= origin.y + h
+        Text { .. } => rect_contains(&shape.kind.bounds(), p),
+        Callout { body, anchor, .. } => {
+            rect_contains(body, p)
+                || p.distance(*anchor) <= pad
+                || point_in_polygon(&crate::shape::callout_outline(*body, *anchor), p)
+        }
+        Image { rect, .. } => {
+            point_in_polygon(&crate::shape::image_corners(*rect, shape.rotation), p)
         }
         Polygon { points, closed } => {
             if shape.style.fill.is_some() && *closed && point_in_polygon(points, p) {
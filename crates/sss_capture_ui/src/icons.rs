@@ -16,6 +16,7 @@ const ICON_SIZE: u32 = 20;
 pub enum ToolbarIcon {
     Pointer,
     Brush,
+    Highlighter,
     Line,
     Arrow,
     Rectangle,
@@ -23,9 +24,16 @@ pub enum ToolbarIcon {
     Ellipse,
     EllipseFilled,
     Blur,
+    Pixelate,
+    Redact,
+    Spotlight,
+    SpotlightEllipse,
+    Magnify,
     Eraser,
     Step,
     Text,
+    Callout,
+    Measure,
     Polygon,
     PolygonFilled,
     Undo,
@@ -39,10 +47,21 @@ pub enum ToolbarIcon {
     Pipette,
     Snap,
     Magnifier,
+    Sticker,
     Border,
     Raise,
     Lower,
     Trash,
+    Group,
+    Ungroup,
+    AlignLeft,
+    AlignCenter,
+    AlignRight,
+    AlignTop,
+    AlignMiddle,
+    AlignBottom,
+    DistributeHorizontal,
+    DistributeVertical,
     GizmoScale,
     GizmoRotate,
 }
@@ -57,6 +76,7 @@ fn raw_svg(icon: ToolbarIcon) -> &'static [u8] {
     match icon {
         ToolbarIcon::Pointer => icon_bytes!("pointer"),
         ToolbarIcon::Brush => icon_bytes!("brush"),
+        ToolbarIcon::Highlighter => icon_bytes!("highlighter"),
         ToolbarIcon::Line => icon_bytes!("line"),
         ToolbarIcon::Arrow => icon_bytes!("arrow"),
         ToolbarIcon::Rectangle => icon_bytes!("rectangle"),
@@ -64,9 +84,16 @@ fn raw_svg(icon: ToolbarIcon) -> &'static [u8] {
         ToolbarIcon::Ellipse => icon_bytes!("ellipse"),
         ToolbarIcon::EllipseFilled => icon_bytes!("ellipse_filled"),
         ToolbarIcon::Blur => icon_bytes!("blur"),
+        ToolbarIcon::Pixelate => icon_bytes!("pixelate"),
+        ToolbarIcon::Redact => icon_bytes!("redact"),
+        ToolbarIcon::Spotlight => icon_bytes!("spotlight"),
+        ToolbarIcon::SpotlightEllipse => icon_bytes!("spotlight_ellipse"),
+        ToolbarIcon::Magnify => icon_bytes!("magnify"),
         ToolbarIcon::Eraser => icon_bytes!("eraser"),
         ToolbarIcon::Step => icon_bytes!("step"),
         ToolbarIcon::Text => icon_bytes!("text"),
+        ToolbarIcon::Callout => icon_bytes!("callout"),
+        ToolbarIcon::Measure => icon_bytes!("measure"),
         ToolbarIcon::Polygon => icon_bytes!("polygon"),
         ToolbarIcon::PolygonFilled => icon_bytes!("polygon_filled"),
         ToolbarIcon::Undo => icon_bytes!("undo"),
@@ -80,10 +107,21 @@ fn raw_svg(icon: ToolbarIcon) -> &'static [u8] {
         ToolbarIcon::Pipette => icon_bytes!("pipette"),
         ToolbarIcon::Snap => icon_bytes!("snap"),
         ToolbarIcon::Magnifier => icon_bytes!("magnifier"),
+        ToolbarIcon::Sticker => icon_bytes!("sticker"),
         ToolbarIcon::Border => icon_bytes!("border"),
         ToolbarIcon::Raise => icon_bytes!("raise"),
         ToolbarIcon::Lower => icon_bytes!("lower"),
         ToolbarIcon::Trash => icon_bytes!("trash"),
+        ToolbarIcon::Group => icon_bytes!("group"),
+        ToolbarIcon::Ungroup => icon_bytes!("ungroup"),
+        ToolbarIcon::AlignLeft => icon_bytes!("align_left"),
+        ToolbarIcon::AlignCenter => icon_bytes!("align_center"),
+        ToolbarIcon::AlignRight => icon_bytes!("align_right"),
+        ToolbarIcon::AlignTop => icon_bytes!("align_top"),
+        ToolbarIcon::AlignMiddle => icon_bytes!("align_middle"),
+        ToolbarIcon::AlignBottom => icon_bytes!("align_bottom"),
+        ToolbarIcon::DistributeHorizontal => icon_bytes!("distribute_horizontal"),
+        ToolbarIcon::DistributeVertical => icon_bytes!("distribute_vertical"),
         ToolbarIcon::GizmoScale => icon_bytes!("gizmo_scale"),
         ToolbarIcon::GizmoRotate => icon_bytes!("gizmo_rotate"),
     }
@@ -178,14 +216,20 @@ pub fn tool_icon(t: &crate::tool::Tool) -> ToolbarIcon {
     match t {
         Tool::Pointer => ToolbarIcon::Pointer,
         Tool::Brush(_) => ToolbarIcon::Brush,
+        Tool::Highlighter(_) => ToolbarIcon::Highlighter,
         Tool::Line(_) => ToolbarIcon::Line,
         Tool::Arrow(_) => ToolbarIcon::Arrow,
         Tool::Rectangle(_) => ToolbarIcon::Rectangle,
         Tool::Ellipse(_) => ToolbarIcon::Ellipse,
         Tool::BlurRect { .. } => ToolbarIcon::Blur,
+        Tool::Pixelate { .. } => ToolbarIcon::Pixelate,
+        Tool::Spotlight { .. } => ToolbarIcon::Spotlight,
+        Tool::Magnify { .. } => ToolbarIcon::Magnify,
         Tool::Eraser { .. } => ToolbarIcon::Eraser,
         Tool::Step(_) => ToolbarIcon::Step,
         Tool::Text(_) => ToolbarIcon::Text,
+        Tool::Callout { .. } => ToolbarIcon::Callout,
+        Tool::Measure { .. } => ToolbarIcon::Measure,
         Tool::Polygon(_) => ToolbarIcon::Polygon,
     }
 }
@@ -196,6 +240,7 @@ pub fn filled_tool_icon(t: &crate::tool::Tool) -> ToolbarIcon {
         Tool::Rectangle(_) => ToolbarIcon::RectangleFilled,
         Tool::Ellipse(_) => ToolbarIcon::EllipseFilled,
         Tool::Polygon(_) => ToolbarIcon::PolygonFilled,
+        Tool::Pixelate { .. } => ToolbarIcon::Redact,
         _ => tool_icon(t),
     }
 }
@@ -208,8 +253,13 @@ pub fn set_active_tool_width(t: &mut crate::tool::Tool, width: f32) {
         | Tool::Arrow(b)
         | Tool::Rectangle(b)
         | Tool::Ellipse(b)
-        | Tool::Polygon(b) => b.width = width,
+        | Tool::Polygon(b)
+        | Tool::Magnify { border: b, .. }
+        | Tool::Callout { brush: b, .. } => b.width = width,
+        // A marker wants to cover a line of text at the default width.
+        Tool::Highlighter(b) => b.width = width * 6.0,
         Tool::Step(s) => s.radius = (width * 4.0 + 4.0).max(6.0),
+        Tool::Pixelate { block_size } => *block_size = (width * 4.0).round().max(2.0) as u32,
         _ => {}
     }
 }
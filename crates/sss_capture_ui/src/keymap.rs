@@ -0,0 +1,574 @@
+//! Keyboard shortcuts of the overlay: key chords mapped to editor
+//! actions, configured through `UiConfig::keybindings`.
+//!
+//! Chords are written `ctrl+shift+z`, `alt+f4`, `h`, `space`; actions in
+//! kebab-case, e.g. `undo`, `tool-3`, `mode-window`, `nudge-left-10`,
+//! `resize-right`, `snap-up`, `align-left`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::geometry::{Align, Distribute};
+use crate::mode::SelectorMode;
+use crate::trigger::{KeyBind, KeyChord};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum KeymapError {
+    #[error("unknown key {0:?}")]
+    Key(String),
+    #[error("unknown modifier {0:?}")]
+    Modifier(String),
+    #[error("unknown action {0:?}")]
+    Action(String),
+}
+
+/// Direction of a [`KeyAction::Nudge`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// Unit offset in screen coordinates (y grows downwards).
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Direction::Left => "left",
+            Direction::Right => "right",
+            Direction::Up => "up",
+            Direction::Down => "down",
+        }
+    }
+}
+
+/// What a key chord does in the overlay.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    /// Pick the tool at this 1-based position of the tool list.
+    SelectTool(usize),
+    Undo,
+    Redo,
+    Copy,
+    Save,
+    Confirm,
+    Cancel,
+    /// Paste a clipboard image onto the canvas.
+    Paste,
+    /// Delete the selected shapes.
+    Delete,
+    /// Erase the last character of the text being typed.
+    Backspace,
+    /// Select every shape.
+    SelectAll,
+    /// Copy the selected shapes next to themselves.
+    Duplicate,
+    /// Keep the selected shapes for [`KeyAction::PasteShapes`].
+    CopyShapes,
+    /// Add the shapes kept by [`KeyAction::CopyShapes`] again.
+    PasteShapes,
+    /// Group the selected shapes, or ungroup a selected group.
+    Group,
+    /// Line the selected shapes up on an edge or centre.
+    Align(Align),
+    /// Space the selected shapes evenly.
+    Distribute(Distribute),
+    /// Switch closed shapes between outline and filled.
+    ToggleFill,
+    /// Flip the output-border toggle of the action toolbar.
+    ToggleBorder,
+    /// Switch between area, monitor and window picking.
+    Mode(SelectorMode),
+    /// Move the selected shapes, or the region when no shape is selected,
+    /// by `step` pixels.
+    Nudge {
+        dir: Direction,
+        step: u32,
+    },
+    /// Grow the region's right / bottom edge by `step` pixels (`Right` /
+    /// `Down`), or shrink it (`Left` / `Up`).
+    Resize {
+        dir: Direction,
+        step: u32,
+    },
+    /// Move the region until its edge facing `dir` meets the next monitor
+    /// or window edge.
+    Snap(Direction),
+    /// Like [`KeyAction::Snap`], moving only that edge.
+    Stretch(Direction),
+    /// Open the field that takes the region as `x,y WxH`.
+    EditRegion,
+    /// In [`SelectorMode::AnyOf`], select the next monitor or window.
+    NextTarget,
+    PrevTarget,
+}
+
+impl fmt::Display for KeyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            KeyAction::SelectTool(n) => return write!(f, "tool-{n}"),
+            KeyAction::Undo => "undo",
+            KeyAction::Redo => "redo",
+            KeyAction::Copy => "copy",
+            KeyAction::Save => "save",
+            KeyAction::Confirm => "confirm",
+            KeyAction::Cancel => "cancel",
+            KeyAction::Paste => "paste",
+            KeyAction::Delete => "delete",
+            KeyAction::Backspace => "backspace",
+            KeyAction::SelectAll => "select-all",
+            KeyAction::Duplicate => "duplicate",
+            KeyAction::CopyShapes => "copy-shapes",
+            KeyAction::PasteShapes => "paste-shapes",
+            KeyAction::Group => "group",
+            KeyAction::Align(Align::Left) => "align-left",
+            KeyAction::Align(Align::Center) => "align-center",
+            KeyAction::Align(Align::Right) => "align-right",
+            KeyAction::Align(Align::Top) => "align-top",
+            KeyAction::Align(Align::Middle) => "align-middle",
+            KeyAction::Align(Align::Bottom) => "align-bottom",
+            KeyAction::Distribute(Distribute::Horizontal) => "distribute-horizontal",
+            KeyAction::Distribute(Distribute::Vertical) => "distribute-vertical",
+            KeyAction::ToggleFill => "toggle-fill",
+            KeyAction::ToggleBorder => "toggle-border",
+            KeyAction::Mode(SelectorMode::Monitor) => "mode-monitor",
+            KeyAction::Mode(SelectorMode::Window) => "mode-window",
+            KeyAction::Mode(_) => "mode-area",
+            KeyAction::Nudge { dir, step: 1 } => return write!(f, "nudge-{}", dir.name()),
+            KeyAction::Nudge { dir, step } => return write!(f, "nudge-{}-{step}", dir.name()),
+            KeyAction::Resize { dir, step: 1 } => return write!(f, "resize-{}", dir.name()),
+            KeyAction::Resize { dir, step } => return write!(f, "resize-{}-{step}", dir.name()),
+            KeyAction::Snap(dir) => return write!(f, "snap-{}", dir.name()),
+            KeyAction::Stretch(dir) => return write!(f, "stretch-{}", dir.name()),
+            KeyAction::EditRegion => "edit-region",
+            KeyAction::NextTarget => "next-target",
+            KeyAction::PrevTarget => "prev-target",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for KeyAction {
+    type Err = KeymapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || KeymapError::Action(s.to_owned());
+        let action = match s.trim().to_ascii_lowercase().as_str() {
+            "undo" => KeyAction::Undo,
+            "redo" => KeyAction::Redo,
+            "copy" => KeyAction::Copy,
+            "save" => KeyAction::Save,
+            "confirm" => KeyAction::Confirm,
+            "cancel" => KeyAction::Cancel,
+            "paste" => KeyAction::Paste,
+            "delete" => KeyAction::Delete,
+            "backspace" => KeyAction::Backspace,
+            "select-all" => KeyAction::SelectAll,
+            "duplicate" => KeyAction::Duplicate,
+            "copy-shapes" => KeyAction::CopyShapes,
+            "paste-shapes" => KeyAction::PasteShapes,
+            "group" => KeyAction::Group,
+            "align-left" => KeyAction::Align(Align::Left),
+            "align-center" => KeyAction::Align(Align::Center),
+            "align-right" => KeyAction::Align(Align::Right),
+            "align-top" => KeyAction::Align(Align::Top),
+            "align-middle" => KeyAction::Align(Align::Middle),
+            "align-bottom" => KeyAction::Align(Align::Bottom),
+            "distribute-horizontal" => KeyAction::Distribute(Distribute::Horizontal),
+            "distribute-vertical" => KeyAction::Distribute(Distribute::Vertical),
+            "toggle-fill" => KeyAction::ToggleFill,
+            "toggle-border" => KeyAction::ToggleBorder,
+            "mode-area" => KeyAction::Mode(SelectorMode::Area),
+            "mode-monitor" => KeyAction::Mode(SelectorMode::Monitor),
+            "mode-window" => KeyAction::Mode(SelectorMode::Window),
+            "edit-region" => KeyAction::EditRegion,
+            "next-target" => KeyAction::NextTarget,
+            "prev-target" => KeyAction::PrevTarget,
+            other => {
+                if let Some(n) = other.strip_prefix("tool-") {
+                    return match n.parse() {
+                        Ok(n) if n > 0 => Ok(KeyAction::SelectTool(n)),
+                        _ => Err(unknown()),
+                    };
+                }
+                let (name, rest) = other.split_once('-').ok_or_else(unknown)?;
+                let (dir, step) = match rest.split_once('-') {
+                    Some((dir, step)) => (dir, Some(step.parse().map_err(|_| unknown())?)),
+                    None => (rest, None),
+                };
+                let dir = match dir {
+                    "left" => Direction::Left,
+                    "right" => Direction::Right,
+                    "up" => Direction::Up,
+                    "down" => Direction::Down,
+                    _ => return Err(unknown()),
+                };
+                match (name, step) {
+                    ("nudge", step) => KeyAction::Nudge {
+                        dir,
+                        step: step.unwrap_or(1),
+                    },
+                    ("resize", step) => KeyAction::Resize {
+                        dir,
+                        step: step.unwrap_or(1),
+                    },
+                    ("snap", None) => KeyAction::Snap(dir),
+                    ("stretch", None) => KeyAction::Stretch(dir),
+                    _ => return Err(unknown()),
+                }
+            }
+        };
+        Ok(action)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (on, name) in [
+            (self.ctrl, "ctrl+"),
+            (self.shift, "shift+"),
+            (self.alt, "alt+"),
+            (self.meta, "meta+"),
+        ] {
+            if on {
+                f.write_str(name)?;
+            }
+        }
+        match self.key {
+            KeyBind::Enter => f.write_str("enter"),
+            KeyBind::Escape => f.write_str("esc"),
+            KeyBind::Space => f.write_str("space"),
+            KeyBind::Tab => f.write_str("tab"),
+            KeyBind::Delete => f.write_str("delete"),
+            KeyBind::Backspace => f.write_str("backspace"),
+            KeyBind::Left => f.write_str("left"),
+            KeyBind::Right => f.write_str("right"),
+            KeyBind::Up => f.write_str("up"),
+            KeyBind::Down => f.write_str("down"),
+            KeyBind::Char(c) => write!(f, "{c}"),
+            KeyBind::F(n) => write!(f, "f{n}"),
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = KeymapError;
+
+    /// `+`-separated modifiers followed by the key; `ctrl++` binds the
+    /// plus key itself.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (mods, key) = match s.strip_suffix("++") {
+            Some(mods) => (mods, "+"),
+            None if s == "+" => ("", "+"),
+            None => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+        let lower = key.to_lowercase();
+        let bind = match lower.as_str() {
+            "enter" | "return" => KeyBind::Enter,
+            "esc" | "escape" => KeyBind::Escape,
+            "space" => KeyBind::Space,
+            "tab" => KeyBind::Tab,
+            "delete" | "del" => KeyBind::Delete,
+            "backspace" => KeyBind::Backspace,
+            "left" => KeyBind::Left,
+            "right" => KeyBind::Right,
+            "up" => KeyBind::Up,
+            "down" => KeyBind::Down,
+            k => {
+                let mut chars = k.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyBind::Char(c),
+                    _ => match k.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                        Some(n @ 1..=24) => KeyBind::F(n),
+                        _ => return Err(KeymapError::Key(key.to_owned())),
+                    },
+                }
+            }
+        };
+        let mut chord = KeyChord::key(bind);
+        for m in mods.split('+').filter(|m| !m.is_empty()) {
+            match m.trim().to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.ctrl = true,
+                "shift" => chord.shift = true,
+                "alt" => chord.alt = true,
+                "meta" | "super" | "cmd" => chord.meta = true,
+                _ => return Err(KeymapError::Modifier(m.to_owned())),
+            }
+        }
+        Ok(chord)
+    }
+}
+
+/// The overlay's key chords and what each one does. User entries are
+/// laid over [`Keymap::default`]; binding a chord to `none` removes it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keymap(HashMap<KeyChord, KeyAction>);
+
+impl Keymap {
+    /// The action bound to `chord`. Shift is part of the character for
+    /// anything but letters, so `?` matches whatever layout produces it.
+    pub fn action(&self, chord: &KeyChord) -> Option<KeyAction> {
+        if let Some(action) = self.0.get(chord) {
+            return Some(*action);
+        }
+        match chord.key {
+            KeyBind::Char(c) if chord.shift && !c.is_alphabetic() => {
+                let mut plain = chord.clone();
+                plain.shift = false;
+                self.0.get(&plain).copied()
+            }
+            _ => None,
+        }
+    }
+
+    pub fn bind(&mut self, chord: KeyChord, action: KeyAction) {
+        self.0.insert(chord, action);
+    }
+
+    pub fn unbind(&mut self, chord: &KeyChord) {
+        self.0.remove(chord);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&KeyChord, &KeyAction)> {
+        self.0.iter()
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let key = |c| KeyChord::key(KeyBind::Char(c));
+        let mut map = HashMap::from([
+            (KeyBind::ESC, KeyAction::Cancel),
+            (KeyBind::ENTER, KeyAction::Confirm),
+            (KeyChord::key(KeyBind::Delete), KeyAction::Delete),
+            (KeyChord::key(KeyBind::Backspace), KeyAction::Backspace),
+            (key('z').ctrl(), KeyAction::Undo),
+            (key('z').ctrl().shift(), KeyAction::Redo),
+            (key('y').ctrl(), KeyAction::Redo),
+            (key('c').ctrl(), KeyAction::Copy),
+            (key('s').ctrl(), KeyAction::Save),
+            (key('v').ctrl(), KeyAction::Paste),
+            (key('g').ctrl(), KeyAction::EditRegion),
+            (key('a').ctrl(), KeyAction::SelectAll),
+            (key('d').ctrl(), KeyAction::Duplicate),
+            (key('c').ctrl().shift(), KeyAction::CopyShapes),
+            (key('v').ctrl().shift(), KeyAction::PasteShapes),
+            (key('g').ctrl().shift(), KeyAction::Group),
+            (KeyChord::key(KeyBind::Tab), KeyAction::NextTarget),
+            (KeyChord::key(KeyBind::Tab).shift(), KeyAction::PrevTarget),
+        ]);
+        for (n, c) in ('1'..='9').enumerate() {
+            map.insert(key(c), KeyAction::SelectTool(n + 1));
+        }
+        for (bind, dir) in [
+            (KeyBind::Left, Direction::Left),
+            (KeyBind::Right, Direction::Right),
+            (KeyBind::Up, Direction::Up),
+            (KeyBind::Down, Direction::Down),
+        ] {
+            let arrow = KeyChord::key(bind);
+            map.insert(arrow.clone(), KeyAction::Nudge { dir, step: 1 });
+            map.insert(arrow.clone().ctrl(), KeyAction::Nudge { dir, step: 10 });
+            map.insert(arrow.clone().shift(), KeyAction::Resize { dir, step: 1 });
+            map.insert(
+                arrow.clone().ctrl().shift(),
+                KeyAction::Resize { dir, step: 10 },
+            );
+            map.insert(arrow.clone().alt(), KeyAction::Snap(dir));
+            map.insert(arrow.alt().shift(), KeyAction::Stretch(dir));
+        }
+        Self(map)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Keymap {
+    /// The bindings as a chord → action table; removed defaults are
+    /// written as `none` so the table reads back to the same map.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut table: std::collections::BTreeMap<String, String> = self
+            .0
+            .iter()
+            .map(|(chord, action)| (chord.to_string(), action.to_string()))
+            .collect();
+        for chord in Keymap::default().0.keys() {
+            if !self.0.contains_key(chord) {
+                table.insert(chord.to_string(), "none".to_owned());
+            }
+        }
+        serde::Serialize::serialize(&table, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Keymap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let table: HashMap<String, String> = serde::Deserialize::deserialize(deserializer)?;
+        let mut map = Keymap::default();
+        for (chord, action) in table {
+            let chord: KeyChord = chord.parse().map_err(D::Error::custom)?;
+            if action.trim().eq_ignore_ascii_case("none") {
+                map.unbind(&chord);
+            } else {
+                map.bind(chord, action.parse().map_err(D::Error::custom)?);
+            }
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(s: &str) -> KeyChord {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn every_action_round_trips_through_its_name() {
+        let mut actions: Vec<KeyAction> = Keymap::default().iter().map(|(_, a)| *a).collect();
+        actions.extend([
+            KeyAction::ToggleFill,
+            KeyAction::ToggleBorder,
+            KeyAction::Mode(SelectorMode::Area),
+            KeyAction::Mode(SelectorMode::Monitor),
+            KeyAction::Mode(SelectorMode::Window),
+            KeyAction::Distribute(Distribute::Horizontal),
+            KeyAction::Distribute(Distribute::Vertical),
+            KeyAction::SelectTool(12),
+            KeyAction::Nudge {
+                dir: Direction::Up,
+                step: 25,
+            },
+        ]);
+        for align in [
+            Align::Left,
+            Align::Center,
+            Align::Right,
+            Align::Top,
+            Align::Middle,
+            Align::Bottom,
+        ] {
+            actions.push(KeyAction::Align(align));
+        }
+        for dir in [
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+            Direction::Down,
+        ] {
+            actions.extend([
+                KeyAction::Snap(dir),
+                KeyAction::Stretch(dir),
+                KeyAction::Resize { dir, step: 1 },
+            ]);
+        }
+        for action in actions {
+            assert_eq!(action.to_string().parse(), Ok(action), "{action}");
+        }
+    }
+
+    #[test]
+    fn action_names_are_case_insensitive_and_reject_junk() {
+        assert_eq!(" Copy-Shapes ".parse(), Ok(KeyAction::CopyShapes));
+        assert_eq!(
+            "nudge-left".parse(),
+            Ok(KeyAction::Nudge {
+                dir: Direction::Left,
+                step: 1
+            })
+        );
+        for junk in [
+            "frobnicate",
+            "tool-0",
+            "tool-x",
+            "snap-left-10",
+            "nudge-sideways",
+        ] {
+            assert_eq!(
+                junk.parse::<KeyAction>(),
+                Err(KeymapError::Action(junk.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn modifiers_parse_in_any_order_and_case() {
+        let expected = KeyChord::key(KeyBind::Char('z')).ctrl().shift();
+        assert_eq!(chord("Shift+CTRL+Z"), expected);
+        assert_eq!(chord("control+shift+z"), expected);
+        assert_eq!(expected.to_string(), "ctrl+shift+z");
+        let all = chord("super+alt+shift+ctrl+f4");
+        assert_eq!(all.to_string(), "ctrl+shift+alt+meta+f4");
+        assert_eq!(chord(&all.to_string()), all);
+        assert_eq!(chord("Escape"), KeyBind::ESC);
+    }
+
+    #[test]
+    fn plus_key_binds_with_and_without_modifiers() {
+        let plus = KeyChord::key(KeyBind::Char('+'));
+        assert_eq!(chord("+"), plus);
+        assert_eq!(chord("ctrl++"), plus.clone().ctrl());
+        assert_eq!(plus.ctrl().to_string(), "ctrl++");
+    }
+
+    #[test]
+    fn unknown_keys_and_modifiers_are_errors() {
+        assert_eq!(
+            "ctrl+foo".parse::<KeyChord>(),
+            Err(KeymapError::Key("foo".into()))
+        );
+        assert_eq!(
+            "f25".parse::<KeyChord>(),
+            Err(KeymapError::Key("f25".into()))
+        );
+        assert_eq!(
+            "hyper+a".parse::<KeyChord>(),
+            Err(KeymapError::Modifier("hyper".into()))
+        );
+    }
+
+    #[test]
+    fn shifted_symbols_fall_back_to_the_plain_binding() {
+        let mut map = Keymap::default();
+        map.bind(chord("?"), KeyAction::ToggleFill);
+        assert_eq!(map.action(&chord("shift+?")), Some(KeyAction::ToggleFill));
+        // Letters keep shift apart: ctrl+shift+z is redo, not undo.
+        assert_eq!(map.action(&chord("ctrl+shift+z")), Some(KeyAction::Redo));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn user_table_overrides_and_unbinds_defaults() {
+        use serde::de::value::{Error, MapDeserializer};
+        use serde::Deserialize;
+
+        let table = [("ctrl+c", "save"), ("ctrl+s", "none"), ("x", "delete")];
+        let map = Keymap::deserialize(MapDeserializer::<_, Error>::new(table.into_iter())).unwrap();
+        assert_eq!(map.action(&chord("ctrl+c")), Some(KeyAction::Save));
+        assert_eq!(map.action(&chord("ctrl+s")), None);
+        assert_eq!(map.action(&chord("x")), Some(KeyAction::Delete));
+        // Defaults the table doesn't mention stay.
+        assert_eq!(map.action(&chord("ctrl+z")), Some(KeyAction::Undo));
+        assert_eq!(map.action(&chord("backspace")), Some(KeyAction::Backspace));
+
+        let bad = [("ctrl+c", "launch-rockets")];
+        assert!(Keymap::deserialize(MapDeserializer::<_, Error>::new(bad.into_iter())).is_err());
+    }
+}
@@ -11,14 +11,20 @@ pub use sss_capture::{
 mod canvas;
 mod color;
 mod config;
+mod countdown;
 mod cursor;
+mod edges;
 mod font;
 mod geometry;
 mod hit;
 mod icons;
+mod keymap;
 mod mode;
+#[cfg(feature = "serde")]
+mod project;
 mod selector;
 mod shape;
+mod sticker;
 mod tool;
 mod trigger;
 
@@ -29,11 +35,21 @@ mod platform;
 pub use canvas::Canvas;
 pub use color::Color;
 pub use config::{ChromeColors, ToolKind, UiConfig};
+pub use countdown::countdown;
+pub use geometry::{Align, Distribute, RegionPreset};
+pub use keymap::{Direction, KeyAction, Keymap, KeymapError};
 pub use mode::SelectorMode;
+#[cfg(feature = "serde")]
+pub use project::{Project, ProjectError};
+pub use render::ora::export_ora;
+pub use render::svg::export_svg;
 pub use selector::{
-    OcrPipeline, Outcome, PostAction, Selection, Selector, SelectorBuilder, SelectorError,
-    TextClipboard,
+    ImageClipboard, OcrPipeline, Outcome, PostAction, Selection, Selector, SelectorBuilder,
+    SelectorError, TextClipboard,
+};
+pub use shape::{
+    ArrowHead, DashPattern, ImagePixels, LineCap, LineJoin, LineStyle, Shape, ShapeId, ShapeKind,
+    SpotlightShape, Style, TextBackground, TextOutline, TextStyle,
 };
-pub use shape::{Shape, ShapeId, ShapeKind, Style, TextStyle};
 pub use tool::{BrushSettings, StepSettings, Tool, ToolPalette};
 pub use trigger::{CaptureTrigger, KeyBind, KeyChord};
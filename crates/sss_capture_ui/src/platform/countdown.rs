@@ -0,0 +1,222 @@
+//! Small always-on-top countdown window, shown when no notification can
+//! carry the delay (macOS, Windows, Linux without a notification daemon).
+//!
+//! The window never takes focus or pointer input, so the menu or tooltip
+//! the user is opening stays open, and it is destroyed shortly before the
+//! capture so it never ends up in the frame.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use winit::application::ApplicationHandler;
+use winit::dpi::{LogicalSize, PhysicalPosition};
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use winit::platform::run_on_demand::EventLoopExtRunOnDemand;
+use winit::window::{Window, WindowAttributes, WindowId, WindowLevel};
+
+use crate::render::gpu::{Gpu, WindowGpu};
+
+/// Side of the square window, in logical pixels.
+const SIDE: f64 = 160.0;
+/// Head start the compositor gets to unmap the window before the capture.
+const CLOSE_MARGIN: Duration = Duration::from_millis(250);
+
+/// Count down to `deadline` in a window, on `event_loop` when the caller
+/// already owns one (winit allows a single event loop per process).
+/// Returns early when no window can be opened; the caller waits out the
+/// rest.
+pub(crate) fn run(event_loop: Option<&mut EventLoop>, deadline: Instant) {
+    let mut app = Countdown {
+        deadline,
+        close_at: deadline.checked_sub(CLOSE_MARGIN).unwrap_or(deadline),
+        shown: 0,
+        window_gpu: None,
+        gpu: None,
+        window: None,
+    };
+    let result = match event_loop {
+        Some(event_loop) => event_loop.run_app_on_demand(&mut app),
+        None => match EventLoop::new() {
+            Ok(event_loop) => event_loop.run_app(&mut app),
+            Err(e) => {
+                tracing::debug!(error = %e, "countdown window: no event loop");
+                return;
+            }
+        },
+    };
+    if let Err(e) = result {
+        tracing::debug!(error = %e, "countdown window: event loop failed");
+    }
+}
+
+struct Countdown {
+    deadline: Instant,
+    close_at: Instant,
+    /// Seconds left in the last drawn frame.
+    shown: u64,
+    // Declared before `gpu` so the surface goes before the device.
+    window_gpu: Option<WindowGpu>,
+    gpu: Option<Gpu>,
+    window: Option<Arc<dyn Window>>,
+}
+
+impl Countdown {
+    fn seconds_left(&self, now: Instant) -> u64 {
+        self.deadline
+            .saturating_duration_since(now)
+            .as_secs_f32()
+            .ceil() as u64
+    }
+
+    fn draw(&mut self) {
+        let left = self.seconds_left(Instant::now());
+        let (Some(window), Some(gpu), Some(window_gpu)) =
+            (&self.window, &self.gpu, &mut self.window_gpu)
+        else {
+            return;
+        };
+        self.shown = left;
+        let raw_input = window_gpu.egui_winit.take_egui_input(&**window);
+        let output = window_gpu.egui_ctx.clone().run(raw_input, |ctx| {
+            egui::CentralPanel::default()
+                .frame(egui::Frame::new().fill(egui::Color32::from_rgb(24, 24, 24)))
+                .show(ctx, |ui| {
+                    ui.centered_and_justified(|ui| {
+                        ui.label(
+                            egui::RichText::new(left.to_string())
+                                .size(96.0)
+                                .strong()
+                                .color(egui::Color32::WHITE),
+                        );
+                    });
+                });
+        });
+        window_gpu.present(gpu, &**window, output);
+    }
+}
+
+impl ApplicationHandler for Countdown {
+    fn can_create_surfaces(&mut self, event_loop: &dyn ActiveEventLoop) {
+        if self.window.is_some() {
+            return;
+        }
+        match open(event_loop) {
+            Ok((window, gpu, window_gpu)) => {
+                window.request_redraw();
+                self.window = Some(window);
+                self.gpu = Some(gpu);
+                self.window_gpu = Some(window_gpu);
+            }
+            Err(e) => {
+                tracing::debug!(error = %e, "countdown window unavailable");
+                event_loop.exit();
+            }
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &dyn ActiveEventLoop) {
+        let now = Instant::now();
+        if now >= self.close_at {
+            // Destroy the window while the loop still runs, so the
+            // compositor has unmapped it by the time we return.
+            self.window_gpu = None;
+            self.window = None;
+            event_loop.exit();
+            return;
+        }
+        let left = self.seconds_left(now);
+        if left != self.shown {
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
+        // Wake when the number next changes, or in time to close.
+        let tick = self
+            .deadline
+            .checked_sub(Duration::from_secs(left.saturating_sub(1)))
+            .unwrap_or(now)
+            .min(self.close_at);
+        event_loop.set_control_flow(ControlFlow::WaitUntil(tick));
+    }
+
+    fn window_event(
+        &mut self,
+        _event_loop: &dyn ActiveEventLoop,
+        _id: WindowId,
+        event: WindowEvent,
+    ) {
+        match event {
+            WindowEvent::RedrawRequested => self.draw(),
+            WindowEvent::SurfaceResized(size) => {
+                if let (Some(gpu), Some(window_gpu)) = (&self.gpu, &mut self.window_gpu) {
+                    window_gpu.resize(gpu, (size.width, size.height));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A borderless, unfocused window above everything else, centred on the
+/// primary monitor where the platform lets clients place windows.
+fn open(event_loop: &dyn ActiveEventLoop) -> Result<(Arc<dyn Window>, Gpu, WindowGpu), String> {
+    let mut attrs = WindowAttributes::default()
+        .with_title("sss countdown")
+        .with_decorations(false)
+        .with_resizable(false)
+        .with_active(false)
+        .with_window_level(WindowLevel::AlwaysOnTop)
+        .with_surface_size(LogicalSize::new(SIDE, SIDE));
+    if let Some(monitor) = event_loop.primary_monitor() {
+        if let (Some(pos), Some(mode)) = (monitor.position(), monitor.current_video_mode()) {
+            let side = (SIDE * monitor.scale_factor()).round() as i32;
+            attrs = attrs.with_position(PhysicalPosition::new(
+                pos.x + (mode.size().width as i32 - side) / 2,
+                pos.y + (mode.size().height as i32 - side) / 2,
+            ));
+        }
+    }
+
+    // A plain toplevel is neither kept on top nor kept from taking focus
+    // on Linux: use a layer-shell overlay on Wayland (centred by the
+    // compositor) and an override-redirect window on X11.
+    #[cfg(target_os = "linux")]
+    {
+        use winit::platform::wayland::ActiveEventLoopExtWayland as _;
+        if event_loop.is_wayland() {
+            use winit::platform::wayland::{KeyboardInteractivity, Layer, WindowAttributesWayland};
+            attrs = attrs.with_platform_attributes(Box::new(
+                WindowAttributesWayland::default()
+                    .with_name("sss-countdown", "")
+                    .with_namespace("sss-countdown")
+                    .with_layer_shell()
+                    .with_layer(Layer::Overlay)
+                    .with_keyboard_interactivity(KeyboardInteractivity::None),
+            ));
+        } else {
+            use winit::platform::x11::WindowAttributesX11;
+            attrs = attrs.with_platform_attributes(Box::new(
+                WindowAttributesX11::default().with_override_redirect(true),
+            ));
+        }
+    }
+
+    let window: Arc<dyn Window> = Arc::from(
+        event_loop
+            .create_window(attrs)
+            .map_err(|e| format!("create window: {e}"))?,
+    );
+    // Let clicks through to whatever is being opened underneath.
+    if let Err(e) = window.set_cursor_hittest(false) {
+        tracing::debug!(error = %e, "countdown window: cannot disable hit testing");
+    }
+
+    let instance = Gpu::new_instance();
+    let surface = instance
+        .create_surface(window.clone())
+        .map_err(|e| format!("create_surface: {e}"))?;
+    let gpu = Gpu::new_with_surface(instance, &surface)?;
+    let window_gpu = WindowGpu::from_surface(window.clone(), surface, &gpu)?;
+    Ok((window, gpu, window_gpu))
+}
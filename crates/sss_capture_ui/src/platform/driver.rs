@@ -6,50 +6,51 @@ use std::sync::Arc;
 use std::sync::mpsc::Receiver;
 
 use sss_capture::Image as CapImage;
-use sss_capture::{Monitor, Rect as IRect};
+use sss_capture::{Capturer, Monitor, Rect as IRect};
 use sss_core::ocr::TextBox;
 use winit::application::ApplicationHandler;
-use winit::event::{ElementState, MouseButton, WindowEvent};
+use winit::event::{ButtonSource, ElementState, Force, MouseButton, PointerSource, WindowEvent};
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::keyboard::{Key, NamedKey};
 use winit::window::{Window, WindowId as WinitWindowId};
 
 use crate::canvas::{Canvas, CanvasEvent};
 use crate::geometry::FPoint;
+use crate::keymap::KeyAction;
 use crate::mode::SelectorMode;
-use crate::selector::{Outcome, PostAction, Selection, Selector, SelectorError};
-use crate::trigger::CaptureTrigger;
+use crate::selector::{Config, Outcome, PostAction, Selection, Selector, SelectorError, Source};
+use crate::trigger::{CaptureTrigger, KeyBind, KeyChord};
 
 /// Entry point invoked by `Selector::run`.
 pub fn run(sel: Selector) -> Result<Selection, SelectorError> {
-    let Selector { config, capturer } = sel;
-    // Eager mode captures up front; failure is non-fatal so the user can still
-    // pick a region and the capture is retried on confirm.
-    let initial = if matches!(config.trigger, CaptureTrigger::Eager) {
-        match capturer.capture_all_with(config.capture_opts) {
-            Ok(img) => Some(img),
-            Err(e) => {
-                tracing::warn!(
-                    error = %e,
-                    "initial eager capture failed; opening the selector \
-                     with no background (capture will retry on confirm)",
-                );
-                tracing::error!(
-                    "sss_capture_ui: initial capture failed ({e}); the GUI \
-                     will open without a background — the capture will be \
-                     attempted again when you confirm a region."
-                );
-                None
-            }
+    let Selector { mut config, source } = sel;
+    // Created first: a delayed capture counts down on this same loop.
+    let mut event_loop =
+        EventLoop::new().map_err(|e| SelectorError::Backend(format!("winit event loop: {e}")))?;
+    let (capturer, initial, monitors) = match source {
+        Source::Capture(capturer) => {
+            let initial = eager_capture(&config, &capturer, &mut event_loop);
+            let monitors = capturer.monitors().map_err(SelectorError::Capture)?;
+            (Some(capturer), initial, monitors)
+        }
+        // An existing image stands in for the eager capture, laid out as a
+        // single detached monitor at the origin so canvas coordinates are
+        // image pixels. Start with the whole image selected: the user is
+        // here to annotate it, and can still drag a smaller crop.
+        Source::Image(image) => {
+            let bounds = IRect::from_xywh(0, 0, image.width(), image.height());
+            config.mode = SelectorMode::Area;
+            config.trigger = CaptureTrigger::Eager;
+            let stored = config.canvas.as_ref().and_then(Canvas::region);
+            config.initial_area = config.initial_area.or(stored).or(Some(bounds));
+            (
+                None,
+                Some(CapImage::new(image)),
+                vec![Monitor::detached("image", bounds)],
+            )
         }
-    } else {
-        None
     };
-
-    let monitors = capturer.monitors().map_err(SelectorError::Capture)?;
-
-    let event_loop =
-        EventLoop::new().map_err(|e| SelectorError::Backend(format!("winit event loop: {e}")))?;
+    let windowed = capturer.is_none();
     event_loop.set_control_flow(ControlFlow::Wait);
 
     let save_path_hint = config.save_path_hint.clone();
@@ -57,7 +58,11 @@ pub fn run(sel: Selector) -> Result<Selection, SelectorError> {
         SelectorMode::AnyOf => SelectorMode::Area,
         m => m,
     };
-    let initial_area = config.initial_area;
+    if !config.confirm_with_enter {
+        config.ui.keybindings.unbind(&KeyBind::ENTER);
+    }
+    let mut canvas = config.canvas.take().unwrap_or_default();
+    let initial_area = config.initial_area.or(canvas.region());
     // Window / Monitor pickers commit on a single click, so OCR has to be
     // ready by the time the user picks. We dispatch the eager full-frame
     // capture immediately for those modes. In Area mode we skip the
@@ -96,6 +101,7 @@ pub fn run(sel: Selector) -> Result<Selection, SelectorError> {
     let result: Rc<RefCell<AppResult>> = Rc::new(RefCell::new(AppResult {
         outcome: None,
         canvas: Canvas::default(),
+        background: None,
         action: PostAction {
             copy: false,
             save: false,
@@ -113,7 +119,6 @@ pub fn run(sel: Selector) -> Result<Selection, SelectorError> {
     let snap_step_init = config.ui.snap_step.max(2.0);
     #[cfg(feature = "editor")]
     let initial_fill = config.ui.default_fill;
-    let mut canvas = Canvas::default();
     if let Some(rect) = initial_area {
         canvas.set_region(Some(rect));
     }
@@ -123,6 +128,8 @@ pub fn run(sel: Selector) -> Result<Selection, SelectorError> {
         capturer,
         monitors,
         initial,
+        windowed,
+        background: None,
         clipboard_worker: None,
         ocr_rx,
         ocr_pending_offset,
@@ -132,6 +139,9 @@ pub fn run(sel: Selector) -> Result<Selection, SelectorError> {
         canvas,
         active_window: None,
         last_cursor: FPoint::default(),
+        key_targets: None,
+        key_target: None,
+        edge_lines: None,
         outcome: None,
         action: PostAction {
             copy: false,
@@ -152,6 +162,8 @@ pub fn run(sel: Selector) -> Result<Selection, SelectorError> {
         #[cfg(feature = "editor")]
         current_fill: initial_fill,
         #[cfg(feature = "editor")]
+        current_line: crate::shape::LineStyle::default(),
+        #[cfg(feature = "editor")]
         radial: None,
         #[cfg(feature = "editor")]
         pipette_pending: false,
@@ -161,11 +173,20 @@ pub fn run(sel: Selector) -> Result<Selection, SelectorError> {
         snap_step: snap_step_init,
         #[cfg(feature = "editor")]
         magnifier_on: false,
+        measure_logical: config.ui.measure_logical,
         #[cfg(feature = "editor")]
         width_popup: None,
         #[cfg(feature = "editor")]
+        line_popup: None,
+        #[cfg(feature = "editor")]
         snap_popup: None,
         #[cfg(feature = "editor")]
+        sticker_popup: None,
+        #[cfg(feature = "editor")]
+        stickers: None,
+        #[cfg(feature = "editor")]
+        region_entry: None,
+        #[cfg(feature = "editor")]
         color_popup: None,
         #[cfg(feature = "editor")]
         gizmo_drag: None,
@@ -183,6 +204,7 @@ pub fn run(sel: Selector) -> Result<Selection, SelectorError> {
         outcome,
         canvas,
         action,
+        background,
     } = Rc::try_unwrap(result)
         .map_err(|_| SelectorError::Backend("event-loop result handle leaked".into()))?
         .into_inner();
@@ -190,21 +212,63 @@ pub fn run(sel: Selector) -> Result<Selection, SelectorError> {
         outcome: outcome.unwrap_or(Outcome::Cancelled),
         canvas,
         action,
+        background,
     })
 }
 
+/// Eager mode captures up front; failure is non-fatal so the user can still
+/// pick a region and the capture is retried on confirm.
+fn eager_capture(
+    config: &Config,
+    capturer: &Capturer,
+    event_loop: &mut EventLoop,
+) -> Option<CapImage> {
+    if let CaptureTrigger::Delayed(delay) = config.trigger {
+        crate::countdown::countdown_in(event_loop, delay);
+    }
+    if !matches!(
+        config.trigger,
+        CaptureTrigger::Eager | CaptureTrigger::Delayed(_)
+    ) {
+        return None;
+    }
+    match capturer.capture_all_with(config.capture_opts) {
+        Ok(img) => Some(img),
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                "initial eager capture failed; opening the selector \
+                 with no background (capture will retry on confirm)",
+            );
+            tracing::error!(
+                "sss_capture_ui: initial capture failed ({e}); the GUI \
+                 will open without a background — the capture will be \
+                 attempted again when you confirm a region."
+            );
+            None
+        }
+    }
+}
+
 #[cfg(feature = "editor")]
 fn set_active_tool_color(t: &mut crate::tool::Tool, color: crate::color::Color) {
     use crate::tool::Tool;
     match t {
         Tool::Brush(b)
+        | Tool::Highlighter(b)
         | Tool::Line(b)
         | Tool::Arrow(b)
         | Tool::Rectangle(b)
         | Tool::Ellipse(b)
-        | Tool::Polygon(b) => b.color = color,
+        | Tool::Polygon(b)
+        | Tool::Magnify { border: b, .. } => b.color = color,
         Tool::Step(s) => s.fill = color,
-        Tool::Text(t) => t.color = color,
+        Tool::Measure { color: c, .. } => *c = color,
+        Tool::Text(t) => t.set_color(color),
+        Tool::Callout { brush, text } => {
+            brush.color = color;
+            text.set_color(color);
+        }
         _ => {}
     }
 }
@@ -214,6 +278,7 @@ struct AppResult {
     outcome: Option<Outcome>,
     canvas: Canvas,
     action: PostAction,
+    background: Option<CapImage>,
 }
 
 #[derive(Default, Clone, Copy, Debug)]
@@ -224,11 +289,73 @@ struct ModState {
     meta: bool,
 }
 
+/// Pressure (0–1) of a touch that reports its force. Only touch carries
+/// one here: winit's Wayland and X11 backends deliver a tablet pen as a
+/// plain mouse pointer, so pen strokes keep a uniform width.
+fn pressure(force: Option<Force>) -> Option<f32> {
+    let p = match force? {
+        Force::Calibrated {
+            force,
+            max_possible_force,
+            ..
+        } if max_possible_force > 0.0 => force / max_possible_force,
+        Force::Calibrated { .. } => return None,
+        Force::Normalized(p) => p,
+    };
+    Some(p.clamp(0.0, 1.0) as f32)
+}
+
+/// The chord a key press forms with the held modifiers; `None` for keys
+/// no binding can name.
+fn key_chord(key: &Key, mods: ModState) -> Option<KeyChord> {
+    let bind = match key.as_ref() {
+        Key::Named(NamedKey::Enter) => KeyBind::Enter,
+        Key::Named(NamedKey::Escape) => KeyBind::Escape,
+        Key::Named(NamedKey::Space) => KeyBind::Space,
+        Key::Named(NamedKey::Tab) => KeyBind::Tab,
+        Key::Named(NamedKey::Delete) => KeyBind::Delete,
+        Key::Named(NamedKey::Backspace) => KeyBind::Backspace,
+        Key::Named(NamedKey::ArrowLeft) => KeyBind::Left,
+        Key::Named(NamedKey::ArrowRight) => KeyBind::Right,
+        Key::Named(NamedKey::ArrowUp) => KeyBind::Up,
+        Key::Named(NamedKey::ArrowDown) => KeyBind::Down,
+        Key::Named(NamedKey::F1) => KeyBind::F(1),
+        Key::Named(NamedKey::F2) => KeyBind::F(2),
+        Key::Named(NamedKey::F3) => KeyBind::F(3),
+        Key::Named(NamedKey::F4) => KeyBind::F(4),
+        Key::Named(NamedKey::F5) => KeyBind::F(5),
+        Key::Named(NamedKey::F6) => KeyBind::F(6),
+        Key::Named(NamedKey::F7) => KeyBind::F(7),
+        Key::Named(NamedKey::F8) => KeyBind::F(8),
+        Key::Named(NamedKey::F9) => KeyBind::F(9),
+        Key::Named(NamedKey::F10) => KeyBind::F(10),
+        Key::Named(NamedKey::F11) => KeyBind::F(11),
+        Key::Named(NamedKey::F12) => KeyBind::F(12),
+        Key::Character(s) => KeyBind::Char(s.chars().next()?.to_lowercase().next()?),
+        _ => return None,
+    };
+    Some(KeyChord {
+        ctrl: mods.ctrl,
+        shift: mods.shift,
+        alt: mods.alt,
+        meta: mods.meta,
+        key: bind,
+    })
+}
+
 struct App {
     config: crate::selector::Config,
-    capturer: Arc<sss_capture::Capturer>,
+    /// `None` when annotating an image handed to
+    /// [`crate::SelectorBuilder::image`]; `initial` then holds that image.
+    capturer: Option<Arc<Capturer>>,
     monitors: Vec<Monitor>,
     initial: Option<CapImage>,
+    /// Open one decorated window instead of a fullscreen overlay per
+    /// monitor. Set in image mode, where there is no desktop to cover.
+    windowed: bool,
+    /// Unannotated pixels of the last [`Self::capture_region`], handed to
+    /// the caller as `Selection::background`.
+    background: Option<CapImage>,
     /// In-flight clipboard-handoff worker, if any. Joined in
     /// `flush_and_exit` so the process doesn't tear down before the
     /// compositor / clipboard manager has read our selection — without
@@ -258,6 +385,14 @@ struct App {
     canvas: Canvas,
     active_window: Option<WinitWindowId>,
     last_cursor: FPoint,
+    /// Monitor bounds followed by window bounds, listed on the first
+    /// keyboard snap or Tab press.
+    key_targets: Option<Vec<IRect>>,
+    /// Index into `key_targets` last picked with Tab.
+    key_target: Option<usize>,
+    /// UI edges of the eager frame, detected when Ctrl first asks for
+    /// edge snapping.
+    edge_lines: Option<Arc<crate::edges::EdgeLines>>,
     outcome: Option<Outcome>,
     action: PostAction,
     /// Session-local toggle for the output border (padding + background
@@ -285,6 +420,10 @@ struct App {
     /// single source of truth across tool switches.
     #[cfg(feature = "editor")]
     current_fill: Option<crate::color::Color>,
+    /// Persistent opacity / dash / cap / join / arrowhead choice, pushed
+    /// to the canvas alongside colour and width.
+    #[cfg(feature = "editor")]
+    current_line: crate::shape::LineStyle,
     /// Right-click radial menu state: (overlay index, popup state, armed
     /// flag). `armed = false` on the first render frame so the click that
     /// opened the menu is not treated as an outside-click close.
@@ -306,14 +445,27 @@ struct App {
     /// Magnifier toggle.
     #[cfg(feature = "editor")]
     magnifier_on: bool,
+    /// Measurements and the readout count logical pixels.
+    measure_logical: bool,
     /// Open popup state: (overlay, window-local origin, armed). `armed`
     /// flips true on the first render frame so the opening click can't
     /// close the popup on the same frame.
     #[cfg(feature = "editor")]
     width_popup: Option<(usize, egui::Pos2, bool)>,
     #[cfg(feature = "editor")]
+    line_popup: Option<(usize, egui::Pos2, bool)>,
+    #[cfg(feature = "editor")]
     snap_popup: Option<(usize, egui::Pos2, bool)>,
     #[cfg(feature = "editor")]
+    sticker_popup: Option<(usize, egui::Pos2, bool)>,
+    /// Images from `UiConfig::sticker_dir`, read when the picker first
+    /// opens.
+    #[cfg(feature = "editor")]
+    stickers: Option<Vec<crate::shape::ImagePixels>>,
+    /// Open `x,y WxH` field: (overlay, text, armed).
+    #[cfg(feature = "editor")]
+    region_entry: Option<(usize, String, bool)>,
+    #[cfg(feature = "editor")]
     color_popup: Option<(usize, egui::Pos2, bool, crate::render::ui::HsvState)>,
     /// Active gizmo drag: stores the original (pre-drag) shape and the
     /// pivot + start metric so each PointerMove recomputes the new shape
@@ -339,14 +491,14 @@ struct App {
 #[derive(Clone, Debug)]
 struct GizmoDrag {
     handle: crate::render::ui::GizmoHandle,
-    /// Scale anchor / rotate center (always the shape's bounds centre).
+    /// Scale anchor / rotate center (always the selection's bounds centre).
     pivot: FPoint,
     /// Distance (scale) or angle in radians (rotate) at drag start.
     start_metric: f32,
-    /// Snapshot of the shape at drag start; every move recomputes from
-    /// this so dragging back and forth doesn't compound floating-point
-    /// error.
-    original: Box<crate::shape::Shape>,
+    /// Snapshot of the selected shapes at drag start; every move
+    /// recomputes from this so dragging back and forth doesn't compound
+    /// floating-point error.
+    originals: Vec<crate::shape::Shape>,
 }
 
 impl App {
@@ -438,17 +590,10 @@ impl App {
                 rs.iter().any(|r| r.contains(win_local))
             })
             .unwrap_or(false);
-        let on_gizmo = {
-            if let Some(sel) = self.canvas.selected() {
-                if let Some(shape) = self.canvas.shapes().iter().find(|s| s.id == sel) {
-                    crate::render::ui::hit_gizmo(shape.bounds(), self.last_cursor).is_some()
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        };
+        let on_gizmo = self
+            .canvas
+            .selection_bounds()
+            .is_some_and(|b| crate::render::ui::hit_gizmo(b, self.last_cursor).is_some());
         let name = crate::cursor::desired_cursor_ext(
             &self.canvas,
             self.last_cursor,
@@ -494,6 +639,13 @@ impl App {
         self.push_current_to_tool();
     }
 
+    /// Apply a line style pick globally.
+    #[cfg(feature = "editor")]
+    fn apply_line_pick(&mut self, line: crate::shape::LineStyle) {
+        self.current_line = line;
+        self.push_current_to_tool();
+    }
+
     /// Re-seat the active tool's brush colour / stroke width / fill from
     /// the persistent `current_*` fields. Call this after every action
     /// that changes the global state (tool switch, colour pick, width
@@ -504,12 +656,302 @@ impl App {
         crate::icons::set_active_tool_width(&mut self.canvas.active_tool, self.current_width);
         set_active_tool_color(&mut self.canvas.active_tool, self.current_color);
         self.canvas.set_fill_color(self.current_fill);
+        self.canvas.set_line_style(self.current_line);
+    }
+
+    /// Put an image on the canvas centred on `at` (global pixels), or on
+    /// the region when `None`, shrunk to fit the region.
+    fn place_image(&mut self, pixels: crate::shape::ImagePixels, at: Option<FPoint>) {
+        let region = self.canvas.region();
+        let center = at
+            .or_else(|| {
+                region.map(|r| {
+                    FPoint::new(
+                        r.x() as f32 + r.width() as f32 / 2.0,
+                        r.y() as f32 + r.height() as f32 / 2.0,
+                    )
+                })
+            })
+            .unwrap_or(self.last_cursor);
+        let max = region.map_or(pixels.image().dimensions(), |r| (r.width(), r.height()));
+        self.canvas.insert_image(pixels, center, max);
+        self.broadcast_redraw();
+    }
+
+    /// Ctrl+V: paste the clipboard image through the injected hook.
+    fn paste_image(&mut self) {
+        let Some(clip) = self.config.image_clipboard.clone() else {
+            tracing::debug!("no image_clipboard hook installed; ignoring paste");
+            return;
+        };
+        match clip() {
+            Some(img) => self.place_image(img.into(), None),
+            None => tracing::debug!("clipboard holds no image"),
+        }
+    }
+
+    /// Read the sticker directory once and keep the decoded images.
+    #[cfg(feature = "editor")]
+    fn load_stickers(&mut self) {
+        if self.stickers.is_some() {
+            return;
+        }
+        let Some(dir) = self.config.ui.sticker_dir.as_deref() else {
+            return;
+        };
+        let stickers = crate::sticker::list_stickers(dir)
+            .iter()
+            .filter_map(|path| match crate::sticker::load_image(path) {
+                Ok(img) => Some(img.into()),
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "skipping sticker");
+                    None
+                }
+            })
+            .collect();
+        self.stickers = Some(stickers);
+    }
+
+    /// Carry out what a key chord is bound to.
+    /// `window` is the overlay that received the key.
+    fn run_key_action(
+        &mut self,
+        action: KeyAction,
+        window: usize,
+        event_loop: &dyn ActiveEventLoop,
+    ) {
+        match action {
+            KeyAction::Cancel => {
+                self.outcome = Some(Outcome::Cancelled);
+                self.flush_and_exit(event_loop);
+                return;
+            }
+            KeyAction::Confirm => {
+                if self.canvas.is_drawing_polygon() {
+                    self.canvas.commit_polygon();
+                } else {
+                    self.confirm(event_loop);
+                    return;
+                }
+            }
+            KeyAction::Copy => {
+                // OCR text first: when at least one text box is
+                // selected, the copy puts the joined text on the
+                // clipboard inline and clears the selection. The
+                // overlay stays open so the user can pick more text
+                // or refine the region; the closing pass only
+                // happens on Esc / Enter / explicit image copy.
+                if !self.copy_selected_text_inline() {
+                    self.action.copy = true;
+                    self.confirm(event_loop);
+                    return;
+                }
+            }
+            KeyAction::Save => {
+                self.action.save = true;
+                self.confirm(event_loop);
+                return;
+            }
+            KeyAction::Undo => self.canvas.handle(CanvasEvent::Undo),
+            KeyAction::Redo => self.canvas.handle(CanvasEvent::Redo),
+            KeyAction::Delete => self.canvas.handle(CanvasEvent::Delete),
+            KeyAction::Backspace => self.canvas.handle(CanvasEvent::TextBackspace),
+            KeyAction::Paste => self.paste_image(),
+            KeyAction::CopyShapes => {
+                self.canvas.copy_selection();
+            }
+            KeyAction::PasteShapes => {
+                self.canvas.paste_shapes();
+            }
+            KeyAction::SelectAll => {
+                self.canvas.set_tool(crate::tool::Tool::Pointer);
+                self.canvas.select_all();
+            }
+            KeyAction::Duplicate => self.canvas.duplicate_selection(),
+            KeyAction::Group => self.canvas.toggle_group(),
+            KeyAction::Align(align) => self.canvas.align_selection(align),
+            KeyAction::Distribute(axis) => self.canvas.distribute_selection(axis),
+            KeyAction::SelectTool(n) => {
+                let tool = n
+                    .checked_sub(1)
+                    .and_then(|i| self.config.palette.tools.get(i));
+                if let Some(tool) = tool.cloned() {
+                    self.canvas.set_tool(tool);
+                    #[cfg(feature = "editor")]
+                    {
+                        self.current_fill = None;
+                        self.push_current_to_tool();
+                    }
+                }
+            }
+            KeyAction::ToggleFill => {
+                #[cfg(feature = "editor")]
+                {
+                    self.current_fill = match self.current_fill {
+                        Some(_) => None,
+                        None => Some(self.current_color),
+                    };
+                    self.push_current_to_tool();
+                }
+            }
+            KeyAction::ToggleBorder => self.border_enabled = !self.border_enabled,
+            // Monitor and window picking need the live desktop.
+            KeyAction::Mode(mode) => {
+                if self.capturer.is_some() {
+                    self.runtime_mode = mode;
+                }
+            }
+            KeyAction::Nudge { dir, step } => {
+                if self.canvas.selected().is_none() {
+                    self.ensure_region();
+                }
+                let (dx, dy) = dir.offset();
+                let step = step as i32;
+                self.canvas.nudge_selection(dx * step, dy * step);
+            }
+            KeyAction::Resize { dir, step } => {
+                self.ensure_region();
+                let (dx, dy) = dir.offset();
+                let step = step as i32;
+                self.canvas.resize_region(dx * step, dy * step);
+            }
+            KeyAction::Snap(dir) | KeyAction::Stretch(dir) => {
+                self.ensure_region();
+                let targets = self.key_targets();
+                let xs: Vec<i32> = targets.iter().flat_map(|r| [r.x(), r.right()]).collect();
+                let ys: Vec<i32> = targets.iter().flat_map(|r| [r.y(), r.bottom()]).collect();
+                let stretch = matches!(action, KeyAction::Stretch(_));
+                if let Some(r) = self.canvas.region() {
+                    let snapped = crate::geometry::snap_to_edge(r, &xs, &ys, dir.offset(), stretch);
+                    self.canvas.set_region(Some(snapped));
+                    self.maybe_redispatch_ocr_for_region(event_loop);
+                }
+            }
+            KeyAction::EditRegion => {
+                #[cfg(feature = "editor")]
+                {
+                    let text = self
+                        .canvas
+                        .region()
+                        .map(|r| format!("{},{} {}x{}", r.x(), r.y(), r.width(), r.height()))
+                        .unwrap_or_default();
+                    self.region_entry = Some((window, text, false));
+                }
+                #[cfg(not(feature = "editor"))]
+                let _ = window;
+            }
+            KeyAction::NextTarget | KeyAction::PrevTarget => {
+                // Area mode keeps Tab for nothing else; only the picker
+                // offers monitors and windows.
+                if self.config.mode == SelectorMode::AnyOf && self.capturer.is_some() {
+                    let n = self.key_targets().len();
+                    if n > 0 {
+                        let next = match (self.key_target, action) {
+                            (None, KeyAction::NextTarget) => 0,
+                            (None, _) => n - 1,
+                            (Some(i), KeyAction::NextTarget) => (i + 1) % n,
+                            (Some(i), _) => (i + n - 1) % n,
+                        };
+                        self.key_target = Some(next);
+                        let rect = self.key_targets()[next];
+                        self.runtime_mode = SelectorMode::Area;
+                        self.canvas.set_region(Some(rect));
+                        self.maybe_redispatch_ocr_for_region(event_loop);
+                    }
+                }
+            }
+        }
+        self.broadcast_redraw();
+    }
+
+    /// Overlay showing most of `bounds`, which owns chrome attached to it.
+    #[cfg(feature = "editor")]
+    fn owner_overlay(&self, bounds: IRect) -> Option<usize> {
+        self.windows
+            .iter()
+            .enumerate()
+            .filter_map(|(i, w)| {
+                let inter = w.monitor.bounds().intersection(&bounds)?;
+                Some((i, inter.width() as u64 * inter.height() as u64))
+            })
+            .filter(|&(_, area)| area > 0)
+            .max_by_key(|&(i, area)| (area, std::cmp::Reverse(i)))
+            .map(|(i, _)| i)
+    }
+
+    /// Holding Ctrl in Area mode snaps region drags to UI edges found in
+    /// the eager frame.
+    fn update_edge_snap(&mut self) {
+        let on = self.mods.ctrl && self.runtime_mode == SelectorMode::Area;
+        if on && self.edge_lines.is_none() {
+            if let Some(img) = self.initial.as_ref() {
+                let bb =
+                    IRect::bounding(&self.monitors.iter().map(|m| m.bounds()).collect::<Vec<_>>())
+                        .unwrap_or_default();
+                let edges = crate::edges::EdgeLines::detect(img.as_rgba(), (bb.x(), bb.y()));
+                tracing::debug!(
+                    vertical = edges.xs.len(),
+                    horizontal = edges.ys.len(),
+                    "detected UI edges"
+                );
+                self.edge_lines = Some(Arc::new(edges));
+            }
+        }
+        self.canvas.set_edge_snap(self.edge_lines.clone().filter(|_| on));
+        // Guide lines follow the modifier mid-drag.
+        self.broadcast_redraw();
+    }
+
+    /// Give the keyboard something to move: half of the monitor under the
+    /// cursor, centred, when no region has been drawn yet.
+    fn ensure_region(&mut self) {
+        if self.canvas.region().is_some() {
+            return;
+        }
+        let cursor = self.last_cursor.to_int();
+        let bounds = self
+            .monitors
+            .iter()
+            .map(|m| m.bounds())
+            .find(|b| b.contains(cursor))
+            .or_else(|| self.monitors.first().map(|m| m.bounds()));
+        if let Some(b) = bounds {
+            self.canvas.set_region(Some(IRect::from_xywh(
+                b.x() + b.width() as i32 / 4,
+                b.y() + b.height() as i32 / 4,
+                (b.width() / 2).max(1),
+                (b.height() / 2).max(1),
+            )));
+        }
+    }
+
+    /// Monitor and window bounds the keyboard can snap to or Tab through.
+    /// Windows are listed once; an image session only has its own frame.
+    fn key_targets(&mut self) -> &[IRect] {
+        self.key_targets.get_or_insert_with(|| {
+            let mut targets: Vec<IRect> = self.monitors.iter().map(|m| m.bounds()).collect();
+            let windows = match self.capturer.as_ref().map(|c| c.windows()) {
+                Some(Ok(windows)) => windows,
+                Some(Err(e)) => {
+                    tracing::debug!("cannot list windows for keyboard targets: {e}");
+                    Vec::new()
+                }
+                None => Vec::new(),
+            };
+            for bounds in windows.iter().map(|w| w.bounds()) {
+                if bounds.width() > 0 && bounds.height() > 0 && !targets.contains(&bounds) {
+                    targets.push(bounds);
+                }
+            }
+            targets
+        })
     }
 
     fn flush_and_exit(&mut self, event_loop: &dyn ActiveEventLoop) {
         let mut r = self.result.borrow_mut();
         r.outcome = self.outcome.take();
         r.canvas = std::mem::take(&mut self.canvas);
+        r.background = self.background.take();
         r.action = PostAction {
             copy: self.action.copy,
             save: self.action.save,
@@ -680,9 +1122,41 @@ fn crop_region(
     Some((crop, clamped, (x, y)))
 }
 
+/// Share of the monitor the editor window may cover. winit reports no work
+/// area, so this leaves room for panels and the window decorations.
+const EDITOR_SCREEN_SHARE: f64 = 0.9;
+
+/// Surface size of the editor window: the image 1:1 when it fits in
+/// `screen`, otherwise scaled down to fit with its aspect ratio kept.
+fn editor_surface_size(image: IRect, screen: Option<(u32, u32)>) -> (u32, u32) {
+    let (w, h) = (image.width().max(1), image.height().max(1));
+    let Some((sw, sh)) = screen else {
+        return (w, h);
+    };
+    let fit = (sw as f64 * EDITOR_SCREEN_SHARE / w as f64)
+        .min(sh as f64 * EDITOR_SCREEN_SHARE / h as f64)
+        .min(1.0);
+    (
+        ((w as f64 * fit).round() as u32).max(1),
+        ((h as f64 * fit).round() as u32).max(1),
+    )
+}
+
+/// Canvas pixels per surface pixel when `bounds` is shown in a surface of
+/// `size`; the larger axis ratio keeps the whole image reachable.
+fn view_scale(bounds: IRect, size: winit::dpi::PhysicalSize<u32>) -> f32 {
+    let sx = bounds.width() as f32 / size.width.max(1) as f32;
+    let sy = bounds.height() as f32 / size.height.max(1) as f32;
+    sx.max(sy)
+}
+
 struct OverlayWindow {
     window: Arc<dyn Window>,
     monitor: Monitor,
+    /// Canvas pixels per surface pixel: 1 for the fullscreen overlays,
+    /// above 1 when the editor window shows a large image scaled down.
+    /// Pointer positions are multiplied by it before they reach the canvas.
+    view_scale: f32,
     #[cfg(feature = "editor")]
     gpu: Option<crate::render::gpu::WindowGpu>,
     /// Eager-captured monitor slice uploaded as an egui texture, painted as
@@ -696,6 +1170,9 @@ struct OverlayWindow {
     /// top, this is just a blurred bg slice.
     #[cfg(feature = "editor")]
     blur_source: Option<(u64, egui::TextureHandle)>,
+    /// Live Pixelate previews; see [`App::refresh_mosaics`].
+    #[cfg(feature = "editor")]
+    mosaics: crate::render::overlay::MosaicCache,
     /// Per-window icon cache. `TextureHandle`s are bound to a single
     /// `egui::Context`, so each window uploads its own rasters.
     #[cfg(feature = "editor")]
@@ -728,6 +1205,18 @@ impl ApplicationHandler for App {
             winit_monitors.len(),
             self.monitors.len()
         );
+        // The editor window opens on the primary monitor; its video mode
+        // bounds how large the window may get.
+        let screen = self
+            .windowed
+            .then(|| {
+                event_loop
+                    .primary_monitor()
+                    .or_else(|| winit_monitors.first().cloned())
+            })
+            .flatten()
+            .and_then(|m| m.current_video_mode())
+            .map(|mode| (mode.size().width, mode.size().height));
         for (i, monitor) in self.monitors.iter().enumerate() {
             let target = winit_monitors.iter().find(|m| {
                 m.position().is_some_and(|pos| {
@@ -736,7 +1225,7 @@ impl ApplicationHandler for App {
             });
             // `Borderless(None)` lets the compositor pick the current output
             // when winit can't enumerate (some Wayland setups).
-            let fullscreen = (!wayland)
+            let fullscreen = (!wayland && !self.windowed)
                 .then(|| Some(winit::monitor::Fullscreen::Borderless(target.cloned())))
                 .flatten();
             tracing::info!(
@@ -748,22 +1237,34 @@ impl ApplicationHandler for App {
                 "creating overlay window",
             );
             let mut attrs = winit::window::WindowAttributes::default()
-                .with_title("sss_capture_ui overlay")
-                .with_decorations(false)
+                .with_title(if self.windowed {
+                    "sss_capture_ui editor"
+                } else {
+                    "sss_capture_ui overlay"
+                })
+                .with_decorations(self.windowed)
                 .with_resizable(false)
                 .with_visible(true)
                 .with_active(true)
                 // Without an explicit surface_size winit-Wayland can open a 0x0
-                // window that the compositor then hides.
-                .with_surface_size(winit::dpi::PhysicalSize::new(
-                    monitor.bounds().width().max(640),
-                    monitor.bounds().height().max(480),
-                ))
+                // window that the compositor then hides. The editor window
+                // shows the image 1:1 when the screen has room for it and
+                // scaled down otherwise; `view_scale` maps the pointer back
+                // to image pixels.
+                .with_surface_size(if self.windowed {
+                    let (w, h) = editor_surface_size(monitor.bounds(), screen);
+                    winit::dpi::PhysicalSize::new(w, h)
+                } else {
+                    winit::dpi::PhysicalSize::new(
+                        monitor.bounds().width().max(640),
+                        monitor.bounds().height().max(480),
+                    )
+                })
                 .with_transparent(matches!(self.config.trigger, CaptureTrigger::Lazy { .. }))
                 .with_fullscreen(fullscreen);
 
             #[cfg(target_os = "linux")]
-            if wayland {
+            if wayland && !self.windowed {
                 use winit::platform::wayland::{
                     Anchor, KeyboardInteractivity, Layer, WindowAttributesWayland,
                 };
@@ -790,9 +1291,15 @@ impl ApplicationHandler for App {
                     let id = window.id();
                     window.request_redraw();
                     tracing::info!(?id, "overlay window created and redraw requested");
+                    let view_scale = if self.windowed {
+                        view_scale(monitor.bounds(), window.surface_size())
+                    } else {
+                        1.0
+                    };
                     let overlay = OverlayWindow {
                         window,
                         monitor: monitor.clone(),
+                        view_scale,
                         #[cfg(feature = "editor")]
                         gpu: None,
                         #[cfg(feature = "editor")]
@@ -800,6 +1307,8 @@ impl ApplicationHandler for App {
                         #[cfg(feature = "editor")]
                         blur_source: None,
                         #[cfg(feature = "editor")]
+                        mosaics: Default::default(),
+                        #[cfg(feature = "editor")]
                         icons: crate::render::ui::IconCache::default(),
                     };
                     self.windows.push(overlay);
@@ -835,10 +1344,11 @@ impl ApplicationHandler for App {
         id: WinitWindowId,
         event: WindowEvent,
     ) {
-        let (origin, _monitor) = match self.windows.iter().find(|w| w.window.id() == id) {
+        let (origin, monitor, scale) = match self.windows.iter().find(|w| w.window.id() == id) {
             Some(w) => (
                 (w.monitor.bounds().x(), w.monitor.bounds().y()),
                 w.monitor.clone(),
+                w.view_scale,
             ),
             None => return,
         };
@@ -865,8 +1375,8 @@ impl ApplicationHandler for App {
         let pos_local = match &event {
             WindowEvent::PointerMoved { position, .. }
             | WindowEvent::PointerButton { position, .. } => Some(egui::Pos2::new(
-                position.x as f32,
-                position.y as f32,
+                position.x as f32 * scale,
+                position.y as f32 * scale,
             )),
             _ => None,
         };
@@ -896,6 +1406,26 @@ impl ApplicationHandler for App {
         #[cfg(not(feature = "editor"))]
         let egui_consumed = false;
 
+        // A finger has no hover: it reports where it lands with the press
+        // itself, so the press can't rely on the last move. Touch force
+        // rides along as brush pressure.
+        if let WindowEvent::PointerButton {
+            position,
+            button,
+            primary: true,
+            ..
+        } = &event
+        {
+            self.last_cursor = FPoint::new(
+                position.x as f32 * scale + origin.0 as f32,
+                position.y as f32 * scale + origin.1 as f32,
+            );
+            self.canvas.set_pressure(match button {
+                ButtonSource::Touch { force, .. } => pressure(*force),
+                _ => None,
+            });
+        }
+
         match event {
             WindowEvent::CloseRequested => {
                 self.outcome = Some(Outcome::Cancelled);
@@ -907,74 +1437,78 @@ impl ApplicationHandler for App {
                 self.mods.shift = m.shift_key();
                 self.mods.alt = m.alt_key();
                 self.mods.meta = m.meta_key();
+                self.canvas.set_adding_region(self.mods.shift);
+                self.canvas.set_extend_selection(self.mods.shift);
+                self.canvas.set_band_select(self.mods.alt);
+                self.update_edge_snap();
             }
             WindowEvent::KeyboardInput { event, .. } => {
                 if event.state != ElementState::Pressed {
                     return;
                 }
-                match event.logical_key.as_ref() {
-                    Key::Named(NamedKey::Escape) => {
-                        self.outcome = Some(Outcome::Cancelled);
-                        self.flush_and_exit(event_loop);
-                    }
-                    Key::Named(NamedKey::Enter) => {
-                        if self.canvas.is_drawing_polygon() {
-                            self.canvas.commit_polygon();
-                            self.broadcast_redraw();
-                        } else if self.config.confirm_with_enter {
-                            self.confirm(event_loop);
-                        }
-                    }
-                    Key::Named(NamedKey::Backspace) => {
-                        self.canvas.handle(CanvasEvent::TextBackspace);
-                    }
-                    Key::Character("z") | Key::Character("Z") if self.mods.ctrl => {
-                        if self.mods.shift {
-                            self.canvas.handle(CanvasEvent::Redo);
-                        } else {
-                            self.canvas.handle(CanvasEvent::Undo);
-                        }
-                    }
-                    Key::Character("y") | Key::Character("Y") if self.mods.ctrl => {
-                        self.canvas.handle(CanvasEvent::Redo);
-                    }
-                    Key::Character("c") | Key::Character("C") if self.mods.ctrl => {
-                        // OCR text first: when at least one text box is
-                        // selected, Ctrl+C copies the joined text to the
-                        // clipboard inline and clears the selection. The
-                        // overlay stays open so the user can pick more text
-                        // or refine the region; the closing pass only
-                        // happens on Esc / Enter / explicit image copy.
-                        if self.copy_selected_text_inline() {
-                            self.broadcast_redraw();
-                        } else {
-                            self.action.copy = true;
-                            self.confirm(event_loop);
-                        }
-                    }
-                    Key::Character("s") | Key::Character("S") if self.mods.ctrl => {
-                        self.action.save = true;
-                        self.confirm(event_loop);
-                    }
-                    Key::Named(NamedKey::Delete) => {
-                        self.canvas.handle(CanvasEvent::Delete);
-                    }
-                    Key::Character(s) => {
-                        if let Some(ch) = s.chars().next() {
-                            if !self.mods.ctrl && !self.mods.alt && !self.mods.meta {
-                                self.canvas.handle(CanvasEvent::TextInput(ch));
-                            }
+                // The region field has the keyboard; egui already got the
+                // event above.
+                #[cfg(feature = "editor")]
+                if self.region_entry.is_some() {
+                    self.broadcast_redraw();
+                    return;
+                }
+                let plain = !self.mods.ctrl && !self.mods.alt && !self.mods.meta;
+                // Text being typed gets its editing keys before any
+                // binding, so single-key shortcuts don't eat letters.
+                // Enter breaks the line; Ctrl+Enter finishes it.
+                if self.canvas.is_typing_text() {
+                    let typed = match event.logical_key.as_ref() {
+                        Key::Named(NamedKey::Enter) if self.mods.ctrl => {
+                            Some(CanvasEvent::TextCommit)
                         }
+                        Key::Named(NamedKey::Enter) => Some(CanvasEvent::TextInput('\n')),
+                        Key::Named(NamedKey::Space) if plain => Some(CanvasEvent::TextInput(' ')),
+                        Key::Character(s) if plain => s.chars().next().map(CanvasEvent::TextInput),
+                        _ => None,
+                    };
+                    if let Some(ev) = typed {
+                        self.canvas.handle(ev);
+                        self.broadcast_redraw();
+                        return;
                     }
-                    _ => {}
+                }
+                // Enter closes the polygon being drawn whatever it is
+                // bound to.
+                if matches!(event.logical_key.as_ref(), Key::Named(NamedKey::Enter))
+                    && self.canvas.is_drawing_polygon()
+                {
+                    self.canvas.commit_polygon();
+                    self.broadcast_redraw();
+                    return;
+                }
+                let action = key_chord(&event.logical_key, self.mods)
+                    .and_then(|chord| self.config.ui.keybindings.action(&chord));
+                if let Some(action) = action {
+                    let window = self.windows.iter().position(|w| w.window.id() == id);
+                    self.run_key_action(action, window.unwrap_or(0), event_loop);
                 }
             }
-            WindowEvent::PointerMoved { position, .. } => {
+            // Further fingers would yank the drag of the first one around.
+            WindowEvent::PointerMoved { primary: false, .. }
+            | WindowEvent::PointerButton { primary: false, .. } => {}
+            WindowEvent::PointerMoved {
+                position, source, ..
+            } => {
+                self.canvas.set_pressure(match source {
+                    PointerSource::Touch { force, .. } => pressure(force),
+                    _ => None,
+                });
                 let raw = FPoint::new(
-                    position.x as f32 + origin.0 as f32,
-                    position.y as f32 + origin.1 as f32,
+                    position.x as f32 * scale + origin.0 as f32,
+                    position.y as f32 * scale + origin.1 as f32,
                 );
                 self.last_cursor = raw;
+                self.canvas.set_measure_scale(if self.measure_logical {
+                    monitor.scale_factor()
+                } else {
+                    1.0
+                });
                 #[cfg(feature = "editor")]
                 let p = if self.snap_on {
                     crate::render::ui::snap_point(&self.canvas, raw, self.snap_step)
@@ -988,10 +1522,9 @@ impl ApplicationHandler for App {
                 // incremental drift, identical to the legacy driver.
                 #[cfg(feature = "editor")]
                 if let Some(g) = self.gizmo_drag.clone() {
-                    if let Some(sel) = self.canvas.selected() {
-                        let dx = p.x - g.pivot.x;
-                        let dy = p.y - g.pivot.y;
-                        let mut new_shape = (*g.original).clone();
+                    let dx = p.x - g.pivot.x;
+                    let dy = p.y - g.pivot.y;
+                    for mut new_shape in g.originals {
                         match g.handle {
                             crate::render::ui::GizmoHandle::Scale => {
                                 let dist = (dx * dx + dy * dy).sqrt().max(1.0);
@@ -1012,8 +1545,7 @@ impl ApplicationHandler for App {
                                 );
                             }
                         }
-                        new_shape.id = sel;
-                        self.canvas.replace_shape(sel, new_shape);
+                        self.canvas.replace_shape(new_shape.id, new_shape);
                     }
                     self.broadcast_redraw();
                     return;
@@ -1089,47 +1621,41 @@ impl ApplicationHandler for App {
                     };
                     match state {
                         ElementState::Pressed => {
-                            // Gizmo hit-test: if a shape is selected and the
+                            // Gizmo hit-test: if shapes are selected and the
                             // pointer hits a transform handle, start a gizmo
                             // drag instead of routing the click to the
                             // canvas (which would deselect or start a move).
                             #[cfg(feature = "editor")]
                             {
-                                if let Some(sel) = self.canvas.selected() {
-                                    if let Some(shape) = self
-                                        .canvas
-                                        .shapes()
-                                        .iter()
-                                        .find(|s| s.id == sel)
-                                        .cloned()
+                                if let Some(bounds) = self.canvas.selection_bounds() {
+                                    if let Some(h) =
+                                        crate::render::ui::hit_gizmo(bounds, self.last_cursor)
                                     {
-                                        let bounds = shape.bounds();
-                                        if let Some(h) = crate::render::ui::hit_gizmo(
-                                            bounds, self.last_cursor,
-                                        ) {
-                                            let cx = bounds.x() as f32
-                                                + bounds.width() as f32 / 2.0;
-                                            let cy = bounds.y() as f32
-                                                + bounds.height() as f32 / 2.0;
-                                            let dx = pt.x - cx;
-                                            let dy = pt.y - cy;
-                                            let metric = match h {
-                                                crate::render::ui::GizmoHandle::Scale => {
-                                                    (dx * dx + dy * dy).sqrt().max(1.0)
-                                                }
-                                                crate::render::ui::GizmoHandle::Rotate => {
-                                                    dy.atan2(dx)
-                                                }
-                                            };
-                                            self.gizmo_drag = Some(GizmoDrag {
-                                                handle: h,
-                                                pivot: FPoint::new(cx, cy),
-                                                start_metric: metric,
-                                                original: Box::new(shape),
-                                            });
-                                            self.broadcast_redraw();
-                                            return;
-                                        }
+                                        let cx = bounds.x() as f32 + bounds.width() as f32 / 2.0;
+                                        let cy = bounds.y() as f32 + bounds.height() as f32 / 2.0;
+                                        let dx = pt.x - cx;
+                                        let dy = pt.y - cy;
+                                        let metric = match h {
+                                            crate::render::ui::GizmoHandle::Scale => {
+                                                (dx * dx + dy * dy).sqrt().max(1.0)
+                                            }
+                                            crate::render::ui::GizmoHandle::Rotate => dy.atan2(dx),
+                                        };
+                                        let originals = self
+                                            .canvas
+                                            .shapes()
+                                            .iter()
+                                            .filter(|s| self.canvas.selection().contains(&s.id))
+                                            .cloned()
+                                            .collect();
+                                        self.gizmo_drag = Some(GizmoDrag {
+                                            handle: h,
+                                            pivot: FPoint::new(cx, cy),
+                                            start_metric: metric,
+                                            originals,
+                                        });
+                                        self.broadcast_redraw();
+                                        return;
                                     }
                                 }
                             }
@@ -1204,8 +1730,8 @@ impl ApplicationHandler for App {
                                 idx,
                                 crate::render::ui::RadialState {
                                     origin: egui::Pos2::new(
-                                        position.x as f32,
-                                        position.y as f32,
+                                        position.x as f32 * scale,
+                                        position.y as f32 * scale,
                                     ),
                                 },
                                 false,
@@ -1235,6 +1761,11 @@ impl ApplicationHandler for App {
                 }
             }
             WindowEvent::SurfaceResized(new_size) => {
+                if self.windowed {
+                    if let Some(win) = self.windows.iter_mut().find(|w| w.window.id() == id) {
+                        win.view_scale = view_scale(win.monitor.bounds(), new_size);
+                    }
+                }
                 #[cfg(feature = "editor")]
                 if let (Some(gpu), Some(win)) = (
                     self.gpu.clone(),
@@ -1245,6 +1776,29 @@ impl ApplicationHandler for App {
                     }
                 }
             }
+            // Image files dropped onto the overlay land where they were
+            // released; several at once fan out so each stays grabbable.
+            WindowEvent::DragDropped { paths, position } => {
+                for (i, path) in paths.iter().enumerate() {
+                    match crate::sticker::load_image(path) {
+                        Ok(img) => {
+                            let step = i as f32 * 24.0;
+                            let at = FPoint::new(
+                                position.x as f32 * scale + origin.0 as f32 + step,
+                                position.y as f32 * scale + origin.1 as f32 + step,
+                            );
+                            self.place_image(img.into(), Some(at));
+                        }
+                        Err(e) => {
+                            tracing::warn!(
+                                path = %path.display(),
+                                error = %e,
+                                "cannot open dropped file"
+                            );
+                        }
+                    }
+                }
+            }
             _ => {}
         }
     }
@@ -1257,7 +1811,8 @@ impl App {
             SelectorMode::Monitor => {
                 let p =
                     sss_capture::Point::new(self.last_cursor.x as i32, self.last_cursor.y as i32);
-                if let Ok(m) = self.capturer.monitor_at(p) {
+                let monitor = self.capturer.as_ref().and_then(|c| c.monitor_at(p).ok());
+                if let Some(m) = monitor {
                     let image = self.capture_region(m.bounds());
                     Outcome::Monitor {
                         monitor: m.id(),
@@ -1273,8 +1828,8 @@ impl App {
                     sss_capture::Point::new(self.last_cursor.x as i32, self.last_cursor.y as i32);
                 let win = self
                     .capturer
-                    .windows()
-                    .ok()
+                    .as_ref()
+                    .and_then(|c| c.windows().ok())
                     .and_then(|ws| ws.into_iter().find(|w| w.bounds().contains(cursor_point)));
                 if let Some(w) = win {
                     let image = self.capture_region(w.bounds());
@@ -1287,6 +1842,17 @@ impl App {
                     Outcome::Cancelled
                 }
             }
+            SelectorMode::Area | SelectorMode::AnyOf if self.canvas.regions().len() > 1 => {
+                let regions = self
+                    .canvas
+                    .regions()
+                    .into_iter()
+                    .map(|r| (r, self.capture_region(r)))
+                    .collect();
+                // One background can't stand for several crops.
+                self.background = None;
+                Outcome::Regions { regions }
+            }
             SelectorMode::Area | SelectorMode::AnyOf => match region {
                 Some(r) if r.width() >= 2 && r.height() >= 2 => {
                     let image = self.capture_region(r);
@@ -1300,7 +1866,7 @@ impl App {
     }
 
     /// Materialise the captured image for `rect`.
-    fn capture_region(&self, rect: sss_capture::Rect) -> Option<CapImage> {
+    fn capture_region(&mut self, rect: sss_capture::Rect) -> Option<CapImage> {
         let raw = match self.initial.clone() {
             Some(img) => {
                 let monitors_bb = sss_capture::Rect::bounding(
@@ -1321,11 +1887,13 @@ impl App {
             }
             None => self
                 .capturer
+                .as_ref()?
                 .capture_region(rect)
                 .ok()
                 .map(|i| i.into_rgba()),
         };
         let mut buf = raw?;
+        self.background = Some(CapImage::new(buf.clone()));
         crate::render::composite::flatten(&mut buf, &self.canvas, (rect.x(), rect.y()));
         Some(CapImage::new(buf))
     }
@@ -1496,6 +2064,66 @@ impl App {
         ))
     }
 
+    /// Keep one block-averaged texture per mosaic on the canvas (plus the
+    /// in-flight drag) for this monitor. Cells are averaged from the bare
+    /// capture, so strokes under a mosaic only pixelate in the composite;
+    /// each texture is a few hundred texels, cheap enough to rebuild on
+    /// every drag frame.
+    fn refresh_mosaics(&mut self, pos: usize, ctx: &egui::Context) {
+        use crate::shape::ShapeKind;
+        let preview = self.canvas.preview_shape();
+        let wanted: Vec<(sss_capture::Rect, u32)> = self
+            .canvas
+            .shapes()
+            .iter()
+            .chain(preview.as_ref())
+            .filter_map(|s| match s.kind {
+                ShapeKind::Pixelate { rect, block_size } if s.style.fill.is_none() => {
+                    Some((rect, block_size))
+                }
+                _ => None,
+            })
+            .collect();
+        let mosaics = &mut self.windows[pos].mosaics;
+        mosaics.retain(|key, _| wanted.contains(key));
+        let Some(initial) = self.initial.as_ref() else {
+            return;
+        };
+        let bg = initial.as_rgba();
+        let monitors_bb = sss_capture::Rect::bounding(
+            &self.monitors.iter().map(|m| m.bounds()).collect::<Vec<_>>(),
+        )
+        .unwrap_or_default();
+        for (rect, block_size) in wanted {
+            if mosaics.contains_key(&(rect, block_size)) {
+                continue;
+            }
+            // Clip to the capture, in its local coordinates.
+            let x0 = (rect.x() - monitors_bb.x()).clamp(0, bg.width() as i32);
+            let y0 = (rect.y() - monitors_bb.y()).clamp(0, bg.height() as i32);
+            let x1 = (rect.right() - monitors_bb.x()).clamp(0, bg.width() as i32);
+            let y1 = (rect.bottom() - monitors_bb.y()).clamp(0, bg.height() as i32);
+            if x1 <= x0 || y1 <= y0 {
+                continue;
+            }
+            let local = sss_capture::Rect::from_xywh(x0, y0, (x1 - x0) as u32, (y1 - y0) as u32);
+            let cells = crate::render::composite::mosaic(bg, local, block_size);
+            let (w, h) = (cells.width() as usize, cells.height() as usize);
+            let tex = ctx.load_texture(
+                format!("sss_capture_ui::mosaic::{pos}::{rect:?}::{block_size}"),
+                egui::ColorImage::from_rgba_unmultiplied([w, h], cells.as_raw()),
+                egui::TextureOptions::NEAREST,
+            );
+            let area = sss_capture::Rect::from_xywh(
+                x0 + monitors_bb.x(),
+                y0 + monitors_bb.y(),
+                local.width(),
+                local.height(),
+            );
+            mosaics.insert((rect, block_size), (area, tex));
+        }
+    }
+
     fn render_window(&mut self, id: WinitWindowId, _event_loop: &dyn ActiveEventLoop) {
         use crate::render::overlay::{draw_canvas, draw_confirm_hint};
 
@@ -1564,6 +2192,7 @@ impl App {
                 }
             }
         }
+        self.refresh_mosaics(pos, &window_gpu.egui_ctx);
         let background = self.windows[pos].background.clone();
         let background_blurred = self
             .windows[pos]
@@ -1573,11 +2202,20 @@ impl App {
         // Take per-window icon cache out so the ctx.run closure can
         // mutably borrow it without aliasing `self.windows`.
         let mut icons = std::mem::take(&mut self.windows[pos].icons);
+        let mosaics = std::mem::take(&mut self.windows[pos].mosaics);
         // Pre-decide if this overlay should host the toolbar — the overlay
         // with the largest overlap with the active region wins; fall back
         // to the focused overlay when there's no region.
         let host_toolbar = self.is_toolbar_host(pos);
 
+        // One egui point per canvas pixel in the editor window too, whatever
+        // the image was scaled to on screen.
+        if self.windowed {
+            let native = window_arc.scale_factor() as f32;
+            window_gpu
+                .egui_ctx
+                .set_zoom_factor(1.0 / (self.windows[pos].view_scale * native));
+        }
         let raw_input = window_gpu.egui_winit.take_egui_input(&*window_arc);
 
         let mut confirm = false;
@@ -1588,7 +2226,16 @@ impl App {
             egui::CentralPanel::default()
                 .frame(egui::Frame::new())
                 .show(ctx, |ui| {
-                    let screen_rect = ui.max_rect();
+                    // A resized editor window can be wider or taller than
+                    // the scaled image; paint only the image's part of it.
+                    let screen_rect = if self.windowed {
+                        egui::Rect::from_min_size(
+                            egui::Pos2::ZERO,
+                            egui::Vec2::new(monitor_w as f32, monitor_h as f32),
+                        )
+                    } else {
+                        ui.max_rect()
+                    };
                     let painter = ui.painter();
                     let monitor_origin = egui::Pos2::new(origin_x as f32, origin_y as f32);
                     if let Some(tex) = background.as_ref() {
@@ -1662,6 +2309,25 @@ impl App {
                         } else {
                             painter.rect_filled(screen_rect, 0.0, dim_color);
                         }
+                        // Earlier regions of a multi-region selection show
+                        // through the dim too.
+                        if let Some(tex) = background.as_ref() {
+                            let sr = screen_rect;
+                            for r in self.canvas.extra_regions() {
+                                let local = egui::Rect::from_min_size(
+                                    egui::Pos2::new(
+                                        sr.min.x + (r.x() - origin_x) as f32,
+                                        sr.min.y + (r.y() - origin_y) as f32,
+                                    ),
+                                    egui::Vec2::new(r.width() as f32, r.height() as f32),
+                                );
+                                let uv = egui::Rect::from_min_max(
+                                    ((local.min - sr.min) / sr.size()).to_pos2(),
+                                    ((local.max - sr.min) / sr.size()).to_pos2(),
+                                );
+                                painter.image(tex.id(), local, uv, egui::Color32::WHITE);
+                            }
+                        }
                     }
                     // Snap grid dots inside the active region so the user
                     // can see where points will snap to.
@@ -1703,46 +2369,81 @@ impl App {
                         &self.canvas,
                         monitor_origin,
                         Some(self.last_cursor),
+                        background.as_ref(),
                         background_blurred.as_ref(),
+                        &mosaics,
                         (monitor_w, monitor_h),
                         region_col,
                     );
+                    let (guide_xs, guide_ys) = self.canvas.edge_guides();
+                    crate::render::ui::draw_edge_guides(
+                        painter,
+                        &guide_xs,
+                        &guide_ys,
+                        monitor_origin,
+                        egui::Vec2::new(monitor_w as f32, monitor_h as f32),
+                        &self.config.ui.chrome,
+                    );
                     // Transform gizmos: only paint on the overlay holding
-                    // the largest slice of the selected shape bounds, so
-                    // the handles don't repeat on every monitor.
-                    if let Some(sel) = self.canvas.selected() {
-                        if let Some(shape) =
-                            self.canvas.shapes().iter().find(|s| s.id == sel)
-                        {
-                            let b = shape.bounds();
-                            let is_owner = {
-                                let mut best: (usize, u64) = (pos, 0);
-                                for (i, w) in self.windows.iter().enumerate() {
-                                    if let Some(inter) = w.monitor.bounds().intersection(&b) {
-                                        let area =
-                                            inter.width() as u64 * inter.height() as u64;
-                                        if area > best.1 {
-                                            best = (i, area);
-                                        }
+                    // the largest slice of the selection bounds, so the
+                    // handles don't repeat on every monitor.
+                    if let Some(b) = self.canvas.selection_bounds() {
+                        let is_owner = {
+                            let mut best: (usize, u64) = (pos, 0);
+                            for (i, w) in self.windows.iter().enumerate() {
+                                if let Some(inter) = w.monitor.bounds().intersection(&b) {
+                                    let area = inter.width() as u64 * inter.height() as u64;
+                                    if area > best.1 {
+                                        best = (i, area);
                                     }
                                 }
-                                best.0 == pos && best.1 > 0
-                            };
-                            if is_owner
-                                && matches!(
-                                    self.canvas.active_tool,
-                                    crate::tool::Tool::Pointer
-                                )
-                            {
-                                crate::render::ui::draw_gizmos(
+                            }
+                            best.0 == pos && best.1 > 0
+                        };
+                        if is_owner && matches!(self.canvas.active_tool, crate::tool::Tool::Pointer)
+                        {
+                            let selected: Vec<&crate::shape::Shape> = self
+                                .canvas
+                                .shapes()
+                                .iter()
+                                .filter(|s| self.canvas.selection().contains(&s.id))
+                                .collect();
+                            if selected.len() > 1 {
+                                crate::render::ui::draw_selection_members(
                                     painter,
-                                    b,
+                                    &selected.iter().map(|s| s.bounds()).collect::<Vec<_>>(),
                                     monitor_origin,
                                     &self.config.ui.chrome,
                                 );
                             }
+                            crate::render::ui::draw_gizmos(
+                                painter,
+                                b,
+                                monitor_origin,
+                                &self.config.ui.chrome,
+                            );
+                            if let [shape] = selected.as_slice() {
+                                if let crate::shape::ShapeKind::Arrow { from, to, bend } =
+                                    shape.kind
+                                {
+                                    crate::render::ui::draw_bend_handle(
+                                        painter,
+                                        crate::shape::arrow_mid(from, to, bend),
+                                        monitor_origin,
+                                        &self.config.ui.chrome,
+                                    );
+                                }
+                            }
                         }
                     }
+                    if let Some(band) = self.canvas.selection_band() {
+                        crate::render::ui::draw_selection_band(
+                            painter,
+                            band,
+                            monitor_origin,
+                            &self.config.ui.chrome,
+                        );
+                    }
                     if self.config.confirm_with_enter {
                         let drawing_poly = matches!(
                             self.canvas.active_tool,
@@ -1788,6 +2489,8 @@ impl App {
                         snap_active: self.snap_on,
                         magnifier_active: self.magnifier_on,
                         snap_step: self.snap_step,
+                        stickers: self.config.ui.sticker_dir.is_some(),
+                        measure_logical: self.measure_logical,
                     },
                     &mut icons,
                 );
@@ -1807,6 +2510,41 @@ impl App {
                         self.push_current_to_tool();
                     }
                 }
+                if let Some(i) = out.select_tool_ellipse {
+                    if let Some(mut tool) = self.config.palette.tools.get(i).cloned() {
+                        if let crate::tool::Tool::Spotlight { shape, .. } = &mut tool {
+                            *shape = crate::shape::SpotlightShape::Ellipse;
+                        }
+                        self.canvas.set_tool(tool);
+                        self.current_fill = None;
+                        self.push_current_to_tool();
+                    }
+                }
+                if let Some(option) = out.text_option {
+                    use crate::render::ui::TextOption;
+                    let fonts = &self.config.ui.text_fonts;
+                    self.canvas.update_text_style(|style| match option {
+                        TextOption::Bold => style.bold = !style.bold,
+                        TextOption::Italic => style.italic = !style.italic,
+                        TextOption::Underline => style.underline = !style.underline,
+                        TextOption::Background => style.cycle_background(),
+                        TextOption::Outline => style.toggle_outline(),
+                        TextOption::Font => style.cycle_family(fonts),
+                    });
+                }
+                if let Some(option) = out.measure_option {
+                    use crate::render::ui::MeasureOption;
+                    match (option, &mut self.canvas.active_tool) {
+                        (MeasureOption::Units, _) => self.measure_logical = !self.measure_logical,
+                        (MeasureOption::Box, crate::tool::Tool::Measure { boxed, .. }) => {
+                            *boxed = !*boxed
+                        }
+                        (MeasureOption::Bake, crate::tool::Tool::Measure { bake, .. }) => {
+                            *bake = !*bake
+                        }
+                        _ => {}
+                    }
+                }
                 if out.undo {
                     self.canvas.handle(CanvasEvent::Undo);
                 }
@@ -1846,6 +2584,13 @@ impl App {
                         Some((pos, origin, false))
                     };
                 }
+                if let Some(origin) = out.open_line_popup {
+                    self.line_popup = if self.line_popup.is_some() {
+                        None
+                    } else {
+                        Some((pos, origin, false))
+                    };
+                }
                 if let Some(origin) = out.open_snap_popup {
                     self.snap_popup = if self.snap_popup.is_some() {
                         None
@@ -1853,6 +2598,14 @@ impl App {
                         Some((pos, origin, false))
                     };
                 }
+                if let Some(origin) = out.open_sticker_popup {
+                    self.sticker_popup = if self.sticker_popup.is_some() {
+                        None
+                    } else {
+                        self.load_stickers();
+                        Some((pos, origin, false))
+                    };
+                }
                 if let Some(origin) = out.open_color_popup {
                     self.color_popup = if self.color_popup.is_some() {
                         None
@@ -1926,52 +2679,80 @@ impl App {
                 }
             }
 
-            // Selection toolbar (raise / lower / trash) — anchored to the
-            // selected shape's bounds on the overlay with the largest
-            // intersection.
+            // Selection toolbar (raise / lower / trash, alignment) —
+            // anchored to the selection bounds on the overlay with the
+            // largest intersection.
             if matches!(self.canvas.active_tool, crate::tool::Tool::Pointer) {
-                if let Some(sel) = self.canvas.selected() {
-                    if let Some(shape) =
-                        self.canvas.shapes().iter().find(|s| s.id == sel)
-                    {
-                        let b = shape.bounds();
-                        let is_owner = {
-                            let mut best: (usize, u64) = (pos, 0);
-                            for (i, w) in self.windows.iter().enumerate() {
-                                if let Some(inter) = w.monitor.bounds().intersection(&b) {
-                                    let area =
-                                        inter.width() as u64 * inter.height() as u64;
-                                    if area > best.1 {
-                                        best = (i, area);
-                                    }
-                                }
-                            }
-                            best.0 == pos && best.1 > 0
-                        };
-                        if is_owner {
-                            let (sel_out, sel_rect) =
-                                crate::render::ui::draw_selection_toolbar(
-                                    ctx,
-                                    b,
-                                    egui::Pos2::new(origin_x as f32, origin_y as f32),
-                                    egui::Vec2::new(
-                                        monitor_w as f32,
-                                        monitor_h as f32,
-                                    ),
-                                    &self.config.ui.chrome,
-                                    &mut icons,
-                                );
-                            local_chrome.push(sel_rect);
-                            if sel_out.raise {
-                                self.canvas.raise_selected();
-                            }
-                            if sel_out.lower {
-                                self.canvas.lower_selected();
-                            }
-                            if sel_out.delete {
-                                self.canvas.handle(CanvasEvent::Delete);
-                            }
+                if let Some(b) = self.canvas.selection_bounds() {
+                    if self.owner_overlay(b) == Some(pos) {
+                        let (sel_out, sel_rect) = crate::render::ui::draw_selection_toolbar(
+                            ctx,
+                            b,
+                            crate::render::ui::SelectionTarget::Shape {
+                                parts: self.canvas.selection_parts(),
+                                grouped: self.canvas.selection_is_group(),
+                            },
+                            egui::Pos2::new(origin_x as f32, origin_y as f32),
+                            egui::Vec2::new(monitor_w as f32, monitor_h as f32),
+                            &self.config.ui.chrome,
+                            &mut icons,
+                        );
+                        local_chrome.push(sel_rect);
+                        if sel_out.raise {
+                            self.canvas.raise_selected();
+                        }
+                        if sel_out.lower {
+                            self.canvas.lower_selected();
                         }
+                        if sel_out.group {
+                            self.canvas.toggle_group();
+                        }
+                        if let Some(align) = sel_out.align {
+                            self.canvas.align_selection(align);
+                        }
+                        if let Some(axis) = sel_out.distribute {
+                            self.canvas.distribute_selection(axis);
+                        }
+                        if sel_out.delete {
+                            self.canvas.handle(CanvasEvent::Delete);
+                        }
+                    }
+                }
+            }
+
+            // Region presets: the same bar hangs off the region with a
+            // chip cycling Free → each `UiConfig::region_presets` entry.
+            let region_bar = !self.config.ui.region_presets.is_empty()
+                && self.runtime_mode == SelectorMode::Area
+                && self.canvas.selected().is_none()
+                && !self.canvas.is_drag_active()
+                && matches!(self.canvas.active_tool, crate::tool::Tool::Pointer);
+            if let Some(b) = self.canvas.region().filter(|_| region_bar) {
+                if self.owner_overlay(b) == Some(pos) {
+                    let (bar_out, bar_rect) = crate::render::ui::draw_selection_toolbar(
+                        ctx,
+                        b,
+                        crate::render::ui::SelectionTarget::Region {
+                            preset: self.canvas.region_preset(),
+                        },
+                        egui::Pos2::new(origin_x as f32, origin_y as f32),
+                        egui::Vec2::new(monitor_w as f32, monitor_h as f32),
+                        &self.config.ui.chrome,
+                        &mut icons,
+                    );
+                    local_chrome.push(bar_rect);
+                    if bar_out.next_preset {
+                        let presets = &self.config.ui.region_presets;
+                        let next = match self.canvas.region_preset() {
+                            None => presets.first().copied(),
+                            Some(cur) => presets
+                                .iter()
+                                .position(|p| *p == cur)
+                                .and_then(|i| presets.get(i + 1))
+                                .copied(),
+                        };
+                        self.canvas.set_region_preset(next);
+                        self.broadcast_redraw();
                     }
                 }
             }
@@ -2003,6 +2784,76 @@ impl App {
                 }
             }
 
+            // Line style popup.
+            if let Some((p_pos, origin, mut armed)) = self.line_popup {
+                if p_pos == pos {
+                    let (out, l_rect) = crate::render::ui::draw_line_popup(
+                        ctx,
+                        origin,
+                        &mut armed,
+                        self.current_line,
+                        &self.config.ui.chrome,
+                    );
+                    if let Some(slot) = self.line_popup.as_mut() {
+                        slot.2 = armed;
+                    }
+                    local_chrome.push(l_rect);
+                    if let Some(line) = out.style {
+                        self.apply_line_pick(line);
+                    }
+                    if out.close {
+                        self.line_popup = None;
+                    }
+                }
+            }
+
+            // Sticker popup.
+            if let Some((p_pos, origin, mut armed)) = self.sticker_popup {
+                if p_pos == pos {
+                    let stickers = self.stickers.clone().unwrap_or_default();
+                    let (out, s_rect) = crate::render::ui::draw_sticker_popup(
+                        ctx,
+                        origin,
+                        &mut armed,
+                        &stickers,
+                        &self.config.ui.chrome,
+                    );
+                    if let Some(slot) = self.sticker_popup.as_mut() {
+                        slot.2 = armed;
+                    }
+                    local_chrome.push(s_rect);
+                    if let Some(pixels) = out.pick.and_then(|i| stickers.get(i)) {
+                        self.place_image(pixels.clone(), None);
+                    }
+                    if out.close {
+                        self.sticker_popup = None;
+                    }
+                }
+            }
+
+            // Region entry field, centred on the overlay.
+            if let Some((p_pos, mut text, mut armed)) = self.region_entry.clone() {
+                if p_pos == pos {
+                    let origin = egui::Pos2::new(monitor_w as f32 / 2.0, monitor_h as f32 / 2.0);
+                    let (out, e_rect) = crate::render::ui::draw_region_entry(
+                        ctx,
+                        origin,
+                        &mut armed,
+                        &mut text,
+                        &self.config.ui.chrome,
+                    );
+                    local_chrome.push(e_rect);
+                    self.region_entry = Some((p_pos, text, armed));
+                    if let Some(rect) = out.apply {
+                        self.canvas.set_region(Some(rect));
+                        self.broadcast_redraw();
+                    }
+                    if out.close {
+                        self.region_entry = None;
+                    }
+                }
+            }
+
             // Color picker popup.
             if let Some((p_pos, origin, armed_in, state_in)) =
                 self.color_popup.clone()
@@ -2108,6 +2959,39 @@ impl App {
                 }
             }
 
+            // Coordinate readout next to the pointer, in the same units as
+            // the Measure tool.
+            if self.config.ui.show_readout && self.active_window == Some(id) {
+                let scale = if self.measure_logical {
+                    self.windows[pos].monitor.scale_factor().max(0.1)
+                } else {
+                    1.0
+                };
+                let unit = if scale == 1.0 { "px" } else { "pt" };
+                let mut text = format!(
+                    "{}, {}",
+                    (self.last_cursor.x / scale).round(),
+                    (self.last_cursor.y / scale).round()
+                );
+                if let Some(r) = self.canvas.region() {
+                    text.push_str(&format!(
+                        "\n{} × {} {unit}",
+                        (r.width() as f32 / scale).round(),
+                        (r.height() as f32 / scale).round()
+                    ));
+                }
+                crate::render::ui::draw_readout(
+                    ctx,
+                    egui::Pos2::new(
+                        self.last_cursor.x - origin_x as f32,
+                        self.last_cursor.y - origin_y as f32,
+                    ),
+                    egui::Vec2::new(monitor_w as f32, monitor_h as f32),
+                    text,
+                    &self.config.ui.chrome,
+                );
+            }
+
             // Radial menu — only drawn on the overlay it was opened on.
             if let Some((radial_pos, state, mut armed)) = self.radial {
                 if radial_pos == pos {
@@ -2119,6 +3003,11 @@ impl App {
                         &self.config.ui.radial_widths,
                         self.current_color,
                         self.current_width,
+                        self.canvas
+                            .active_tool
+                            .uses_line_style()
+                            .then_some(self.current_line),
+                        matches!(self.canvas.active_tool, crate::tool::Tool::Arrow(_)),
                         &self.config.ui.chrome,
                     );
                     if let Some(slot) = self.radial.as_mut() {
@@ -2133,6 +3022,18 @@ impl App {
                             crate::render::ui::RadialPick::Width(w) => {
                                 self.apply_width_pick(w);
                             }
+                            crate::render::ui::RadialPick::Dash(dash) => {
+                                self.apply_line_pick(crate::shape::LineStyle {
+                                    dash,
+                                    ..self.current_line
+                                });
+                            }
+                            crate::render::ui::RadialPick::Head(head) => {
+                                self.apply_line_pick(crate::shape::LineStyle {
+                                    head,
+                                    ..self.current_line
+                                });
+                            }
                         }
                         self.radial = None;
                     } else if outcome.close {
@@ -2150,6 +3051,7 @@ impl App {
             self.chrome_rects.insert(pos, local_chrome);
         }
         self.windows[pos].icons = icons;
+        self.windows[pos].mosaics = mosaics;
 
         window_gpu
             .egui_winit
@@ -3,8 +3,13 @@
 //! `winit-wayland` backend; X11 and other backends fall back to a
 //! borderless fullscreen toplevel.
 
+#[cfg(feature = "editor")]
+mod countdown;
 mod driver;
 
+#[cfg(feature = "editor")]
+pub(crate) use countdown::run as countdown_window;
+
 pub(crate) fn run(
     sel: crate::selector::Selector,
 ) -> Result<crate::selector::Selection, crate::selector::SelectorError> {
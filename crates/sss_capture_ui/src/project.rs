@@ -0,0 +1,356 @@
+//! `.sss` projects: an image plus the shapes drawn on it, kept editable.
+//!
+//! A project is a regular PNG of the *unannotated* image carrying the
+//! serialized [`Canvas`] in an `iTXt` chunk, so any viewer still opens it
+//! and `sss edit` can bring every shape back as a live object.
+
+use std::io::{BufWriter, Cursor, Write};
+use std::path::Path;
+
+use image::RgbaImage;
+use thiserror::Error;
+
+use crate::canvas::Canvas;
+
+/// `iTXt` keyword holding the canvas JSON.
+const CHUNK_KEYWORD: &str = "sss:canvas";
+/// Bumped on incompatible changes to the JSON layout.
+const FORMAT_VERSION: u32 = 1;
+
+/// An annotation session that can be written to disk and reopened.
+#[derive(Clone, Debug)]
+pub struct Project {
+    /// Pixels under the annotations, without any shape painted in.
+    pub image: RgbaImage,
+    /// Shapes in image pixel coordinates.
+    pub canvas: Canvas,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Document {
+    version: u32,
+    canvas: Canvas,
+}
+
+impl Project {
+    pub const EXTENSION: &'static str = "sss";
+
+    pub fn new(image: RgbaImage, canvas: Canvas) -> Self {
+        Self { image, canvas }
+    }
+
+    /// The image with every shape flattened in, as the overlay would
+    /// produce it.
+    pub fn render(&self) -> RgbaImage {
+        let mut out = self.image.clone();
+        crate::render::composite::flatten(&mut out, &self.canvas, (0, 0));
+        out
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ProjectError> {
+        let file = std::fs::File::create(path)?;
+        self.write(BufWriter::new(file))
+    }
+
+    pub fn write(&self, w: impl Write) -> Result<(), ProjectError> {
+        let json = serde_json::to_string(&Document {
+            version: FORMAT_VERSION,
+            canvas: self.canvas.clone(),
+        })?;
+        let mut encoder = png::Encoder::new(w, self.image.width(), self.image.height());
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.add_itxt_chunk(CHUNK_KEYWORD.to_string(), json)?;
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(self.image.as_raw())?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProjectError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Parse a project from the bytes of a `.sss` file. Fails with
+    /// [`ProjectError::NotAProject`] for a plain image; see
+    /// [`Self::is_project`] to tell the two apart up front.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProjectError> {
+        let json = canvas_chunk(bytes)?.ok_or(ProjectError::NotAProject)?;
+        let doc: Document = serde_json::from_str(&json)?;
+        if !(1..=FORMAT_VERSION).contains(&doc.version) {
+            return Err(ProjectError::UnsupportedVersion(doc.version));
+        }
+        let image =
+            image::load_from_memory_with_format(bytes, image::ImageFormat::Png)?.into_rgba8();
+        Ok(Self {
+            image,
+            canvas: doc.canvas,
+        })
+    }
+
+    /// True when `bytes` is a PNG carrying an sss canvas.
+    pub fn is_project(bytes: &[u8]) -> bool {
+        matches!(canvas_chunk(bytes), Ok(Some(_)))
+    }
+}
+
+fn canvas_chunk(bytes: &[u8]) -> Result<Option<String>, ProjectError> {
+    let reader = png::Decoder::new(Cursor::new(bytes)).read_info()?;
+    match reader
+        .info()
+        .utf8_text
+        .iter()
+        .find(|c| c.keyword == CHUNK_KEYWORD)
+    {
+        Some(chunk) => Ok(Some(chunk.get_text()?)),
+        None => Ok(None),
+    }
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ProjectError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("PNG encoding failed: {0}")]
+    Encode(#[from] png::EncodingError),
+    #[error("not a PNG: {0}")]
+    Decode(#[from] png::DecodingError),
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+    #[error("malformed project data: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the image has no sss annotations")]
+    NotAProject,
+    #[error("project format version {0} is not one this build understands")]
+    UnsupportedVersion(u32),
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+    use sss_capture::Rect;
+
+    use super::*;
+    use crate::color::Color;
+    use crate::geometry::FPoint;
+    use crate::shape::{
+        ImagePixels, LineStyle, Shape, ShapeId, ShapeKind, SpotlightShape, Style, TextBackground,
+        TextOutline, TextStyle,
+    };
+
+    /// One sample of every kind. The match in [`kind_name`] stops
+    /// compiling when a kind is added, as a reminder to add it here.
+    fn every_kind() -> Vec<ShapeKind> {
+        let p = FPoint::new;
+        let rect = Rect::from_xywh(4, 6, 20, 10);
+        let text_style = TextStyle {
+            color: Color::BLUE,
+            size: 13.5,
+            bold: true,
+            italic: true,
+            underline: true,
+            family: "serif".to_owned(),
+            background: Some(TextBackground {
+                color: Color::WHITE,
+                padding: 3.0,
+                pill: true,
+            }),
+            outline: Some(TextOutline {
+                color: Color::BLACK,
+                width: 1.5,
+            }),
+        };
+        vec![
+            ShapeKind::FreehandStroke {
+                points: vec![p(1.0, 2.0), p(3.25, 4.5), p(6.0, 1.0)],
+                widths: vec![0.2, 0.75, 1.0],
+            },
+            ShapeKind::Highlight {
+                points: vec![p(0.0, 5.0), p(30.0, 5.0)],
+            },
+            ShapeKind::Line {
+                from: p(0.5, 0.5),
+                to: p(10.0, 20.0),
+            },
+            ShapeKind::Arrow {
+                from: p(0.0, 0.0),
+                to: p(15.0, 8.0),
+                bend: Some(p(9.0, -3.0)),
+            },
+            ShapeKind::Rectangle { rect },
+            ShapeKind::Ellipse { rect },
+            ShapeKind::BlurRect { rect, radius: 7.5 },
+            ShapeKind::Pixelate {
+                rect,
+                block_size: 6,
+            },
+            ShapeKind::Spotlight {
+                rect,
+                shape: SpotlightShape::Ellipse,
+                amount: 90,
+                desaturate: true,
+            },
+            ShapeKind::Magnify {
+                source: rect,
+                target: p(40.0, 30.0),
+                zoom: 2.5,
+            },
+            ShapeKind::Measure {
+                from: p(2.0, 2.0),
+                to: p(22.0, 12.0),
+                boxed: true,
+                scale: 2.0,
+                baked: true,
+            },
+            ShapeKind::Callout {
+                body: rect,
+                anchor: p(-5.0, 40.0),
+                text: "see here".to_owned(),
+                text_style: text_style.clone(),
+            },
+            ShapeKind::Step {
+                center: p(12.0, 12.0),
+                number: 3,
+                radius: 11.0,
+            },
+            ShapeKind::Text {
+                origin: p(8.0, 9.0),
+                content: "multi\nline ✓".to_owned(),
+                style: text_style,
+            },
+            ShapeKind::Polygon {
+                points: vec![p(0.0, 0.0), p(10.0, 0.0), p(5.0, 8.0)],
+                closed: true,
+            },
+            ShapeKind::Image {
+                rect,
+                pixels: ImagePixels::new(RgbaImage::from_fn(3, 2, |x, y| {
+                    Rgba([x as u8 * 80, y as u8 * 120, 7, 200])
+                })),
+            },
+        ]
+    }
+
+    fn kind_name(kind: &ShapeKind) -> &'static str {
+        match kind {
+            ShapeKind::FreehandStroke { .. } => "freehand",
+            ShapeKind::Highlight { .. } => "highlight",
+            ShapeKind::Line { .. } => "line",
+            ShapeKind::Arrow { .. } => "arrow",
+            ShapeKind::Rectangle { .. } => "rectangle",
+            ShapeKind::Ellipse { .. } => "ellipse",
+            ShapeKind::BlurRect { .. } => "blur",
+            ShapeKind::Pixelate { .. } => "pixelate",
+            ShapeKind::Spotlight { .. } => "spotlight",
+            ShapeKind::Magnify { .. } => "magnify",
+            ShapeKind::Measure { .. } => "measure",
+            ShapeKind::Callout { .. } => "callout",
+            ShapeKind::Step { .. } => "step",
+            ShapeKind::Text { .. } => "text",
+            ShapeKind::Polygon { .. } => "polygon",
+            ShapeKind::Image { .. } => "image",
+        }
+    }
+
+    fn sample_project() -> Project {
+        let mut canvas = Canvas::default();
+        canvas.set_region(Some(Rect::from_xywh(2, 3, 50, 40)));
+        for (i, kind) in every_kind().into_iter().enumerate() {
+            canvas.shapes_mut().push(Shape {
+                id: ShapeId(i as u64 + 1),
+                kind,
+                style: Style {
+                    stroke: Color::GREEN,
+                    stroke_width: 4.5,
+                    fill: (i % 2 == 0).then_some(Color::SHADOW),
+                    line: LineStyle {
+                        opacity: 0.5,
+                        ..LineStyle::default()
+                    },
+                },
+                rotation: i as f32 * 0.125,
+                group: (i < 2).then_some(ShapeId(1)),
+            });
+        }
+        let image = RgbaImage::from_fn(64, 48, |x, y| Rgba([x as u8, y as u8, 99, 255]));
+        Project::new(image, canvas)
+    }
+
+    fn to_bytes(project: &Project) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        project.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// A PNG carrying `json` as its canvas chunk.
+    fn png_with_chunk(json: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, 1, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder
+            .add_itxt_chunk(CHUNK_KEYWORD.to_string(), json.to_owned())
+            .unwrap();
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[0; 4]).unwrap();
+        writer.finish().unwrap();
+        bytes
+    }
+
+    #[test]
+    fn every_shape_kind_survives_save_and_load() {
+        let project = sample_project();
+        let names: std::collections::HashSet<_> = project
+            .canvas
+            .shapes()
+            .iter()
+            .map(|s| kind_name(&s.kind))
+            .collect();
+        assert_eq!(names.len(), project.canvas.shapes().len());
+
+        let bytes = to_bytes(&project);
+        assert!(Project::is_project(&bytes));
+        let loaded = Project::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.image, project.image);
+        assert_eq!(loaded.canvas.region(), project.canvas.region());
+        assert_eq!(
+            format!("{:?}", loaded.canvas.shapes()),
+            format!("{:?}", project.canvas.shapes())
+        );
+        // Writing the loaded project again gives the same file.
+        assert_eq!(to_bytes(&loaded), bytes);
+    }
+
+    #[test]
+    fn plain_png_is_not_a_project() {
+        let mut bytes = Vec::new();
+        RgbaImage::new(2, 2)
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+        assert!(!Project::is_project(&bytes));
+        assert!(matches!(
+            Project::from_bytes(&bytes),
+            Err(ProjectError::NotAProject)
+        ));
+    }
+
+    #[test]
+    fn unknown_format_versions_are_rejected() {
+        for version in [0, FORMAT_VERSION + 1, u32::MAX] {
+            let bytes = png_with_chunk(&format!(r#"{{"version":{version},"canvas":{{}}}}"#));
+            assert!(
+                matches!(
+                    Project::from_bytes(&bytes),
+                    Err(ProjectError::UnsupportedVersion(v)) if v == version
+                ),
+                "version {version}"
+            );
+        }
+        let current = png_with_chunk(&format!(r#"{{"version":{FORMAT_VERSION},"canvas":{{}}}}"#));
+        assert!(Project::from_bytes(&current).is_ok());
+        assert!(matches!(
+            Project::from_bytes(&png_with_chunk(r#"{"canvas":{}}"#)),
+            Err(ProjectError::Json(_))
+        ));
+    }
+}
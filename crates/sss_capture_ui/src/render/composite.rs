@@ -8,14 +8,17 @@ use image::{Rgba, RgbaImage};
 use crate::canvas::Canvas;
 use crate::color::Color;
 use crate::geometry::FPoint;
-use crate::shape::{Shape, ShapeKind, TextStyle};
+use crate::shape::{
+    ArrowHead, LineCap, LineJoin, Shape, ShapeKind, SpotlightShape, Style, TextStyle,
+};
 use sss_capture::Rect;
 
 /// Render every shape in `canvas` onto `image`; `origin` is the canvas
 /// coordinate that the image's top-left corresponds to.
 pub fn flatten(image: &mut RgbaImage, canvas: &Canvas, origin: (i32, i32)) {
-    for shape in canvas.shapes() {
-        paint_one(image, shape, origin);
+    let shapes = canvas.shapes();
+    for i in 0..shapes.len() {
+        paint_at(image, shapes, i, origin);
     }
 }
 
@@ -28,25 +31,23 @@ pub fn flatten_below_first_blur(
     canvas: &Canvas,
     origin: (i32, i32),
 ) {
-    for shape in canvas.shapes() {
+    let shapes = canvas.shapes();
+    for (i, shape) in shapes.iter().enumerate() {
         if matches!(shape.kind, ShapeKind::BlurRect { .. }) {
             break;
         }
-        paint_one(image, shape, origin);
+        paint_at(image, shapes, i, origin);
     }
 }
 
 /// Like [`flatten`] but also paints in-flight previews on top.
 #[allow(dead_code)]
 pub fn flatten_with_preview(image: &mut RgbaImage, canvas: &Canvas, origin: (i32, i32)) {
-    for shape in canvas.shapes() {
-        paint_one(image, shape, origin);
-    }
-    if let Some(preview) = canvas.preview_shape() {
-        paint_one(image, &preview, origin);
-    }
-    if let Some(text) = canvas.pending_text() {
-        paint_one(image, &text, origin);
+    let mut shapes = canvas.shapes().to_vec();
+    shapes.extend(canvas.preview_shape());
+    shapes.extend(canvas.pending_text());
+    for i in 0..shapes.len() {
+        paint_at(image, &shapes, i, origin);
     }
     if let Some(verts) = canvas.polygon_vertices() {
         if verts.len() >= 2 {
@@ -79,11 +80,46 @@ pub fn flatten_with_preview(image: &mut RgbaImage, canvas: &Canvas, origin: (i32
     }
 }
 
-fn paint_one(image: &mut RgbaImage, shape: &Shape, origin: (i32, i32)) {
+/// Paint `shapes[i]` where it sits in the stack. Spotlights act together:
+/// the topmost one dims around the union of all of them and the others
+/// paint nothing.
+pub(super) fn paint_at(image: &mut RgbaImage, shapes: &[Shape], i: usize, origin: (i32, i32)) {
+    let shape = &shapes[i];
+    if !matches!(shape.kind, ShapeKind::Spotlight { .. }) {
+        paint_one(image, shape, origin);
+    } else if top_spotlight(shapes) == Some(i) {
+        apply_spotlights(image, shapes, origin);
+    }
+}
+
+/// Index of the spotlight whose z-position and settings apply.
+pub(super) fn top_spotlight(shapes: &[Shape]) -> Option<usize> {
+    shapes
+        .iter()
+        .rposition(|s| matches!(s.kind, ShapeKind::Spotlight { .. }))
+}
+
+/// Paint a shape on its own; a spotlight only sees its own area. Use
+/// [`paint_at`] when the rest of the stack is at hand.
+pub(super) fn paint_one(image: &mut RgbaImage, shape: &Shape, origin: (i32, i32)) {
     match &shape.kind {
+        ShapeKind::Spotlight { .. } => apply_spotlights(image, std::slice::from_ref(shape), origin),
         ShapeKind::BlurRect { rect, radius } => {
             apply_blur(image, *rect, *radius, origin);
         }
+        ShapeKind::Pixelate { rect, block_size } => match shape.style.fill {
+            // Always opaque: a translucent redaction would leak the text.
+            Some(Color([r, g, b, _])) => {
+                fill_rect(image, local_rect(*rect, origin), Color([r, g, b, 255]))
+            }
+            None => apply_pixelate(image, *rect, *block_size, origin),
+        },
+        ShapeKind::Highlight { points } => apply_highlight(image, points, &shape.style, origin),
+        ShapeKind::Magnify {
+            source,
+            target,
+            zoom,
+        } => apply_magnify(image, *source, *target, *zoom, &shape.style, origin),
         _ => draw_shape(image, shape, origin),
     }
 }
@@ -91,67 +127,70 @@ fn paint_one(image: &mut RgbaImage, shape: &Shape, origin: (i32, i32)) {
 fn draw_shape(img: &mut RgbaImage, shape: &Shape, origin: (i32, i32)) {
     use ShapeKind::*;
     match &shape.kind {
-        FreehandStroke { points } => {
-            let color = shape.style.stroke;
-            let w = shape.style.stroke_width.max(1.0) as i32;
-            let smoothed = crate::shape::smoothed_freehand(points, w as f32);
-            if smoothed.len() == 1 {
-                fill_disk(img, local(smoothed[0], origin), (w / 2).max(0), color);
-            } else if smoothed.len() >= 2 {
-                let r = (w / 2).max(0);
-                for pair in smoothed.windows(2) {
-                    let a = local(pair[0], origin);
-                    let b = local(pair[1], origin);
-                    stroke_line_aa(img, a, b, color, w);
-                    // Disk at every joint covers miter gaps when the curve
-                    // turns sharply.
-                    fill_disk(img, a, r, color);
-                }
-                // End cap.
-                if let Some(last) = smoothed.last() {
-                    fill_disk(img, local(*last, origin), r, color);
-                }
-            }
+        FreehandStroke { points, widths } if !widths.is_empty() => {
+            let width = shape.style.stroke_width.max(1.0);
+            let smoothed = crate::shape::smoothed_freehand_widths(points, widths, width);
+            stroke_pressure_path(img, &smoothed, &shape.style, origin);
         }
-        Line { from, to } => {
-            stroke_line_aa(
-                img,
-                local(*from, origin),
-                local(*to, origin),
-                shape.style.stroke,
-                shape.style.stroke_width.max(1.0) as i32,
-            );
+        FreehandStroke { points, .. } => {
+            let smoothed = crate::shape::smoothed_freehand(points, shape.style.stroke_width);
+            stroke_path(img, &smoothed, false, &shape.style, origin);
         }
-        Arrow { from, to } => {
-            let a = local(*from, origin);
-            let b = local(*to, origin);
-            let w = shape.style.stroke_width.max(1.0) as i32;
-            stroke_line_aa(img, a, b, shape.style.stroke, w);
-            draw_arrowhead(img, a, b, shape.style.stroke, w);
+        Line { from, to } => stroke_path(img, &[*from, *to], false, &shape.style, origin),
+        Measure {
+            from,
+            to,
+            boxed,
+            scale,
+            baked,
+        } => {
+            if *baked {
+                draw_measure(img, *from, *to, *boxed, *scale, &shape.style, origin);
+            }
         }
+        Arrow { from, to, bend } => draw_arrow(img, *from, *to, *bend, &shape.style, origin),
         Rectangle { rect } => {
             let r = local_rect(*rect, origin);
             if let Some(fill) = shape.style.fill {
-                fill_rect(img, r, fill);
+                fill_rect(img, r, shape.style.line.apply(fill));
             }
-            stroke_rect(
-                img,
-                r,
-                shape.style.stroke,
-                shape.style.stroke_width.max(1.0) as i32,
-            );
+            // Through the centres of the edge pixels.
+            let (x0, y0) = (rect.x() as f32 + 0.5, rect.y() as f32 + 0.5);
+            let (x1, y1) = (rect.right() as f32 - 0.5, rect.bottom() as f32 - 0.5);
+            let corners = [
+                FPoint::new(x0, y0),
+                FPoint::new(x1, y0),
+                FPoint::new(x1, y1),
+                FPoint::new(x0, y1),
+            ];
+            stroke_path(img, &corners, true, &shape.style, origin);
         }
         Ellipse { rect } => {
             let r = local_rect(*rect, origin);
-            stroke_ellipse(
+            if let Some(fill) = shape.style.fill {
+                fill_ellipse(img, r, shape.style.line.apply(fill));
+            }
+            stroke_path(
                 img,
-                r,
-                shape.style.stroke,
-                shape.style.stroke_width.max(1.0) as i32,
-                shape.style.fill,
+                &crate::shape::ellipse_points(*rect),
+                true,
+                &shape.style,
+                origin,
             );
         }
-        BlurRect { .. } => {}
+        BlurRect { .. }
+        | Pixelate { .. }
+        | Highlight { .. }
+        | Spotlight { .. }
+        | Magnify { .. } => {}
+        Image { rect, pixels } => draw_image(
+            img,
+            *rect,
+            pixels.image(),
+            shape.rotation,
+            shape.style.line.opacity,
+            origin,
+        ),
         Step {
             center,
             number,
@@ -172,40 +211,67 @@ fn draw_shape(img: &mut RgbaImage, shape: &Shape, origin: (i32, i32)) {
         } => {
             draw_text(img, local(*o, origin), content, style);
         }
-        Polygon { points, closed } => {
-            if points.is_empty() {
-                return;
+        Callout {
+            body,
+            anchor,
+            text,
+            text_style,
+        } => {
+            let outline = crate::shape::callout_outline(*body, *anchor);
+            if let Some(fill) = shape.style.fill {
+                fill_polygon(img, &outline, origin, fill);
             }
             let w = shape.style.stroke_width.max(1.0) as i32;
-            if *closed {
-                if let Some(fill) = shape.style.fill {
-                    fill_polygon(img, points, origin, fill);
-                }
-            }
-            for pair in points.windows(2) {
+            for (i, a) in outline.iter().enumerate() {
+                let b = outline[(i + 1) % outline.len()];
                 stroke_line_aa(
                     img,
-                    local(pair[0], origin),
-                    local(pair[1], origin),
+                    local(*a, origin),
+                    local(b, origin),
                     shape.style.stroke,
                     w,
                 );
             }
-            if *closed && points.len() >= 3 {
-                let last = points[points.len() - 1];
-                let first = points[0];
-                stroke_line_aa(
-                    img,
-                    local(last, origin),
-                    local(first, origin),
-                    shape.style.stroke,
-                    w,
-                );
+            let (at, wrapped) = crate::shape::callout_text(*body, text, text_style);
+            draw_text(img, local(at, origin), &wrapped, text_style);
+        }
+        Polygon { points, closed } => {
+            if *closed {
+                if let Some(fill) = shape.style.fill {
+                    fill_polygon(img, points, origin, shape.style.line.apply(fill));
+                }
             }
+            stroke_path(
+                img,
+                points,
+                *closed && points.len() >= 3,
+                &shape.style,
+                origin,
+            );
         }
     }
 }
 
+/// A measurement's lines with its label on a pill in the middle.
+fn draw_measure(
+    img: &mut RgbaImage,
+    from: FPoint,
+    to: FPoint,
+    boxed: bool,
+    scale: f32,
+    style: &Style,
+    origin: (i32, i32),
+) {
+    let w = style.stroke_width.max(1.0) as i32;
+    for (a, b) in crate::shape::measure_segments(from, to, boxed) {
+        stroke_line_aa(img, local(a, origin), local(b, origin), style.stroke, w);
+    }
+    let label = crate::shape::measure_label(from, to, boxed, scale);
+    let text = crate::shape::measure_text_style(style.stroke);
+    let at = crate::shape::measure_label_origin(from, to, &label, &text);
+    draw_text(img, local(at, origin), &label, &text);
+}
+
 fn fill_polygon(img: &mut RgbaImage, points: &[FPoint], origin: (i32, i32), color: Color) {
     if points.len() < 3 {
         return;
@@ -289,6 +355,100 @@ fn blend(dst: [u8; 4], src: [u8; 4]) -> [u8; 4] {
     ]
 }
 
+/// Stretch `pixels` over `rect` turned by `rotation` about its centre.
+/// Sampled bilinearly, after a box-ish downscale when shrinking a lot, so
+/// scaled and rotated images stay smooth.
+fn draw_image(
+    img: &mut RgbaImage,
+    rect: Rect,
+    pixels: &RgbaImage,
+    rotation: f32,
+    opacity: f32,
+    origin: (i32, i32),
+) {
+    let (rw, rh) = (rect.width(), rect.height());
+    if pixels.width() == 0 || pixels.height() == 0 || rw == 0 || rh == 0 {
+        return;
+    }
+    let shrunk;
+    let pixels = if pixels.width() > rw * 3 / 2 || pixels.height() > rh * 3 / 2 {
+        shrunk = imageops::resize(pixels, rw, rh, imageops::FilterType::Triangle);
+        &shrunk
+    } else {
+        pixels
+    };
+    let (sw, sh) = pixels.dimensions();
+    let (kx, ky) = (sw as f32 / rw as f32, sh as f32 / rh as f32);
+    let (hw, hh) = (rw as f32 / 2.0, rh as f32 / 2.0);
+    let (cx, cy) = (rect.x() as f32 + hw, rect.y() as f32 + hh);
+    let (ox, oy) = (origin.0 as f32, origin.1 as f32);
+
+    let corners = crate::shape::image_corners(rect, rotation);
+    let (iw, ih) = img.dimensions();
+    let (mut lo, mut hi) = ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN));
+    for p in &corners {
+        lo = (lo.0.min(p.x - ox), lo.1.min(p.y - oy));
+        hi = (hi.0.max(p.x - ox), hi.1.max(p.y - oy));
+    }
+    let (x0, y0) = ((lo.0.floor() as i32).max(0), (lo.1.floor() as i32).max(0));
+    let (x1, y1) = (
+        (hi.0.ceil() as i32).min(iw as i32),
+        (hi.1.ceil() as i32).min(ih as i32),
+    );
+    let (sn, cs) = (-rotation).sin_cos();
+    let opacity = opacity.clamp(0.0, 1.0);
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let (dx, dy) = (x as f32 + 0.5 + ox - cx, y as f32 + 0.5 + oy - cy);
+            let u = (dx * cs - dy * sn + hw) * kx - 0.5;
+            let v = (dx * sn + dy * cs + hh) * ky - 0.5;
+            if u < -1.0 || v < -1.0 || u > sw as f32 || v > sh as f32 {
+                continue;
+            }
+            let [r, g, b, a] = sample_bilinear(pixels, u, v);
+            if a > 0 {
+                let a = (a as f32 * opacity).round() as u8;
+                px(img, x, y, Color([r, g, b, a]));
+            }
+        }
+    }
+}
+
+/// `img` at fractional pixel `(u, v)`, with transparency outside it so
+/// edges fade out. Colour is alpha-weighted to avoid dark fringes.
+fn sample_bilinear(img: &RgbaImage, u: f32, v: f32) -> [u8; 4] {
+    let (w, h) = (img.width() as i32, img.height() as i32);
+    let (fx, fy) = (u.floor(), v.floor());
+    let (tx, ty) = (u - fx, v - fy);
+    let mut acc = [0.0f32; 4];
+    for (dx, dy, weight) in [
+        (0, 0, (1.0 - tx) * (1.0 - ty)),
+        (1, 0, tx * (1.0 - ty)),
+        (0, 1, (1.0 - tx) * ty),
+        (1, 1, tx * ty),
+    ] {
+        let (x, y) = (fx as i32 + dx, fy as i32 + dy);
+        if x < 0 || y < 0 || x >= w || y >= h {
+            continue;
+        }
+        let p = img.get_pixel(x as u32, y as u32).0;
+        let a = p[3] as f32 * weight;
+        for (sum, c) in acc.iter_mut().zip(&p[..3]) {
+            *sum += *c as f32 * a;
+        }
+        acc[3] += a;
+    }
+    if acc[3] <= 0.0 {
+        return [0; 4];
+    }
+    [
+        (acc[0] / acc[3]).round() as u8,
+        (acc[1] / acc[3]).round() as u8,
+        (acc[2] / acc[3]).round() as u8,
+        acc[3].round() as u8,
+    ]
+}
+
 /// Anti-aliased thick line via distance-based coverage.
 fn stroke_line_aa(img: &mut RgbaImage, a: (i32, i32), b: (i32, i32), c: Color, width: i32) {
     let af = (a.0 as f32, a.1 as f32);
@@ -395,7 +555,7 @@ fn stroke_rect(img: &mut RgbaImage, r: Rect, c: Color, w: i32) {
     stroke_line(img, (x0, y1), (x0, y0), c, w);
 }
 
-fn stroke_ellipse(img: &mut RgbaImage, r: Rect, c: Color, _w: i32, fill: Option<Color>) {
+fn fill_ellipse(img: &mut RgbaImage, r: Rect, fill: Color) {
     let cx = r.x() as f32 + r.width() as f32 / 2.0;
     let cy = r.y() as f32 + r.height() as f32 / 2.0;
     let rx = r.width() as f32 / 2.0;
@@ -403,24 +563,12 @@ fn stroke_ellipse(img: &mut RgbaImage, r: Rect, c: Color, _w: i32, fill: Option<
     if rx == 0.0 || ry == 0.0 {
         return;
     }
-    let circumference = (rx + ry) * f32::consts::TAU;
-    let steps = circumference.ceil().max(64.0) as usize;
-    let mut prev = ((cx + rx).round() as i32, (cy).round() as i32);
-    for i in 1..=steps {
-        let t = (i as f32 / steps as f32) * std::f32::consts::TAU;
-        let x = (cx + rx * t.cos()).round() as i32;
-        let y = (cy + ry * t.sin()).round() as i32;
-        stroke_line(img, prev, (x, y), c, 1);
-        prev = (x, y);
-    }
-    if let Some(fill) = fill {
-        for y in r.y()..(r.y() + r.height() as i32) {
-            for x in r.x()..(r.x() + r.width() as i32) {
-                let nx = (x as f32 - cx) / rx;
-                let ny = (y as f32 - cy) / ry;
-                if nx * nx + ny * ny <= 1.0 {
-                    px(img, x, y, fill);
-                }
+    for y in r.y()..(r.y() + r.height() as i32) {
+        for x in r.x()..(r.x() + r.width() as i32) {
+            let nx = (x as f32 + 0.5 - cx) / rx;
+            let ny = (y as f32 + 0.5 - cy) / ry;
+            if nx * nx + ny * ny <= 1.0 {
+                px(img, x, y, fill);
             }
         }
     }
@@ -438,24 +586,351 @@ fn draw_circle_outline(img: &mut RgbaImage, c: (i32, i32), r: i32, color: Color,
     }
 }
 
-fn draw_arrowhead(img: &mut RgbaImage, from: (i32, i32), to: (i32, i32), color: Color, w: i32) {
-    let dx = (to.0 - from.0) as f32;
-    let dy = (to.1 - from.1) as f32;
-    let len = (dx * dx + dy * dy).sqrt().max(1.0);
-    let ux = dx / len;
-    let uy = dy / len;
-    let head = (w as f32 * 3.0).max(10.0);
-    let spread = 0.5;
-    let p1 = (
-        to.0 - (ux * head + uy * head * spread) as i32,
-        to.1 - (uy * head - ux * head * spread) as i32,
-    );
-    let p2 = (
-        to.0 - (ux * head - uy * head * spread) as i32,
-        to.1 - (uy * head + ux * head * spread) as i32,
-    );
-    stroke_line(img, to, p1, color, w);
-    stroke_line(img, to, p2, color, w);
+fn draw_arrow(
+    img: &mut RgbaImage,
+    from: FPoint,
+    to: FPoint,
+    bend: Option<FPoint>,
+    style: &Style,
+    origin: (i32, i32),
+) {
+    let line = style.line;
+    let width = style.stroke_width.max(1.0);
+    let path = crate::shape::arrow_path(from, to, bend);
+    let tail = if line.double_headed {
+        line.head.inset(width)
+    } else {
+        0.0
+    };
+    let shaft = crate::shape::trim_path(&path, tail, line.head.inset(width));
+    let n = path.len();
+    let mut tips = vec![(path[n - 1], path[n - 2])];
+    if line.double_headed {
+        tips.push((path[0], path[1]));
+    }
+    let pad = crate::shape::arrowhead_len(width) + width;
+    let mut mask = StrokeMask::new(img, &path, pad, origin);
+    mask.path(&shaft, false, style);
+    let half = width / 2.0;
+    for (tip, back) in tips {
+        let (h1, h2) = crate::shape::arrowhead_wings(tip, back, width);
+        match line.head {
+            ArrowHead::Open => {
+                mask.segment(tip, h1, half, End::Round, End::Round);
+                mask.segment(tip, h2, half, End::Round, End::Round);
+            }
+            ArrowHead::Filled => mask.convex(&[tip, h1, h2]),
+            ArrowHead::Dot => mask.disk(tip, ArrowHead::dot_radius(width)),
+            ArrowHead::Bar => {
+                let (a, b) = crate::shape::arrowhead_bar(tip, back, width);
+                mask.segment(a, b, half, End::Round, End::Round);
+            }
+        }
+    }
+    mask.blend(img, line.apply(style.stroke));
+}
+
+/// Stroke a polyline (closed: a loop) with the dashes, caps and joins of
+/// `style`.
+fn stroke_path(
+    img: &mut RgbaImage,
+    points: &[FPoint],
+    closed: bool,
+    style: &Style,
+    origin: (i32, i32),
+) {
+    if points.is_empty() {
+        return;
+    }
+    let mut mask = StrokeMask::new(img, points, style.stroke_width.max(1.0) * 2.0, origin);
+    mask.path(points, closed, style);
+    mask.blend(img, style.line.apply(style.stroke));
+}
+
+/// Stroke a pressure-sensitive freehand path, each point carrying its
+/// width. Ink runs solid with round ends whatever the dash and cap
+/// settings; a dash pattern along a changing width reads as noise.
+fn stroke_pressure_path(
+    img: &mut RgbaImage,
+    points: &[(FPoint, f32)],
+    style: &Style,
+    origin: (i32, i32),
+) {
+    let Some(&(first, first_width)) = points.first() else {
+        return;
+    };
+    let path: Vec<FPoint> = points.iter().map(|&(p, _)| p).collect();
+    let mut mask = StrokeMask::new(img, &path, style.stroke_width.max(1.0) * 2.0, origin);
+    if points.len() == 1 {
+        mask.disk(first, first_width / 2.0);
+    }
+    for w in points.windows(2) {
+        let ((a, wa), (b, wb)) = (w[0], w[1]);
+        mask.taper(a, b, wa / 2.0, wb / 2.0);
+    }
+    mask.blend(img, style.line.apply(style.stroke));
+}
+
+/// How a stroke segment ends: a half disc, or flat and pushed out by the
+/// given length.
+#[derive(Clone, Copy)]
+enum End {
+    Round,
+    Flat(f32),
+}
+
+/// Coverage of one stroke, built up from segments, caps, joins and
+/// arrowheads and blended once, so a translucent stroke doesn't darken
+/// where its pieces overlap. Covers the stroke's bounds clipped to the
+/// image; coordinates are canvas coordinates.
+struct StrokeMask {
+    origin: (f32, f32),
+    x0: i32,
+    y0: i32,
+    w: i32,
+    h: i32,
+    cov: Vec<f32>,
+}
+
+impl StrokeMask {
+    fn new(img: &RgbaImage, points: &[FPoint], pad: f32, origin: (i32, i32)) -> Self {
+        let (iw, ih) = img.dimensions();
+        let o = (origin.0 as f32, origin.1 as f32);
+        let (mut lo, mut hi) = ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN));
+        for p in points {
+            lo = (lo.0.min(p.x - o.0), lo.1.min(p.y - o.1));
+            hi = (hi.0.max(p.x - o.0), hi.1.max(p.y - o.1));
+        }
+        let x0 = ((lo.0 - pad).floor() as i32).max(0);
+        let y0 = ((lo.1 - pad).floor() as i32).max(0);
+        let x1 = ((hi.0 + pad).ceil() as i32).min(iw as i32);
+        let y1 = ((hi.1 + pad).ceil() as i32).min(ih as i32);
+        let (w, h) = ((x1 - x0).max(0), (y1 - y0).max(0));
+        Self {
+            origin: o,
+            x0,
+            y0,
+            w,
+            h,
+            cov: vec![0.0; (w * h) as usize],
+        }
+    }
+
+    /// Raise the coverage of every pixel in `lo..hi` (image-local) to
+    /// `f` at its centre.
+    fn paint(&mut self, lo: (f32, f32), hi: (f32, f32), f: impl Fn(f32, f32) -> f32) {
+        let xa = (lo.0.floor() as i32 - 1).max(self.x0);
+        let ya = (lo.1.floor() as i32 - 1).max(self.y0);
+        let xb = (hi.0.ceil() as i32 + 1).min(self.x0 + self.w);
+        let yb = (hi.1.ceil() as i32 + 1).min(self.y0 + self.h);
+        for y in ya..yb {
+            for x in xa..xb {
+                let c = f(x as f32 + 0.5, y as f32 + 0.5).clamp(0.0, 1.0);
+                let i = ((y - self.y0) * self.w + (x - self.x0)) as usize;
+                if c > self.cov[i] {
+                    self.cov[i] = c;
+                }
+            }
+        }
+    }
+
+    fn local(&self, p: FPoint) -> (f32, f32) {
+        (p.x - self.origin.0, p.y - self.origin.1)
+    }
+
+    fn disk(&mut self, center: FPoint, r: f32) {
+        let c = self.local(center);
+        self.paint((c.0 - r, c.1 - r), (c.0 + r, c.1 + r), |x, y| {
+            r + 0.5 - (x - c.0).hypot(y - c.1)
+        });
+    }
+
+    fn segment(&mut self, a: FPoint, b: FPoint, half: f32, start: End, end: End) {
+        let (a, b) = (self.local(a), self.local(b));
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let len = dx.hypot(dy);
+        if len < 1.0e-4 {
+            return;
+        }
+        let (ux, uy) = (dx / len, dy / len);
+        let grow = half * 2.0 + 1.0;
+        let lo = (a.0.min(b.0) - grow, a.1.min(b.1) - grow);
+        let hi = (a.0.max(b.0) + grow, a.1.max(b.1) + grow);
+        self.paint(lo, hi, |x, y| {
+            let (px, py) = (x - a.0, y - a.1);
+            let along = px * ux + py * uy;
+            let mut c = half + 0.5 - (px * uy - py * ux).abs();
+            match start {
+                End::Flat(e) => c = c.min(e + 0.5 + along),
+                End::Round if along < 0.0 => c = half + 0.5 - px.hypot(py),
+                End::Round => {}
+            }
+            match end {
+                End::Flat(e) => c = c.min(e + 0.5 + len - along),
+                End::Round if along > len => c = half + 0.5 - (x - b.0).hypot(y - b.1),
+                End::Round => {}
+            }
+            c
+        });
+    }
+
+    /// Segment whose half width runs from `ha` at `a` to `hb` at `b`,
+    /// with round ends.
+    fn taper(&mut self, a: FPoint, b: FPoint, ha: f32, hb: f32) {
+        let (a, b) = (self.local(a), self.local(b));
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let len2 = dx * dx + dy * dy;
+        let grow = ha.max(hb) + 1.0;
+        let lo = (a.0.min(b.0) - grow, a.1.min(b.1) - grow);
+        let hi = (a.0.max(b.0) + grow, a.1.max(b.1) + grow);
+        self.paint(lo, hi, |x, y| {
+            let t = if len2 > f32::EPSILON {
+                (((x - a.0) * dx + (y - a.1) * dy) / len2).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let half = ha + (hb - ha) * t;
+            half + 0.5 - (x - a.0 - t * dx).hypot(y - a.1 - t * dy)
+        });
+    }
+
+    /// Anti-aliased convex polygon, either winding.
+    fn convex(&mut self, points: &[FPoint]) {
+        if points.len() < 3 {
+            return;
+        }
+        let pts: Vec<(f32, f32)> = points.iter().map(|p| self.local(*p)).collect();
+        let area: f32 = (0..pts.len())
+            .map(|i| {
+                let (a, b) = (pts[i], pts[(i + 1) % pts.len()]);
+                a.0 * b.1 - b.0 * a.1
+            })
+            .sum();
+        let sign = if area < 0.0 { -1.0 } else { 1.0 };
+        let edges: Vec<(f32, f32, f32, f32)> = (0..pts.len())
+            .filter_map(|i| {
+                let (a, b) = (pts[i], pts[(i + 1) % pts.len()]);
+                let len = (b.0 - a.0).hypot(b.1 - a.1);
+                (len > 1.0e-4).then(|| (a.0, a.1, (b.0 - a.0) / len, (b.1 - a.1) / len))
+            })
+            .collect();
+        let lo = pts
+            .iter()
+            .fold((f32::MAX, f32::MAX), |m, p| (m.0.min(p.0), m.1.min(p.1)));
+        let hi = pts
+            .iter()
+            .fold((f32::MIN, f32::MIN), |m, p| (m.0.max(p.0), m.1.max(p.1)));
+        self.paint(lo, hi, |x, y| {
+            edges
+                .iter()
+                .map(|&(ax, ay, ux, uy)| sign * (ux * (y - ay) - uy * (x - ax)))
+                .fold(f32::MAX, f32::min)
+                + 0.5
+        });
+    }
+
+    /// The dashed, capped and joined stroke of `style` along `points`.
+    fn path(&mut self, points: &[FPoint], closed: bool, style: &Style) {
+        let line = style.line;
+        let width = style.stroke_width.max(1.0);
+        let pieces = match line.dash.pattern(width, line.cap) {
+            Some(pattern) => crate::shape::dash_path(points, closed, &pattern)
+                .into_iter()
+                .map(|run| (run, false))
+                .collect(),
+            None => vec![(points.to_vec(), closed)],
+        };
+        for (mut pts, closed) in pieces {
+            pts.dedup_by(|b, a| a.distance(*b) < 1.0e-3);
+            self.polyline(&pts, closed, width / 2.0, line.cap, line.join);
+        }
+    }
+
+    fn polyline(&mut self, pts: &[FPoint], closed: bool, half: f32, cap: LineCap, join: LineJoin) {
+        let n = pts.len();
+        if n == 1 {
+            let p = pts[0];
+            match cap {
+                LineCap::Round => self.disk(p, half),
+                LineCap::Square => self.convex(&[
+                    FPoint::new(p.x - half, p.y - half),
+                    FPoint::new(p.x + half, p.y - half),
+                    FPoint::new(p.x + half, p.y + half),
+                    FPoint::new(p.x - half, p.y + half),
+                ]),
+                LineCap::Butt => {}
+            }
+            return;
+        }
+        let cap_end = match cap {
+            LineCap::Butt => End::Flat(0.0),
+            LineCap::Round => End::Round,
+            LineCap::Square => End::Flat(half),
+        };
+        // Inner ends overlap by half a pixel so no seam shows at joints.
+        let joint = End::Flat(0.5);
+        let segments = if closed { n } else { n - 1 };
+        for i in 0..segments {
+            let start = if closed || i > 0 { joint } else { cap_end };
+            let end = if closed || i + 2 < n { joint } else { cap_end };
+            self.segment(pts[i], pts[(i + 1) % n], half, start, end);
+        }
+        let corners = if closed { 0..n } else { 1..n - 1 };
+        for i in corners {
+            let (prev, v, next) = (pts[(i + n - 1) % n], pts[i], pts[(i + 1) % n]);
+            self.join(prev, v, next, half, join);
+        }
+    }
+
+    /// Fill the outer wedge where the segments `prev`→`v` and `v`→`next`
+    /// meet.
+    fn join(&mut self, prev: FPoint, v: FPoint, next: FPoint, half: f32, join: LineJoin) {
+        if join == LineJoin::Round {
+            self.disk(v, half);
+            return;
+        }
+        let unit = |a: FPoint, b: FPoint| {
+            let len = a.distance(b).max(1.0e-6);
+            ((b.x - a.x) / len, (b.y - a.y) / len)
+        };
+        let (u1, u2) = (unit(prev, v), unit(v, next));
+        if (u1.0 * u2.1 - u1.1 * u2.0).abs() < 1.0e-4 {
+            return;
+        }
+        // Normals on the outside of the turn.
+        let mut n1 = (-u1.1, u1.0);
+        if n1.0 * u2.0 + n1.1 * u2.1 > 0.0 {
+            n1 = (-n1.0, -n1.1);
+        }
+        let mut n2 = (-u2.1, u2.0);
+        if n2.0 * u1.0 + n2.1 * u1.1 < 0.0 {
+            n2 = (-n2.0, -n2.1);
+        }
+        let a = FPoint::new(v.x + n1.0 * half, v.y + n1.1 * half);
+        let b = FPoint::new(v.x + n2.0 * half, v.y + n2.1 * half);
+        let (mx, my) = (n1.0 + n2.0, n1.1 + n2.1);
+        let m_len = mx.hypot(my);
+        // SVG's default miter limit: past 4 widths the corner is bevelled.
+        let cos_half = m_len / 2.0;
+        if join == LineJoin::Miter && m_len > 1.0e-4 && 1.0 / cos_half <= 4.0 {
+            let reach = half / cos_half;
+            let tip = FPoint::new(v.x + mx / m_len * reach, v.y + my / m_len * reach);
+            self.convex(&[v, a, tip, b]);
+        } else {
+            self.convex(&[v, a, b]);
+        }
+    }
+
+    fn blend(&self, img: &mut RgbaImage, c: Color) {
+        for (i, &cov) in self.cov.iter().enumerate() {
+            if cov <= 0.0 {
+                continue;
+            }
+            let x = self.x0 + i as i32 % self.w;
+            let y = self.y0 + i as i32 / self.w;
+            px(img, x, y, c.with_alpha((c.0[3] as f32 * cov) as u8));
+        }
+    }
 }
 
 fn apply_blur(img: &mut RgbaImage, rect: Rect, radius: f32, origin: (i32, i32)) {
@@ -479,6 +954,273 @@ fn apply_blur(img: &mut RgbaImage, rect: Rect, radius: f32, origin: (i32, i32))
     imageops::replace(img, &blurred, x as i64, y as i64);
 }
 
+fn apply_pixelate(img: &mut RgbaImage, rect: Rect, block: u32, origin: (i32, i32)) {
+    let r = local_rect(rect, origin);
+    let (iw, ih) = img.dimensions();
+    let x0 = r.x().clamp(0, iw as i32) as u32;
+    let y0 = r.y().clamp(0, ih as i32) as u32;
+    let x1 = (r.x() + r.width() as i32).clamp(0, iw as i32) as u32;
+    let y1 = (r.y() + r.height() as i32).clamp(0, ih as i32) as u32;
+    if x1 <= x0 || y1 <= y0 {
+        return;
+    }
+    let block = block.max(1);
+    let clipped = Rect::from_xywh(x0 as i32, y0 as i32, x1 - x0, y1 - y0);
+    let cells = mosaic(img, clipped, block);
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let cell = cells.get_pixel((x - x0) / block, (y - y0) / block);
+            img.put_pixel(x, y, *cell);
+        }
+    }
+}
+
+/// Multiply `points` into the image: a marker darkens what it covers
+/// instead of painting over it, so text under the stroke stays readable.
+fn apply_highlight(img: &mut RgbaImage, points: &[FPoint], style: &Style, origin: (i32, i32)) {
+    let Some((patch, x0, y0)) = highlight_patch(points, style, origin, img.dimensions()) else {
+        return;
+    };
+    for (x, y, src) in patch.enumerate_pixels() {
+        let a = src[3] as f32 / 255.0;
+        if a == 0.0 {
+            continue;
+        }
+        let dst = img.get_pixel_mut(x0 + x, y0 + y);
+        for c in 0..3 {
+            let m = 1.0 - a + a * src[c] as f32 / 255.0;
+            dst[c] = (dst[c] as f32 * m).round() as u8;
+        }
+    }
+}
+
+/// The highlighter stroke as a patch of `style.stroke` whose alpha is the
+/// coverage, clipped to an image of `size`; returns the patch and its
+/// image-local top-left. Coverage is the maximum over all segments, so
+/// overlapping segments don't darken twice the way stacked source-over
+/// dabs would.
+pub(super) fn highlight_patch(
+    points: &[FPoint],
+    style: &Style,
+    origin: (i32, i32),
+    size: (u32, u32),
+) -> Option<(RgbaImage, u32, u32)> {
+    let width = style.stroke_width.max(1.0);
+    let half = width / 2.0;
+    let pts: Vec<(f32, f32)> = crate::shape::smoothed_freehand(points, width)
+        .iter()
+        .map(|p| (p.x - origin.0 as f32, p.y - origin.1 as f32))
+        .collect();
+    let first = *pts.first()?;
+    let (mut lo, mut hi) = (first, first);
+    for &(x, y) in &pts {
+        lo = (lo.0.min(x), lo.1.min(y));
+        hi = (hi.0.max(x), hi.1.max(y));
+    }
+    let x0 = ((lo.0 - half - 1.0).floor().max(0.0) as u32).min(size.0);
+    let y0 = ((lo.1 - half - 1.0).floor().max(0.0) as u32).min(size.1);
+    let x1 = ((hi.0 + half + 1.0).ceil().max(0.0) as u32).min(size.0);
+    let y1 = ((hi.1 + half + 1.0).ceil().max(0.0) as u32).min(size.1);
+    if x1 <= x0 || y1 <= y0 {
+        return None;
+    }
+
+    let mut patch = RgbaImage::new(x1 - x0, y1 - y0);
+    let [r, g, b, alpha] = style.stroke.0;
+    let segments: Vec<((f32, f32), (f32, f32))> = if pts.len() == 1 {
+        vec![(first, first)]
+    } else {
+        pts.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    for (p0, p1) in segments {
+        let sx0 = ((p0.0.min(p1.0) - half - 1.0).floor().max(x0 as f32) as u32).min(x1);
+        let sy0 = ((p0.1.min(p1.1) - half - 1.0).floor().max(y0 as f32) as u32).min(y1);
+        let sx1 = ((p0.0.max(p1.0) + half + 1.0).ceil().max(0.0) as u32).min(x1);
+        let sy1 = ((p0.1.max(p1.1) + half + 1.0).ceil().max(0.0) as u32).min(y1);
+        for y in sy0..sy1 {
+            for x in sx0..sx1 {
+                let d = dist_to_segment((x as f32 + 0.5, y as f32 + 0.5), p0, p1);
+                let coverage = (half + 0.5 - d).clamp(0.0, 1.0);
+                let cov = (coverage * alpha as f32).round() as u8;
+                let p = patch.get_pixel_mut(x - x0, y - y0);
+                if cov > p[3] {
+                    *p = Rgba([r, g, b, cov]);
+                }
+            }
+        }
+    }
+    Some((patch, x0, y0))
+}
+
+fn dist_to_segment(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > f32::EPSILON {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (cx, cy) = (a.0 + t * dx - p.0, a.1 + t * dy - p.1);
+    (cx * cx + cy * cy).sqrt()
+}
+
+/// Darken, and optionally desaturate, every pixel outside the spotlights in
+/// `shapes`, using the settings of the topmost one.
+fn apply_spotlights(img: &mut RgbaImage, shapes: &[Shape], origin: (i32, i32)) {
+    let Some(ShapeKind::Spotlight {
+        amount, desaturate, ..
+    }) = top_spotlight(shapes).map(|i| &shapes[i].kind)
+    else {
+        return;
+    };
+    let keep = 1.0 - *amount as f32 / 255.0;
+    let desaturate = *desaturate;
+    let size = img.dimensions();
+    for_each_outside_spotlights(shapes, origin, size, |x, y| {
+        let p = img.get_pixel_mut(x, y);
+        if desaturate {
+            let luma = 0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32;
+            p.0[..3].fill(luma.round() as u8);
+        }
+        for c in &mut p.0[..3] {
+            *c = (*c as f32 * keep).round() as u8;
+        }
+    });
+}
+
+/// A copy of `src` with the spotlight areas cleared, leaving only the
+/// surround opaque.
+pub(super) fn outside_spotlights(
+    src: &RgbaImage,
+    shapes: &[Shape],
+    origin: (i32, i32),
+) -> RgbaImage {
+    let mut out = RgbaImage::new(src.width(), src.height());
+    for_each_outside_spotlights(shapes, origin, src.dimensions(), |x, y| {
+        out.put_pixel(x, y, *src.get_pixel(x, y));
+    });
+    out
+}
+
+/// Call `f` with every pixel of an image of `size` that lies outside the
+/// union of the spotlights in `shapes`, sampling at pixel centres.
+pub(super) fn for_each_outside_spotlights(
+    shapes: &[Shape],
+    origin: (i32, i32),
+    size: (u32, u32),
+    mut f: impl FnMut(u32, u32),
+) {
+    let spots: Vec<(Rect, SpotlightShape)> = shapes
+        .iter()
+        .filter_map(|s| match s.kind {
+            ShapeKind::Spotlight { rect, shape, .. } => Some((local_rect(rect, origin), shape)),
+            _ => None,
+        })
+        .collect();
+    let mut spans = Vec::with_capacity(spots.len());
+    for y in 0..size.1 {
+        let cy = y as f32 + 0.5;
+        spans.clear();
+        spans.extend(spots.iter().filter_map(|(r, s)| s.span(*r, cy)));
+        for x in 0..size.0 {
+            let cx = x as f32 + 0.5;
+            if !spans.iter().any(|&(x0, x1)| cx >= x0 && cx < x1) {
+                f(x, y);
+            }
+        }
+    }
+}
+
+/// Paint a magnifier callout: connectors first, then the enlarged copy of
+/// `source` over them, then both borders.
+fn apply_magnify(
+    img: &mut RgbaImage,
+    source: Rect,
+    target: FPoint,
+    zoom: f32,
+    style: &Style,
+    origin: (i32, i32),
+) {
+    let copy = crate::shape::magnified_rect(source, target, zoom);
+    let w = style.stroke_width.max(1.0) as i32;
+    for (a, b) in crate::shape::magnify_connectors(source, copy) {
+        stroke_line_aa(img, local(a, origin), local(b, origin), style.stroke, w);
+    }
+
+    let src = local_rect(source, origin);
+    let dst = local_rect(copy, origin);
+    let bounds = Rect::from_xywh(0, 0, img.width(), img.height());
+    if let Some(clipped) = src.intersection(&bounds) {
+        let patch = imageops::crop_imm(
+            img,
+            clipped.x() as u32,
+            clipped.y() as u32,
+            clipped.width(),
+            clipped.height(),
+        )
+        .to_image();
+        let filter = if looks_like_pixel_art(&patch) {
+            imageops::FilterType::Nearest
+        } else {
+            imageops::FilterType::Lanczos3
+        };
+        let sx = dst.width() as f32 / src.width().max(1) as f32;
+        let sy = dst.height() as f32 / src.height().max(1) as f32;
+        let scaled = imageops::resize(
+            &patch,
+            ((clipped.width() as f32 * sx).round() as u32).max(1),
+            ((clipped.height() as f32 * sy).round() as u32).max(1),
+            filter,
+        );
+        // A source cut off by the image edge keeps its place in the copy.
+        let x = dst.x() as f32 + (clipped.x() - src.x()) as f32 * sx;
+        let y = dst.y() as f32 + (clipped.y() - src.y()) as f32 * sy;
+        imageops::replace(img, &scaled, x.round() as i64, y.round() as i64);
+    }
+    stroke_rect(img, dst, style.stroke, w);
+    stroke_rect(img, src, style.stroke, (w / 2).max(1));
+}
+
+/// Flat-coloured sources (pixel art, icons, UI chrome) keep hard pixel
+/// edges when enlarged; sources with many distinct colours (photos,
+/// gradients) are resampled smoothly instead.
+fn looks_like_pixel_art(img: &RgbaImage) -> bool {
+    let limit = (img.width() as usize * img.height() as usize / 4).max(16);
+    let mut seen = std::collections::HashSet::new();
+    img.pixels().all(|p| {
+        seen.insert(p.0);
+        seen.len() <= limit
+    })
+}
+
+/// Average colour of every `block`-sized cell of `rect` (image-local,
+/// already clipped), one pixel per cell; partial cells at the right and
+/// bottom edges average what they cover. The live preview stretches this
+/// with nearest sampling so it matches the composite.
+pub(crate) fn mosaic(img: &RgbaImage, rect: Rect, block: u32) -> RgbaImage {
+    let block = block.max(1);
+    let (x0, y0) = (rect.x().max(0) as u32, rect.y().max(0) as u32);
+    let cols = rect.width().div_ceil(block);
+    let rows = rect.height().div_ceil(block);
+    RgbaImage::from_fn(cols, rows, |cx, cy| {
+        let mut sum = [0u64; 4];
+        let mut n = 0u64;
+        let xs = x0 + cx * block..(x0 + (cx + 1) * block).min(x0 + rect.width());
+        for y in y0 + cy * block..(y0 + (cy + 1) * block).min(y0 + rect.height()) {
+            for x in xs.clone() {
+                if let Some(p) = img.get_pixel_checked(x, y) {
+                    for (s, c) in sum.iter_mut().zip(p.0) {
+                        *s += c as u64;
+                    }
+                    n += 1;
+                }
+            }
+        }
+        let n = n.max(1);
+        Rgba(sum.map(|s| (s / n) as u8))
+    })
+}
+
 fn draw_number_centered(img: &mut RgbaImage, c: (i32, i32), n: u32, color: Color, radius: f32) {
     let s = n.to_string();
     let px = (radius * 1.1).max(8.0);
@@ -490,5 +1232,670 @@ fn draw_number_centered(img: &mut RgbaImage, c: (i32, i32), n: u32, color: Color
 }
 
 fn draw_text(img: &mut RgbaImage, origin: (i32, i32), text: &str, style: &TextStyle) {
-    crate::font::draw_text_rgba(img, origin.0, origin.1, text, style.color.0, style.size);
+    if let Some(bg) = style.background {
+        let (w, h) = crate::font::text_size(text, style);
+        let (bw, bh) = (w + bg.padding * 2.0, h + bg.padding * 2.0);
+        let min = (origin.0 as f32 - bg.padding, origin.1 as f32 - bg.padding);
+        fill_rounded_rect(img, min, (bw, bh), bg.radius(bh), bg.color);
+    }
+    let halo = style.outline.map_or(0.0, |o| o.width);
+    // Room for italic overhang and descenders past the line box, plus the
+    // halo grown around them.
+    let margin = (style.size * 0.25 + halo).ceil() as u32 + 1;
+    let mask = crate::font::text_mask(text, style, margin);
+    let at = (origin.0 - margin as i32, origin.1 - margin as i32);
+    if let Some(o) = style.outline {
+        let grown = dilate(&mask.pixels, mask.width, mask.height, o.width);
+        blend_coverage(img, at, mask.width, &grown, o.color);
+    }
+    blend_coverage(img, at, mask.width, &mask.pixels, style.color);
+}
+
+/// `text` on a transparent image, for the overlay's live preview. Also
+/// returns where the image's top-left sits relative to the text origin.
+pub(crate) fn text_image(text: &str, style: &TextStyle) -> (RgbaImage, (i32, i32)) {
+    let (w, h) = crate::font::text_size(text, style);
+    let pad = (style.margin() + style.size * 0.25).ceil() as u32 + 2;
+    let mut img = RgbaImage::new(w.ceil() as u32 + pad * 2, h.ceil() as u32 + pad * 2);
+    draw_text(&mut img, (pad as i32, pad as i32), text, style);
+    (img, (-(pad as i32), -(pad as i32)))
+}
+
+/// Blend `color` over `img`, scaled per pixel by `coverage`: a row-major
+/// mask `width` pixels wide whose top-left lands on `at`.
+fn blend_coverage(img: &mut RgbaImage, at: (i32, i32), width: u32, coverage: &[u8], color: Color) {
+    for (i, &c) in coverage.iter().enumerate() {
+        if c == 0 {
+            continue;
+        }
+        let x = at.0 + (i as u32 % width) as i32;
+        let y = at.1 + (i as u32 / width) as i32;
+        let a = (color.0[3] as u16 * c as u16 / 255) as u8;
+        px(img, x, y, color.with_alpha(a));
+    }
+}
+
+/// Grow a coverage mask by `r` pixels: every pixel takes the strongest
+/// neighbour within the disk, fading over the last pixel of the radius.
+fn dilate(mask: &[u8], w: u32, h: u32, r: f32) -> Vec<u8> {
+    let reach = r.ceil() as i32 + 1;
+    let kernel: Vec<(i32, i32, f32)> = (-reach..=reach)
+        .flat_map(|dy| (-reach..=reach).map(move |dx| (dx, dy)))
+        .filter_map(|(dx, dy)| {
+            let k = (r + 0.5 - ((dx * dx + dy * dy) as f32).sqrt()).clamp(0.0, 1.0);
+            (k > 0.0).then_some((dx, dy, k))
+        })
+        .collect();
+    let mut out = vec![0u8; mask.len()];
+    for y in 0..h as i32 {
+        for x in 0..w as i32 {
+            let mut best = 0.0f32;
+            for &(dx, dy, k) in &kernel {
+                let (sx, sy) = (x + dx, y + dy);
+                if sx < 0 || sy < 0 || sx >= w as i32 || sy >= h as i32 {
+                    continue;
+                }
+                best = best.max(mask[(sy as u32 * w + sx as u32) as usize] as f32 * k);
+            }
+            out[(y as u32 * w + x as u32) as usize] = best as u8;
+        }
+    }
+    out
+}
+
+/// Anti-aliased rectangle with corner radius `r`, clamped to half the
+/// shorter side.
+fn fill_rounded_rect(img: &mut RgbaImage, min: (f32, f32), size: (f32, f32), r: f32, c: Color) {
+    let (hw, hh) = (size.0 / 2.0, size.1 / 2.0);
+    let r = r.min(hw).min(hh).max(0.0);
+    let (cx, cy) = (min.0 + hw, min.1 + hh);
+    for y in min.1.floor() as i32..(min.1 + size.1).ceil() as i32 {
+        for x in min.0.floor() as i32..(min.0 + size.0).ceil() as i32 {
+            // Signed distance to the rounded outline, negative inside.
+            let qx = (x as f32 + 0.5 - cx).abs() - (hw - r);
+            let qy = (y as f32 + 0.5 - cy).abs() - (hh - r);
+            let d = qx.max(0.0).hypot(qy.max(0.0)) + qx.max(qy).min(0.0) - r;
+            let k = (0.5 - d).clamp(0.0, 1.0);
+            if k > 0.0 {
+                px(img, x, y, c.with_alpha((c.0[3] as f32 * k) as u8));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::{
+        measure_text_style, DashPattern, LineStyle, ShapeId, TextBackground, TextOutline,
+    };
+
+    fn shape(kind: ShapeKind, style: Style) -> Shape {
+        Shape {
+            id: ShapeId(1),
+            kind,
+            style,
+            rotation: 0.0,
+            group: None,
+        }
+    }
+
+    /// `image` with `shapes` flattened onto it.
+    fn flat(mut image: RgbaImage, shapes: Vec<Shape>) -> RgbaImage {
+        let mut canvas = Canvas::default();
+        canvas.shapes_mut().extend(shapes);
+        flatten(&mut image, &canvas, (0, 0));
+        image
+    }
+
+    /// Red rising by 10 per column.
+    fn gradient(w: u32, h: u32) -> RgbaImage {
+        RgbaImage::from_fn(w, h, |x, _| Rgba([(x * 10) as u8, 0, 0, 255]))
+    }
+
+    #[test]
+    fn mosaic_averages_each_cell() {
+        let cells = mosaic(&gradient(10, 5), Rect::from_xywh(2, 0, 7, 5), 4);
+        assert_eq!(cells.dimensions(), (2, 2));
+        // Columns 2..6, then the partial cell 6..9.
+        assert_eq!(cells.get_pixel(0, 0).0[0], 35);
+        assert_eq!(cells.get_pixel(1, 0).0[0], 70);
+    }
+
+    #[test]
+    fn pixelate_replaces_each_cell_with_its_average() {
+        let rect = Rect::from_xywh(2, 0, 8, 4);
+        let image = flat(
+            gradient(12, 6),
+            vec![shape(
+                ShapeKind::Pixelate {
+                    rect,
+                    block_size: 4,
+                },
+                Style::default(),
+            )],
+        );
+        assert_eq!(image.get_pixel(2, 0).0, [35, 0, 0, 255]);
+        assert_eq!(image.get_pixel(5, 3).0, [35, 0, 0, 255]);
+        assert_eq!(image.get_pixel(6, 0).0, [75, 0, 0, 255]);
+        // Outside the rect the capture is untouched.
+        assert_eq!(image.get_pixel(1, 0).0, [10, 0, 0, 255]);
+        assert_eq!(image.get_pixel(10, 0).0, [100, 0, 0, 255]);
+        assert_eq!(image.get_pixel(5, 4).0, [50, 0, 0, 255]);
+    }
+
+    #[test]
+    fn pixelate_with_a_fill_is_an_opaque_box() {
+        let style = Style {
+            fill: Some(Color::rgba(10, 20, 30, 100)),
+            ..Style::default()
+        };
+        let image = flat(
+            gradient(12, 6),
+            vec![shape(
+                ShapeKind::Pixelate {
+                    rect: Rect::from_xywh(2, 1, 8, 4),
+                    block_size: 4,
+                },
+                style,
+            )],
+        );
+        // Translucent fills are made opaque: nothing may show through.
+        assert_eq!(image.get_pixel(2, 1).0, [10, 20, 30, 255]);
+        assert_eq!(image.get_pixel(9, 4).0, [10, 20, 30, 255]);
+        assert_eq!(image.get_pixel(1, 1).0, [10, 0, 0, 255]);
+        assert_eq!(image.get_pixel(5, 0).0, [50, 0, 0, 255]);
+    }
+
+    fn marker(points: &[(f32, f32)]) -> Shape {
+        shape(
+            ShapeKind::Highlight {
+                points: points.iter().map(|&(x, y)| FPoint::new(x, y)).collect(),
+            },
+            Style {
+                stroke: Color::YELLOW,
+                stroke_width: 8.0,
+                fill: None,
+                ..Style::default()
+            },
+        )
+    }
+
+    #[test]
+    fn highlight_multiplies_instead_of_painting_over() {
+        let half = RgbaImage::from_fn(40, 20, |x, _| {
+            if x < 20 {
+                Rgba([255; 4])
+            } else {
+                Rgba([0, 0, 0, 255])
+            }
+        });
+        let image = flat(half, vec![marker(&[(2.0, 10.0), (38.0, 10.0)])]);
+        // White takes the marker colour; black text stays black.
+        assert_eq!(image.get_pixel(10, 10).0, [240, 200, 0, 255]);
+        assert_eq!(image.get_pixel(30, 10).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(10, 1).0, [255; 4]);
+    }
+
+    #[test]
+    fn highlight_does_not_darken_where_it_overlaps_itself() {
+        let white = || RgbaImage::from_pixel(40, 20, Rgba([255; 4]));
+        let once = flat(white(), vec![marker(&[(2.0, 10.0), (38.0, 10.0)])]);
+        let back_and_forth = flat(
+            white(),
+            vec![marker(&[(2.0, 10.0), (38.0, 10.0), (4.0, 10.0)])],
+        );
+        assert_eq!(once.get_pixel(20, 10), back_and_forth.get_pixel(20, 10));
+    }
+
+    fn spotlight(rect: Rect, shape: SpotlightShape, amount: u8, desaturate: bool) -> Shape {
+        self::shape(
+            ShapeKind::Spotlight {
+                rect,
+                shape,
+                amount,
+                desaturate,
+            },
+            Style::default(),
+        )
+    }
+
+    #[test]
+    fn spotlight_dims_everything_outside_the_union() {
+        let image = flat(
+            RgbaImage::from_pixel(30, 30, Rgba([200, 200, 200, 255])),
+            vec![
+                spotlight(
+                    Rect::from_xywh(0, 0, 10, 10),
+                    SpotlightShape::Rect,
+                    128,
+                    false,
+                ),
+                spotlight(
+                    Rect::from_xywh(5, 5, 10, 10),
+                    SpotlightShape::Ellipse,
+                    128,
+                    false,
+                ),
+            ],
+        );
+        assert_eq!(image.get_pixel(2, 2).0, [200, 200, 200, 255]);
+        assert_eq!(image.get_pixel(10, 10).0, [200, 200, 200, 255]);
+        // Inside the ellipse's box but outside the ellipse.
+        assert_eq!(image.get_pixel(14, 6).0, [100, 100, 100, 255]);
+        assert_eq!(image.get_pixel(25, 25).0, [100, 100, 100, 255]);
+    }
+
+    #[test]
+    fn topmost_spotlight_settings_win() {
+        let image = flat(
+            RgbaImage::from_pixel(30, 30, Rgba([255, 0, 0, 255])),
+            vec![
+                spotlight(
+                    Rect::from_xywh(0, 0, 10, 10),
+                    SpotlightShape::Rect,
+                    255,
+                    false,
+                ),
+                spotlight(
+                    Rect::from_xywh(20, 20, 10, 10),
+                    SpotlightShape::Rect,
+                    128,
+                    true,
+                ),
+            ],
+        );
+        // Desaturated to luma 76, then dimmed by half.
+        assert_eq!(image.get_pixel(15, 15).0, [38, 38, 38, 255]);
+        assert_eq!(image.get_pixel(5, 5).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(25, 25).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn magnify_copies_the_source_enlarged_and_ties_it_back() {
+        const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+        const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+        let source = Rect::from_xywh(4, 4, 4, 4);
+        let target = FPoint::new(30.0, 6.0);
+        let image = RgbaImage::from_fn(40, 40, |x, y| {
+            if (4..6).contains(&x) && (4..8).contains(&y) {
+                RED
+            } else {
+                BLUE
+            }
+        });
+        let image = flat(
+            image,
+            vec![shape(
+                ShapeKind::Magnify {
+                    source,
+                    target,
+                    zoom: 3.0,
+                },
+                Style {
+                    stroke: Color::WHITE,
+                    stroke_width: 1.0,
+                    fill: None,
+                    ..Style::default()
+                },
+            )],
+        );
+        let copy = crate::shape::magnified_rect(source, target, 3.0);
+        assert_eq!(copy, Rect::from_xywh(24, 0, 12, 12));
+        // The red half of the source fills the left half of the copy,
+        // with hard edges since the source is flat-coloured.
+        assert_eq!(*image.get_pixel(27, 5), RED);
+        assert_eq!(*image.get_pixel(29, 5), RED);
+        assert_eq!(*image.get_pixel(30, 5), BLUE);
+        assert_eq!(*image.get_pixel(33, 5), BLUE);
+        // Borders around the copy and the source.
+        assert_eq!(image.get_pixel(24, 5).0, [255; 4]);
+        assert_eq!(image.get_pixel(4, 6).0, [255; 4]);
+        // Connectors run between the two.
+        let connectors = crate::shape::magnify_connectors(source, copy);
+        assert_eq!(connectors.len(), 2);
+        for (a, b) in connectors {
+            let mid = image.get_pixel(((a.x + b.x) / 2.0) as u32, ((a.y + b.y) / 2.0) as u32);
+            assert_ne!(*mid, BLUE);
+        }
+        // Away from all of it the capture is untouched.
+        assert_eq!(*image.get_pixel(15, 30), BLUE);
+    }
+
+    fn text(content: &str, style: TextStyle) -> Shape {
+        shape(
+            ShapeKind::Text {
+                origin: FPoint::new(10.0, 10.0),
+                content: content.to_owned(),
+                style,
+            },
+            Style::default(),
+        )
+    }
+
+    #[test]
+    fn text_background_box_spans_every_line_plus_padding() {
+        const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+        let style = TextStyle {
+            color: Color::WHITE,
+            size: 20.0,
+            background: Some(TextBackground {
+                color: Color::BLUE,
+                padding: 4.0,
+                pill: false,
+            }),
+            ..TextStyle::default()
+        };
+        let content = "ab\nabcd";
+        let (w, h) = crate::font::text_size(content, &style);
+        let image = flat(
+            RgbaImage::from_pixel(120, 80, RED),
+            vec![text(content, style)],
+        );
+        let (right, bottom) = ((10.0 + w + 4.0) as u32, (10.0 + h + 4.0) as u32);
+        // Padding on every side, away from the rounded corners.
+        for (x, y) in [(7, 30), (right - 1, 30), (30, 7), (30, bottom - 1)] {
+            assert_eq!(image.get_pixel(x, y).0, Color::BLUE.0, "({x}, {y})");
+        }
+        for (x, y) in [(5, 30), (right + 1, 30), (30, 5), (30, bottom + 1)] {
+            assert_eq!(*image.get_pixel(x, y), RED, "({x}, {y})");
+        }
+        // The glyphs sit on the box.
+        assert!(image.pixels().any(|p| p.0 == [255; 4]));
+    }
+
+    #[test]
+    fn text_halo_surrounds_the_glyphs_without_covering_them() {
+        let plain = TextStyle {
+            color: Color::WHITE,
+            size: 30.0,
+            ..TextStyle::default()
+        };
+        let haloed = TextStyle {
+            outline: Some(TextOutline {
+                color: Color::BLACK,
+                width: 3.0,
+            }),
+            ..plain.clone()
+        };
+        let red = || RgbaImage::from_pixel(60, 60, Rgba([255, 0, 0, 255]));
+        let without = flat(red(), vec![text("H", plain)]);
+        let with = flat(red(), vec![text("H", haloed)]);
+        let mut halo = 0;
+        for (x, y, p) in with.enumerate_pixels() {
+            let before = without.get_pixel(x, y);
+            if before.0 == [255; 4] {
+                // Full glyph coverage stays the text colour.
+                assert_eq!(p.0, [255; 4], "({x}, {y})");
+            } else if p.0 == [0, 0, 0, 255] {
+                halo += 1;
+                // Black only within the halo's reach of the glyph.
+                let near = (x.saturating_sub(4)..=x + 4)
+                    .flat_map(|nx| (y.saturating_sub(4)..=y + 4).map(move |ny| (nx, ny)))
+                    .any(|(nx, ny)| {
+                        without
+                            .get_pixel_checked(nx, ny)
+                            .is_some_and(|q| q.0 != [255, 0, 0, 255])
+                    });
+                assert!(near, "({x}, {y})");
+            }
+        }
+        assert!(halo > 0);
+    }
+
+    #[test]
+    fn callout_wraps_its_text_inside_a_bubble_with_a_tail() {
+        const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+        let body = Rect::from_xywh(10, 10, 80, 50);
+        let text_style = TextStyle {
+            color: Color::BLUE,
+            size: 10.0,
+            ..TextStyle::default()
+        };
+        let content = "hello world again and again";
+        let (_, wrapped) = crate::shape::callout_text(body, content, &text_style);
+        assert!(wrapped.lines().count() > 1, "{wrapped}");
+        let image = flat(
+            RgbaImage::from_pixel(100, 90, RED),
+            vec![shape(
+                ShapeKind::Callout {
+                    body,
+                    anchor: FPoint::new(30.0, 85.0),
+                    text: content.to_owned(),
+                    text_style,
+                },
+                Style {
+                    stroke: Color::BLACK,
+                    stroke_width: 2.0,
+                    fill: Some(Color::WHITE),
+                    ..Style::default()
+                },
+            )],
+        );
+        // The tail runs from the bottom edge down to the anchor.
+        for y in [63, 70] {
+            assert_eq!(image.get_pixel(30, y).0, [255; 4], "tail at {y}");
+        }
+        // Near the tip the outline covers the narrow fill.
+        assert_ne!(*image.get_pixel(30, 82), RED);
+        assert_eq!(*image.get_pixel(60, 75), RED);
+        assert_eq!(*image.get_pixel(5, 20), RED);
+        // Every line of text is inside the body, on more than one line.
+        let ink: Vec<(u32, u32)> = image
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p[2] > p[0].saturating_add(60))
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert!(ink
+            .iter()
+            .all(|&(x, y)| (12..88).contains(&x) && (12..58).contains(&y)));
+        let rows = ink.iter().map(|&(_, y)| y);
+        let (top, bottom) = (rows.clone().min().unwrap(), rows.max().unwrap());
+        assert!(bottom - top > 12, "{top}..{bottom}");
+    }
+
+    /// `kind` stroked 4 px wide in black with `line` on white.
+    fn stroked(kind: ShapeKind, line: LineStyle) -> RgbaImage {
+        flat(
+            RgbaImage::from_pixel(100, 100, Rgba([255; 4])),
+            vec![shape(
+                kind,
+                Style {
+                    stroke: Color::BLACK,
+                    stroke_width: 4.0,
+                    fill: None,
+                    line,
+                },
+            )],
+        )
+    }
+
+    fn horizontal(from: f32, to: f32) -> ShapeKind {
+        ShapeKind::Line {
+            from: FPoint::new(from, 50.5),
+            to: FPoint::new(to, 50.5),
+        }
+    }
+
+    #[test]
+    fn dashes_leave_gaps() {
+        let image = stroked(
+            horizontal(0.0, 100.0),
+            LineStyle {
+                dash: DashPattern::Dashed,
+                cap: LineCap::Butt,
+                ..LineStyle::default()
+            },
+        );
+        let row: String = (0..40)
+            .map(|x| {
+                if image.get_pixel(x, 50)[0] < 128 {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        assert_eq!(row, "################........################");
+    }
+
+    #[test]
+    fn square_caps_reach_past_the_end_and_butt_caps_do_not() {
+        let cap = |cap| {
+            stroked(
+                horizontal(20.0, 40.0),
+                LineStyle {
+                    cap,
+                    ..LineStyle::default()
+                },
+            )
+        };
+        assert_eq!(cap(LineCap::Butt).get_pixel(19, 50)[0], 255);
+        assert_eq!(cap(LineCap::Square).get_pixel(18, 50)[0], 0);
+    }
+
+    #[test]
+    fn only_miter_joins_fill_the_outer_corner() {
+        let corner = |join| {
+            let rect = Rect::from_xywh(20, 20, 40, 40);
+            let line = LineStyle {
+                join,
+                ..LineStyle::default()
+            };
+            stroked(ShapeKind::Rectangle { rect }, line)
+        };
+        assert_eq!(corner(LineJoin::Miter).get_pixel(19, 19)[0], 0);
+        assert!(corner(LineJoin::Bevel).get_pixel(18, 18)[0] > 200);
+        assert!(corner(LineJoin::Round).get_pixel(18, 18)[0] > 200);
+    }
+
+    #[test]
+    fn translucent_strokes_do_not_darken_where_segments_meet() {
+        let image = stroked(
+            ShapeKind::Polygon {
+                points: vec![
+                    FPoint::new(10.0, 10.0),
+                    FPoint::new(60.0, 10.0),
+                    FPoint::new(60.0, 60.0),
+                ],
+                closed: false,
+            },
+            LineStyle {
+                opacity: 0.5,
+                ..LineStyle::default()
+            },
+        );
+        assert_eq!(image.get_pixel(35, 10), image.get_pixel(60, 10));
+        assert!((120..=135).contains(&image.get_pixel(35, 10)[0]));
+    }
+
+    #[test]
+    fn every_arrowhead_marks_both_ends_of_a_double_headed_arrow() {
+        let arrow = ShapeKind::Arrow {
+            from: FPoint::new(10.0, 50.0),
+            to: FPoint::new(90.0, 50.0),
+            bend: None,
+        };
+        for head in ArrowHead::ALL {
+            let line = LineStyle {
+                head,
+                double_headed: true,
+                ..LineStyle::default()
+            };
+            let image = stroked(arrow.clone(), line);
+            assert!(image.get_pixel(88, 50)[0] < 100, "{head:?}");
+            assert!(image.get_pixel(12, 50)[0] < 100, "{head:?}");
+        }
+        // A filled head is solid between its wings.
+        let line = LineStyle {
+            head: ArrowHead::Filled,
+            ..LineStyle::default()
+        };
+        assert_eq!(stroked(arrow, line).get_pixel(82, 51)[0], 0);
+    }
+
+    #[test]
+    fn bent_arrows_pass_through_their_midpoint() {
+        let (from, to) = (FPoint::new(10.0, 80.0), FPoint::new(90.0, 80.0));
+        let bend = crate::shape::bend_through(from, to, FPoint::new(50.0, 20.0));
+        let image = stroked(
+            ShapeKind::Arrow {
+                from,
+                to,
+                bend: Some(bend),
+            },
+            LineStyle::default(),
+        );
+        assert_eq!(image.get_pixel(50, 20)[0], 0);
+        assert_eq!(image.get_pixel(50, 80)[0], 255);
+    }
+
+    fn measured(from: (f32, f32), to: (f32, f32), boxed: bool, baked: bool) -> RgbaImage {
+        flat(
+            RgbaImage::from_pixel(120, 100, Rgba([128, 128, 128, 255])),
+            vec![shape(
+                ShapeKind::Measure {
+                    from: FPoint::new(from.0, from.1),
+                    to: FPoint::new(to.0, to.1),
+                    boxed,
+                    scale: 1.0,
+                    baked,
+                },
+                Style {
+                    stroke: Color::RED,
+                    stroke_width: 2.0,
+                    fill: None,
+                    ..Style::default()
+                },
+            )],
+        )
+    }
+
+    #[test]
+    fn measure_draws_its_line_ticks_and_a_centred_label() {
+        const GREY: [u8; 4] = [128, 128, 128, 255];
+        let image = measured((10.0, 70.0), (110.0, 70.0), false, true);
+        assert_ne!(image.get_pixel(20, 70).0, GREY);
+        // Ticks across both ends.
+        assert_ne!(image.get_pixel(10, 65).0, GREY);
+        assert_ne!(image.get_pixel(110, 75).0, GREY);
+        assert_eq!(image.get_pixel(20, 65).0, GREY);
+        // The label is white on a red pill centred on the line.
+        let label = crate::shape::measure_label(
+            FPoint::new(10.0, 70.0),
+            FPoint::new(110.0, 70.0),
+            false,
+            1.0,
+        );
+        assert_eq!(label, "100 px");
+        let (w, h) = crate::font::text_size(&label, &measure_text_style(Color::RED));
+        let (x0, x1) = (60.0 - w / 2.0, 60.0 + w / 2.0);
+        let (y0, y1) = (70.0 - h / 2.0, 70.0 + h / 2.0);
+        let white: Vec<(u32, u32)> = image
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p[1] > 200)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert!(!white.is_empty());
+        assert!(white.iter().all(|&(x, y)| {
+            (x0 - 1.0..x1 + 1.0).contains(&(x as f32)) && (y0 - 3.0..y1 + 3.0).contains(&(y as f32))
+        }));
+        assert_eq!(image.get_pixel(60, (y0 - 2.0) as u32).0, Color::RED.0);
+    }
+
+    #[test]
+    fn boxed_measure_outlines_the_box() {
+        const GREY: [u8; 4] = [128, 128, 128, 255];
+        let image = measured((10.0, 10.0), (110.0, 90.0), true, true);
+        for (x, y) in [(30, 10), (110, 30), (30, 90), (10, 30)] {
+            assert_ne!(image.get_pixel(x, y).0, GREY, "({x}, {y})");
+        }
+        assert_eq!(image.get_pixel(30, 30).0, GREY);
+    }
+
+    #[test]
+    fn unbaked_measure_leaves_the_image_alone() {
+        let image = measured((10.0, 70.0), (110.0, 70.0), false, false);
+        assert!(image.pixels().all(|p| p.0 == [128, 128, 128, 255]));
+    }
 }
@@ -141,6 +141,74 @@ impl WindowGpu {
         })
     }
 
+    /// Tessellate one egui frame and present it on the surface.
+    pub fn present(&mut self, gpu: &Gpu, window: &dyn Window, output: egui::FullOutput) {
+        self.egui_winit
+            .handle_platform_output(window, output.platform_output);
+        let primitives = self
+            .egui_ctx
+            .tessellate(output.shapes, output.pixels_per_point);
+        let screen_desc = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: [self.size.0, self.size.1],
+            pixels_per_point: output.pixels_per_point,
+        };
+        for (id, image_delta) in &output.textures_delta.set {
+            self.renderer
+                .update_texture(&gpu.device, &gpu.queue, *id, image_delta);
+        }
+
+        let frame = match self.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost) => {
+                self.surface.configure(&gpu.device, &self.config);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "wgpu: get_current_texture failed");
+                return;
+            }
+        };
+        let view = frame
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = gpu
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("sss_capture_ui encoder"),
+            });
+        self.renderer.update_buffers(
+            &gpu.device,
+            &gpu.queue,
+            &mut encoder,
+            &primitives,
+            &screen_desc,
+        );
+        {
+            let pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("sss_capture_ui pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            let pass = &mut pass.forget_lifetime();
+            self.renderer.render(pass, &primitives, &screen_desc);
+        }
+        gpu.queue.submit(std::iter::once(encoder.finish()));
+        frame.present();
+
+        for id in &output.textures_delta.free {
+            self.renderer.free_texture(id);
+        }
+    }
+
     pub fn resize(&mut self, gpu: &Gpu, new_size: (u32, u32)) {
         if new_size.0 == 0 || new_size.1 == 0 || new_size == self.size {
             return;
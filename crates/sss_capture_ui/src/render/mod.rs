@@ -1,6 +1,9 @@
-//! CPU `composite` finaliser and (feature-gated) GPU `overlay` preview.
+//! CPU `composite` finaliser, `svg` / `ora` exporters and (feature-gated)
+//! GPU `overlay` preview.
 
 pub mod composite;
+pub mod ora;
+pub mod svg;
 
 #[cfg(feature = "editor")]
 pub mod gpu;
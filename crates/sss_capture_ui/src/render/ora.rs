@@ -0,0 +1,457 @@
+//! Layered OpenRaster (`.ora`) exporter for Krita, GIMP and MyPaint.
+//!
+//! The stack is the untouched capture at the bottom, then one transparent
+//! layer per shape in z-order. Blurs and mosaics have no pixels of their
+//! own, so each one becomes an opaque layer holding the processed region
+//! of everything below it — the same pixels [`super::composite::flatten`]
+//! produces. Highlighter strokes keep their own pixels on a layer with
+//! the multiply composite op. All spotlights share the topmost one's
+//! layer: a translucent black surround, or the processed pixels when it
+//! desaturates.
+//!
+//! An `.ora` file is a zip archive; PNG layers are already deflated, so
+//! every entry is stored uncompressed and the writer needs nothing beyond
+//! a CRC.
+
+use std::io::{self, Cursor, Write};
+
+use image::{imageops, ImageError, ImageFormat, RgbaImage};
+use sss_capture::Rect;
+
+use crate::canvas::Canvas;
+use crate::shape::ShapeKind;
+
+const MIMETYPE: &str = "image/openraster";
+/// Longest side of `Thumbnails/thumbnail.png`, per the spec.
+const THUMBNAIL_MAX: u32 = 256;
+const SOURCE_OVER: &str = "svg:src-over";
+
+/// Write `image` plus the shapes of `canvas` as an OpenRaster archive.
+/// `origin` is the canvas coordinate of the image's top-left corner, as in
+/// [`super::composite::flatten`].
+pub fn export_ora(
+    image: &RgbaImage,
+    canvas: &Canvas,
+    origin: (i32, i32),
+    w: impl Write,
+) -> Result<(), ImageError> {
+    let (width, height) = image.dimensions();
+    let mut zip = ZipWriter::new(w);
+    zip.add("mimetype", MIMETYPE.as_bytes())?;
+
+    // `flat` tracks the composite so far: blur layers are cut from it and
+    // it ends up as the merged image.
+    let mut flat = image.clone();
+    let mut layers = vec![(
+        "Capture".to_string(),
+        "data/capture.png".to_string(),
+        SOURCE_OVER,
+    )];
+    zip.add("data/capture.png", &encode(image)?)?;
+    let shapes = canvas.shapes();
+    let top_spotlight = super::composite::top_spotlight(shapes);
+    for (i, shape) in shapes.iter().enumerate() {
+        let name = format!("{} {}", layer_name(&shape.kind), i + 1);
+        let src = format!("data/layer-{:03}.png", i + 1);
+        // A magnifier copies pixels from below, so its layer is whatever
+        // it changed.
+        let before = matches!(shape.kind, ShapeKind::Magnify { .. }).then(|| flat.clone());
+        super::composite::paint_at(&mut flat, shapes, i, origin);
+        let mut op = SOURCE_OVER;
+        let layer = match &shape.kind {
+            ShapeKind::Spotlight { .. } if top_spotlight != Some(i) => continue,
+            ShapeKind::Measure { baked: false, .. } => continue,
+            ShapeKind::Spotlight {
+                desaturate: true, ..
+            } => super::composite::outside_spotlights(&flat, shapes, origin),
+            ShapeKind::Spotlight { amount, .. } => {
+                let dim = RgbaImage::from_pixel(width, height, image::Rgba([0, 0, 0, *amount]));
+                super::composite::outside_spotlights(&dim, shapes, origin)
+            }
+            ShapeKind::BlurRect { rect, .. } => patch_layer(&flat, *rect, origin),
+            ShapeKind::Pixelate { rect, .. } if shape.style.fill.is_none() => {
+                patch_layer(&flat, *rect, origin)
+            }
+            ShapeKind::Magnify { .. } => match &before {
+                Some(before) => changed_layer(before, &flat),
+                None => RgbaImage::new(width, height),
+            },
+            ShapeKind::Highlight { points } => {
+                op = "svg:multiply";
+                let mut layer = RgbaImage::new(width, height);
+                if let Some((patch, x, y)) =
+                    super::composite::highlight_patch(points, &shape.style, origin, (width, height))
+                {
+                    imageops::replace(&mut layer, &patch, x as i64, y as i64);
+                }
+                layer
+            }
+            _ => {
+                let mut layer = RgbaImage::new(width, height);
+                super::composite::paint_one(&mut layer, shape, origin);
+                layer
+            }
+        };
+        zip.add(&src, &encode(&layer)?)?;
+        layers.push((name, src, op));
+    }
+
+    let mut stack = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <image version=\"0.0.5\" w=\"{width}\" h=\"{height}\">\n<stack>\n"
+    );
+    // stack.xml lists the topmost layer first.
+    for (name, src, op) in layers.iter().rev() {
+        stack.push_str(&format!(
+            "<layer name=\"{}\" src=\"{src}\" x=\"0\" y=\"0\" opacity=\"1.0\" \
+             visibility=\"visible\" composite-op=\"{op}\"/>\n",
+            escape(name)
+        ));
+    }
+    stack.push_str("</stack>\n</image>\n");
+    zip.add("stack.xml", stack.as_bytes())?;
+
+    zip.add("mergedimage.png", &encode(&flat)?)?;
+    let scale = THUMBNAIL_MAX as f32 / width.max(height).max(1) as f32;
+    let thumbnail = if scale < 1.0 {
+        imageops::thumbnail(
+            &flat,
+            ((width as f32 * scale) as u32).max(1),
+            ((height as f32 * scale) as u32).max(1),
+        )
+    } else {
+        flat
+    };
+    zip.add("Thumbnails/thumbnail.png", &encode(&thumbnail)?)?;
+    zip.finish()?;
+    Ok(())
+}
+
+/// An opaque patch of `flat` covering `rect`, transparent elsewhere.
+fn patch_layer(flat: &RgbaImage, rect: Rect, origin: (i32, i32)) -> RgbaImage {
+    let (w, h) = flat.dimensions();
+    let mut layer = RgbaImage::new(w, h);
+    let x0 = (rect.x() - origin.0).clamp(0, w as i32) as u32;
+    let y0 = (rect.y() - origin.1).clamp(0, h as i32) as u32;
+    let x1 = (rect.right() - origin.0).clamp(0, w as i32) as u32;
+    let y1 = (rect.bottom() - origin.1).clamp(0, h as i32) as u32;
+    if x1 > x0 && y1 > y0 {
+        let patch = imageops::crop_imm(flat, x0, y0, x1 - x0, y1 - y0).to_image();
+        imageops::replace(&mut layer, &patch, x0 as i64, y0 as i64);
+    }
+    layer
+}
+
+/// The pixels of `after` that differ from `before`, transparent elsewhere.
+fn changed_layer(before: &RgbaImage, after: &RgbaImage) -> RgbaImage {
+    let mut layer = after.clone();
+    for (p, b) in layer.pixels_mut().zip(before.pixels()) {
+        if p == b {
+            *p = image::Rgba([0; 4]);
+        }
+    }
+    layer
+}
+
+fn layer_name(kind: &ShapeKind) -> &'static str {
+    match kind {
+        ShapeKind::FreehandStroke { .. } => "Brush",
+        ShapeKind::Highlight { .. } => "Highlight",
+        ShapeKind::Line { .. } => "Line",
+        ShapeKind::Arrow { .. } => "Arrow",
+        ShapeKind::Rectangle { .. } => "Rectangle",
+        ShapeKind::Ellipse { .. } => "Ellipse",
+        ShapeKind::BlurRect { .. } => "Blur",
+        ShapeKind::Pixelate { .. } => "Redaction",
+        ShapeKind::Spotlight { .. } => "Spotlight",
+        ShapeKind::Magnify { .. } => "Magnifier",
+        ShapeKind::Measure { .. } => "Measurement",
+        ShapeKind::Step { .. } => "Step",
+        ShapeKind::Text { .. } => "Text",
+        ShapeKind::Callout { .. } => "Callout",
+        ShapeKind::Polygon { .. } => "Polygon",
+        ShapeKind::Image { .. } => "Image",
+    }
+}
+
+fn encode(image: &RgbaImage) -> Result<Vec<u8>, ImageError> {
+    let mut png = Cursor::new(Vec::new());
+    image.write_to(&mut png, ImageFormat::Png)?;
+    Ok(png.into_inner())
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+}
+
+/// Just enough of the zip format for stored entries: local headers as the
+/// data goes out, the central directory on [`ZipWriter::finish`].
+struct ZipWriter<W> {
+    out: W,
+    offset: u32,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+impl<W: Write> ZipWriter<W> {
+    fn new(out: W) -> Self {
+        Self {
+            out,
+            offset: 0,
+            central: Vec::new(),
+            entries: 0,
+        }
+    }
+
+    fn add(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let size = u32::try_from(data.len())
+            .map_err(|_| io::Error::other("layer too large for a zip entry"))?;
+        let crc = crc32fast::hash(data);
+        // Version 2.0, no flags, stored, DOS timestamp 1980-01-01 00:00.
+        let fields = |buf: &mut Vec<u8>| {
+            buf.extend_from_slice(&20u16.to_le_bytes());
+            buf.extend_from_slice(&0u16.to_le_bytes());
+            buf.extend_from_slice(&0u16.to_le_bytes());
+            buf.extend_from_slice(&0u16.to_le_bytes());
+            buf.extend_from_slice(&0x21u16.to_le_bytes());
+            buf.extend_from_slice(&crc.to_le_bytes());
+            buf.extend_from_slice(&size.to_le_bytes());
+            buf.extend_from_slice(&size.to_le_bytes());
+            buf.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            buf.extend_from_slice(&0u16.to_le_bytes());
+        };
+
+        let mut local = 0x0403_4b50u32.to_le_bytes().to_vec();
+        fields(&mut local);
+        local.extend_from_slice(name.as_bytes());
+        self.out.write_all(&local)?;
+        self.out.write_all(data)?;
+
+        self.central
+            .extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes());
+        fields(&mut self.central);
+        // Comment length, disk number, internal and external attributes.
+        self.central.extend_from_slice(&[0; 10]);
+        self.central.extend_from_slice(&self.offset.to_le_bytes());
+        self.central.extend_from_slice(name.as_bytes());
+
+        self.offset = (local.len() as u64 + data.len() as u64)
+            .checked_add(self.offset as u64)
+            .and_then(|o| u32::try_from(o).ok())
+            .ok_or_else(|| io::Error::other("archive too large for zip32"))?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        self.out.write_all(&self.central)?;
+        let mut end = 0x0605_4b50u32.to_le_bytes().to_vec();
+        end.extend_from_slice(&[0; 4]);
+        end.extend_from_slice(&self.entries.to_le_bytes());
+        end.extend_from_slice(&self.entries.to_le_bytes());
+        end.extend_from_slice(&(self.central.len() as u32).to_le_bytes());
+        end.extend_from_slice(&self.offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        self.out.write_all(&end)?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use image::Rgba;
+
+    use super::*;
+    use crate::geometry::FPoint;
+    use crate::shape::{Shape, ShapeId, SpotlightShape, Style};
+
+    fn shape(id: u64, kind: ShapeKind) -> Shape {
+        Shape {
+            id: ShapeId(id),
+            kind,
+            style: Style::default(),
+            rotation: 0.0,
+            group: None,
+        }
+    }
+
+    fn capture() -> RgbaImage {
+        RgbaImage::from_fn(60, 40, |x, y| Rgba([(x * 4) as u8, (y * 6) as u8, 90, 255]))
+    }
+
+    fn canvas() -> Canvas {
+        let spot = |id, rect| {
+            shape(
+                id,
+                ShapeKind::Spotlight {
+                    rect,
+                    shape: SpotlightShape::Rect,
+                    amount: 100,
+                    desaturate: false,
+                },
+            )
+        };
+        let mut canvas = Canvas::default();
+        canvas.shapes_mut().extend([
+            shape(
+                1,
+                ShapeKind::Rectangle {
+                    rect: Rect::from_xywh(2, 2, 20, 10),
+                },
+            ),
+            shape(
+                2,
+                ShapeKind::BlurRect {
+                    rect: Rect::from_xywh(30, 4, 10, 8),
+                    radius: 3.0,
+                },
+            ),
+            spot(3, Rect::from_xywh(0, 0, 10, 10)),
+            shape(
+                4,
+                ShapeKind::Measure {
+                    from: FPoint::new(0.0, 30.0),
+                    to: FPoint::new(50.0, 30.0),
+                    boxed: false,
+                    scale: 1.0,
+                    baked: false,
+                },
+            ),
+            shape(
+                5,
+                ShapeKind::Highlight {
+                    points: vec![FPoint::new(4.0, 20.0), FPoint::new(40.0, 20.0)],
+                },
+            ),
+            spot(6, Rect::from_xywh(40, 20, 10, 10)),
+        ]);
+        canvas
+    }
+
+    fn export() -> zip::ZipArchive<Cursor<Vec<u8>>> {
+        let mut out = Vec::new();
+        export_ora(&capture(), &canvas(), (0, 0), &mut out).unwrap();
+        zip::ZipArchive::new(Cursor::new(out)).unwrap()
+    }
+
+    fn read(archive: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str) -> Vec<u8> {
+        let mut data = Vec::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        data
+    }
+
+    fn png(archive: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str) -> RgbaImage {
+        image::load_from_memory(&read(archive, name))
+            .unwrap()
+            .to_rgba8()
+    }
+
+    #[test]
+    fn mimetype_is_the_first_entry_and_stored() {
+        let mut archive = export();
+        let first = archive.by_index(0).unwrap();
+        assert_eq!(first.name(), "mimetype");
+        assert_eq!(first.compression(), zip::CompressionMethod::Stored);
+        drop(first);
+        assert_eq!(read(&mut archive, "mimetype"), MIMETYPE.as_bytes());
+        // Every entry is stored and reads back with a matching CRC.
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            assert_eq!(entry.compression(), zip::CompressionMethod::Stored);
+            std::io::copy(&mut entry, &mut std::io::sink()).unwrap();
+        }
+    }
+
+    #[test]
+    fn stack_lists_one_layer_per_exported_shape_topmost_first() {
+        let mut archive = export();
+        let stack = String::from_utf8(read(&mut archive, "stack.xml")).unwrap();
+        assert!(stack.contains("<image version=\"0.0.5\" w=\"60\" h=\"40\">"));
+        let layers: Vec<_> = stack
+            .lines()
+            .filter(|l| l.starts_with("<layer "))
+            .map(|l| {
+                let attr = |key: &str| {
+                    let start = l.find(&format!(" {key}=\"")).unwrap() + key.len() + 3;
+                    l[start..start + l[start..].find('"').unwrap()].to_owned()
+                };
+                (attr("name"), attr("src"), attr("composite-op"))
+            })
+            .collect();
+        // The unbaked measurement and the lower spotlight get no layer.
+        let expected = [
+            ("Spotlight 6", "data/layer-006.png", SOURCE_OVER),
+            ("Highlight 5", "data/layer-005.png", "svg:multiply"),
+            ("Blur 2", "data/layer-002.png", SOURCE_OVER),
+            ("Rectangle 1", "data/layer-001.png", SOURCE_OVER),
+            ("Capture", "data/capture.png", SOURCE_OVER),
+        ];
+        assert_eq!(layers.len(), expected.len());
+        for ((name, src, op), (e_name, e_src, e_op)) in layers.iter().zip(expected) {
+            assert_eq!(
+                (name.as_str(), src.as_str(), op.as_str()),
+                (e_name, e_src, e_op)
+            );
+            assert_eq!(png(&mut archive, src).dimensions(), (60, 40));
+        }
+        assert!(archive.by_name("data/layer-003.png").is_err());
+        assert!(archive.by_name("data/layer-004.png").is_err());
+    }
+
+    #[test]
+    fn base_layer_is_the_capture_and_the_merge_is_the_flattened_image() {
+        let mut archive = export();
+        assert_eq!(png(&mut archive, "data/capture.png"), capture());
+        let mut flat = capture();
+        super::super::composite::flatten(&mut flat, &canvas(), (0, 0));
+        assert_eq!(png(&mut archive, "mergedimage.png"), flat);
+        assert_eq!(png(&mut archive, "Thumbnails/thumbnail.png"), flat);
+    }
+
+    #[test]
+    fn blur_is_an_opaque_layer_of_its_own_region() {
+        let mut archive = export();
+        let blur = png(&mut archive, "data/layer-002.png");
+        for (x, y, p) in blur.enumerate_pixels() {
+            let inside = (30..40).contains(&x) && (4..12).contains(&y);
+            assert_eq!(p.0[3], if inside { 255 } else { 0 }, "({x}, {y})");
+        }
+        // The blur ran over the capture: the gradient is smoothed, not
+        // copied.
+        assert_ne!(blur.get_pixel(30, 4), capture().get_pixel(30, 4));
+    }
+
+    #[test]
+    fn spotlight_layer_dims_outside_every_spotlight() {
+        let mut archive = export();
+        let layer = png(&mut archive, "data/layer-006.png");
+        assert_eq!(layer.get_pixel(5, 5).0[3], 0);
+        assert_eq!(layer.get_pixel(45, 25).0[3], 0);
+        assert_eq!(layer.get_pixel(55, 5).0, [0, 0, 0, 100]);
+    }
+
+    #[test]
+    fn large_captures_get_a_scaled_thumbnail() {
+        let mut out = Vec::new();
+        let image = RgbaImage::new(1024, 512);
+        export_ora(&image, &Canvas::default(), (0, 0), &mut out).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(out)).unwrap();
+        assert_eq!(
+            png(&mut archive, "Thumbnails/thumbnail.png").dimensions(),
+            (256, 128)
+        );
+        assert_eq!(
+            png(&mut archive, "mergedimage.png").dimensions(),
+            (1024, 512)
+        );
+    }
+}
@@ -8,8 +8,9 @@ use sss_capture::Rect as CapRect;
 
 use crate::canvas::Canvas;
 use crate::color::Color;
+use crate::geometry::FPoint;
 use crate::mode::SelectorMode;
-use crate::shape::{Shape, ShapeKind};
+use crate::shape::{ArrowHead, ImagePixels, LineCap, LineJoin, Shape, ShapeKind, Style, TextStyle};
 use crate::tool::{BrushSettings, StepSettings, Tool, ToolPalette};
 
 /// Toolbar request flags; reset to false each frame.
@@ -130,17 +131,34 @@ pub fn draw_toolbar(
     out
 }
 
+/// Opacity of the on-screen highlighter stroke, standing in for the
+/// multiply blend the composite applies.
+const HIGHLIGHT_PREVIEW_ALPHA: f32 = 0.45;
+
+/// Block-averaged cells for the live Pixelate preview, keyed by the
+/// shape's rect and cell size so the in-flight drag gets one too. The
+/// value is the area the cells cover (the rect clipped to the capture)
+/// and a one-texel-per-cell texture.
+pub type MosaicCache = std::collections::HashMap<(CapRect, u32), (CapRect, egui::TextureHandle)>;
+
 /// Paint the region rubber-band and every shape onto an egui painter.
+#[allow(clippy::too_many_arguments)]
 pub fn draw_canvas(
     painter: &egui::Painter,
     canvas: &Canvas,
     screen_offset: Pos2,
     pointer_global: Option<crate::geometry::FPoint>,
+    background: Option<&egui::TextureHandle>,
     blurred_bg: Option<&egui::TextureHandle>,
+    mosaics: &MosaicCache,
     monitor_size_px: (u32, u32),
     region_color: Color32,
 ) {
-    if let Some(rect) = canvas.region() {
+    // Earlier regions of a multi-region selection, then the active one;
+    // numbered once there is more than one.
+    let extra = canvas.extra_regions();
+    let regions: Vec<_> = extra.iter().copied().chain(canvas.region()).collect();
+    for (i, rect) in regions.iter().enumerate() {
         let r = EguiRect::from_min_size(
             Pos2::new(
                 rect.x() as f32 - screen_offset.x,
@@ -150,18 +168,75 @@ pub fn draw_canvas(
         );
         let stroke = Stroke::new(1.5, region_color);
         draw_dashed_rect(painter, r, stroke, 8.0, 5.0);
+        if regions.len() > 1 {
+            let c = r.min + Vec2::splat(12.0);
+            painter.circle_filled(c, 10.0, region_color);
+            painter.text(
+                c,
+                egui::Align2::CENTER_CENTER,
+                (i + 1).to_string(),
+                egui::FontId::proportional(12.0),
+                Color32::WHITE,
+            );
+        }
     }
+    // Spotlights, the in-flight one included, dim around their union at
+    // the topmost one's place in the stack.
+    let preview = canvas.preview_shape();
+    let spots: Vec<&Shape> = canvas
+        .shapes()
+        .iter()
+        .chain(preview.as_ref())
+        .filter(|s| matches!(s.kind, ShapeKind::Spotlight { .. }))
+        .collect();
     for shape in canvas.shapes() {
-        draw_shape(painter, shape, screen_offset, blurred_bg, monitor_size_px);
+        if spots.last().is_some_and(|top| std::ptr::eq(*top, shape)) {
+            draw_spotlight_dim(painter, &spots, screen_offset);
+        }
+        draw_shape(
+            painter,
+            shape,
+            screen_offset,
+            background,
+            blurred_bg,
+            mosaics,
+            monitor_size_px,
+        );
     }
     if matches!(canvas.active_tool, Tool::Pointer) && canvas.has_ocr() {
         draw_ocr_boxes(painter, canvas, screen_offset, region_color);
     }
-    if let Some(preview) = canvas.preview_shape() {
-        draw_shape(painter, &preview, screen_offset, blurred_bg, monitor_size_px);
+    if let Some(preview) = preview.as_ref() {
+        if spots.last().is_some_and(|top| std::ptr::eq(*top, preview)) {
+            draw_spotlight_dim(painter, &spots, screen_offset);
+        }
+        draw_shape(
+            painter,
+            preview,
+            screen_offset,
+            background,
+            blurred_bg,
+            mosaics,
+            monitor_size_px,
+        );
     }
     if let Some(pending) = canvas.pending_text() {
-        draw_shape(painter, &pending, screen_offset, blurred_bg, monitor_size_px);
+        draw_shape(
+            painter,
+            &pending,
+            screen_offset,
+            background,
+            blurred_bg,
+            mosaics,
+            monitor_size_px,
+        );
+        draw_text_caret(painter, &pending, screen_offset);
+    }
+    let editing = canvas
+        .editing_callout()
+        .and_then(|id| canvas.shapes().iter().find(|s| s.id == id));
+    if let Some(text) = editing.and_then(callout_text_shape) {
+        draw_text_caret(painter, &text, screen_offset);
     }
     // Polygon-in-progress preview: mirror what the committed polygon will
     // look like (fill if fill mode is on, closing line back to the first
@@ -335,61 +410,262 @@ pub fn draw_confirm_hint(
     painter.galley(panel_pos + pad, galley, text_color);
 }
 
+/// Darken the painter's area outside the union of `spots`, with the
+/// settings of the last one. Built from horizontal bands that only break
+/// where the spans change, so rectangles cost a few quads and ellipses one
+/// band per row of their height. egui cannot desaturate; a grey wash
+/// stands in for it.
+fn draw_spotlight_dim(painter: &egui::Painter, spots: &[&Shape], off: Pos2) {
+    let Some(ShapeKind::Spotlight {
+        amount, desaturate, ..
+    }) = spots.last().map(|s| &s.kind)
+    else {
+        return;
+    };
+    let color = if *desaturate {
+        Color32::from_rgba_unmultiplied(64, 64, 64, *amount)
+    } else {
+        Color32::from_black_alpha(*amount)
+    };
+    let clip = painter.clip_rect();
+    let gaps_at = |y: f32| -> Vec<(f32, f32)> {
+        let mut spans: Vec<(f32, f32)> = spots
+            .iter()
+            .filter_map(|s| match s.kind {
+                ShapeKind::Spotlight { rect, shape, .. } => shape.span(rect, y + off.y),
+                _ => None,
+            })
+            .map(|(x0, x1)| (x0 - off.x, x1 - off.x))
+            .collect();
+        spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut gaps = Vec::new();
+        let mut x = clip.left();
+        for (x0, x1) in spans {
+            if x0 > x {
+                gaps.push((x, x0.min(clip.right())));
+            }
+            x = x.max(x1);
+        }
+        if x < clip.right() {
+            gaps.push((x, clip.right()));
+        }
+        gaps
+    };
+
+    let mut mesh = egui::Mesh::default();
+    let mut flush = |top: f32, bottom: f32, gaps: &[(f32, f32)]| {
+        for &(x0, x1) in gaps {
+            if x1 > x0 {
+                let r = EguiRect::from_min_max(Pos2::new(x0, top), Pos2::new(x1, bottom));
+                mesh.add_colored_rect(r, color);
+            }
+        }
+    };
+    let mut band_top = clip.top();
+    let mut band = gaps_at(band_top + 0.5);
+    let mut y = band_top + 1.0;
+    while y < clip.bottom() {
+        let gaps = gaps_at(y + 0.5);
+        if gaps != band {
+            flush(band_top, y, &band);
+            band_top = y;
+            band = gaps;
+        }
+        y += 1.0;
+    }
+    flush(band_top, clip.bottom(), &band);
+    painter.add(mesh);
+}
+
+/// Rasterised text previews of one egui context, keyed by style and
+/// content.
+type TextTextures = std::collections::HashMap<String, (egui::TextureHandle, (i32, i32))>;
+
+/// Text rendered by the compositor and uploaded as a texture, so the
+/// preview matches the export for every family and effect. Returns the
+/// texture and its offset from the text origin.
+fn text_texture(
+    ctx: &egui::Context,
+    content: &str,
+    style: &TextStyle,
+) -> Option<(egui::TextureHandle, (i32, i32))> {
+    if content.is_empty() {
+        return None;
+    }
+    let key = format!("{style:?}\u{0}{content}");
+    let id = egui::Id::new("sss::text_textures");
+    let hit = ctx.data_mut(|d| {
+        d.get_temp_mut_or_default::<TextTextures>(id)
+            .get(&key)
+            .cloned()
+    });
+    if hit.is_some() {
+        return hit;
+    }
+    let (img, offset) = crate::render::composite::text_image(content, style);
+    let size = [img.width() as usize, img.height() as usize];
+    let image = egui::ColorImage::from_rgba_unmultiplied(size, img.as_raw());
+    let tex = ctx.load_texture("sss::text", image, egui::TextureOptions::NEAREST);
+    ctx.data_mut(|d| {
+        let cache = d.get_temp_mut_or_default::<TextTextures>(id);
+        // Typing mints a texture per keystroke; drop the lot now and then.
+        if cache.len() >= 64 {
+            cache.clear();
+        }
+        cache.insert(key, (tex.clone(), offset));
+    });
+    Some((tex, offset))
+}
+
+/// Uploaded image shapes of one egui context, keyed by their pixel
+/// buffer. The pixels are kept alongside so the key can't be reused.
+type ImageTextures = std::collections::HashMap<usize, (ImagePixels, egui::TextureHandle)>;
+
+/// The texture of an image shape or sticker, uploaded on first sight.
+pub(crate) fn image_texture(ctx: &egui::Context, pixels: &ImagePixels) -> egui::TextureHandle {
+    let id = egui::Id::new("sss::image_textures");
+    let hit = ctx.data_mut(|d| {
+        d.get_temp_mut_or_default::<ImageTextures>(id)
+            .get(&pixels.key())
+            .map(|(_, tex)| tex.clone())
+    });
+    if let Some(tex) = hit {
+        return tex;
+    }
+    let img = pixels.image();
+    let size = [img.width() as usize, img.height() as usize];
+    let image = egui::ColorImage::from_rgba_unmultiplied(size, img.as_raw());
+    let tex = ctx.load_texture("sss::image", image, egui::TextureOptions::LINEAR);
+    ctx.data_mut(|d| {
+        let cache = d.get_temp_mut_or_default::<ImageTextures>(id);
+        // Roomy enough for a sticker picker full of thumbnails.
+        if cache.len() >= 128 {
+            cache.clear();
+        }
+        cache.insert(pixels.key(), (pixels.clone(), tex.clone()));
+    });
+    tex
+}
+
+/// Insertion bar after the last character of the text being typed.
+fn draw_text_caret(painter: &egui::Painter, shape: &Shape, off: Pos2) {
+    let ShapeKind::Text {
+        origin,
+        content,
+        style,
+    } = &shape.kind
+    else {
+        return;
+    };
+    let (_, line) = crate::font::line_metrics(style);
+    let last = content.rsplit('\n').next().unwrap_or_default();
+    let row = content.matches('\n').count() as f32;
+    let x = origin.x + crate::font::line_width(last, style) + 1.0 - off.x;
+    let y = origin.y + row * line - off.y;
+    painter.line_segment(
+        [Pos2::new(x, y), Pos2::new(x, y + style.size)],
+        Stroke::new(1.5, to_color32(style.color)),
+    );
+}
+
+/// `text` on a pill of `color` centred on `center`, like the composite's
+/// measurement labels.
+fn paint_label_pill(painter: &egui::Painter, center: Pos2, text: String, color: Color32) {
+    let [r, g, b, _] = Color::rgb(color.r(), color.g(), color.b()).contrasting().0;
+    let galley = painter.layout_no_wrap(
+        text,
+        egui::FontId::proportional(12.0),
+        Color32::from_rgb(r, g, b),
+    );
+    let pill = EguiRect::from_center_size(center, galley.size() + Vec2::new(10.0, 6.0));
+    painter.rect_filled(pill, pill.height() / 2.0, color);
+    painter.galley(
+        center - galley.size() / 2.0,
+        galley,
+        Color32::from_rgb(r, g, b),
+    );
+}
+
+/// The wrapped text of a callout as a plain Text shape.
+fn callout_text_shape(shape: &Shape) -> Option<Shape> {
+    let ShapeKind::Callout {
+        body,
+        text,
+        text_style,
+        ..
+    } = &shape.kind
+    else {
+        return None;
+    };
+    let (origin, content) = crate::shape::callout_text(*body, text, text_style);
+    Some(Shape {
+        kind: ShapeKind::Text {
+            origin,
+            content,
+            style: text_style.clone(),
+        },
+        ..shape.clone()
+    })
+}
+
 fn draw_shape(
     painter: &egui::Painter,
     shape: &Shape,
     off: Pos2,
+    background: Option<&egui::TextureHandle>,
     blurred_bg: Option<&egui::TextureHandle>,
+    mosaics: &MosaicCache,
     monitor_size_px: (u32, u32),
 ) {
     let stroke = Stroke::new(shape.style.stroke_width, to_color32(shape.style.stroke));
-    let fill = shape.style.fill.map(to_color32);
+    let fill = shape
+        .style
+        .fill
+        .map(|f| to_color32(shape.style.line.apply(f)));
     match &shape.kind {
-        ShapeKind::FreehandStroke { points } => {
+        ShapeKind::FreehandStroke { points, widths } if !widths.is_empty() => {
+            let smoothed = crate::shape::smoothed_freehand_widths(points, widths, stroke.width);
+            paint_pressure_path(painter, &smoothed, &shape.style, off);
+        }
+        ShapeKind::FreehandStroke { points, .. } => {
             let smoothed = crate::shape::smoothed_freehand(points, stroke.width);
-            let pts: Vec<Pos2> = smoothed
+            paint_path(painter, &smoothed, false, &shape.style, off);
+        }
+        // egui has no multiply blend; a translucent single path is close
+        // enough on screen, and the composite does the real multiply.
+        ShapeKind::Highlight { points } => {
+            let color = stroke.color.gamma_multiply(HIGHLIGHT_PREVIEW_ALPHA);
+            let pts: Vec<Pos2> = crate::shape::smoothed_freehand(points, stroke.width)
                 .iter()
                 .map(|p| Pos2::new(p.x - off.x, p.y - off.y))
                 .collect();
             if pts.len() == 1 {
-                painter.circle_filled(pts[0], stroke.width * 0.5, stroke.color);
+                painter.circle_filled(pts[0], stroke.width * 0.5, color);
             } else if pts.len() >= 2 {
-                painter.add(egui::Shape::line(pts.clone(), stroke));
-                // Round caps at endpoints — egui polylines are butt by default.
-                let r = stroke.width * 0.5;
-                painter.circle_filled(*pts.first().unwrap(), r, stroke.color);
-                painter.circle_filled(*pts.last().unwrap(), r, stroke.color);
+                painter.add(egui::Shape::line(pts, Stroke::new(stroke.width, color)));
             }
         }
         ShapeKind::Line { from, to } => {
-            painter.line_segment(
-                [
-                    Pos2::new(from.x - off.x, from.y - off.y),
-                    Pos2::new(to.x - off.x, to.y - off.y),
-                ],
-                stroke,
-            );
+            paint_path(painter, &[*from, *to], false, &shape.style, off);
         }
-        ShapeKind::Arrow { from, to } => {
-            let a = Pos2::new(from.x - off.x, from.y - off.y);
-            let b = Pos2::new(to.x - off.x, to.y - off.y);
-            painter.line_segment([a, b], stroke);
-            let dx = b.x - a.x;
-            let dy = b.y - a.y;
-            let len = (dx * dx + dy * dy).sqrt().max(1.0);
-            let ux = dx / len;
-            let uy = dy / len;
-            let head = (stroke.width * 3.0).max(10.0);
-            let p1 = Pos2::new(
-                b.x - (ux * head + uy * head * 0.5),
-                b.y - (uy * head - ux * head * 0.5),
-            );
-            let p2 = Pos2::new(
-                b.x - (ux * head - uy * head * 0.5),
-                b.y - (uy * head + ux * head * 0.5),
-            );
-            painter.line_segment([b, p1], stroke);
-            painter.line_segment([b, p2], stroke);
+        ShapeKind::Arrow { from, to, bend } => {
+            paint_arrow(painter, *from, *to, *bend, &shape.style, off);
+        }
+        // Shown whether or not it gets baked into the output.
+        ShapeKind::Measure {
+            from,
+            to,
+            boxed,
+            scale,
+            ..
+        } => {
+            let local = |p: FPoint| Pos2::new(p.x - off.x, p.y - off.y);
+            for (a, b) in crate::shape::measure_segments(*from, *to, *boxed) {
+                painter.line_segment([local(a), local(b)], stroke);
+            }
+            let label = crate::shape::measure_label(*from, *to, *boxed, *scale);
+            let mid = FPoint::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0);
+            paint_label_pill(painter, local(mid), label, stroke.color);
         }
         ShapeKind::Rectangle { rect } => {
             let r = EguiRect::from_min_size(
@@ -399,7 +675,15 @@ fn draw_shape(
             if let Some(f) = fill {
                 painter.rect_filled(r, 0.0, f);
             }
-            painter.rect_stroke(r, 0.0, stroke, egui::StrokeKind::Middle);
+            let (x0, y0) = (rect.x() as f32, rect.y() as f32);
+            let (x1, y1) = (rect.right() as f32, rect.bottom() as f32);
+            let corners = [
+                FPoint::new(x0, y0),
+                FPoint::new(x1, y0),
+                FPoint::new(x1, y1),
+                FPoint::new(x0, y1),
+            ];
+            paint_path(painter, &corners, true, &shape.style, off);
         }
         ShapeKind::BlurRect { rect, .. } => {
             // Live preview: blit the pre-blurred bg slice into the rect so
@@ -432,17 +716,104 @@ fn draw_shape(
             let dash_stroke = Stroke::new(1.0, Color32::from_rgb(200, 220, 255));
             draw_dashed_rect(painter, r, dash_stroke, 6.0, 4.0);
         }
+        ShapeKind::Pixelate { rect, block_size } => {
+            let r = EguiRect::from_min_size(
+                Pos2::new(rect.x() as f32 - off.x, rect.y() as f32 - off.y),
+                Vec2::new(rect.width() as f32, rect.height() as f32),
+            );
+            if let Some(Color([cr, cg, cb, _])) = shape.style.fill {
+                painter.rect_filled(r, 0.0, Color32::from_rgb(cr, cg, cb));
+            } else if let Some((area, tex)) = mosaics.get(&(*rect, *block_size)) {
+                // Stretch whole cells so partial edge cells keep their
+                // true size, then clip back to the covered area.
+                let a = EguiRect::from_min_size(
+                    Pos2::new(area.x() as f32 - off.x, area.y() as f32 - off.y),
+                    Vec2::new(area.width() as f32, area.height() as f32),
+                );
+                let [cols, rows] = tex.size();
+                let full = EguiRect::from_min_size(
+                    a.min,
+                    Vec2::new(
+                        (cols as u32 * block_size) as f32,
+                        (rows as u32 * block_size) as f32,
+                    ),
+                );
+                painter.with_clip_rect(a.intersect(painter.clip_rect())).image(
+                    tex.id(),
+                    full,
+                    EguiRect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                    Color32::WHITE,
+                );
+            } else {
+                painter.rect_filled(r, 0.0, Color32::from_rgba_unmultiplied(180, 200, 230, 70));
+            }
+            let dash_stroke = Stroke::new(1.0, Color32::from_rgb(200, 220, 255));
+            draw_dashed_rect(painter, r, dash_stroke, 6.0, 4.0);
+        }
         ShapeKind::Ellipse { rect } => {
             let r = EguiRect::from_min_size(
                 Pos2::new(rect.x() as f32 - off.x, rect.y() as f32 - off.y),
                 Vec2::new(rect.width() as f32, rect.height() as f32),
             );
-            painter.add(egui::Shape::Ellipse(egui::epaint::EllipseShape {
-                center: r.center(),
-                radius: r.size() / 2.0,
-                fill: fill.unwrap_or(Color32::TRANSPARENT),
-                stroke: stroke.into(),
-            }));
+            if let Some(f) = fill {
+                painter.add(egui::Shape::Ellipse(egui::epaint::EllipseShape {
+                    center: r.center(),
+                    radius: r.size() / 2.0,
+                    fill: f,
+                    stroke: Stroke::NONE.into(),
+                }));
+            }
+            let outline = crate::shape::ellipse_points(*rect);
+            paint_path(painter, &outline, true, &shape.style, off);
+        }
+        // The dim itself is drawn by `draw_spotlight_dim`.
+        ShapeKind::Spotlight { .. } => {}
+        ShapeKind::Magnify {
+            source,
+            target,
+            zoom,
+        } => {
+            let copy = crate::shape::magnified_rect(*source, *target, *zoom);
+            let to_screen = |r: &CapRect| {
+                EguiRect::from_min_size(
+                    Pos2::new(r.x() as f32 - off.x, r.y() as f32 - off.y),
+                    Vec2::new(r.width() as f32, r.height() as f32),
+                )
+            };
+            let (src, dst) = (to_screen(source), to_screen(&copy));
+            for (a, b) in crate::shape::magnify_connectors(*source, copy) {
+                painter.line_segment(
+                    [
+                        Pos2::new(a.x - off.x, a.y - off.y),
+                        Pos2::new(b.x - off.x, b.y - off.y),
+                    ],
+                    stroke,
+                );
+            }
+            // Stretch this monitor's raw background slice into the copy;
+            // a source on another monitor only gets its outline here.
+            let (mw, mh) = (monitor_size_px.0 as f32, monitor_size_px.1 as f32);
+            let bounds = EguiRect::from_min_size(Pos2::ZERO, Vec2::new(mw, mh));
+            match background {
+                Some(tex) if bounds.contains_rect(src) => {
+                    painter.image(
+                        tex.id(),
+                        dst,
+                        EguiRect::from_min_max(
+                            Pos2::new(src.min.x / mw, src.min.y / mh),
+                            Pos2::new(src.max.x / mw, src.max.y / mh),
+                        ),
+                        Color32::WHITE,
+                    );
+                }
+                _ => painter.rect_filled(
+                    dst,
+                    0.0,
+                    Color32::from_rgba_unmultiplied(180, 200, 230, 70),
+                ),
+            }
+            painter.rect_stroke(src, 0.0, stroke, egui::StrokeKind::Middle);
+            painter.rect_stroke(dst, 0.0, stroke, egui::StrokeKind::Middle);
         }
         ShapeKind::Step {
             center,
@@ -465,34 +836,263 @@ fn draw_shape(
             content,
             style,
         } => {
-            painter.text(
-                Pos2::new(origin.x - off.x, origin.y - off.y),
-                egui::Align2::LEFT_TOP,
-                content,
-                egui::FontId::proportional(style.size),
-                to_color32(style.color),
-            );
+            if let Some((tex, (dx, dy))) = text_texture(painter.ctx(), content, style) {
+                let r = EguiRect::from_min_size(
+                    Pos2::new(origin.x + dx as f32 - off.x, origin.y + dy as f32 - off.y),
+                    tex.size_vec2(),
+                );
+                painter.image(
+                    tex.id(),
+                    r,
+                    EguiRect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                    Color32::WHITE,
+                );
+            }
+        }
+        ShapeKind::Callout { body, anchor, .. } => {
+            let outline = crate::shape::callout_outline(*body, *anchor);
+            let pts: Vec<Pos2> = outline
+                .iter()
+                .map(|p| Pos2::new(p.x - off.x, p.y - off.y))
+                .collect();
+            // The outline is concave once it has a tail; fill the body and
+            // the tail triangle separately.
+            if let Some(f) = fill {
+                let r = EguiRect::from_min_size(
+                    Pos2::new(body.x() as f32 - off.x, body.y() as f32 - off.y),
+                    Vec2::new(body.width() as f32, body.height() as f32),
+                );
+                painter.rect_filled(r, crate::shape::callout_radius(*body), f);
+                if let Some(i) = outline.iter().position(|p| p == anchor) {
+                    let tail = vec![pts[i - 1], pts[i], pts[(i + 1) % pts.len()]];
+                    painter.add(egui::Shape::convex_polygon(tail, f, Stroke::NONE));
+                }
+            }
+            painter.add(egui::Shape::closed_line(pts, stroke));
+            if let Some(text) = callout_text_shape(shape) {
+                draw_shape(
+                    painter,
+                    &text,
+                    off,
+                    background,
+                    blurred_bg,
+                    mosaics,
+                    monitor_size_px,
+                );
+            }
+        }
+        ShapeKind::Image { rect, pixels } => {
+            let tex = image_texture(painter.ctx(), pixels);
+            let corners = crate::shape::image_corners(*rect, shape.rotation);
+            let uvs = [
+                Pos2::new(0.0, 0.0),
+                Pos2::new(1.0, 0.0),
+                Pos2::new(1.0, 1.0),
+                Pos2::new(0.0, 1.0),
+            ];
+            let tint = Color32::WHITE.gamma_multiply(shape.style.line.opacity.clamp(0.0, 1.0));
+            let mut mesh = egui::Mesh::with_texture(tex.id());
+            for (c, uv) in corners.iter().zip(uvs) {
+                mesh.vertices.push(egui::epaint::Vertex {
+                    pos: Pos2::new(c.x - off.x, c.y - off.y),
+                    uv,
+                    color: tint,
+                });
+            }
+            mesh.indices.extend([0, 1, 2, 0, 2, 3]);
+            painter.add(egui::Shape::mesh(mesh));
         }
         ShapeKind::Polygon { points, closed } => {
             if points.is_empty() {
                 return;
             }
-            let pts: Vec<Pos2> = points
-                .iter()
-                .map(|p| Pos2::new(p.x - off.x, p.y - off.y))
-                .collect();
             if let Some(fill) = fill {
-                if *closed && pts.len() >= 3 {
-                    painter.add(egui::Shape::convex_polygon(pts.clone(), fill, stroke));
-                    return;
+                if *closed && points.len() >= 3 {
+                    let pts: Vec<Pos2> = points
+                        .iter()
+                        .map(|p| Pos2::new(p.x - off.x, p.y - off.y))
+                        .collect();
+                    painter.add(egui::Shape::convex_polygon(pts, fill, Stroke::NONE));
+                }
+            }
+            paint_path(
+                painter,
+                points,
+                *closed && points.len() >= 3,
+                &shape.style,
+                off,
+            );
+        }
+    }
+}
+
+/// Stroke a polyline (closed: a loop) with the dashes, caps and joins of
+/// `style`. egui paths have neither caps nor round joins, so round ones
+/// are discs and square caps push the run ends out; miter and bevel both
+/// get egui's own join.
+fn paint_path(painter: &egui::Painter, points: &[FPoint], closed: bool, style: &Style, off: Pos2) {
+    if points.is_empty() {
+        return;
+    }
+    let line = style.line;
+    let stroke = Stroke::new(style.stroke_width, to_color32(line.apply(style.stroke)));
+    let r = style.stroke_width * 0.5;
+    let dashed = line.dash.pattern(style.stroke_width, line.cap);
+    let runs = match &dashed {
+        Some(pattern) => crate::shape::dash_path(points, closed, pattern),
+        None if closed && points.len() >= 2 => {
+            let mut loop_ = points.to_vec();
+            loop_.push(points[0]);
+            vec![loop_]
+        }
+        None => vec![points.to_vec()],
+    };
+    let looped = closed && dashed.is_none();
+    for run in runs {
+        let mut pts: Vec<Pos2> = run
+            .iter()
+            .map(|p| Pos2::new(p.x - off.x, p.y - off.y))
+            .collect();
+        if pts.len() == 1 {
+            if line.cap != LineCap::Butt {
+                painter.circle_filled(pts[0], r, stroke.color);
+            }
+            continue;
+        }
+        let n = pts.len();
+        if !looped {
+            match line.cap {
+                LineCap::Round => {
+                    painter.circle_filled(pts[0], r, stroke.color);
+                    painter.circle_filled(pts[n - 1], r, stroke.color);
+                }
+                LineCap::Square => {
+                    pts[0] = push_out(pts[0], pts[1], r);
+                    pts[n - 1] = push_out(pts[n - 1], pts[n - 2], r);
                 }
+                LineCap::Butt => {}
             }
-            painter.add(egui::Shape::line(pts, stroke));
-            if *closed && points.len() >= 3 {
-                let first = Pos2::new(points[0].x - off.x, points[0].y - off.y);
-                let last = points[points.len() - 1];
-                let last = Pos2::new(last.x - off.x, last.y - off.y);
-                painter.line_segment([last, first], stroke);
+        }
+        if line.join == LineJoin::Round {
+            // Only where the path turns; smooth curves need no discs.
+            let (lo, hi) = if looped { (0, n - 1) } else { (1, n - 1) };
+            for i in lo..hi {
+                let prev = if i == 0 { pts[n - 2] } else { pts[i - 1] };
+                let (d0, d1) = (
+                    (pts[i] - prev).normalized(),
+                    (pts[i + 1] - pts[i]).normalized(),
+                );
+                if d0.dot(d1) < 0.9 {
+                    painter.circle_filled(pts[i], r, stroke.color);
+                }
+            }
+        }
+        painter.add(egui::Shape::line(pts, stroke));
+    }
+}
+
+/// Stroke a pressure-sensitive freehand path, each point carrying its
+/// width: one triangle strip between the offset edges, with discs for
+/// the round ends.
+fn paint_pressure_path(
+    painter: &egui::Painter,
+    points: &[(FPoint, f32)],
+    style: &Style,
+    off: Pos2,
+) {
+    let color = to_color32(style.line.apply(style.stroke));
+    let pts: Vec<(Pos2, f32)> = points
+        .iter()
+        .map(|&(p, w)| (Pos2::new(p.x - off.x, p.y - off.y), w * 0.5))
+        .collect();
+    let n = pts.len();
+    let (Some(&(first, r0)), Some(&(last, r1))) = (pts.first(), pts.last()) else {
+        return;
+    };
+    painter.circle_filled(first, r0, color);
+    if n == 1 {
+        return;
+    }
+    painter.circle_filled(last, r1, color);
+    let mut mesh = egui::Mesh::default();
+    for (i, &(p, r)) in pts.iter().enumerate() {
+        let (a, b) = (pts[i.saturating_sub(1)].0, pts[(i + 1).min(n - 1)].0);
+        let d = (b - a).normalized();
+        let normal = egui::vec2(-d.y, d.x) * r;
+        mesh.colored_vertex(p + normal, color);
+        mesh.colored_vertex(p - normal, color);
+        if i > 0 {
+            let k = 2 * i as u32;
+            mesh.add_triangle(k - 2, k - 1, k);
+            mesh.add_triangle(k - 1, k + 1, k);
+        }
+    }
+    painter.add(mesh);
+}
+
+/// `a` moved `by` further away from `b`.
+fn push_out(a: Pos2, b: Pos2, by: f32) -> Pos2 {
+    let d = a - b;
+    if d.length() < 1.0e-4 {
+        return a;
+    }
+    a + d.normalized() * by
+}
+
+/// An arrow's shaft, straight or curved, and its head(s).
+fn paint_arrow(
+    painter: &egui::Painter,
+    from: FPoint,
+    to: FPoint,
+    bend: Option<FPoint>,
+    style: &Style,
+    off: Pos2,
+) {
+    let line = style.line;
+    let width = style.stroke_width.max(1.0);
+    let path = crate::shape::arrow_path(from, to, bend);
+    let tail = if line.double_headed {
+        line.head.inset(width)
+    } else {
+        0.0
+    };
+    let shaft = crate::shape::trim_path(&path, tail, line.head.inset(width));
+    paint_path(painter, &shaft, false, style, off);
+
+    let color = to_color32(line.apply(style.stroke));
+    let stroke = Stroke::new(style.stroke_width, color);
+    let at = |p: FPoint| Pos2::new(p.x - off.x, p.y - off.y);
+    let n = path.len();
+    let mut tips = vec![(path[n - 1], path[n - 2])];
+    if line.double_headed {
+        tips.push((path[0], path[1]));
+    }
+    for (tip, back) in tips {
+        let (h1, h2) = crate::shape::arrowhead_wings(tip, back, width);
+        match line.head {
+            ArrowHead::Open => {
+                painter.line_segment([at(tip), at(h1)], stroke);
+                painter.line_segment([at(tip), at(h2)], stroke);
+                for p in [tip, h1, h2] {
+                    painter.circle_filled(at(p), stroke.width * 0.5, color);
+                }
+            }
+            ArrowHead::Filled => {
+                painter.add(egui::Shape::convex_polygon(
+                    vec![at(tip), at(h1), at(h2)],
+                    color,
+                    Stroke::NONE,
+                ));
+            }
+            ArrowHead::Dot => {
+                painter.circle_filled(at(tip), ArrowHead::dot_radius(width), color);
+            }
+            ArrowHead::Bar => {
+                let (a, b) = crate::shape::arrowhead_bar(tip, back, width);
+                painter.line_segment([at(a), at(b)], stroke);
+                for p in [a, b] {
+                    painter.circle_filled(at(p), stroke.width * 0.5, color);
+                }
             }
         }
     }
@@ -576,14 +1176,25 @@ fn to_color32(c: Color) -> Color32 {
 fn apply_color(tool: &mut Tool, color: Color) {
     match tool {
         Tool::Brush(b)
+        | Tool::Highlighter(b)
         | Tool::Line(b)
         | Tool::Arrow(b)
         | Tool::Rectangle(b)
         | Tool::Ellipse(b)
-        | Tool::Polygon(b) => b.color = color,
+        | Tool::Polygon(b)
+        | Tool::Magnify { border: b, .. } => b.color = color,
         Tool::Step(s) => s.fill = color,
-        Tool::Text(t) => t.color = color,
-        Tool::Pointer | Tool::Eraser { .. } | Tool::BlurRect { .. } => {}
+        Tool::Measure { color: c, .. } => *c = color,
+        Tool::Text(t) => t.set_color(color),
+        Tool::Callout { brush, text } => {
+            brush.color = color;
+            text.set_color(color);
+        }
+        Tool::Pointer
+        | Tool::Eraser { .. }
+        | Tool::BlurRect { .. }
+        | Tool::Pixelate { .. }
+        | Tool::Spotlight { .. } => {}
     }
 }
 
@@ -0,0 +1,1051 @@
+//! SVG exporter: the capture as an embedded `<image>` with every shape on
+//! top as a vector element, so annotations stay crisp when scaled and can
+//! be edited in Inkscape.
+//!
+//! Geometry mirrors [`super::composite`]. Blur and mosaic regions cannot be
+//! expressed as vectors without processing whatever sits under them in the
+//! viewer, so each one becomes a raster patch cut from the composite at
+//! that point in z-order — the same pixels the PNG export would contain.
+//! A darkening spotlight is a masked translucent rectangle; a desaturating
+//! one is a raster of everything outside the spotlight areas. A magnifier
+//! keeps its borders and connectors as vectors around a raster copy.
+
+use std::fmt::Write as _;
+use std::io::Cursor;
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use image::{imageops, ImageFormat, RgbaImage};
+
+use crate::canvas::Canvas;
+use crate::color::Color;
+use crate::geometry::FPoint;
+use crate::shape::{ArrowHead, LineCap, LineJoin, Shape, ShapeKind, SpotlightShape, Style};
+use sss_capture::Rect;
+
+/// Font stack for text and step numbers; Hack is what the rasteriser
+/// embeds, so viewers that have it installed match the PNG exactly.
+const FONT_FAMILY: &str = "Hack, 'DejaVu Sans Mono', monospace";
+
+/// Render `image` plus the shapes of `canvas` as a standalone SVG
+/// document. `origin` is the canvas coordinate of the image's top-left
+/// corner, as in [`super::composite::flatten`].
+pub fn export_svg(image: &RgbaImage, canvas: &Canvas, origin: (i32, i32)) -> String {
+    let (w, h) = image.dimensions();
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <svg xmlns=\"http://www.w3.org/2000/svg\" \
+         xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+         width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n"
+    );
+    embed_image(&mut out, image, 0, 0, "capture");
+
+    // Raster state at the current z-position, only kept when there is a
+    // patch to cut.
+    let shapes = canvas.shapes();
+    let mut work = shapes.iter().any(is_patch).then(|| image.clone());
+    let top_spotlight = super::composite::top_spotlight(shapes);
+    for (i, shape) in shapes.iter().enumerate() {
+        if let Some(work) = work.as_mut() {
+            super::composite::paint_at(work, shapes, i, origin);
+        }
+        if let ShapeKind::Spotlight {
+            amount, desaturate, ..
+        } = shape.kind
+        {
+            if top_spotlight != Some(i) {
+                continue;
+            }
+            match work.as_ref() {
+                Some(work) if desaturate => {
+                    let patch = super::composite::outside_spotlights(work, shapes, origin);
+                    embed_image(&mut out, &patch, 0, 0, "spotlight");
+                }
+                _ => write_spotlight_mask(&mut out, shapes, amount, origin, (w, h)),
+            }
+            continue;
+        }
+        if let ShapeKind::Magnify {
+            source,
+            target,
+            zoom,
+        } = shape.kind
+        {
+            if let Some(work) = work.as_ref() {
+                let copy = crate::shape::magnified_rect(source, target, zoom);
+                embed_region(&mut out, work, copy, origin, "magnify");
+            }
+            write_shape(&mut out, shape, origin);
+            continue;
+        }
+        if !is_patch(shape) {
+            write_shape(&mut out, shape, origin);
+            continue;
+        }
+        if let Some(work) = work.as_ref() {
+            embed_region(&mut out, work, shape.bounds(), origin, "blur");
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Shapes exported as pixels rather than vectors.
+fn is_patch(shape: &Shape) -> bool {
+    match shape.kind {
+        ShapeKind::BlurRect { .. } => true,
+        ShapeKind::Pixelate { .. } => shape.style.fill.is_none(),
+        ShapeKind::Spotlight { desaturate, .. } => desaturate,
+        ShapeKind::Magnify { .. } => true,
+        _ => false,
+    }
+}
+
+fn write_shape(out: &mut String, shape: &Shape, origin: (i32, i32)) {
+    // Opacity applies to the shape as a whole, so overlapping parts of it
+    // don't show through each other.
+    if shape.style.line.opacity < 1.0 {
+        let _ = write!(
+            out,
+            "<g opacity=\"{:.3}\">",
+            shape.style.line.opacity.max(0.0)
+        );
+        let mut opaque = shape.clone();
+        opaque.style.line.opacity = 1.0;
+        write_shape(out, &opaque, origin);
+        out.push_str("</g>\n");
+        return;
+    }
+    let style = &shape.style;
+    let width = style.stroke_width.max(1.0);
+    let p = |pt: FPoint| (pt.x - origin.0 as f32, pt.y - origin.1 as f32);
+    match &shape.kind {
+        ShapeKind::FreehandStroke { points, widths } if !widths.is_empty() => {
+            let pts = crate::shape::smoothed_freehand_widths(points, widths, width);
+            let _ = writeln!(
+                out,
+                "<path d=\"{}\"{}/>",
+                pressure_outline(pts.iter().map(|&(pt, w)| (p(pt), w / 2.0))),
+                paint("fill", style.stroke)
+            );
+        }
+        ShapeKind::FreehandStroke { points, .. } => {
+            let pts = crate::shape::smoothed_freehand(points, width);
+            if pts.len() == 1 {
+                let (x, y) = p(pts[0]);
+                let _ = writeln!(
+                    out,
+                    "<circle cx=\"{x}\" cy=\"{y}\" r=\"{}\"{}/>",
+                    width / 2.0,
+                    paint("fill", style.stroke)
+                );
+            } else if pts.len() >= 2 {
+                let _ = writeln!(
+                    out,
+                    "<polyline points=\"{}\" fill=\"none\"{}/>",
+                    point_list(pts.iter().map(|&pt| p(pt))),
+                    stroke(style)
+                );
+            }
+        }
+        // Multiply like the compositor; viewers without blend modes show
+        // a plain translucent marker.
+        ShapeKind::Highlight { points } => {
+            let pts = crate::shape::smoothed_freehand(points, width);
+            if pts.is_empty() {
+                return;
+            }
+            let _ = writeln!(
+                out,
+                "<polyline points=\"{}\" fill=\"none\"{} style=\"mix-blend-mode:multiply\"/>",
+                point_list(pts.iter().map(|&pt| p(pt))),
+                stroke(style)
+            );
+        }
+        ShapeKind::Line { from, to } => {
+            let ((x1, y1), (x2, y2)) = (p(*from), p(*to));
+            let _ = writeln!(
+                out,
+                "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\"{}/>",
+                stroke(style)
+            );
+        }
+        ShapeKind::Arrow { from, to, bend } => {
+            let line = style.line;
+            let path = crate::shape::arrow_path(*from, *to, *bend);
+            let tail = if line.double_headed {
+                line.head.inset(width)
+            } else {
+                0.0
+            };
+            let shaft = crate::shape::trim_path(&path, tail, line.head.inset(width));
+            let _ = write!(
+                out,
+                "<g><polyline points=\"{}\" fill=\"none\"{}/>",
+                point_list(shaft.iter().map(|&pt| p(pt))),
+                stroke(style)
+            );
+            let n = path.len();
+            let mut tips = vec![(path[n - 1], path[n - 2])];
+            if line.double_headed {
+                tips.push((path[0], path[1]));
+            }
+            for (tip, back) in tips {
+                write_arrowhead(out, line.head, tip, back, style, origin);
+            }
+            out.push_str("</g>\n");
+        }
+        ShapeKind::Rectangle { rect } => {
+            let _ = writeln!(
+                out,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}{}/>",
+                rect.x() - origin.0,
+                rect.y() - origin.1,
+                rect.width(),
+                rect.height(),
+                fill(style),
+                stroke(style)
+            );
+        }
+        ShapeKind::Ellipse { rect } => {
+            let rx = rect.width() as f32 / 2.0;
+            let ry = rect.height() as f32 / 2.0;
+            let _ = writeln!(
+                out,
+                "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{rx}\" ry=\"{ry}\"{}{}/>",
+                (rect.x() - origin.0) as f32 + rx,
+                (rect.y() - origin.1) as f32 + ry,
+                fill(style),
+                stroke(style)
+            );
+        }
+        // The copy itself is a raster; see `export_svg`.
+        ShapeKind::Magnify {
+            source,
+            target,
+            zoom,
+        } => {
+            let copy = crate::shape::magnified_rect(*source, *target, *zoom);
+            let _ = write!(out, "<g fill=\"none\"{}>", stroke(style));
+            for (a, b) in crate::shape::magnify_connectors(*source, copy) {
+                let ((x1, y1), (x2, y2)) = (p(a), p(b));
+                let _ = write!(
+                    out,
+                    "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\"/>"
+                );
+            }
+            for (r, stroke_width) in [(copy, width), (*source, (width / 2.0).max(1.0))] {
+                let _ = write!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" stroke-width=\"{stroke_width}\"/>",
+                    r.x() - origin.0,
+                    r.y() - origin.1,
+                    r.width(),
+                    r.height()
+                );
+            }
+            out.push_str("</g>\n");
+        }
+        // Spotlights act together; see `write_spotlight_mask`.
+        ShapeKind::BlurRect { .. } | ShapeKind::Spotlight { .. } => {}
+        // Solid redaction; the mosaic variant is a raster patch.
+        ShapeKind::Pixelate { rect, .. } => {
+            let Some(Color([r, g, b, _])) = style.fill else {
+                return;
+            };
+            let _ = writeln!(
+                out,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}/>",
+                rect.x() - origin.0,
+                rect.y() - origin.1,
+                rect.width(),
+                rect.height(),
+                paint("fill", Color([r, g, b, 255]))
+            );
+        }
+        ShapeKind::Step {
+            center,
+            number,
+            radius,
+        } => {
+            let (cx, cy) = p(*center);
+            let _ = writeln!(
+                out,
+                "<g><circle cx=\"{cx}\" cy=\"{cy}\" r=\"{radius}\"{} stroke=\"#ffffff\"/>\
+                 <text x=\"{cx}\" y=\"{cy}\" font-family=\"{FONT_FAMILY}\" font-size=\"{}\" \
+                 fill=\"#ffffff\" text-anchor=\"middle\" dominant-baseline=\"central\">{number}</text></g>",
+                paint("fill", style.fill.unwrap_or(style.stroke)),
+                (radius * 1.1).max(8.0)
+            );
+        }
+        ShapeKind::Text {
+            origin: o,
+            content,
+            style: text,
+        } => {
+            // The rasteriser treats `origin` as the top of the first line
+            // box; each line is a `<tspan>` on its own baseline.
+            let (x, y) = p(*o);
+            if let Some(bg) = text.background {
+                let (w, h) = crate::font::text_size(content, text);
+                let (bw, bh) = (w + bg.padding * 2.0, h + bg.padding * 2.0);
+                let r = bg.radius(bh);
+                let _ = writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{bw}\" height=\"{bh}\" rx=\"{r}\" ry=\"{r}\"{}/>",
+                    x - bg.padding,
+                    y - bg.padding,
+                    paint("fill", bg.color)
+                );
+            }
+            let mut attrs = format!(
+                " font-family=\"{}\" font-size=\"{}\"",
+                escape(&font_stack(&text.family)),
+                text.size
+            );
+            for (on, attr) in [
+                (text.bold, " font-weight=\"bold\""),
+                (text.italic, " font-style=\"italic\""),
+                (text.underline, " text-decoration=\"underline\""),
+            ] {
+                if on {
+                    attrs.push_str(attr);
+                }
+            }
+            // The halo is a stroke painted under the fill, twice as wide
+            // as the outline since half of it lies inside the glyphs.
+            if let Some(halo) = text.outline {
+                let _ = write!(
+                    attrs,
+                    "{} stroke-width=\"{}\" stroke-linejoin=\"round\" paint-order=\"stroke\"",
+                    paint("stroke", halo.color),
+                    halo.width * 2.0
+                );
+            }
+            let (ascent, line) = crate::font::line_metrics(text);
+            let _ = write!(
+                out,
+                "<text{attrs}{} xml:space=\"preserve\">",
+                paint("fill", text.color)
+            );
+            for (i, l) in content.split('\n').enumerate() {
+                let _ = write!(
+                    out,
+                    "<tspan x=\"{x}\" y=\"{}\">{}</tspan>",
+                    y + ascent + i as f32 * line,
+                    escape(l)
+                );
+            }
+            let _ = writeln!(out, "</text>");
+        }
+        ShapeKind::Callout {
+            body,
+            anchor,
+            text,
+            text_style,
+        } => {
+            let outline = crate::shape::callout_outline(*body, *anchor);
+            let _ = writeln!(
+                out,
+                "<polygon points=\"{}\"{}{}/>",
+                point_list(outline.iter().map(|&pt| p(pt))),
+                fill(style),
+                stroke(style)
+            );
+            let (at, wrapped) = crate::shape::callout_text(*body, text, text_style);
+            write_shape(
+                out,
+                &Shape {
+                    kind: ShapeKind::Text {
+                        origin: at,
+                        content: wrapped,
+                        style: text_style.clone(),
+                    },
+                    ..shape.clone()
+                },
+                origin,
+            );
+        }
+        ShapeKind::Measure {
+            from,
+            to,
+            boxed,
+            scale,
+            baked,
+        } => {
+            if !baked {
+                return;
+            }
+            for (a, b) in crate::shape::measure_segments(*from, *to, *boxed) {
+                let ((x1, y1), (x2, y2)) = (p(a), p(b));
+                let _ = writeln!(
+                    out,
+                    "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\"{}/>",
+                    stroke(style)
+                );
+            }
+            let label = crate::shape::measure_label(*from, *to, *boxed, *scale);
+            let text = crate::shape::measure_text_style(style.stroke);
+            write_shape(
+                out,
+                &Shape {
+                    kind: ShapeKind::Text {
+                        origin: crate::shape::measure_label_origin(*from, *to, &label, &text),
+                        content: label,
+                        style: text,
+                    },
+                    ..shape.clone()
+                },
+                origin,
+            );
+        }
+        ShapeKind::Polygon { points, closed } => {
+            if points.is_empty() {
+                return;
+            }
+            let (element, fill_attr) = if *closed && points.len() >= 3 {
+                ("polygon", fill(style))
+            } else {
+                ("polyline", " fill=\"none\"".to_string())
+            };
+            let _ = writeln!(
+                out,
+                "<{element} points=\"{}\"{fill_attr}{}/>",
+                point_list(points.iter().map(|&pt| p(pt))),
+                stroke(style)
+            );
+        }
+        ShapeKind::Image { rect, pixels } => {
+            let mut png = Cursor::new(Vec::new());
+            if let Err(e) = pixels.image().write_to(&mut png, ImageFormat::Png) {
+                tracing::warn!(error = %e, "svg export: cannot encode image shape");
+                return;
+            }
+            let (x, y) = p(FPoint::new(rect.x() as f32, rect.y() as f32));
+            let (w, h) = (rect.width() as f32, rect.height() as f32);
+            let transform = if shape.rotation != 0.0 {
+                format!(
+                    " transform=\"rotate({:.3} {} {})\"",
+                    shape.rotation.to_degrees(),
+                    x + w / 2.0,
+                    y + h / 2.0
+                )
+            } else {
+                String::new()
+            };
+            let _ = writeln!(
+                out,
+                "<image x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" \
+                 preserveAspectRatio=\"none\"{transform} \
+                 xlink:href=\"data:image/png;base64,{}\"/>",
+                BASE64_STANDARD.encode(png.get_ref())
+            );
+        }
+    }
+}
+
+/// A black rectangle at `amount` opacity over the whole image, masked so
+/// the union of every spotlight stays clear.
+fn write_spotlight_mask(
+    out: &mut String,
+    shapes: &[Shape],
+    amount: u8,
+    origin: (i32, i32),
+    (w, h): (u32, u32),
+) {
+    let _ = write!(
+        out,
+        "<mask id=\"spotlight\"><rect width=\"{w}\" height=\"{h}\" fill=\"#ffffff\"/>"
+    );
+    for shape in shapes {
+        let ShapeKind::Spotlight { rect, shape, .. } = shape.kind else {
+            continue;
+        };
+        let (x, y) = (rect.x() - origin.0, rect.y() - origin.1);
+        let (rw, rh) = (rect.width() as f32, rect.height() as f32);
+        let _ = match shape {
+            SpotlightShape::Rect => write!(
+                out,
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{rw}\" height=\"{rh}\" fill=\"#000000\"/>"
+            ),
+            SpotlightShape::Ellipse => write!(
+                out,
+                "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" fill=\"#000000\"/>",
+                x as f32 + rw / 2.0,
+                y as f32 + rh / 2.0,
+                rw / 2.0,
+                rh / 2.0
+            ),
+        };
+    }
+    let _ = writeln!(
+        out,
+        "</mask>\n<rect width=\"{w}\" height=\"{h}\"{} mask=\"url(#spotlight)\"/>",
+        paint("fill", Color([0, 0, 0, amount]))
+    );
+}
+
+/// One arrowhead at `tip`, pointing away from `back`. Heads are never
+/// dashed.
+fn write_arrowhead(
+    out: &mut String,
+    head: ArrowHead,
+    tip: FPoint,
+    back: FPoint,
+    style: &Style,
+    origin: (i32, i32),
+) {
+    let width = style.stroke_width.max(1.0);
+    let p = |pt: FPoint| (pt.x - origin.0 as f32, pt.y - origin.1 as f32);
+    let solid = format!(
+        "{} stroke-width=\"{width}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"",
+        paint("stroke", style.stroke)
+    );
+    let (h1, h2) = crate::shape::arrowhead_wings(tip, back, width);
+    let _ = match head {
+        ArrowHead::Open => write!(
+            out,
+            "<polyline points=\"{}\" fill=\"none\"{solid}/>",
+            point_list([p(h1), p(tip), p(h2)])
+        ),
+        ArrowHead::Filled => write!(
+            out,
+            "<polygon points=\"{}\"{}{solid}/>",
+            point_list([p(h1), p(tip), p(h2)]),
+            paint("fill", style.stroke)
+        ),
+        ArrowHead::Dot => {
+            let (x, y) = p(tip);
+            write!(
+                out,
+                "<circle cx=\"{x}\" cy=\"{y}\" r=\"{}\"{}/>",
+                ArrowHead::dot_radius(width),
+                paint("fill", style.stroke)
+            )
+        }
+        ArrowHead::Bar => {
+            let (a, b) = crate::shape::arrowhead_bar(tip, back, width);
+            write!(
+                out,
+                "<polyline points=\"{}\" fill=\"none\"{solid}/>",
+                point_list([p(a), p(b)])
+            )
+        }
+    };
+}
+
+fn point_list(points: impl IntoIterator<Item = (f32, f32)>) -> String {
+    let mut s = String::new();
+    for (x, y) in points {
+        if !s.is_empty() {
+            s.push(' ');
+        }
+        let _ = write!(s, "{x},{y}");
+    }
+    s
+}
+
+/// Path data covering a stroke of varying width: a disc at every point
+/// and a trapezoid along every segment, all wound the same way so the
+/// nonzero fill merges them without overlaps doubling the alpha.
+fn pressure_outline(points: impl IntoIterator<Item = ((f32, f32), f32)>) -> String {
+    let mut d = String::new();
+    let mut prev: Option<((f32, f32), f32)> = None;
+    for ((x, y), r) in points {
+        let _ = write!(
+            d,
+            "M{},{y}A{r},{r} 0 1 1 {},{y}A{r},{r} 0 1 1 {},{y}Z",
+            x - r,
+            x + r,
+            x - r
+        );
+        if let Some(((px, py), pr)) = prev {
+            let len = (x - px).hypot(y - py);
+            if len > 1.0e-4 {
+                let (nx, ny) = (-(y - py) / len, (x - px) / len);
+                let _ = write!(
+                    d,
+                    "M{},{}L{},{}L{},{}L{},{}Z",
+                    px - nx * pr,
+                    py - ny * pr,
+                    x - nx * r,
+                    y - ny * r,
+                    x + nx * r,
+                    y + ny * r,
+                    px + nx * pr,
+                    py + ny * pr
+                );
+            }
+        }
+        prev = Some(((x, y), r));
+    }
+    d
+}
+
+fn stroke(style: &Style) -> String {
+    let line = style.line;
+    let cap = match line.cap {
+        LineCap::Butt => "butt",
+        LineCap::Round => "round",
+        LineCap::Square => "square",
+    };
+    let join = match line.join {
+        LineJoin::Miter => "miter",
+        LineJoin::Round => "round",
+        LineJoin::Bevel => "bevel",
+    };
+    let width = style.stroke_width.max(1.0);
+    let mut s = format!(
+        "{} stroke-width=\"{width}\" stroke-linecap=\"{cap}\" stroke-linejoin=\"{join}\"",
+        paint("stroke", style.stroke),
+    );
+    if let Some(pattern) = line.dash.pattern(width, line.cap) {
+        let _ = write!(
+            s,
+            " stroke-dasharray=\"{}\"",
+            pattern
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+    }
+    s
+}
+
+fn fill(style: &Style) -> String {
+    match style.fill {
+        Some(c) => paint("fill", c),
+        None => " fill=\"none\"".to_string(),
+    }
+}
+
+/// ` attr="#rrggbb"` plus an `attr-opacity` when the colour is translucent;
+/// 8-digit hex is not SVG 1.1 and Inkscape would drop the alpha.
+fn paint(attr: &str, c: Color) -> String {
+    let [r, g, b, a] = c.0;
+    let mut s = format!(" {attr}=\"#{r:02x}{g:02x}{b:02x}\"");
+    if a < 255 {
+        let _ = write!(s, " {attr}-opacity=\"{:.3}\"", a as f32 / 255.0);
+    }
+    s
+}
+
+/// Embed the part of `work` under `rect` (canvas coordinates), clipped to
+/// the image.
+fn embed_region(out: &mut String, work: &RgbaImage, rect: Rect, origin: (i32, i32), id: &str) {
+    let (w, h) = work.dimensions();
+    let x0 = (rect.x() - origin.0).clamp(0, w as i32) as u32;
+    let y0 = (rect.y() - origin.1).clamp(0, h as i32) as u32;
+    let x1 = (rect.right() - origin.0).clamp(0, w as i32) as u32;
+    let y1 = (rect.bottom() - origin.1).clamp(0, h as i32) as u32;
+    if x1 > x0 && y1 > y0 {
+        let patch = imageops::crop_imm(work, x0, y0, x1 - x0, y1 - y0).to_image();
+        embed_image(out, &patch, x0, y0, id);
+    }
+}
+
+fn embed_image(out: &mut String, image: &RgbaImage, x: u32, y: u32, id: &str) {
+    let mut png = Cursor::new(Vec::new());
+    if let Err(e) = image.write_to(&mut png, ImageFormat::Png) {
+        tracing::warn!(error = %e, "svg export: cannot encode {id} raster");
+        return;
+    }
+    let _ = writeln!(
+        out,
+        "<image x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{}\" \
+         xlink:href=\"data:image/png;base64,{}\"/>",
+        image.width(),
+        image.height(),
+        BASE64_STANDARD.encode(png.get_ref())
+    );
+}
+
+/// `family` ahead of the default stack, so viewers without it still get a
+/// monospace face. The result is CSS; [`escape`] it for the attribute.
+fn font_stack(family: &str) -> String {
+    let family = family.trim();
+    if family.is_empty() || family.eq_ignore_ascii_case(crate::font::DEFAULT_FAMILY) {
+        FONT_FAMILY.to_owned()
+    } else if matches!(
+        family,
+        "sans-serif" | "serif" | "monospace" | "cursive" | "fantasy"
+    ) {
+        format!("{family}, {FONT_FAMILY}")
+    } else {
+        // A quote or backslash in the name would end the CSS string early.
+        let family = family.replace('\\', "\\\\").replace('\'', "\\'");
+        format!("'{family}', {FONT_FAMILY}")
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+
+    use super::*;
+    use crate::shape::{
+        DashPattern, ImagePixels, LineStyle, ShapeId, TextBackground, TextOutline, TextStyle,
+    };
+
+    fn p(x: f32, y: f32) -> FPoint {
+        FPoint::new(x, y)
+    }
+
+    fn shape(kind: ShapeKind) -> Shape {
+        Shape {
+            id: ShapeId(1),
+            kind,
+            style: Style {
+                stroke: Color::RED,
+                stroke_width: 2.0,
+                fill: None,
+                line: LineStyle::default(),
+            },
+            rotation: 0.0,
+            group: None,
+        }
+    }
+
+    /// A 40×30 horizontal gradient, so patches cut from it can be told
+    /// apart.
+    fn capture() -> RgbaImage {
+        RgbaImage::from_fn(40, 30, |x, _| Rgba([(x * 6) as u8, 0, 0, 255]))
+    }
+
+    fn export(shapes: Vec<Shape>) -> String {
+        let mut canvas = Canvas::default();
+        canvas.shapes_mut().extend(shapes);
+        export_svg(&capture(), &canvas, (0, 0))
+    }
+
+    /// Pixels of the `n`th embedded raster; the capture is the first.
+    fn raster(svg: &str, n: usize) -> RgbaImage {
+        let data = svg.split("base64,").nth(n + 1).expect("raster");
+        let data = &data[..data.find('"').unwrap()];
+        image::load_from_memory(&BASE64_STANDARD.decode(data).unwrap())
+            .unwrap()
+            .to_rgba8()
+    }
+
+    #[test]
+    fn capture_is_embedded_at_full_size() {
+        let svg = export(Vec::new());
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("width=\"40\" height=\"30\" viewBox=\"0 0 40 30\""));
+        assert!(svg.contains("<image x=\"0\" y=\"0\" width=\"40\" height=\"30\""));
+        assert_eq!(raster(&svg, 0), capture());
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn freehand_is_a_polyline_or_a_pressure_outline() {
+        let points = vec![p(2.0, 2.0), p(10.0, 4.0), p(20.0, 2.0)];
+        let svg = export(vec![shape(ShapeKind::FreehandStroke {
+            points: points.clone(),
+            widths: Vec::new(),
+        })]);
+        assert!(svg.contains("<polyline points=\"2,2 "));
+        assert!(svg.contains(
+            "fill=\"none\" stroke=\"#dc322f\" stroke-width=\"2\" \
+             stroke-linecap=\"round\" stroke-linejoin=\"round\"/>"
+        ));
+
+        let svg = export(vec![shape(ShapeKind::FreehandStroke {
+            points,
+            widths: vec![1.0, 0.5, 0.2],
+        })]);
+        assert!(!svg.contains("<polyline"));
+        assert!(svg.contains("<path d=\"M"));
+        assert!(svg.contains("\" fill=\"#dc322f\"/>"));
+    }
+
+    #[test]
+    fn highlight_multiplies() {
+        let svg = export(vec![shape(ShapeKind::Highlight {
+            points: vec![p(0.0, 10.0), p(30.0, 10.0)],
+        })]);
+        assert!(svg.contains("<polyline points=\"0,10 "));
+        assert!(svg.contains("style=\"mix-blend-mode:multiply\""));
+    }
+
+    #[test]
+    fn line_rectangle_and_ellipse_keep_their_geometry() {
+        let rect = Rect::from_xywh(4, 6, 20, 10);
+        let mut filled = shape(ShapeKind::Rectangle { rect });
+        filled.style.fill = Some(Color::rgba(0, 0, 255, 51));
+        let svg = export(vec![
+            shape(ShapeKind::Line {
+                from: p(1.0, 2.0),
+                to: p(30.0, 25.0),
+            }),
+            filled,
+            shape(ShapeKind::Ellipse { rect }),
+        ]);
+        assert!(svg.contains("<line x1=\"1\" y1=\"2\" x2=\"30\" y2=\"25\" stroke=\"#dc322f\""));
+        assert!(svg.contains(
+            "<rect x=\"4\" y=\"6\" width=\"20\" height=\"10\" \
+             fill=\"#0000ff\" fill-opacity=\"0.200\" stroke=\"#dc322f\""
+        ));
+        assert!(svg.contains("<ellipse cx=\"14\" cy=\"11\" rx=\"10\" ry=\"5\" fill=\"none\""));
+    }
+
+    #[test]
+    fn translucent_shapes_are_grouped_under_one_opacity() {
+        let mut line = shape(ShapeKind::Line {
+            from: p(0.0, 0.0),
+            to: p(10.0, 0.0),
+        });
+        line.style.line.opacity = 0.5;
+        line.style.line.dash = DashPattern::Dashed;
+        let svg = export(vec![line]);
+        assert!(svg.contains("<g opacity=\"0.500\"><line "));
+        assert!(svg.contains("stroke-dasharray=\"8 6\""));
+    }
+
+    #[test]
+    fn arrow_is_a_shaft_with_separate_heads() {
+        let mut arrow = shape(ShapeKind::Arrow {
+            from: p(2.0, 15.0),
+            to: p(36.0, 15.0),
+            bend: None,
+        });
+        arrow.style.line.head = ArrowHead::Filled;
+        arrow.style.line.double_headed = true;
+        let svg = export(vec![arrow]);
+        // The shaft stops short of both tips, under the heads.
+        assert!(svg.contains("<g><polyline points=\"10,15 28,15\" fill=\"none\""));
+        assert!(svg.contains("<polygon points=\"26,20 36,15 26,10\" fill=\"#dc322f\""));
+        assert!(svg.contains("<polygon points=\"12,10 2,15 12,20\" fill=\"#dc322f\""));
+    }
+
+    #[test]
+    fn blur_and_mosaic_are_raster_patches() {
+        let rect = Rect::from_xywh(4, 6, 8, 4);
+        let svg = export(vec![
+            shape(ShapeKind::BlurRect { rect, radius: 3.0 }),
+            shape(ShapeKind::Pixelate {
+                rect: Rect::from_xywh(20, 6, 8, 4),
+                block_size: 4,
+            }),
+        ]);
+        assert!(svg.contains("<image x=\"4\" y=\"6\" width=\"8\" height=\"4\""));
+        assert!(svg.contains("<image x=\"20\" y=\"6\" width=\"8\" height=\"4\""));
+        let mosaic = raster(&svg, 2);
+        assert_eq!(mosaic.get_pixel(0, 0), mosaic.get_pixel(3, 3));
+        assert_ne!(mosaic.get_pixel(0, 0), mosaic.get_pixel(4, 0));
+    }
+
+    #[test]
+    fn solid_redaction_is_an_opaque_rect() {
+        let mut redact = shape(ShapeKind::Pixelate {
+            rect: Rect::from_xywh(4, 6, 8, 4),
+            block_size: 4,
+        });
+        redact.style.fill = Some(Color::rgba(0, 0, 0, 100));
+        let svg = export(vec![redact]);
+        assert_eq!(svg.matches("<image").count(), 1);
+        assert!(svg.contains("<rect x=\"4\" y=\"6\" width=\"8\" height=\"4\" fill=\"#000000\"/>"));
+    }
+
+    #[test]
+    fn spotlights_share_one_mask_or_one_raster() {
+        let spot = |rect, shape, desaturate| {
+            self::shape(ShapeKind::Spotlight {
+                rect,
+                shape,
+                amount: 128,
+                desaturate,
+            })
+        };
+        let svg = export(vec![
+            spot(Rect::from_xywh(0, 0, 10, 10), SpotlightShape::Rect, false),
+            spot(
+                Rect::from_xywh(20, 10, 10, 6),
+                SpotlightShape::Ellipse,
+                false,
+            ),
+        ]);
+        assert_eq!(svg.matches("<mask id=\"spotlight\">").count(), 1);
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"10\" height=\"10\" fill=\"#000000\"/>"));
+        assert!(svg.contains("<ellipse cx=\"25\" cy=\"13\" rx=\"5\" ry=\"3\" fill=\"#000000\"/>"));
+        assert!(svg.contains("fill-opacity=\"0.502\" mask=\"url(#spotlight)\""));
+
+        let svg = export(vec![spot(
+            Rect::from_xywh(0, 0, 10, 10),
+            SpotlightShape::Rect,
+            true,
+        )]);
+        assert!(!svg.contains("<mask"));
+        let outside = raster(&svg, 1);
+        assert_eq!(outside.dimensions(), (40, 30));
+        assert_eq!(outside.get_pixel(5, 5).0[3], 0);
+        assert_eq!(outside.get_pixel(30, 20).0[3], 255);
+    }
+
+    #[test]
+    fn magnify_is_a_raster_copy_with_vector_borders() {
+        let svg = export(vec![shape(ShapeKind::Magnify {
+            source: Rect::from_xywh(4, 4, 4, 4),
+            target: p(30.0, 6.0),
+            zoom: 3.0,
+        })]);
+        assert!(svg.contains("<image x=\"24\" y=\"0\" width=\"12\" height=\"12\""));
+        assert!(svg.contains("<g fill=\"none\" stroke=\"#dc322f\""));
+        assert_eq!(svg.matches("<line ").count(), 2);
+        assert!(
+            svg.contains("<rect x=\"24\" y=\"0\" width=\"12\" height=\"12\" stroke-width=\"2\"/>")
+        );
+        assert!(svg.contains("<rect x=\"4\" y=\"4\" width=\"4\" height=\"4\" stroke-width=\"1\"/>"));
+    }
+
+    #[test]
+    fn measure_is_exported_only_when_baked() {
+        let measure = |baked| {
+            shape(ShapeKind::Measure {
+                from: p(2.0, 2.0),
+                to: p(32.0, 2.0),
+                boxed: false,
+                scale: 1.0,
+                baked,
+            })
+        };
+        let svg = export(vec![measure(true)]);
+        assert!(svg.contains("<line x1=\"2\" y1=\"2\" x2=\"32\" y2=\"2\""));
+        assert!(svg.contains(">30 px</tspan>"));
+
+        let svg = export(vec![measure(false)]);
+        assert!(!svg.contains("<line"));
+        assert!(!svg.contains("<text"));
+    }
+
+    #[test]
+    fn callout_is_a_bubble_around_its_text() {
+        let mut callout = shape(ShapeKind::Callout {
+            body: Rect::from_xywh(0, 0, 80, 20),
+            anchor: p(10.0, 28.0),
+            text: "hi".to_owned(),
+            text_style: TextStyle::default(),
+        });
+        callout.style.fill = Some(Color::WHITE);
+        let svg = export(vec![callout]);
+        assert!(svg.contains("<polygon points=\""));
+        assert!(svg.contains("fill=\"#ffffff\" stroke=\"#dc322f\""));
+        assert!(svg.contains(" 10,28 "));
+        assert_eq!(svg.matches("stroke-linejoin").count(), 1);
+        assert!(svg.contains(">hi</tspan></text>"));
+    }
+
+    #[test]
+    fn step_is_a_numbered_disc() {
+        let mut step = shape(ShapeKind::Step {
+            center: p(10.0, 10.0),
+            number: 7,
+            radius: 8.0,
+        });
+        step.style.fill = Some(Color::BLUE);
+        let svg = export(vec![step]);
+        assert!(svg
+            .contains("<circle cx=\"10\" cy=\"10\" r=\"8\" fill=\"#3c78e6\" stroke=\"#ffffff\"/>"));
+        assert!(svg.contains("text-anchor=\"middle\" dominant-baseline=\"central\">7</text>"));
+    }
+
+    #[test]
+    fn text_keeps_lines_styles_and_escapes() {
+        let style = TextStyle {
+            color: Color::BLACK,
+            size: 12.0,
+            bold: true,
+            underline: true,
+            family: "Tom & Jerry's".to_owned(),
+            background: Some(TextBackground {
+                color: Color::YELLOW,
+                padding: 2.0,
+                pill: false,
+            }),
+            outline: Some(TextOutline {
+                color: Color::WHITE,
+                width: 1.5,
+            }),
+            ..TextStyle::default()
+        };
+        let svg = export(vec![shape(ShapeKind::Text {
+            origin: p(5.0, 5.0),
+            content: "a < b\n\"c\"".to_owned(),
+            style,
+        })]);
+        assert!(svg.contains("<rect x=\"3\" y=\"3\" "));
+        assert!(svg.contains("fill=\"#f0c800\"/>"));
+        assert!(svg.contains(
+            "font-family=\"'Tom &amp; Jerry\\'s', Hack, 'DejaVu Sans Mono', monospace\""
+        ));
+        assert!(svg.contains("font-weight=\"bold\""));
+        assert!(svg.contains("text-decoration=\"underline\""));
+        assert!(!svg.contains("font-style"));
+        assert!(svg.contains("stroke=\"#ffffff\" stroke-width=\"3\""));
+        assert!(svg.contains("paint-order=\"stroke\" fill=\"#000000\""));
+        assert_eq!(svg.matches("<tspan x=\"5\"").count(), 2);
+        assert!(svg.contains(">a &lt; b</tspan>"));
+        assert!(svg.contains(">&quot;c&quot;</tspan>"));
+    }
+
+    #[test]
+    fn font_stack_falls_back_to_the_default_faces() {
+        assert_eq!(font_stack(""), FONT_FAMILY);
+        assert_eq!(font_stack("hack"), FONT_FAMILY);
+        assert_eq!(font_stack("serif"), format!("serif, {FONT_FAMILY}"));
+        assert_eq!(font_stack(" Inter "), format!("'Inter', {FONT_FAMILY}"));
+        assert_eq!(font_stack("a\\b"), format!("'a\\\\b', {FONT_FAMILY}"));
+    }
+
+    #[test]
+    fn polygon_fills_only_when_closed() {
+        let points = vec![p(2.0, 2.0), p(20.0, 2.0), p(10.0, 20.0)];
+        let mut closed = shape(ShapeKind::Polygon {
+            points: points.clone(),
+            closed: true,
+        });
+        closed.style.fill = Some(Color::GREEN);
+        let svg = export(vec![
+            closed,
+            shape(ShapeKind::Polygon {
+                points,
+                closed: false,
+            }),
+        ]);
+        assert!(svg.contains("<polygon points=\"2,2 20,2 10,20\" fill=\"#32b450\""));
+        assert!(svg.contains("<polyline points=\"2,2 20,2 10,20\" fill=\"none\""));
+    }
+
+    #[test]
+    fn image_shape_embeds_its_pixels_and_rotation() {
+        let pixels = RgbaImage::from_pixel(2, 2, Rgba([1, 2, 3, 255]));
+        let mut image = shape(ShapeKind::Image {
+            rect: Rect::from_xywh(10, 10, 8, 4),
+            pixels: ImagePixels::new(pixels.clone()),
+        });
+        image.rotation = std::f32::consts::FRAC_PI_2;
+        let svg = export(vec![image]);
+        assert!(svg.contains(
+            "<image x=\"10\" y=\"10\" width=\"8\" height=\"4\" preserveAspectRatio=\"none\" \
+             transform=\"rotate(90.000 14 12)\""
+        ));
+        assert_eq!(raster(&svg, 1), pixels);
+    }
+
+    #[test]
+    fn origin_shifts_shapes_into_image_space() {
+        let mut canvas = Canvas::default();
+        canvas.shapes_mut().push(shape(ShapeKind::Rectangle {
+            rect: Rect::from_xywh(104, 56, 20, 10),
+        }));
+        let svg = export_svg(&capture(), &canvas, (100, 50));
+        assert!(svg.contains("<rect x=\"4\" y=\"6\" width=\"20\" height=\"10\""));
+    }
+}
@@ -5,12 +5,13 @@
 //!
 //! - Main toolbar is a rounded chip anchored above / below the active
 //!   region. Carries tools | colour swatch + width chip | pipette / snap /
-//!   magnifier toggles.
+//!   magnifier toggles | sticker picker.
 //! - Side action toolbar pinned next to the region: undo / redo / clear /
 //!   confirm / copy / save / cancel.
 //! - Selection toolbar near the bounds of the selected shape: raise /
 //!   lower / trash.
-//! - Radial menu opens on right-click — 4-column colour grid + width row.
+//! - Radial menu opens on right-click — 4-column colour grid + width row,
+//!   plus dash / arrowhead rows for tools that take a line style.
 //! - HSV color picker popup opens on swatch click — SV quad + hue strip +
 //!   editable hex input.
 
@@ -25,6 +26,9 @@ use crate::icons::{
     filled_tool_icon, rasterise as rasterise_icon, set_active_tool_width, tool_icon, ToolbarIcon,
 };
 use crate::mode::SelectorMode;
+use crate::shape::{
+    ArrowHead, DashPattern, ImagePixels, LineCap, LineJoin, LineStyle, SpotlightShape,
+};
 use crate::tool::{Tool, ToolPalette};
 
 const TB_BTN: f32 = 26.0;
@@ -94,7 +98,9 @@ pub(crate) struct ToolbarOutcome {
     pub toggle_snap: bool,
     pub toggle_magnifier: bool,
     pub open_width_popup: Option<Pos2>,
+    pub open_line_popup: Option<Pos2>,
     pub open_snap_popup: Option<Pos2>,
+    pub open_sticker_popup: Option<Pos2>,
     pub open_color_popup: Option<Pos2>,
     pub raise_selected: bool,
     pub lower_selected: bool,
@@ -104,11 +110,43 @@ pub(crate) struct ToolbarOutcome {
     pub select_tool: Option<usize>,
     /// `Some(i)` if the user picked tools palette index `i` as filled.
     pub select_tool_filled: Option<usize>,
+    /// `Some(i)` if the user picked tools palette index `i` (a Spotlight)
+    /// with the elliptical area.
+    pub select_tool_ellipse: Option<usize>,
+    /// Text-style chip clicked while the Text tool is active.
+    pub text_option: Option<TextOption>,
+    /// Measure chip clicked while the Measure tool is active.
+    pub measure_option: Option<MeasureOption>,
     pub undo: bool,
     pub redo: bool,
     pub clear_all: bool,
 }
 
+/// Text-style chips shown next to the width chip while the Text tool is
+/// active.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum TextOption {
+    Bold,
+    Italic,
+    Underline,
+    /// Cycles none → box → pill.
+    Background,
+    Outline,
+    /// Next entry of `UiConfig::text_fonts`.
+    Font,
+}
+
+/// Chips shown next to the width chip while the Measure tool is active.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum MeasureOption {
+    /// Box (W × H) instead of a dimension line.
+    Box,
+    /// Keep new measurements in the output.
+    Bake,
+    /// Physical ↔ logical pixels.
+    Units,
+}
+
 /// Toggle states for the chip buttons (pipette / snap / magnifier).
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct ToolbarConfig {
@@ -116,6 +154,10 @@ pub(crate) struct ToolbarConfig {
     pub snap_active: bool,
     pub magnifier_active: bool,
     pub snap_step: f32,
+    /// Show the sticker picker chip (a sticker directory is configured).
+    pub stickers: bool,
+    /// Measurements count logical pixels.
+    pub measure_logical: bool,
 }
 
 /// Render the floating toolbar inside `ctx`. Returns user-triggered
@@ -145,10 +187,41 @@ pub(crate) fn draw_toolbar(
 
     let active_disc = std::mem::discriminant(&canvas.active_tool);
     let fill_on = canvas.fill_mode();
+    let active_spotlight = match &canvas.active_tool {
+        Tool::Spotlight { shape, .. } => Some(*shape),
+        _ => None,
+    };
     for (i, tool) in palette.tools.iter().enumerate() {
+        // Spotlight pairs its rectangle button with an ellipse one.
+        if matches!(tool, Tool::Spotlight { .. }) {
+            for (shape, action, icon) in [
+                (
+                    SpotlightShape::Rect,
+                    Action::SelectTool(i),
+                    ToolbarIcon::Spotlight,
+                ),
+                (
+                    SpotlightShape::Ellipse,
+                    Action::SelectToolEllipse(i),
+                    ToolbarIcon::SpotlightEllipse,
+                ),
+            ] {
+                buttons.push(Button {
+                    kind: ButtonKind::Tool,
+                    action,
+                    icon: Some(icon),
+                    label: None,
+                    tint: None,
+                    bg_tint: None,
+                    active: active_spotlight == Some(shape),
+                    width: TB_BTN,
+                });
+            }
+            continue;
+        }
         let is_closed = matches!(
             tool,
-            Tool::Rectangle(_) | Tool::Ellipse(_) | Tool::Polygon(_)
+            Tool::Rectangle(_) | Tool::Ellipse(_) | Tool::Polygon(_) | Tool::Pixelate { .. }
         );
         let outlined_active =
             std::mem::discriminant(tool) == active_disc && (!is_closed || !fill_on);
@@ -204,6 +277,78 @@ pub(crate) fn draw_toolbar(
         active: false,
         width: TB_BTN + 8.0,
     });
+    // Line style chip (click opens the dash / cap / join / head popup).
+    if canvas.active_tool.uses_line_style() {
+        let line = canvas.line_style();
+        buttons.push(Button {
+            kind: ButtonKind::Chip,
+            action: Action::OpenLineStyle,
+            icon: None,
+            label: Some(dash_label(line.dash).to_owned()),
+            tint: None,
+            bg_tint: None,
+            active: line != LineStyle::default(),
+            width: TB_BTN + 8.0,
+        });
+    }
+
+    if let Tool::Text(style) | Tool::Callout { text: style, .. } = &canvas.active_tool {
+        let background = match style.background {
+            None => "Box",
+            Some(bg) if !bg.pill => "Box",
+            Some(_) => "Pill",
+        };
+        let family: String = style.family.chars().take(10).collect();
+        for (option, label, active, width) in [
+            (TextOption::Bold, "B", style.bold, TB_BTN),
+            (TextOption::Italic, "I", style.italic, TB_BTN),
+            (TextOption::Underline, "U", style.underline, TB_BTN),
+            (
+                TextOption::Background,
+                background,
+                style.background.is_some(),
+                TB_BTN + 8.0,
+            ),
+            (
+                TextOption::Outline,
+                "Halo",
+                style.outline.is_some(),
+                TB_BTN + 8.0,
+            ),
+            (TextOption::Font, family.as_str(), false, TB_BTN * 2.0 + 8.0),
+        ] {
+            buttons.push(Button {
+                kind: ButtonKind::Chip,
+                action: Action::TextOption(option),
+                icon: None,
+                label: Some(label.to_owned()),
+                tint: None,
+                bg_tint: None,
+                active,
+                width,
+            });
+        }
+    }
+
+    if let Tool::Measure { boxed, bake, .. } = &canvas.active_tool {
+        let units = if cfg.measure_logical { "pt" } else { "px" };
+        for (option, label, active) in [
+            (MeasureOption::Box, "Box", *boxed),
+            (MeasureOption::Bake, "Bake", *bake),
+            (MeasureOption::Units, units, cfg.measure_logical),
+        ] {
+            buttons.push(Button {
+                kind: ButtonKind::Chip,
+                action: Action::MeasureOption(option),
+                icon: None,
+                label: Some(label.to_owned()),
+                tint: None,
+                bg_tint: None,
+                active,
+                width: TB_BTN + 8.0,
+            });
+        }
+    }
 
     // Pipette / Snap / Magnifier toggles.
     buttons.push(Button {
@@ -247,6 +392,18 @@ pub(crate) fn draw_toolbar(
         active: cfg.magnifier_active,
         width: TB_BTN,
     });
+    if cfg.stickers {
+        buttons.push(Button {
+            kind: ButtonKind::Chip,
+            action: Action::OpenStickers,
+            icon: Some(ToolbarIcon::Sticker),
+            label: None,
+            tint: None,
+            bg_tint: None,
+            active: false,
+            width: TB_BTN,
+        });
+    }
 
     // Confirm / copy / save / cancel / undo / redo / clear live on the
     // separate side action toolbar (`draw_action_toolbar`), not here.
@@ -340,6 +497,9 @@ enum ButtonKind {
 enum Action {
     SelectTool(usize),
     SelectToolFilled(usize),
+    SelectToolEllipse(usize),
+    TextOption(TextOption),
+    MeasureOption(MeasureOption),
     Undo,
     Redo,
     ClearAll,
@@ -349,7 +509,9 @@ enum Action {
     Save,
     OpenColor,
     OpenWidth,
+    OpenLineStyle,
     OpenSnap,
+    OpenStickers,
     TogglePipette,
     ToggleSnap,
     ToggleMagnifier,
@@ -481,6 +643,9 @@ fn draw_button(
         match &b.action {
             Action::SelectTool(i) => out.select_tool = Some(*i),
             Action::SelectToolFilled(i) => out.select_tool_filled = Some(*i),
+            Action::SelectToolEllipse(i) => out.select_tool_ellipse = Some(*i),
+            Action::TextOption(o) => out.text_option = Some(*o),
+            Action::MeasureOption(o) => out.measure_option = Some(*o),
             Action::Undo => out.undo = true,
             Action::Redo => out.redo = true,
             Action::ClearAll => out.clear_all = true,
@@ -496,7 +661,9 @@ fn draw_button(
             }
             Action::OpenColor => out.open_color_popup = Some(rect.center_bottom()),
             Action::OpenWidth => out.open_width_popup = Some(rect.center_bottom()),
+            Action::OpenLineStyle => out.open_line_popup = Some(rect.center_bottom()),
             Action::OpenSnap => out.open_snap_popup = Some(rect.center_bottom()),
+            Action::OpenStickers => out.open_sticker_popup = Some(rect.center_bottom()),
             Action::TogglePipette => out.toggle_pipette = true,
             Action::ToggleSnap => out.toggle_snap = true,
             Action::ToggleMagnifier => out.toggle_magnifier = true,
@@ -550,6 +717,8 @@ fn anchor(
 pub(crate) enum RadialPick {
     Color(SssColor),
     Width(f32),
+    Dash(DashPattern),
+    Head(ArrowHead),
 }
 
 #[derive(Default, Clone, Copy, Debug)]
@@ -559,6 +728,9 @@ pub(crate) struct RadialOutcome {
     pub close: bool,
 }
 
+/// `line` adds a dash row when the active tool takes a line style, and
+/// `show_heads` an arrowhead row below it.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn draw_radial(
     ctx: &egui::Context,
     state: &RadialState,
@@ -567,6 +739,8 @@ pub(crate) fn draw_radial(
     widths: &[f32],
     current_color: SssColor,
     current_width: f32,
+    line: Option<LineStyle>,
+    show_heads: bool,
     chrome: &ChromeColors,
 ) -> (RadialOutcome, Rect) {
     let cols = RADIAL_COLS;
@@ -575,8 +749,17 @@ pub(crate) fn draw_radial(
     let grid_w = cols as f32 * RADIAL_CELL + (cols as f32 - 1.0) * RADIAL_GAP;
     let grid_h = rows as f32 * RADIAL_CELL + (rows as f32 - 1.0) * RADIAL_GAP;
     let widths_row_h = RADIAL_CELL;
+    let line_rows = match line {
+        Some(_) if show_heads => 2,
+        Some(_) => 1,
+        None => 0,
+    };
     let total_w = grid_w + RADIAL_PAD * 2.0;
-    let total_h = grid_h + RADIAL_GAP + widths_row_h + RADIAL_PAD * 2.0;
+    let total_h = grid_h
+        + RADIAL_GAP
+        + widths_row_h
+        + line_rows as f32 * (RADIAL_GAP + RADIAL_CELL)
+        + RADIAL_PAD * 2.0;
 
     let bg = Color32::from_rgb(
         chrome.toolbar_bg.0[0],
@@ -683,6 +866,46 @@ pub(crate) fn draw_radial(
                 }
             }
 
+            // dash / arrowhead rows
+            if let Some(line) = line {
+                let fg = Color32::from_rgb(
+                    chrome.toolbar_fg.0[0],
+                    chrome.toolbar_fg.0[1],
+                    chrome.toolbar_fg.0[2],
+                );
+                let mut row_y = widths_y + RADIAL_CELL + RADIAL_GAP;
+                let mut picks: Vec<Vec<(RadialPick, bool)>> = vec![DashPattern::ALL
+                    .iter()
+                    .map(|d| (RadialPick::Dash(*d), *d == line.dash))
+                    .collect()];
+                if show_heads {
+                    picks.push(
+                        ArrowHead::ALL
+                            .iter()
+                            .map(|h| (RadialPick::Head(*h), *h == line.head))
+                            .collect(),
+                    );
+                }
+                for row in picks {
+                    for (i, (pick, is_current)) in row.into_iter().enumerate() {
+                        let cx = rect.min.x + RADIAL_PAD + i as f32 * (RADIAL_CELL + RADIAL_GAP);
+                        let cell =
+                            Rect::from_min_size(Pos2::new(cx, row_y), Vec2::splat(RADIAL_CELL));
+                        let is_hovered = hover_pos.map_or(false, |p| cell.contains(p));
+                        painter.rect_filled(cell, 4.0, chip_fill(chrome, is_current, is_hovered));
+                        match pick {
+                            RadialPick::Dash(d) => paint_dash_glyph(&painter, cell, d, fg),
+                            RadialPick::Head(h) => paint_head_glyph(&painter, cell, h, fg),
+                            _ => {}
+                        }
+                        if clicked && is_hovered {
+                            outcome.pick = Some(pick);
+                        }
+                    }
+                    row_y += RADIAL_CELL + RADIAL_GAP;
+                }
+            }
+
             // Skip the click that opened the popup — only honour
             // outside-click-close after the popup has been visible for at
             // least one frame.
@@ -702,7 +925,8 @@ pub(crate) fn draw_radial(
 
 /// Apply a radial pick to the active tool / canvas state. Returns the
 /// updated `(current_color, current_width)` pair for the caller to mirror
-/// back into `App`. Kept for ergonomic re-use by callers that don't want
+/// back into `App`; dash and head picks go straight to the canvas line
+/// style. Kept for ergonomic re-use by callers that don't want
 /// to repeat the match.
 #[allow(dead_code)]
 pub(crate) fn apply_radial_pick(
@@ -725,10 +949,101 @@ pub(crate) fn apply_radial_pick(
             width = w;
             set_active_tool_width(&mut canvas.active_tool, w);
         }
+        RadialPick::Dash(dash) => {
+            let line = canvas.line_style();
+            canvas.set_line_style(LineStyle { dash, ..line });
+        }
+        RadialPick::Head(head) => {
+            let line = canvas.line_style();
+            canvas.set_line_style(LineStyle { head, ..line });
+        }
     }
     (color, width)
 }
 
+/// Background of a selectable cell in the radial menu / line popup.
+fn chip_fill(chrome: &ChromeColors, current: bool, hovered: bool) -> Color32 {
+    if current {
+        Color32::from_rgb(
+            chrome.button_active_bg.0[0],
+            chrome.button_active_bg.0[1],
+            chrome.button_active_bg.0[2],
+        )
+    } else if hovered {
+        Color32::from_rgb(
+            chrome.button_bg.0[0].saturating_add(30),
+            chrome.button_bg.0[1].saturating_add(30),
+            chrome.button_bg.0[2].saturating_add(30),
+        )
+    } else {
+        Color32::from_rgb(
+            chrome.button_bg.0[0],
+            chrome.button_bg.0[1],
+            chrome.button_bg.0[2],
+        )
+    }
+}
+
+/// Short text form of a dash pattern for the toolbar chip.
+fn dash_label(dash: DashPattern) -> &'static str {
+    match dash {
+        DashPattern::Solid => "───",
+        DashPattern::Dashed => "- - -",
+        DashPattern::Dotted => "· · ·",
+        DashPattern::DashDot => "-·-·",
+    }
+}
+
+/// A horizontal sample of `dash` across `cell`.
+fn paint_dash_glyph(painter: &egui::Painter, cell: Rect, dash: DashPattern, color: Color32) {
+    use crate::geometry::FPoint;
+    let y = cell.center().y;
+    let (x0, x1) = (cell.min.x + 4.0, cell.max.x - 4.0);
+    let line = [FPoint::new(x0, y), FPoint::new(x1, y)];
+    let runs = match dash.pattern(2.0, LineCap::Butt) {
+        Some(pattern) => crate::shape::dash_path(&line, false, &pattern),
+        None => vec![line.to_vec()],
+    };
+    for run in runs {
+        let pts: Vec<Pos2> = run.iter().map(|p| Pos2::new(p.x, p.y)).collect();
+        painter.add(egui::Shape::line(pts, Stroke::new(2.0, color)));
+    }
+}
+
+/// A short arrow pointing right across `cell` with a `head` tip.
+fn paint_head_glyph(painter: &egui::Painter, cell: Rect, head: ArrowHead, color: Color32) {
+    use crate::geometry::FPoint;
+    let y = cell.center().y;
+    let (tail, tip) = (
+        FPoint::new(cell.min.x + 4.0, y),
+        FPoint::new(cell.max.x - 5.0, y),
+    );
+    let at = |p: FPoint| Pos2::new(p.x, p.y);
+    let stroke = Stroke::new(1.5, color);
+    let width = 1.5;
+    let shaft_end = FPoint::new(tip.x - head.inset(width), y);
+    painter.line_segment([at(tail), at(shaft_end)], stroke);
+    let (h1, h2) = crate::shape::arrowhead_wings(tip, tail, width);
+    match head {
+        ArrowHead::Open => {
+            painter.line_segment([at(tip), at(h1)], stroke);
+            painter.line_segment([at(tip), at(h2)], stroke);
+        }
+        ArrowHead::Filled => {
+            painter.add(egui::Shape::convex_polygon(
+                vec![at(tip), at(h1), at(h2)],
+                color,
+                Stroke::NONE,
+            ));
+        }
+        ArrowHead::Dot => painter.circle_filled(at(tip), ArrowHead::dot_radius(width), color),
+        ArrowHead::Bar => {
+            let (a, b) = crate::shape::arrowhead_bar(tip, tail, width);
+            painter.line_segment([at(a), at(b)], stroke);
+        }
+    }
+}
+
 // ============================================================================
 // Width / Snap popups
 // ============================================================================
@@ -846,6 +1161,440 @@ pub(crate) fn draw_slider_popup(
     (out, popup_rect)
 }
 
+// ============================================================================
+// Line style popup
+// ============================================================================
+
+const LINE_POPUP_W: f32 = 250.0;
+const LINE_LABEL_W: f32 = 44.0;
+const LINE_CHIP_H: f32 = 22.0;
+
+/// Outcome from [`draw_line_popup`]: the edited style whenever a control
+/// changed it this frame.
+#[derive(Default, Clone, Copy, Debug)]
+pub(crate) struct LinePopupOutcome {
+    pub style: Option<LineStyle>,
+    pub close: bool,
+}
+
+/// One entry of a chip row in the line popup.
+enum LineChip {
+    Dash(DashPattern),
+    Cap(LineCap),
+    Join(LineJoin),
+    Head(ArrowHead),
+    DoubleHeaded,
+}
+
+/// Opacity slider plus dash / cap / join / arrowhead chip rows.
+pub(crate) fn draw_line_popup(
+    ctx: &egui::Context,
+    origin: Pos2,
+    armed: &mut bool,
+    current: LineStyle,
+    chrome: &ChromeColors,
+) -> (LinePopupOutcome, Rect) {
+    let mut out = LinePopupOutcome::default();
+    let rows: [(&str, Vec<LineChip>); 4] = [
+        ("Dash", DashPattern::ALL.map(LineChip::Dash).into()),
+        ("Cap", LineCap::ALL.map(LineChip::Cap).into()),
+        ("Join", LineJoin::ALL.map(LineChip::Join).into()),
+        ("Head", {
+            let mut heads: Vec<LineChip> = ArrowHead::ALL.map(LineChip::Head).into();
+            heads.push(LineChip::DoubleHeaded);
+            heads
+        }),
+    ];
+    let slider_h = 34.0;
+    let total_h = POPUP_PAD * 2.0 + slider_h + rows.len() as f32 * (LINE_CHIP_H + RADIAL_GAP);
+    let popup_rect = Rect::from_min_size(
+        Pos2::new(origin.x - LINE_POPUP_W / 2.0, origin.y + 4.0),
+        Vec2::new(LINE_POPUP_W, total_h),
+    );
+    let bg = Color32::from_rgb(
+        chrome.toolbar_bg.0[0],
+        chrome.toolbar_bg.0[1],
+        chrome.toolbar_bg.0[2],
+    );
+    let border = Color32::from_rgb(
+        chrome.toolbar_border.0[0],
+        chrome.toolbar_border.0[1],
+        chrome.toolbar_border.0[2],
+    );
+    let fg = Color32::from_rgb(
+        chrome.toolbar_fg.0[0],
+        chrome.toolbar_fg.0[1],
+        chrome.toolbar_fg.0[2],
+    );
+    let accent = Color32::from_rgb(
+        chrome.button_active_bg.0[0],
+        chrome.button_active_bg.0[1],
+        chrome.button_active_bg.0[2],
+    );
+
+    egui::Area::new(egui::Id::new("sss::popup::line"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(popup_rect.min)
+        .show(ctx, |ui| {
+            let (rect, resp) = ui.allocate_exact_size(popup_rect.size(), Sense::click());
+            let painter = ui.painter_at(rect);
+            painter.rect_filled(rect, 6.0, bg);
+            painter.rect_stroke(
+                rect,
+                6.0,
+                Stroke::new(1.0, border),
+                egui::StrokeKind::Inside,
+            );
+            let hover_pos = ui.input(|i| i.pointer.hover_pos());
+            let mut style = current;
+
+            // Opacity slider.
+            painter.text(
+                Pos2::new(rect.min.x + POPUP_PAD, rect.min.y + POPUP_PAD),
+                egui::Align2::LEFT_TOP,
+                format!("Opacity: {:.0}%", current.opacity * 100.0),
+                FontId::proportional(11.0),
+                fg,
+            );
+            let track_y = rect.min.y + POPUP_PAD + slider_h - 10.0;
+            let track_x0 = rect.min.x + POPUP_PAD;
+            let track_x1 = rect.max.x - POPUP_PAD;
+            let track_rect = Rect::from_min_max(
+                Pos2::new(track_x0, track_y - 3.0),
+                Pos2::new(track_x1, track_y + 3.0),
+            );
+            painter.rect_filled(track_rect, 3.0, Color32::from_rgb(70, 70, 74));
+            let knob_x = track_x0 + current.opacity.clamp(0.0, 1.0) * (track_x1 - track_x0);
+            painter.circle_filled(Pos2::new(knob_x, track_y), 7.0, accent);
+            painter.circle_stroke(
+                Pos2::new(knob_x, track_y),
+                7.0,
+                Stroke::new(1.0, Color32::WHITE),
+            );
+            let slider = ui.interact(
+                track_rect.expand2(Vec2::new(0.0, 8.0)),
+                egui::Id::new("sss::popup::line::opacity"),
+                Sense::click_and_drag(),
+            );
+            if slider.dragged() || slider.clicked() {
+                if let Some(p) = ui.input(|i| i.pointer.interact_pos()) {
+                    let t = ((p.x - track_x0) / (track_x1 - track_x0)).clamp(0.05, 1.0);
+                    style.opacity = t;
+                }
+            }
+
+            // Chip rows.
+            let mut row_y = rect.min.y + POPUP_PAD + slider_h;
+            for (label, chips) in rows.iter() {
+                painter.text(
+                    Pos2::new(rect.min.x + POPUP_PAD, row_y + LINE_CHIP_H / 2.0),
+                    egui::Align2::LEFT_CENTER,
+                    *label,
+                    FontId::proportional(11.0),
+                    fg,
+                );
+                let x0 = rect.min.x + POPUP_PAD + LINE_LABEL_W;
+                let n = chips.len() as f32;
+                let chip_w = (rect.max.x - POPUP_PAD - x0 - (n - 1.0) * RADIAL_GAP) / n;
+                for (i, chip) in chips.iter().enumerate() {
+                    let cell = Rect::from_min_size(
+                        Pos2::new(x0 + i as f32 * (chip_w + RADIAL_GAP), row_y),
+                        Vec2::new(chip_w, LINE_CHIP_H),
+                    );
+                    let is_current = match chip {
+                        LineChip::Dash(d) => *d == current.dash,
+                        LineChip::Cap(c) => *c == current.cap,
+                        LineChip::Join(j) => *j == current.join,
+                        LineChip::Head(h) => *h == current.head,
+                        LineChip::DoubleHeaded => current.double_headed,
+                    };
+                    let is_hovered = hover_pos.map_or(false, |p| cell.contains(p));
+                    painter.rect_filled(cell, 4.0, chip_fill(chrome, is_current, is_hovered));
+                    let text = |t: &str| {
+                        painter.text(
+                            cell.center(),
+                            egui::Align2::CENTER_CENTER,
+                            t,
+                            FontId::proportional(11.0),
+                            fg,
+                        );
+                    };
+                    match chip {
+                        LineChip::Dash(d) => paint_dash_glyph(&painter, cell, *d, fg),
+                        LineChip::Cap(LineCap::Butt) => text("Butt"),
+                        LineChip::Cap(LineCap::Round) => text("Round"),
+                        LineChip::Cap(LineCap::Square) => text("Square"),
+                        LineChip::Join(LineJoin::Miter) => text("Miter"),
+                        LineChip::Join(LineJoin::Round) => text("Round"),
+                        LineChip::Join(LineJoin::Bevel) => text("Bevel"),
+                        LineChip::Head(h) => paint_head_glyph(&painter, cell, *h, fg),
+                        LineChip::DoubleHeaded => text("Both"),
+                    }
+                    let clicked = ui
+                        .interact(
+                            cell,
+                            egui::Id::new(("sss::popup::line::chip", *label, i)),
+                            Sense::click(),
+                        )
+                        .clicked();
+                    if clicked {
+                        match chip {
+                            LineChip::Dash(d) => style.dash = *d,
+                            LineChip::Cap(c) => style.cap = *c,
+                            LineChip::Join(j) => style.join = *j,
+                            LineChip::Head(h) => style.head = *h,
+                            LineChip::DoubleHeaded => style.double_headed = !style.double_headed,
+                        }
+                    }
+                }
+                row_y += LINE_CHIP_H + RADIAL_GAP;
+            }
+            if style != current {
+                out.style = Some(style);
+            }
+
+            // Click outside popup closes it (after the opening click).
+            if !*armed {
+                *armed = true;
+            } else if !resp.clicked() && ui.input(|i| i.pointer.any_click()) {
+                if let Some(p) = ui.input(|i| i.pointer.interact_pos()) {
+                    if !rect.contains(p) {
+                        out.close = true;
+                    }
+                }
+            }
+        });
+
+    (out, popup_rect)
+}
+
+// ============================================================================
+// Sticker popup
+// ============================================================================
+
+const STICKER_CELL: f32 = 48.0;
+const STICKER_COLS: usize = 5;
+
+#[derive(Default, Clone, Copy, Debug)]
+pub(crate) struct StickerPopupOutcome {
+    /// Index into the stickers passed in.
+    pub pick: Option<usize>,
+    pub close: bool,
+}
+
+/// Grid of sticker thumbnails; a click picks one and closes the popup.
+pub(crate) fn draw_sticker_popup(
+    ctx: &egui::Context,
+    origin: Pos2,
+    armed: &mut bool,
+    stickers: &[ImagePixels],
+    chrome: &ChromeColors,
+) -> (StickerPopupOutcome, Rect) {
+    let mut out = StickerPopupOutcome::default();
+    let cols = stickers.len().clamp(1, STICKER_COLS);
+    let rows = stickers.len().div_ceil(STICKER_COLS).max(1);
+    let size = Vec2::new(
+        POPUP_PAD * 2.0 + cols as f32 * STICKER_CELL + (cols - 1) as f32 * RADIAL_GAP,
+        POPUP_PAD * 2.0 + rows as f32 * STICKER_CELL + (rows - 1) as f32 * RADIAL_GAP,
+    );
+    let popup_rect = Rect::from_min_size(Pos2::new(origin.x - size.x / 2.0, origin.y + 4.0), size);
+    let bg = Color32::from_rgb(
+        chrome.toolbar_bg.0[0],
+        chrome.toolbar_bg.0[1],
+        chrome.toolbar_bg.0[2],
+    );
+    let border = Color32::from_rgb(
+        chrome.toolbar_border.0[0],
+        chrome.toolbar_border.0[1],
+        chrome.toolbar_border.0[2],
+    );
+    let fg = Color32::from_rgb(
+        chrome.toolbar_fg.0[0],
+        chrome.toolbar_fg.0[1],
+        chrome.toolbar_fg.0[2],
+    );
+
+    egui::Area::new(egui::Id::new("sss::popup::sticker"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(popup_rect.min)
+        .show(ctx, |ui| {
+            let (rect, resp) = ui.allocate_exact_size(popup_rect.size(), Sense::click());
+            let painter = ui.painter_at(rect);
+            painter.rect_filled(rect, 6.0, bg);
+            painter.rect_stroke(
+                rect,
+                6.0,
+                Stroke::new(1.0, border),
+                egui::StrokeKind::Inside,
+            );
+            if stickers.is_empty() {
+                painter.text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    "No stickers",
+                    FontId::proportional(11.0),
+                    fg,
+                );
+            }
+            let hover_pos = ui.input(|i| i.pointer.hover_pos());
+            for (i, pixels) in stickers.iter().enumerate() {
+                let (row, col) = (i / STICKER_COLS, i % STICKER_COLS);
+                let cell = Rect::from_min_size(
+                    Pos2::new(
+                        rect.min.x + POPUP_PAD + col as f32 * (STICKER_CELL + RADIAL_GAP),
+                        rect.min.y + POPUP_PAD + row as f32 * (STICKER_CELL + RADIAL_GAP),
+                    ),
+                    Vec2::splat(STICKER_CELL),
+                );
+                let is_hovered = hover_pos.is_some_and(|p| cell.contains(p));
+                painter.rect_filled(cell, 4.0, chip_fill(chrome, false, is_hovered));
+                // Letterbox the thumbnail inside the cell.
+                let (w, h) = pixels.image().dimensions();
+                let fit = (STICKER_CELL - 6.0) / w.max(h).max(1) as f32;
+                let thumb = Rect::from_center_size(
+                    cell.center(),
+                    Vec2::new(w as f32 * fit, h as f32 * fit),
+                );
+                let tex = crate::render::overlay::image_texture(ctx, pixels);
+                painter.image(
+                    tex.id(),
+                    thumb,
+                    Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                    Color32::WHITE,
+                );
+                let clicked = ui
+                    .interact(
+                        cell,
+                        egui::Id::new(("sss::popup::sticker::cell", i)),
+                        Sense::click(),
+                    )
+                    .clicked();
+                if clicked {
+                    out.pick = Some(i);
+                    out.close = true;
+                }
+            }
+
+            // Click outside popup closes it (after the opening click).
+            if !*armed {
+                *armed = true;
+            } else if !resp.clicked() && ui.input(|i| i.pointer.any_click()) {
+                if let Some(p) = ui.input(|i| i.pointer.interact_pos()) {
+                    if !rect.contains(p) {
+                        out.close = true;
+                    }
+                }
+            }
+        });
+
+    (out, popup_rect)
+}
+
+// ============================================================================
+// Region entry popup
+// ============================================================================
+
+const ENTRY_W: f32 = 180.0;
+const ENTRY_H: f32 = 26.0;
+
+#[derive(Default, Clone, Copy, Debug)]
+pub(crate) struct RegionEntryOutcome {
+    /// Parsed region, set when Enter is pressed on valid input.
+    pub apply: Option<sss_capture::Rect>,
+    pub close: bool,
+}
+
+/// Text field taking the region as `x,y WxH`. Enter applies it, Escape or
+/// a click outside closes; unparseable input is drawn in red.
+pub(crate) fn draw_region_entry(
+    ctx: &egui::Context,
+    origin: Pos2,
+    armed: &mut bool,
+    text: &mut String,
+    chrome: &ChromeColors,
+) -> (RegionEntryOutcome, Rect) {
+    let mut out = RegionEntryOutcome::default();
+    let size = Vec2::new(ENTRY_W + POPUP_PAD * 2.0, ENTRY_H + POPUP_PAD * 2.0);
+    let popup_rect = Rect::from_center_size(origin, size);
+    let bg = Color32::from_rgb(
+        chrome.toolbar_bg.0[0],
+        chrome.toolbar_bg.0[1],
+        chrome.toolbar_bg.0[2],
+    );
+    let border = Color32::from_rgb(
+        chrome.toolbar_border.0[0],
+        chrome.toolbar_border.0[1],
+        chrome.toolbar_border.0[2],
+    );
+    let fg = Color32::from_rgb(
+        chrome.toolbar_fg.0[0],
+        chrome.toolbar_fg.0[1],
+        chrome.toolbar_fg.0[2],
+    );
+    let parsed = crate::geometry::parse_xywh(text);
+
+    egui::Area::new(egui::Id::new("sss::popup::region"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(popup_rect.min)
+        .show(ctx, |ui| {
+            let (rect, resp) = ui.allocate_exact_size(popup_rect.size(), Sense::click());
+            let painter = ui.painter_at(rect);
+            painter.rect_filled(rect, 6.0, bg);
+            painter.rect_stroke(
+                rect,
+                6.0,
+                Stroke::new(1.0, border),
+                egui::StrokeKind::Inside,
+            );
+            let field = rect.shrink(POPUP_PAD);
+            let mut child = ui.new_child(
+                egui::UiBuilder::new()
+                    .max_rect(field)
+                    .id_salt("sss::popup::region::field"),
+            );
+            let color = if parsed.is_some() || text.trim().is_empty() {
+                fg
+            } else {
+                Color32::from_rgb(230, 80, 80)
+            };
+            let edit_resp = child.add_sized(
+                field.size(),
+                egui::TextEdit::singleline(text)
+                    .font(FontId::monospace(13.0))
+                    .text_color(color)
+                    .hint_text("x,y WxH")
+                    .horizontal_align(egui::Align::Center),
+            );
+            if !*armed {
+                edit_resp.request_focus();
+            }
+            // A single-line TextEdit gives up focus on Enter.
+            if edit_resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                out.apply = parsed;
+                out.close = parsed.is_some();
+                if parsed.is_none() {
+                    edit_resp.request_focus();
+                }
+            }
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                out.close = true;
+            }
+
+            // Click outside closes (only after the opening frame).
+            if !*armed {
+                *armed = true;
+            } else if !resp.clicked() && ui.input(|i| i.pointer.any_click()) {
+                if let Some(p) = ui.input(|i| i.pointer.interact_pos()) {
+                    if !rect.contains(p) {
+                        out.close = true;
+                    }
+                }
+            }
+        });
+
+    (out, popup_rect)
+}
+
 // ============================================================================
 // HSV color picker popup
 // ============================================================================
@@ -1152,6 +1901,65 @@ fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
     (h, s, v)
 }
 
+// ============================================================================
+// Readout
+// ============================================================================
+
+/// Pointer coordinates and selection size in a small panel above and to
+/// the right of the pointer, flipped to stay on the monitor. `text` is
+/// drawn as is, one row per line.
+pub(crate) fn draw_readout(
+    ctx: &egui::Context,
+    pointer_local: Pos2,
+    monitor_size: Vec2,
+    text: String,
+    chrome: &ChromeColors,
+) {
+    const OFFSET: f32 = 16.0;
+    const PAD: Vec2 = Vec2::new(6.0, 3.0);
+    let fg = Color32::from_rgb(
+        chrome.toolbar_fg.0[0],
+        chrome.toolbar_fg.0[1],
+        chrome.toolbar_fg.0[2],
+    );
+    let bg = Color32::from_rgb(
+        chrome.toolbar_bg.0[0],
+        chrome.toolbar_bg.0[1],
+        chrome.toolbar_bg.0[2],
+    );
+    let border = Color32::from_rgb(
+        chrome.toolbar_border.0[0],
+        chrome.toolbar_border.0[1],
+        chrome.toolbar_border.0[2],
+    );
+    egui::Area::new(egui::Id::new("sss::readout"))
+        .order(egui::Order::Tooltip)
+        .fixed_pos(Pos2::ZERO)
+        .interactable(false)
+        .show(ctx, |ui| {
+            let painter = ui.painter();
+            let galley = painter.layout_no_wrap(text, FontId::monospace(11.0), fg);
+            let size = galley.size() + PAD * 2.0;
+            let mut x = pointer_local.x + OFFSET;
+            if x + size.x > monitor_size.x - 4.0 {
+                x = pointer_local.x - OFFSET - size.x;
+            }
+            let mut y = pointer_local.y - OFFSET - size.y;
+            if y < 4.0 {
+                y = pointer_local.y + OFFSET;
+            }
+            let rect = Rect::from_min_size(Pos2::new(x, y), size);
+            painter.rect_filled(rect, 3.0, bg.gamma_multiply(0.9));
+            painter.rect_stroke(
+                rect,
+                3.0,
+                Stroke::new(1.0, border),
+                egui::StrokeKind::Inside,
+            );
+            painter.galley(rect.min + PAD, galley, fg);
+        });
+}
+
 // ============================================================================
 // Magnifier
 // ============================================================================
@@ -1277,27 +2085,49 @@ pub(crate) fn draw_magnifier(
 }
 
 // ============================================================================
-// Selection toolbar (raise / lower / trash) attached to the selected shape
+// Selection toolbar (raise / lower / trash) attached to the selected shapes
 // ============================================================================
 
 const SEL_BTN: f32 = 24.0;
 const SEL_BTN_GAP: f32 = 4.0;
 const SEL_BTN_PAD: f32 = 6.0;
 const SEL_BAR_OFFSET: f32 = 10.0;
+const SEL_PRESET_W: f32 = 76.0;
+
+/// What the selection toolbar is attached to.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum SelectionTarget {
+    /// Selected shapes: raise / lower / trash, plus group / ungroup and
+    /// alignment once more than one part is selected. A group counts as
+    /// one part.
+    Shape { parts: usize, grouped: bool },
+    /// The region: a chip naming the active preset (`Free`, `16:9`, …);
+    /// a click moves to the next one.
+    Region {
+        preset: Option<crate::geometry::RegionPreset>,
+    },
+}
 
 #[derive(Default, Clone, Copy, Debug)]
 pub(crate) struct SelectionDecorOutcome {
     pub raise: bool,
     pub lower: bool,
     pub delete: bool,
+    /// Group the selection, or ungroup a selected group.
+    pub group: bool,
+    pub align: Option<crate::geometry::Align>,
+    pub distribute: Option<crate::geometry::Distribute>,
+    pub next_preset: bool,
 }
 
-/// Render the selection toolbar (raise / lower / trash) anchored near the
-/// shape's bounds. Returns the action flags + the rect occupied by the
-/// bar so the driver can hit-test events against it.
+/// Render the selection toolbar anchored near `bounds`: above a shape,
+/// below the region (the main toolbar sits above it). Returns the action
+/// flags + the rect occupied by the bar so the driver can hit-test events
+/// against it.
 pub(crate) fn draw_selection_toolbar(
     ctx: &egui::Context,
     bounds: sss_capture::Rect,
+    target: SelectionTarget,
     monitor_origin: Pos2,
     monitor_size: Vec2,
     chrome: &ChromeColors,
@@ -1310,26 +2140,56 @@ pub(crate) fn draw_selection_toolbar(
     let lx = bounds.x() as f32 - monitor_origin.x;
     let ly = bounds.y() as f32 - monitor_origin.y;
 
-    let entries: [(crate::icons::ToolbarIcon, &str); 3] = [
-        (crate::icons::ToolbarIcon::Raise, "raise"),
-        (crate::icons::ToolbarIcon::Lower, "lower"),
-        (crate::icons::ToolbarIcon::Trash, "trash"),
-    ];
-    let n = entries.len() as f32;
-    let total_w = SEL_BTN_PAD * 2.0 + n * SEL_BTN + (n - 1.0) * SEL_BTN_GAP;
+    use crate::icons::ToolbarIcon as I;
+    let mut entries: Vec<(I, &str)> = Vec::new();
+    if let SelectionTarget::Shape { parts, grouped } = target {
+        entries.extend([(I::Raise, "raise"), (I::Lower, "lower")]);
+        if grouped {
+            entries.push((I::Ungroup, "ungroup"));
+        } else if parts > 1 {
+            entries.push((I::Group, "group"));
+        }
+        if parts > 1 {
+            entries.extend([
+                (I::AlignLeft, "align_left"),
+                (I::AlignCenter, "align_center"),
+                (I::AlignRight, "align_right"),
+                (I::AlignTop, "align_top"),
+                (I::AlignMiddle, "align_middle"),
+                (I::AlignBottom, "align_bottom"),
+            ]);
+        }
+        if parts > 2 {
+            entries.extend([
+                (I::DistributeHorizontal, "distribute_h"),
+                (I::DistributeVertical, "distribute_v"),
+            ]);
+        }
+        entries.push((I::Trash, "trash"));
+    }
+    let total_w = match target {
+        SelectionTarget::Shape { .. } => {
+            let n = entries.len() as f32;
+            SEL_BTN_PAD * 2.0 + n * SEL_BTN + (n - 1.0) * SEL_BTN_GAP
+        }
+        SelectionTarget::Region { .. } => SEL_BTN_PAD * 2.0 + SEL_PRESET_W,
+    };
     let total_h = SEL_BTN_PAD * 2.0 + SEL_BTN;
 
-    // Prefer above; fall back to below; clamp inside monitor.
+    // Prefer above (below for the region); fall back to the other side;
+    // clamp inside monitor.
     let mut bar_x = lx + bw - total_w + 6.0;
     bar_x = bar_x.clamp(8.0, (monitor_size.x - total_w - 8.0).max(8.0));
     let above = ly - total_h - SEL_BAR_OFFSET;
     let below = ly + bh + SEL_BAR_OFFSET;
-    let bar_y = if above >= 8.0 {
-        above
-    } else if below + total_h <= monitor_size.y - 8.0 {
-        below
-    } else {
-        8.0
+    let fits_above = above >= 8.0;
+    let fits_below = below + total_h <= monitor_size.y - 8.0;
+    let bar_y = match target {
+        SelectionTarget::Shape { .. } if fits_above => above,
+        SelectionTarget::Region { .. } if fits_below => below,
+        _ if fits_above => above,
+        _ if fits_below => below,
+        _ => 8.0,
     }
     .clamp(8.0, (monitor_size.y - total_h - 8.0).max(8.0));
 
@@ -1387,7 +2247,7 @@ pub(crate) fn draw_selection_toolbar(
                     )
                 };
                 painter.rect_filled(btn_rect, 4.0, bgc);
-                let tint = if matches!(icon, crate::icons::ToolbarIcon::Trash) {
+                let tint = if matches!(icon, I::Trash) {
                     [220, 70, 70]
                 } else {
                     [fg.r(), fg.g(), fg.b()]
@@ -1402,14 +2262,44 @@ pub(crate) fn draw_selection_toolbar(
                     );
                 }
                 if resp.clicked() {
+                    use crate::geometry::{Align, Distribute};
                     match icon {
-                        crate::icons::ToolbarIcon::Raise => out.raise = true,
-                        crate::icons::ToolbarIcon::Lower => out.lower = true,
-                        crate::icons::ToolbarIcon::Trash => out.delete = true,
+                        I::Raise => out.raise = true,
+                        I::Lower => out.lower = true,
+                        I::Trash => out.delete = true,
+                        I::Group | I::Ungroup => out.group = true,
+                        I::AlignLeft => out.align = Some(Align::Left),
+                        I::AlignCenter => out.align = Some(Align::Center),
+                        I::AlignRight => out.align = Some(Align::Right),
+                        I::AlignTop => out.align = Some(Align::Top),
+                        I::AlignMiddle => out.align = Some(Align::Middle),
+                        I::AlignBottom => out.align = Some(Align::Bottom),
+                        I::DistributeHorizontal => out.distribute = Some(Distribute::Horizontal),
+                        I::DistributeVertical => out.distribute = Some(Distribute::Vertical),
                         _ => {}
                     }
                 }
             }
+            if let SelectionTarget::Region { preset } = target {
+                let chip = Rect::from_min_size(
+                    bar_rect.min + Vec2::splat(SEL_BTN_PAD),
+                    Vec2::new(SEL_PRESET_W, SEL_BTN),
+                );
+                let resp = ui.interact(chip, egui::Id::new("sss::sel_preset"), Sense::click());
+                let fill = chip_fill(chrome, preset.is_some(), resp.hovered());
+                painter.rect_filled(chip, 4.0, fill);
+                let label = preset.map_or_else(|| "Free".to_owned(), |p| p.to_string());
+                painter.text(
+                    chip.center(),
+                    egui::Align2::CENTER_CENTER,
+                    label,
+                    FontId::proportional(12.0),
+                    fg,
+                );
+                if resp.clicked() {
+                    out.next_preset = true;
+                }
+            }
             ui.allocate_rect(bar_rect, Sense::hover());
         });
 
@@ -1675,6 +2565,30 @@ pub(crate) fn draw_action_toolbar(
     (out, bar_rect)
 }
 
+/// Full-length guide lines through the region edges that snapped onto a
+/// detected UI edge (`xs` vertical, `ys` horizontal, desktop px).
+pub(crate) fn draw_edge_guides(
+    painter: &egui::Painter,
+    xs: &[i32],
+    ys: &[i32],
+    screen_offset: Pos2,
+    monitor_size: Vec2,
+    chrome: &ChromeColors,
+) {
+    let stroke = Stroke::new(
+        1.0,
+        Color32::from_rgb(chrome.accent.0[0], chrome.accent.0[1], chrome.accent.0[2]),
+    );
+    for &x in xs {
+        let lx = x as f32 - screen_offset.x;
+        painter.line_segment([Pos2::new(lx, 0.0), Pos2::new(lx, monitor_size.y)], stroke);
+    }
+    for &y in ys {
+        let ly = y as f32 - screen_offset.y;
+        painter.line_segment([Pos2::new(0.0, ly), Pos2::new(monitor_size.x, ly)], stroke);
+    }
+}
+
 pub(crate) fn draw_gizmos(
     painter: &egui::Painter,
     bounds: sss_capture::Rect,
@@ -1739,6 +2653,94 @@ pub(crate) fn draw_gizmos(
     painter.circle_stroke(rot, GIZMO_HANDLE_R, Stroke::new(1.5, accent));
 }
 
+/// Thin outline around each member of a multi-shape selection, inside
+/// the dashed box [`draw_gizmos`] draws around all of them.
+pub(crate) fn draw_selection_members(
+    painter: &egui::Painter,
+    bounds: &[sss_capture::Rect],
+    screen_offset: Pos2,
+    chrome: &ChromeColors,
+) {
+    let accent = Color32::from_rgb(
+        chrome.button_active_border.0[0],
+        chrome.button_active_border.0[1],
+        chrome.button_active_border.0[2],
+    );
+    for b in bounds {
+        let rect = Rect::from_min_size(
+            Pos2::new(
+                b.x() as f32 - screen_offset.x,
+                b.y() as f32 - screen_offset.y,
+            ),
+            Vec2::new(b.width() as f32, b.height() as f32),
+        );
+        painter.rect_stroke(
+            rect,
+            0.0,
+            Stroke::new(1.0, accent.gamma_multiply(0.6)),
+            egui::StrokeKind::Outside,
+        );
+    }
+}
+
+/// Rubber band of an Alt+drag selection. `band` is in global px.
+pub(crate) fn draw_selection_band(
+    painter: &egui::Painter,
+    band: crate::geometry::FRect,
+    screen_offset: Pos2,
+    chrome: &ChromeColors,
+) {
+    let accent = Color32::from_rgb(
+        chrome.button_active_border.0[0],
+        chrome.button_active_border.0[1],
+        chrome.button_active_border.0[2],
+    );
+    let rect = Rect::from_min_size(
+        Pos2::new(band.x - screen_offset.x, band.y - screen_offset.y),
+        Vec2::new(band.w, band.h),
+    );
+    painter.rect_filled(rect, 0.0, accent.gamma_multiply(0.15));
+    painter.rect_stroke(
+        rect,
+        0.0,
+        Stroke::new(1.0, accent),
+        egui::StrokeKind::Inside,
+    );
+}
+
+/// Draw the mid-point handle of a selected arrow; dragging it bends the
+/// arrow through the pointer. `mid` is in global px.
+pub(crate) fn draw_bend_handle(
+    painter: &egui::Painter,
+    mid: crate::geometry::FPoint,
+    screen_offset: Pos2,
+    chrome: &ChromeColors,
+) {
+    let accent = Color32::from_rgb(
+        chrome.button_active_border.0[0],
+        chrome.button_active_border.0[1],
+        chrome.button_active_border.0[2],
+    );
+    let fill = Color32::from_rgb(
+        chrome.button_active_bg.0[0],
+        chrome.button_active_bg.0[1],
+        chrome.button_active_bg.0[2],
+    );
+    let c = Pos2::new(mid.x - screen_offset.x, mid.y - screen_offset.y);
+    let r = crate::canvas::BEND_GRAB * 0.7;
+    let diamond = vec![
+        Pos2::new(c.x, c.y - r),
+        Pos2::new(c.x + r, c.y),
+        Pos2::new(c.x, c.y + r),
+        Pos2::new(c.x - r, c.y),
+    ];
+    painter.add(egui::Shape::convex_polygon(
+        diamond,
+        fill,
+        Stroke::new(1.5, accent),
+    ));
+}
+
 // ============================================================================
 // Snap helpers
 // ============================================================================
@@ -1768,11 +2770,17 @@ fn shape_vertices(shape: &crate::shape::Shape) -> Vec<crate::geometry::FPoint> {
     use crate::geometry::FPoint;
     use crate::shape::ShapeKind;
     match &shape.kind {
-        ShapeKind::FreehandStroke { points } | ShapeKind::Polygon { points, .. } => points.clone(),
-        ShapeKind::Line { from, to } | ShapeKind::Arrow { from, to } => vec![*from, *to],
+        ShapeKind::FreehandStroke { points, .. }
+        | ShapeKind::Highlight { points }
+        | ShapeKind::Polygon { points, .. } => points.clone(),
+        ShapeKind::Line { from, to }
+        | ShapeKind::Arrow { from, to, .. }
+        | ShapeKind::Measure { from, to, .. } => vec![*from, *to],
         ShapeKind::Rectangle { rect }
         | ShapeKind::Ellipse { rect }
-        | ShapeKind::BlurRect { rect, .. } => {
+        | ShapeKind::BlurRect { rect, .. }
+        | ShapeKind::Pixelate { rect, .. }
+        | ShapeKind::Spotlight { rect, .. } => {
             let x0 = rect.x() as f32;
             let y0 = rect.y() as f32;
             let x1 = x0 + rect.width() as f32;
@@ -1784,8 +2792,37 @@ fn shape_vertices(shape: &crate::shape::Shape) -> Vec<crate::geometry::FPoint> {
                 FPoint::new(x1, y1),
             ]
         }
+        ShapeKind::Image { rect, .. } => {
+            crate::shape::image_corners(*rect, shape.rotation).to_vec()
+        }
+        ShapeKind::Magnify { source, target, .. } => {
+            let x0 = source.x() as f32;
+            let y0 = source.y() as f32;
+            let x1 = x0 + source.width() as f32;
+            let y1 = y0 + source.height() as f32;
+            vec![
+                FPoint::new(x0, y0),
+                FPoint::new(x1, y0),
+                FPoint::new(x0, y1),
+                FPoint::new(x1, y1),
+                *target,
+            ]
+        }
         ShapeKind::Step { center, .. } => vec![*center],
         ShapeKind::Text { origin, .. } => vec![*origin],
+        ShapeKind::Callout { body, anchor, .. } => {
+            let x0 = body.x() as f32;
+            let y0 = body.y() as f32;
+            let x1 = x0 + body.width() as f32;
+            let y1 = y0 + body.height() as f32;
+            vec![
+                FPoint::new(x0, y0),
+                FPoint::new(x1, y0),
+                FPoint::new(x0, y1),
+                FPoint::new(x1, y1),
+                *anchor,
+            ]
+        }
     }
 }
 
@@ -1810,13 +2847,24 @@ pub(crate) fn sample_capture(
 fn apply_tool_color(t: &mut Tool, c: SssColor) {
     match t {
         Tool::Brush(b)
+        | Tool::Highlighter(b)
         | Tool::Line(b)
         | Tool::Arrow(b)
         | Tool::Rectangle(b)
         | Tool::Ellipse(b)
-        | Tool::Polygon(b) => b.color = c,
+        | Tool::Polygon(b)
+        | Tool::Magnify { border: b, .. } => b.color = c,
         Tool::Step(s) => s.fill = c,
-        Tool::Text(t) => t.color = c,
-        Tool::Pointer | Tool::Eraser { .. } | Tool::BlurRect { .. } => {}
+        Tool::Measure { color, .. } => *color = c,
+        Tool::Text(t) => t.set_color(c),
+        Tool::Callout { brush, text } => {
+            brush.color = c;
+            text.set_color(c);
+        }
+        Tool::Pointer
+        | Tool::Eraser { .. }
+        | Tool::BlurRect { .. }
+        | Tool::Pixelate { .. }
+        | Tool::Spotlight { .. } => {}
     }
 }
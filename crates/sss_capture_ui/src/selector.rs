@@ -39,6 +39,11 @@ pub type OcrPipeline =
 pub type TextClipboard =
     Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
 
+/// Closure that reads an image from the system clipboard, for pasting it
+/// onto the canvas with Ctrl+V. `None` when the clipboard holds no image.
+/// Injected for the same reason as [`TextClipboard`].
+pub type ImageClipboard = Arc<dyn Fn() -> Option<RgbaImage> + Send + Sync>;
+
 /// What the overlay produced.
 #[derive(Clone, Debug)]
 pub enum Outcome {
@@ -56,16 +61,23 @@ pub enum Outcome {
         rect: Rect,
         image: Option<Image>,
     },
+    /// Several areas confirmed together (Shift+drag adds one), in the
+    /// order they were drawn, each cropped with its annotations.
+    Regions {
+        regions: Vec<(Rect, Option<Image>)>,
+    },
     Cancelled,
 }
 
 impl Outcome {
+    /// The single captured image; `None` for [`Outcome::Regions`], whose
+    /// crops come one per region.
     pub fn image(&self) -> Option<&Image> {
         match self {
             Outcome::Region { image, .. }
             | Outcome::Monitor { image, .. }
             | Outcome::Window { image, .. } => image.as_ref(),
-            Outcome::Cancelled => None,
+            Outcome::Regions { .. } | Outcome::Cancelled => None,
         }
     }
 
@@ -74,15 +86,20 @@ impl Outcome {
             Outcome::Region { image, .. }
             | Outcome::Monitor { image, .. }
             | Outcome::Window { image, .. } => image,
-            Outcome::Cancelled => None,
+            Outcome::Regions { .. } | Outcome::Cancelled => None,
         }
     }
 
+    /// The captured rectangle; the bounding box of all of them for
+    /// [`Outcome::Regions`].
     pub fn rect(&self) -> Option<Rect> {
         match self {
             Outcome::Region { rect, .. }
             | Outcome::Monitor { rect, .. }
             | Outcome::Window { rect, .. } => Some(*rect),
+            Outcome::Regions { regions } => {
+                Rect::bounding(&regions.iter().map(|(r, _)| *r).collect::<Vec<_>>())
+            }
             Outcome::Cancelled => None,
         }
     }
@@ -117,6 +134,10 @@ pub struct Selection {
     pub outcome: Outcome,
     pub canvas: Canvas,
     pub action: PostAction,
+    /// The outcome's pixels before `canvas` was flattened into them, for
+    /// callers that keep the annotations editable (see `Project`). `None`
+    /// when the overlay was cancelled.
+    pub background: Option<Image>,
 }
 
 /// Builder for [`Selector`].
@@ -127,14 +148,17 @@ pub struct SelectorBuilder {
     palette_override: Option<ToolPalette>,
     trigger: CaptureTrigger,
     capturer: Option<Arc<Capturer>>,
+    image: Option<RgbaImage>,
     capture_opts: CaptureOptions,
     confirm_with_enter: bool,
     show_copy: bool,
     show_save: bool,
     save_path_hint: Option<PathBuf>,
     initial_area: Option<Rect>,
+    canvas: Option<Canvas>,
     ocr_pipeline: Option<OcrPipeline>,
     text_clipboard: Option<TextClipboard>,
+    image_clipboard: Option<ImageClipboard>,
 }
 
 impl std::fmt::Debug for SelectorBuilder {
@@ -146,14 +170,17 @@ impl std::fmt::Debug for SelectorBuilder {
             .field("palette_override", &self.palette_override)
             .field("trigger", &self.trigger)
             .field("capturer", &self.capturer)
+            .field("image", &self.image.as_ref().map(|i| (i.width(), i.height())))
             .field("capture_opts", &self.capture_opts)
             .field("confirm_with_enter", &self.confirm_with_enter)
             .field("show_copy", &self.show_copy)
             .field("show_save", &self.show_save)
             .field("save_path_hint", &self.save_path_hint)
             .field("initial_area", &self.initial_area)
+            .field("canvas", &self.canvas)
             .field("ocr_pipeline", &self.ocr_pipeline.as_ref().map(|_| "<fn>"))
             .field("text_clipboard", &self.text_clipboard.as_ref().map(|_| "<fn>"))
+            .field("image_clipboard", &self.image_clipboard.as_ref().map(|_| "<fn>"))
             .finish()
     }
 }
@@ -167,14 +194,17 @@ impl Default for SelectorBuilder {
             palette_override: None,
             trigger: CaptureTrigger::default(),
             capturer: None,
+            image: None,
             capture_opts: CaptureOptions::default(),
             confirm_with_enter: true,
             show_copy: true,
             show_save: true,
             save_path_hint: None,
             initial_area: None,
+            canvas: None,
             ocr_pipeline: None,
             text_clipboard: None,
+            image_clipboard: None,
         }
     }
 }
@@ -217,6 +247,15 @@ impl SelectorBuilder {
         self
     }
 
+    /// Annotate `image` instead of capturing the desktop. The overlay opens
+    /// as a normal window the size of the image, in `Area` mode with the
+    /// whole image selected; the mode, trigger and capturer settings are
+    /// ignored. Confirming yields an [`Outcome::Region`] in image pixels.
+    pub fn image(mut self, image: RgbaImage) -> Self {
+        self.image = Some(image);
+        self
+    }
+
     pub fn capture_options(mut self, opts: CaptureOptions) -> Self {
         self.capture_opts = opts;
         self
@@ -250,6 +289,15 @@ impl SelectorBuilder {
         self
     }
 
+    /// Open with the shapes of an earlier session, e.g. a reopened
+    /// `Project`. Their coordinates must match the overlay's: image pixels
+    /// together with [`Self::image`], desktop pixels otherwise. A region
+    /// stored in the canvas stands in for [`Self::initial_area`].
+    pub fn canvas(mut self, canvas: Canvas) -> Self {
+        self.canvas = Some(canvas);
+        self
+    }
+
     /// Plug an OCR pipeline in. When set, the eager-captured frame is
     /// pushed into the closure as soon as the overlay opens; results
     /// flow back through the returned `Receiver` and end up in the
@@ -268,14 +316,22 @@ impl SelectorBuilder {
         self
     }
 
+    /// Provide the closure Ctrl+V reads a pasted image from. Without it,
+    /// images can still be dropped onto the overlay or picked as stickers.
+    pub fn image_clipboard(mut self, clip: ImageClipboard) -> Self {
+        self.image_clipboard = Some(clip);
+        self
+    }
+
     pub fn build(self) -> Result<Selector, SelectorError> {
-        let capturer = match self.capturer {
-            Some(c) => c,
-            None => Arc::new(
+        let source = match (self.image, self.capturer) {
+            (Some(image), _) => Source::Image(image),
+            (None, Some(c)) => Source::Capture(c),
+            (None, None) => Source::Capture(Arc::new(
                 Capturer::builder()
                     .build()
                     .map_err(SelectorError::Capture)?,
-            ),
+            )),
         };
         let palette = self
             .palette_override
@@ -294,10 +350,12 @@ impl SelectorBuilder {
                 show_save: self.show_save,
                 save_path_hint: self.save_path_hint,
                 initial_area: self.initial_area,
+                canvas: self.canvas,
                 ocr_pipeline: self.ocr_pipeline,
                 text_clipboard: self.text_clipboard,
+                image_clipboard: self.image_clipboard,
             },
-            capturer,
+            source,
         })
     }
 }
@@ -306,7 +364,25 @@ impl SelectorBuilder {
 #[derive(Debug)]
 pub struct Selector {
     pub(crate) config: Config,
-    pub(crate) capturer: Arc<Capturer>,
+    pub(crate) source: Source,
+}
+
+/// Where the overlay's pixels come from.
+pub(crate) enum Source {
+    Capture(Arc<Capturer>),
+    Image(RgbaImage),
+}
+
+impl std::fmt::Debug for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Capture(c) => f.debug_tuple("Capture").field(c).finish(),
+            Source::Image(i) => f
+                .debug_tuple("Image")
+                .field(&(i.width(), i.height()))
+                .finish(),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -322,8 +398,10 @@ pub(crate) struct Config {
     pub show_save: bool,
     pub save_path_hint: Option<PathBuf>,
     pub initial_area: Option<Rect>,
+    pub canvas: Option<Canvas>,
     pub ocr_pipeline: Option<OcrPipeline>,
     pub text_clipboard: Option<TextClipboard>,
+    pub image_clipboard: Option<ImageClipboard>,
 }
 
 impl std::fmt::Debug for Config {
@@ -340,8 +418,10 @@ impl std::fmt::Debug for Config {
             .field("show_save", &self.show_save)
             .field("save_path_hint", &self.save_path_hint)
             .field("initial_area", &self.initial_area)
+            .field("canvas", &self.canvas)
             .field("ocr_pipeline", &self.ocr_pipeline.as_ref().map(|_| "<fn>"))
             .field("text_clipboard", &self.text_clipboard.as_ref().map(|_| "<fn>"))
+            .field("image_clipboard", &self.image_clipboard.as_ref().map(|_| "<fn>"))
             .finish()
     }
 }
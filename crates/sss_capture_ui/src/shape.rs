@@ -1,12 +1,17 @@
 //! Persistent shape model for the annotation canvas.
 
+use std::sync::Arc;
+
 use crate::color::Color;
 use crate::geometry::FPoint;
 use crate::tool::{BrushSettings, StepSettings};
+use image::RgbaImage;
 use sss_capture::Rect;
 
 /// Strongly typed shape identifier; monotonic per canvas and never reused.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ShapeId(pub(crate) u64);
 
 impl ShapeId {
@@ -17,18 +22,29 @@ impl ShapeId {
 
 /// A single editable artefact on the canvas.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shape {
     pub id: ShapeId,
     pub kind: ShapeKind,
     pub style: Style,
     /// Rotation in radians, around the bounding-box center.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub rotation: f32,
+    /// Group the shape belongs to, named after one of its members when
+    /// it was formed. Grouped shapes are selected and moved together.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub group: Option<ShapeId>,
 }
 
 impl Shape {
     /// Axis-aligned bounding rectangle in canvas pixels.
     pub fn bounds(&self) -> Rect {
-        self.kind.bounds()
+        match &self.kind {
+            ShapeKind::Image { rect, .. } if self.rotation != 0.0 => {
+                bounding_of_points(&image_corners(*rect, self.rotation))
+            }
+            kind => kind.bounds(),
+        }
     }
 
     pub fn contains(&self, p: FPoint) -> bool {
@@ -37,17 +53,31 @@ impl Shape {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "kebab-case"))]
 pub enum ShapeKind {
     FreehandStroke {
         points: Vec<FPoint>,
+        /// Per-point fraction of the stroke width, recorded from touch
+        /// pressure; empty for a uniform (mouse) stroke.
+        #[cfg_attr(feature = "serde", serde(default))]
+        widths: Vec<f32>,
+    },
+    /// Wide marker stroke, multiply-blended so whatever it covers stays
+    /// readable.
+    Highlight {
+        points: Vec<FPoint>,
     },
     Line {
         from: FPoint,
         to: FPoint,
     },
+    /// Straight arrow, or a quadratic Bézier when `bend` holds the
+    /// control point; see [`arrow_path`].
     Arrow {
         from: FPoint,
         to: FPoint,
+        bend: Option<FPoint>,
     },
     Rectangle {
         rect: Rect,
@@ -60,6 +90,49 @@ pub enum ShapeKind {
         rect: Rect,
         radius: f32,
     },
+    /// Rectangle redacted during composition: a mosaic of `block_size`
+    /// pixel cells, or a solid box of `Style::fill` when one is set.
+    Pixelate {
+        rect: Rect,
+        block_size: u32,
+    },
+    /// Area left untouched while everything outside the union of all
+    /// spotlights is darkened by `amount` (0..=255), or desaturated and
+    /// then darkened. The topmost spotlight's settings win.
+    Spotlight {
+        rect: Rect,
+        shape: SpotlightShape,
+        amount: u8,
+        desaturate: bool,
+    },
+    /// Enlarged, bordered copy of `source`, centred on `target` and tied
+    /// to the source by connector lines; see [`magnified_rect`].
+    Magnify {
+        source: Rect,
+        target: FPoint,
+        zoom: f32,
+    },
+    /// Dimension line from `from` to `to` labelled with its length, or
+    /// with `boxed` the box they span labelled W × H; see
+    /// [`measure_label`]. Lengths are divided by `scale`, the monitor's
+    /// scale factor when measuring logical pixels. Unless `baked`, it
+    /// shows in the overlay only and is left out of the output.
+    Measure {
+        from: FPoint,
+        to: FPoint,
+        boxed: bool,
+        scale: f32,
+        baked: bool,
+    },
+    /// Speech bubble: a rounded `body` holding `text` wrapped to its
+    /// width, with a tail pointing at `anchor`. `Style` strokes and fills
+    /// the bubble.
+    Callout {
+        body: Rect,
+        anchor: FPoint,
+        text: String,
+        text_style: TextStyle,
+    },
     Step {
         center: FPoint,
         number: u32,
@@ -75,16 +148,40 @@ pub enum ShapeKind {
         points: Vec<FPoint>,
         closed: bool,
     },
+    /// Pasted, dropped or sticker image stretched over `rect` and turned
+    /// by the shape's `rotation` about the rect's centre.
+    Image {
+        rect: Rect,
+        pixels: ImagePixels,
+    },
 }
 
 impl ShapeKind {
     pub fn bounds(&self) -> Rect {
         use ShapeKind::*;
         match self {
-            FreehandStroke { points } => bounding_of_points(points),
+            FreehandStroke { points, .. } | Highlight { points } => bounding_of_points(points),
             Polygon { points, .. } => bounding_of_points(points),
-            Line { from, to } | Arrow { from, to } => bounding_of_points(&[*from, *to]),
-            Rectangle { rect } | Ellipse { rect } | BlurRect { rect, .. } => *rect,
+            Line { from, to } | Measure { from, to, .. } => bounding_of_points(&[*from, *to]),
+            Arrow { from, to, bend } => bounding_of_points(&arrow_path(*from, *to, *bend)),
+            Rectangle { rect }
+            | Ellipse { rect }
+            | BlurRect { rect, .. }
+            | Pixelate { rect, .. }
+            | Spotlight { rect, .. }
+            | Image { rect, .. } => *rect,
+            Magnify {
+                source,
+                target,
+                zoom,
+            } => {
+                let copy = magnified_rect(*source, *target, *zoom);
+                Rect::bounding(&[*source, copy]).unwrap_or(copy)
+            }
+            Callout { body, anchor, .. } => {
+                let tip = Rect::from_xywh(anchor.x.floor() as i32, anchor.y.floor() as i32, 1, 1);
+                Rect::bounding(&[*body, tip]).unwrap_or(*body)
+            }
             Step { center, radius, .. } => {
                 let r = *radius;
                 Rect::from_xywh(
@@ -99,12 +196,436 @@ impl ShapeKind {
                 content,
                 style,
             } => {
-                let w = (content.chars().count() as f32 * style.size * 0.55).ceil() as u32;
-                let h = style.size.ceil() as u32;
-                Rect::from_xywh(origin.x as i32, origin.y as i32, w.max(1), h.max(1))
+                let (w, h) = crate::font::text_size(content, style);
+                let m = style.margin();
+                Rect::from_xywh(
+                    (origin.x - m).floor() as i32,
+                    (origin.y - m).floor() as i32,
+                    (w + m * 2.0).ceil().max(1.0) as u32,
+                    (h + m * 2.0).ceil().max(1.0) as u32,
+                )
+            }
+        }
+    }
+}
+
+/// Pixels of a [`ShapeKind::Image`]. Shared, so undo snapshots don't
+/// copy them; serializes as a base64 PNG.
+#[derive(Clone, Debug)]
+pub struct ImagePixels(Arc<RgbaImage>);
+
+impl ImagePixels {
+    pub fn new(image: RgbaImage) -> Self {
+        Self(Arc::new(image))
+    }
+
+    pub fn image(&self) -> &RgbaImage {
+        &self.0
+    }
+
+    /// Identity of the shared buffer, for caches of derived data.
+    pub(crate) fn key(&self) -> usize {
+        Arc::as_ptr(&self.0) as usize
+    }
+}
+
+impl From<RgbaImage> for ImagePixels {
+    fn from(image: RgbaImage) -> Self {
+        Self::new(image)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ImagePixels {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use base64::prelude::{Engine as _, BASE64_STANDARD};
+        let mut png = std::io::Cursor::new(Vec::new());
+        self.0
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&BASE64_STANDARD.encode(png.get_ref()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ImagePixels {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use base64::prelude::{Engine as _, BASE64_STANDARD};
+        use serde::de::Error;
+        let text = String::deserialize(deserializer)?;
+        let png = BASE64_STANDARD.decode(&text).map_err(D::Error::custom)?;
+        let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+            .map_err(D::Error::custom)?;
+        Ok(Self::new(image.to_rgba8()))
+    }
+}
+
+/// Corners of `rect` turned by `rotation` radians about its centre,
+/// clockwise from the top-left.
+pub fn image_corners(rect: Rect, rotation: f32) -> [FPoint; 4] {
+    let (cx, cy) = (
+        rect.x() as f32 + rect.width() as f32 / 2.0,
+        rect.y() as f32 + rect.height() as f32 / 2.0,
+    );
+    let (hw, hh) = (rect.width() as f32 / 2.0, rect.height() as f32 / 2.0);
+    let (sn, cs) = rotation.sin_cos();
+    [(-hw, -hh), (hw, -hh), (hw, hh), (-hw, hh)]
+        .map(|(dx, dy)| FPoint::new(cx + dx * cs - dy * sn, cy + dx * sn + dy * cs))
+}
+
+/// Where a [`ShapeKind::Magnify`] draws its copy: `source` scaled by
+/// `zoom` and centred on `target`.
+pub fn magnified_rect(source: Rect, target: FPoint, zoom: f32) -> Rect {
+    let w = (source.width() as f32 * zoom).round().max(1.0);
+    let h = (source.height() as f32 * zoom).round().max(1.0);
+    Rect::from_xywh(
+        (target.x - w / 2.0).round() as i32,
+        (target.y - h / 2.0).round() as i32,
+        w as u32,
+        h as u32,
+    )
+}
+
+/// Half-length of the ticks across the ends of a measurement line.
+const MEASURE_TICK: f32 = 6.0;
+
+/// Segments of a [`ShapeKind::Measure`]: the four box edges, or the
+/// dimension line with a tick across each end.
+pub fn measure_segments(from: FPoint, to: FPoint, boxed: bool) -> Vec<(FPoint, FPoint)> {
+    if boxed {
+        let (tr, bl) = (FPoint::new(to.x, from.y), FPoint::new(from.x, to.y));
+        return vec![(from, tr), (tr, to), (to, bl), (bl, from)];
+    }
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let len = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+    let (nx, ny) = (-dy / len * MEASURE_TICK, dx / len * MEASURE_TICK);
+    let tick = |p: FPoint| {
+        (
+            FPoint::new(p.x - nx, p.y - ny),
+            FPoint::new(p.x + nx, p.y + ny),
+        )
+    };
+    vec![(from, to), tick(from), tick(to)]
+}
+
+/// Label of a [`ShapeKind::Measure`]: `W × H` for a box, the length for
+/// a line, plus its horizontal and vertical spans when it runs diagonally.
+/// Physical pixels (`scale` 1) read `px`, logical ones `pt`.
+pub fn measure_label(from: FPoint, to: FPoint, boxed: bool, scale: f32) -> String {
+    let scale = if scale > 0.0 { scale } else { 1.0 };
+    let (unit, step) = if scale == 1.0 {
+        ("px", 1.0)
+    } else {
+        ("pt", 10.0)
+    };
+    let num = |v: f32| {
+        let v = (v * step).round() / step;
+        if v.fract() == 0.0 {
+            format!("{v:.0}")
+        } else {
+            format!("{v:.1}")
+        }
+    };
+    let (w, h) = ((to.x - from.x).abs() / scale, (to.y - from.y).abs() / scale);
+    if boxed {
+        return format!("{} × {} {unit}", num(w), num(h));
+    }
+    let len = num((w * w + h * h).sqrt());
+    if w.min(h) < 0.5 {
+        format!("{len} {unit}")
+    } else {
+        format!("{len} {unit} ({} × {})", num(w), num(h))
+    }
+}
+
+/// Text style of a measurement label: small type on a pill of `color`.
+pub fn measure_text_style(color: Color) -> TextStyle {
+    TextStyle {
+        color: color.contrasting(),
+        size: 12.0,
+        background: Some(TextBackground {
+            color,
+            padding: 3.0,
+            pill: true,
+        }),
+        ..TextStyle::default()
+    }
+}
+
+/// Top-left of a measurement label's text block, centring it between
+/// `from` and `to`.
+pub fn measure_label_origin(from: FPoint, to: FPoint, label: &str, style: &TextStyle) -> FPoint {
+    let (w, h) = crate::font::text_size(label, style);
+    FPoint::new((from.x + to.x - w) / 2.0, (from.y + to.y - h) / 2.0)
+}
+
+/// Connector lines of a magnifier callout: the lines between matching
+/// corners of `source` and `target` that leave both rectangles on one
+/// side, i.e. the outline of the "cone". Empty when the two overlap.
+pub fn magnify_connectors(source: Rect, target: Rect) -> Vec<(FPoint, FPoint)> {
+    if source.intersection(&target).is_some() {
+        return Vec::new();
+    }
+    let corners = |r: Rect| {
+        let (x0, y0) = (r.x() as f32, r.y() as f32);
+        let (x1, y1) = (r.right() as f32, r.bottom() as f32);
+        [
+            FPoint::new(x0, y0),
+            FPoint::new(x1, y0),
+            FPoint::new(x1, y1),
+            FPoint::new(x0, y1),
+        ]
+    };
+    let (a, b) = (corners(source), corners(target));
+    let all: Vec<FPoint> = a.iter().chain(&b).copied().collect();
+    (0..4)
+        .filter(|&k| {
+            let (dx, dy) = (b[k].x - a[k].x, b[k].y - a[k].y);
+            let side = |p: &FPoint| dx * (p.y - a[k].y) - dy * (p.x - a[k].x);
+            all.iter().all(|p| side(p) >= -0.5) || all.iter().all(|p| side(p) <= 0.5)
+        })
+        .map(|k| (a[k], b[k]))
+        .collect()
+}
+
+/// Corner radius of a callout body.
+pub fn callout_radius(body: Rect) -> f32 {
+    (body.width().min(body.height()) as f32 / 4.0).min(12.0)
+}
+
+/// Closed outline of a [`ShapeKind::Callout`], clockwise: the rounded
+/// body with the tail spliced into the edge facing `anchor`. No tail when
+/// the anchor sits inside the body.
+pub fn callout_outline(body: Rect, anchor: FPoint) -> Vec<FPoint> {
+    use std::f32::consts::{FRAC_PI_2, PI};
+    let (x0, y0) = (body.x() as f32, body.y() as f32);
+    let (x1, y1) = (body.right() as f32, body.bottom() as f32);
+    let (w, h) = (x1 - x0, y1 - y0);
+    let r = callout_radius(body);
+    let inside = anchor.x >= x0 && anchor.x <= x1 && anchor.y >= y0 && anchor.y <= y1;
+    // Edge the tail leaves from, clockwise from the top.
+    let nx = (anchor.x - (x0 + x1) / 2.0) / w.max(1.0);
+    let ny = (anchor.y - (y0 + y1) / 2.0) / h.max(1.0);
+    let edge = match (nx.abs() > ny.abs(), nx > 0.0, ny > 0.0) {
+        _ if inside => None,
+        (true, true, _) => Some(1),
+        (true, false, _) => Some(3),
+        (false, _, true) => Some(2),
+        (false, _, false) => Some(0),
+    };
+    // Tail base centred on the anchor's projection, kept clear of corners.
+    let base = |lo: f32, hi: f32, at: f32| {
+        let half = ((hi - lo) * 0.15).clamp(4.0, 16.0);
+        let (lo, hi) = (lo + r + half, hi - r - half);
+        let c = if lo > hi {
+            (lo + hi) / 2.0
+        } else {
+            at.clamp(lo, hi)
+        };
+        (c - half, c + half)
+    };
+    let corners = [
+        (FPoint::new(x0 + r, y0 + r), PI),
+        (FPoint::new(x1 - r, y0 + r), PI + FRAC_PI_2),
+        (FPoint::new(x1 - r, y1 - r), 0.0),
+        (FPoint::new(x0 + r, y1 - r), FRAC_PI_2),
+    ];
+    let mut out = Vec::new();
+    for (i, (c, start)) in corners.into_iter().enumerate() {
+        for k in 0..=6 {
+            let a = start + k as f32 / 6.0 * FRAC_PI_2;
+            out.push(FPoint::new(c.x + r * a.cos(), c.y + r * a.sin()));
+        }
+        if edge != Some(i) {
+            continue;
+        }
+        let (b0, b1) = match i {
+            0 => {
+                let (a, b) = base(x0, x1, anchor.x);
+                (FPoint::new(a, y0), FPoint::new(b, y0))
+            }
+            1 => {
+                let (a, b) = base(y0, y1, anchor.y);
+                (FPoint::new(x1, a), FPoint::new(x1, b))
+            }
+            2 => {
+                let (a, b) = base(x0, x1, anchor.x);
+                (FPoint::new(b, y1), FPoint::new(a, y1))
+            }
+            _ => {
+                let (a, b) = base(y0, y1, anchor.y);
+                (FPoint::new(x0, b), FPoint::new(x0, a))
+            }
+        };
+        out.extend([b0, anchor, b1]);
+    }
+    out
+}
+
+/// Where a callout's text goes and how it wraps: the top-left of the text
+/// block and `text` broken to fit inside the body's padding.
+pub fn callout_text(body: Rect, text: &str, style: &TextStyle) -> (FPoint, String) {
+    let pad = (style.size * 0.5).max(callout_radius(body) / 2.0);
+    let origin = FPoint::new(body.x() as f32 + pad, body.y() as f32 + pad);
+    let width = (body.width() as f32 - pad * 2.0).max(style.size);
+    (origin, crate::font::wrap(text, style, width))
+}
+
+/// Points along an arrow's shaft from `from` to `to`: the two ends, or
+/// the quadratic Bézier with control point `bend` flattened to a polyline.
+pub fn arrow_path(from: FPoint, to: FPoint, bend: Option<FPoint>) -> Vec<FPoint> {
+    let Some(c) = bend else {
+        return vec![from, to];
+    };
+    let steps = ((from.distance(c) + c.distance(to)) / 6.0).clamp(8.0, 64.0) as usize;
+    (0..=steps)
+        .map(|i| {
+            let t = i as f32 / steps as f32;
+            let (a, b, d) = ((1.0 - t) * (1.0 - t), 2.0 * (1.0 - t) * t, t * t);
+            FPoint::new(
+                a * from.x + b * c.x + d * to.x,
+                a * from.y + b * c.y + d * to.y,
+            )
+        })
+        .collect()
+}
+
+/// Point halfway along an arrow's shaft, where its bend handle sits.
+pub fn arrow_mid(from: FPoint, to: FPoint, bend: Option<FPoint>) -> FPoint {
+    let c = bend.unwrap_or(FPoint::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0));
+    FPoint::new(
+        (from.x + 2.0 * c.x + to.x) / 4.0,
+        (from.y + 2.0 * c.y + to.y) / 4.0,
+    )
+}
+
+/// Control point that bends the arrow so it passes through `mid`
+/// halfway along; the inverse of [`arrow_mid`].
+pub fn bend_through(from: FPoint, to: FPoint, mid: FPoint) -> FPoint {
+    FPoint::new(
+        (4.0 * mid.x - from.x - to.x) / 2.0,
+        (4.0 * mid.y - from.y - to.y) / 2.0,
+    )
+}
+
+/// Length of an arrowhead on a stroke of `width`.
+pub fn arrowhead_len(width: f32) -> f32 {
+    (width * 3.0).max(10.0)
+}
+
+/// The two back corners of an arrowhead at `tip`, pointing away from
+/// `back`, on a stroke of `width`.
+pub fn arrowhead_wings(tip: FPoint, back: FPoint, width: f32) -> (FPoint, FPoint) {
+    let (dx, dy) = (tip.x - back.x, tip.y - back.y);
+    let len = (dx * dx + dy * dy).sqrt().max(1.0e-3);
+    let (ux, uy) = (dx / len, dy / len);
+    let head = arrowhead_len(width);
+    let spread = 0.5;
+    (
+        FPoint::new(
+            tip.x - (ux * head + uy * head * spread),
+            tip.y - (uy * head - ux * head * spread),
+        ),
+        FPoint::new(
+            tip.x - (ux * head - uy * head * spread),
+            tip.y - (uy * head + ux * head * spread),
+        ),
+    )
+}
+
+/// Ends of a bar arrowhead: a stroke across `tip`, as wide as the other
+/// heads.
+pub fn arrowhead_bar(tip: FPoint, back: FPoint, width: f32) -> (FPoint, FPoint) {
+    let (h1, h2) = arrowhead_wings(tip, back, width);
+    let (mx, my) = ((h1.x + h2.x) / 2.0, (h1.y + h2.y) / 2.0);
+    (
+        FPoint::new(tip.x + h1.x - mx, tip.y + h1.y - my),
+        FPoint::new(tip.x + h2.x - mx, tip.y + h2.y - my),
+    )
+}
+
+/// `points` with `start` and `end` pixels of length cut off its two ends,
+/// so a shaft stops at the base of a solid head.
+pub fn trim_path(points: &[FPoint], start: f32, end: f32) -> Vec<FPoint> {
+    fn cut_front(pts: &mut Vec<FPoint>, mut amount: f32) {
+        while amount > 0.0 && pts.len() >= 2 {
+            let d = pts[0].distance(pts[1]);
+            if d > amount {
+                let t = amount / d;
+                pts[0] = FPoint::new(
+                    pts[0].x + (pts[1].x - pts[0].x) * t,
+                    pts[0].y + (pts[1].y - pts[0].y) * t,
+                );
+                return;
+            }
+            amount -= d;
+            pts.remove(0);
+        }
+    }
+    let mut pts = points.to_vec();
+    cut_front(&mut pts, start);
+    pts.reverse();
+    cut_front(&mut pts, end);
+    pts.reverse();
+    pts
+}
+
+/// Split a stroke into the visible runs of a dash `pattern` (alternating
+/// on / off lengths). A closed path also dashes its closing edge.
+pub fn dash_path(points: &[FPoint], closed: bool, pattern: &[f32]) -> Vec<Vec<FPoint>> {
+    let mut pts = points.to_vec();
+    if closed && pts.len() >= 2 {
+        pts.push(pts[0]);
+    }
+    let total: f32 = pattern.iter().sum();
+    if pts.len() < 2 || total <= 0.0 {
+        return vec![pts];
+    }
+    let mut runs = Vec::new();
+    let mut run: Vec<FPoint> = vec![pts[0]];
+    let (mut slot, mut left) = (0, pattern[0]);
+    for pair in pts.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let len = a.distance(b);
+        let mut at = 0.0;
+        while len - at > left {
+            at += left;
+            let t = at / len;
+            let p = FPoint::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t);
+            if slot % 2 == 0 {
+                run.push(p);
+                runs.push(std::mem::take(&mut run));
+            } else {
+                run = vec![p];
             }
+            slot = (slot + 1) % pattern.len();
+            left = pattern[slot];
+        }
+        left -= len - at;
+        if slot % 2 == 0 {
+            run.push(b);
         }
     }
+    if slot % 2 == 0 && run.len() >= 2 {
+        runs.push(run);
+    }
+    runs
+}
+
+/// Outline of the ellipse inscribed in `r`, one point per couple of
+/// pixels of circumference.
+pub fn ellipse_points(r: Rect) -> Vec<FPoint> {
+    let cx = r.x() as f32 + r.width() as f32 / 2.0;
+    let cy = r.y() as f32 + r.height() as f32 / 2.0;
+    let rx = r.width() as f32 / 2.0;
+    let ry = r.height() as f32 / 2.0;
+    let steps = ((rx + ry) * std::f32::consts::PI).ceil().clamp(32.0, 720.0) as usize;
+    (0..steps)
+        .map(|i| {
+            let t = i as f32 / steps as f32 * std::f32::consts::TAU;
+            FPoint::new(cx + rx * t.cos(), cy + ry * t.sin())
+        })
+        .collect()
 }
 
 fn bounding_of_points(pts: &[FPoint]) -> Rect {
@@ -129,12 +650,46 @@ fn bounding_of_points(pts: &[FPoint]) -> Rect {
     )
 }
 
+/// Outline of a spotlight area.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum SpotlightShape {
+    #[default]
+    Rect,
+    Ellipse,
+}
+
+impl SpotlightShape {
+    /// Horizontal extent `(x0, x1)` of the area inscribed in `rect` on the
+    /// row at height `y`, or `None` when the row misses it.
+    pub fn span(self, rect: Rect, y: f32) -> Option<(f32, f32)> {
+        let (x0, y0) = (rect.x() as f32, rect.y() as f32);
+        let (w, h) = (rect.width() as f32, rect.height() as f32);
+        if w <= 0.0 || h <= 0.0 || y < y0 || y > y0 + h {
+            return None;
+        }
+        match self {
+            SpotlightShape::Rect => Some((x0, x0 + w)),
+            SpotlightShape::Ellipse => {
+                let (rx, ry) = (w / 2.0, h / 2.0);
+                let t = (y - y0 - ry) / ry;
+                let half = rx * (1.0 - t * t).max(0.0).sqrt();
+                Some((x0 + rx - half, x0 + rx + half))
+            }
+        }
+    }
+}
+
 /// Visual style for non-text shapes.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Style {
     pub stroke: Color,
     pub stroke_width: f32,
     pub fill: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub line: LineStyle,
 }
 
 impl Default for Style {
@@ -143,6 +698,7 @@ impl Default for Style {
             stroke: Color::RED,
             stroke_width: 3.0,
             fill: None,
+            line: LineStyle::default(),
         }
     }
 }
@@ -153,6 +709,7 @@ impl From<BrushSettings> for Style {
             stroke: b.color,
             stroke_width: b.width,
             fill: b.fill,
+            line: LineStyle::default(),
         }
     }
 }
@@ -163,15 +720,172 @@ impl From<StepSettings> for Style {
             stroke: s.fill,
             stroke_width: 0.0,
             fill: Some(s.fill),
+            line: LineStyle::default(),
+        }
+    }
+}
+
+/// How a stroke is drawn beyond its colour and width. Arrowhead settings
+/// only matter to arrows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "kebab-case"))]
+pub struct LineStyle {
+    /// Multiplies the stroke and fill alpha (0..=1).
+    pub opacity: f32,
+    pub dash: DashPattern,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub head: ArrowHead,
+    /// Put a head on the tail end of an arrow too.
+    pub double_headed: bool,
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            dash: DashPattern::Solid,
+            cap: LineCap::Round,
+            join: LineJoin::Round,
+            head: ArrowHead::Open,
+            double_headed: false,
+        }
+    }
+}
+
+impl LineStyle {
+    /// `c` with its alpha scaled by `opacity`.
+    pub fn apply(&self, c: Color) -> Color {
+        c.with_alpha((c.0[3] as f32 * self.opacity.clamp(0.0, 1.0)).round() as u8)
+    }
+}
+
+/// Dash pattern of a stroke.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum DashPattern {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+    DashDot,
+}
+
+impl DashPattern {
+    pub const ALL: [DashPattern; 4] = [
+        DashPattern::Solid,
+        DashPattern::Dashed,
+        DashPattern::Dotted,
+        DashPattern::DashDot,
+    ];
+
+    /// Alternating on / off lengths for a stroke of `width`, or `None`
+    /// when solid. Dots are zero-length runs that the cap makes visible.
+    pub fn pattern(self, width: f32, cap: LineCap) -> Option<Vec<f32>> {
+        let w = width.max(1.0);
+        // Round and square caps grow every run by a width; keep the gaps.
+        let (grow, dot) = match cap {
+            LineCap::Butt => (0.0, w),
+            LineCap::Round | LineCap::Square => (w, 0.01),
+        };
+        match self {
+            DashPattern::Solid => None,
+            DashPattern::Dashed => Some(vec![w * 4.0, w * 2.0 + grow]),
+            DashPattern::Dotted => Some(vec![dot, w * 2.0 + grow - dot]),
+            DashPattern::DashDot => Some(vec![w * 4.0, w * 2.0 + grow, dot, w * 2.0 + grow - dot]),
+        }
+    }
+}
+
+/// Shape of an open stroke's ends.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum LineCap {
+    Butt,
+    #[default]
+    Round,
+    Square,
+}
+
+impl LineCap {
+    pub const ALL: [LineCap; 3] = [LineCap::Butt, LineCap::Round, LineCap::Square];
+}
+
+/// Shape of the corners where stroke segments meet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum LineJoin {
+    Miter,
+    #[default]
+    Round,
+    Bevel,
+}
+
+impl LineJoin {
+    pub const ALL: [LineJoin; 3] = [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel];
+}
+
+/// Decoration at the tip of an arrow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum ArrowHead {
+    /// Two strokes forming a V.
+    #[default]
+    Open,
+    /// Solid triangle.
+    Filled,
+    Dot,
+    /// Short stroke across the tip.
+    Bar,
+}
+
+impl ArrowHead {
+    pub const ALL: [ArrowHead; 4] = [
+        ArrowHead::Open,
+        ArrowHead::Filled,
+        ArrowHead::Dot,
+        ArrowHead::Bar,
+    ];
+
+    /// How far the shaft stops short of the tip so it doesn't poke
+    /// through a solid head.
+    pub fn inset(self, width: f32) -> f32 {
+        match self {
+            ArrowHead::Filled => arrowhead_len(width) * 0.8,
+            ArrowHead::Dot => Self::dot_radius(width) * 0.5,
+            ArrowHead::Open | ArrowHead::Bar => 0.0,
         }
     }
+
+    pub fn dot_radius(width: f32) -> f32 {
+        (width * 1.5).max(4.0)
+    }
 }
 
+/// Style of a text annotation. `origin` is the top-left of the first
+/// line; the background and outline extend [`TextStyle::margin`] beyond
+/// the glyph block.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct TextStyle {
     pub color: Color,
     pub size: f32,
     pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// Font family: `Hack` (embedded), a system family name, or one of
+    /// `sans-serif` / `serif` / `monospace`. Unknown names fall back to
+    /// Hack.
+    pub family: String,
+    pub background: Option<TextBackground>,
+    /// Halo around the glyphs, for contrast on busy backgrounds.
+    pub outline: Option<TextOutline>,
 }
 
 impl Default for TextStyle {
@@ -180,56 +894,192 @@ impl Default for TextStyle {
             color: Color::RED,
             size: 18.0,
             bold: false,
+            italic: false,
+            underline: false,
+            family: crate::font::DEFAULT_FAMILY.to_owned(),
+            background: None,
+            outline: None,
         }
     }
 }
 
+impl TextStyle {
+    /// How far the background and outline reach past the glyph block.
+    pub fn margin(&self) -> f32 {
+        let bg = self.background.map_or(0.0, |b| b.padding);
+        let halo = self.outline.map_or(0.0, |o| o.width);
+        bg.max(halo)
+    }
+
+    /// Set the text colour; background and outline switch to whichever of
+    /// black or white contrasts with it.
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        let contrast = color.contrasting();
+        if let Some(bg) = self.background.as_mut() {
+            bg.color = contrast.with_alpha(bg.color.0[3]);
+        }
+        if let Some(o) = self.outline.as_mut() {
+            o.color = contrast;
+        }
+    }
+
+    /// Step the background through none → box → pill → none.
+    pub fn cycle_background(&mut self) {
+        self.background = match self.background {
+            None => Some(TextBackground {
+                color: self.color.contrasting().with_alpha(200),
+                padding: (self.size * 0.3).round().max(2.0),
+                pill: false,
+            }),
+            Some(bg) if !bg.pill => Some(TextBackground { pill: true, ..bg }),
+            Some(_) => None,
+        };
+    }
+
+    pub fn toggle_outline(&mut self) {
+        self.outline = match self.outline {
+            Some(_) => None,
+            None => Some(TextOutline {
+                color: self.color.contrasting(),
+                width: (self.size / 8.0).max(1.5),
+            }),
+        };
+    }
+
+    /// Switch to the family after the current one in `families`, wrapping
+    /// around; an unlisted family moves to the first entry.
+    pub fn cycle_family(&mut self, families: &[String]) {
+        let Some(first) = families.first() else {
+            return;
+        };
+        self.family = families
+            .iter()
+            .position(|f| f.eq_ignore_ascii_case(&self.family))
+            .and_then(|i| families.get(i + 1))
+            .unwrap_or(first)
+            .clone();
+    }
+
+    /// Multiply size, padding and outline width by `factor`.
+    pub fn scale(&mut self, factor: f32) {
+        self.size = (self.size * factor).max(6.0);
+        if let Some(bg) = self.background.as_mut() {
+            bg.padding *= factor;
+        }
+        if let Some(o) = self.outline.as_mut() {
+            o.width = (o.width * factor).max(0.5);
+        }
+    }
+}
+
+/// Filled box behind a text annotation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextBackground {
+    pub color: Color,
+    /// Space between the glyph block and the box edge.
+    pub padding: f32,
+    /// Fully rounded ends instead of slightly rounded corners.
+    pub pill: bool,
+}
+
+impl TextBackground {
+    /// Corner radius for a box of height `h`.
+    pub fn radius(&self, h: f32) -> f32 {
+        if self.pill {
+            h / 2.0
+        } else {
+            self.padding.min(h / 4.0)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextOutline {
+    pub color: Color,
+    pub width: f32,
+}
+
+/// Thinnest a pressure stroke gets, as a fraction of its stroke width, so
+/// a feather-light touch still leaves a visible line.
+pub const MIN_PRESSURE_WIDTH: f32 = 0.2;
+
+/// Fraction of the stroke width drawn at `pressure` (0–1).
+pub fn pressure_width(pressure: f32) -> f32 {
+    MIN_PRESSURE_WIDTH + (1.0 - MIN_PRESSURE_WIDTH) * pressure.clamp(0.0, 1.0)
+}
+
 /// Smooth a freehand polyline: drop clustered samples and densify with a
 /// uniform Catmull–Rom spline. Output is suitable for direct rendering with
 /// any polyline rasteriser — kinks vanish and slow-hand jitter is averaged
 /// out without introducing visible drift away from input vertices.
 pub fn smoothed_freehand(points: &[FPoint], width: f32) -> Vec<FPoint> {
+    smoothed_freehand_widths(points, &[], width)
+        .into_iter()
+        .map(|(p, _)| p)
+        .collect()
+}
+
+/// [`smoothed_freehand`] carrying a width along: each output point comes
+/// with its stroke width in pixels, interpolated from the per-point
+/// fractions in `widths` (missing entries count as the full `width`).
+pub fn smoothed_freehand_widths(
+    points: &[FPoint],
+    widths: &[f32],
+    width: f32,
+) -> Vec<(FPoint, f32)> {
+    let at = |i: usize| width * widths.get(i).copied().unwrap_or(1.0);
     if points.len() < 2 {
-        return points.to_vec();
+        return points
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| (p, at(i)))
+            .collect();
     }
     let min_gap = (width * 0.4).max(0.75);
-    let mut filtered: Vec<FPoint> = Vec::with_capacity(points.len());
-    for &p in points {
+    let mut filtered: Vec<(FPoint, f32)> = Vec::with_capacity(points.len());
+    for (i, &p) in points.iter().enumerate() {
         let push = match filtered.last() {
-            Some(last) => last.distance(p) >= min_gap,
+            Some(last) => last.0.distance(p) >= min_gap,
             None => true,
         };
         if push {
-            filtered.push(p);
+            filtered.push((p, at(i)));
         }
     }
     // Always keep last raw sample so the rendered stroke reaches the cursor.
     if let Some(&last) = points.last() {
-        if filtered.last().map(|p| *p != last).unwrap_or(true) {
-            filtered.push(last);
+        if filtered.last().map(|p| p.0 != last).unwrap_or(true) {
+            filtered.push((last, at(points.len() - 1)));
         }
     }
     if filtered.len() < 3 {
         return filtered;
     }
     let n = filtered.len();
-    let mut out: Vec<FPoint> = Vec::with_capacity(n * 8);
+    let mut out: Vec<(FPoint, f32)> = Vec::with_capacity(n * 8);
     out.push(filtered[0]);
     for i in 0..n - 1 {
-        let p0 = if i == 0 { filtered[0] } else { filtered[i - 1] };
-        let p1 = filtered[i];
-        let p2 = filtered[i + 1];
+        let p0 = if i == 0 {
+            filtered[0].0
+        } else {
+            filtered[i - 1].0
+        };
+        let (p1, w1) = filtered[i];
+        let (p2, w2) = filtered[i + 1];
         let p3 = if i + 2 >= n {
-            filtered[n - 1]
+            filtered[n - 1].0
         } else {
-            filtered[i + 2]
+            filtered[i + 2].0
         };
         // Adapt sample count to segment length so short hops don't waste verts.
         let dist = p1.distance(p2);
         let segments = (dist.ceil() as usize / 2).clamp(2, 10);
         for s in 1..=segments {
             let t = s as f32 / segments as f32;
-            out.push(catmull_rom(p0, p1, p2, p3, t));
+            out.push((catmull_rom(p0, p1, p2, p3, t), w1 + (w2 - w1) * t));
         }
     }
     out
@@ -250,3 +1100,49 @@ fn catmull_rom(p0: FPoint, p1: FPoint, p2: FPoint, p3: FPoint, t: f32) -> FPoint
             + (-p0.y + 3.0 * p1.y - 3.0 * p2.y + p3.y) * t3);
     FPoint::new(x, y)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(n: usize) -> Vec<FPoint> {
+        (0..n).map(|i| FPoint::new(i as f32 * 4.0, 0.0)).collect()
+    }
+
+    #[test]
+    fn pressure_width_spans_min_to_full() {
+        assert_eq!(pressure_width(0.0), MIN_PRESSURE_WIDTH);
+        assert_eq!(pressure_width(1.0), 1.0);
+        assert_eq!(pressure_width(0.5), (1.0 + MIN_PRESSURE_WIDTH) / 2.0);
+        assert_eq!(pressure_width(-1.0), MIN_PRESSURE_WIDTH);
+        assert_eq!(pressure_width(7.0), 1.0);
+    }
+
+    #[test]
+    fn widths_follow_the_smoothed_points() {
+        let points = line(6);
+        let widths = [1.0, 0.8, 0.6, 0.4, 0.2, 0.2];
+        let smoothed = smoothed_freehand_widths(&points, &widths, 10.0);
+        let plain = smoothed_freehand(&points, 10.0);
+        assert_eq!(smoothed.len(), plain.len());
+        for ((p, _), q) in smoothed.iter().zip(&plain) {
+            assert_eq!(p, q);
+        }
+        assert_eq!(smoothed.first().unwrap().1, 10.0);
+        assert!((smoothed.last().unwrap().1 - 2.0).abs() < 1e-4);
+        // Monotonic input, monotonic output: no overshoot between samples.
+        assert!(smoothed.windows(2).all(|w| w[1].1 <= w[0].1 + 1e-4));
+    }
+
+    #[test]
+    fn missing_widths_count_as_full() {
+        let points = line(4);
+        let uniform = smoothed_freehand_widths(&points, &[], 6.0);
+        assert!(uniform.iter().all(|&(_, w)| w == 6.0));
+        let short = smoothed_freehand_widths(&points, &[0.5], 6.0);
+        assert_eq!(short.first().unwrap().1, 3.0);
+        assert_eq!(short.last().unwrap().1, 6.0);
+        let single = smoothed_freehand_widths(&points[..1], &[0.5], 6.0);
+        assert_eq!(single, vec![(points[0], 3.0)]);
+    }
+}
@@ -0,0 +1,88 @@
+//! Images placed on the canvas as shapes: pasted, dropped onto the
+//! overlay, or picked from the configured sticker directory.
+
+use std::path::{Path, PathBuf};
+
+use image::RgbaImage;
+use thiserror::Error;
+
+/// Longest side an SVG sticker is rasterised at; the shape can still be
+/// scaled up afterwards.
+const SVG_MAX_SIDE: f32 = 512.0;
+
+#[derive(Debug, Error)]
+pub(crate) enum StickerError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+    #[error("cannot render SVG: {0}")]
+    Svg(String),
+}
+
+/// Decode an image file; SVGs are rendered at their natural size, capped
+/// to [`SVG_MAX_SIDE`].
+pub(crate) fn load_image(path: &Path) -> Result<RgbaImage, StickerError> {
+    if is_svg(path) {
+        return render_svg(&std::fs::read(path)?);
+    }
+    Ok(image::open(path)?.to_rgba8())
+}
+
+/// PNG and SVG files in `dir`, sorted by name. An unreadable directory
+/// yields nothing.
+pub(crate) fn list_stickers(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(expand_user(dir)) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && (is_svg(p) || has_extension(p, "png")))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn render_svg(bytes: &[u8]) -> Result<RgbaImage, StickerError> {
+    let tree = usvg::Tree::from_data(bytes, &usvg::Options::default())
+        .map_err(|e| StickerError::Svg(e.to_string()))?;
+    let size = tree.size();
+    let scale = (SVG_MAX_SIDE / size.width().max(size.height())).min(1.0);
+    let width = (size.width() * scale).ceil().max(1.0) as u32;
+    let height = (size.height() * scale).ceil().max(1.0) as u32;
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| StickerError::Svg("empty canvas".into()))?;
+    let transform = tiny_skia::Transform::from_scale(scale, scale);
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+    // tiny-skia keeps premultiplied alpha; image shapes are straight.
+    let rgba = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| {
+            let c = p.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect();
+    RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| StickerError::Svg("pixel buffer size mismatch".into()))
+}
+
+fn is_svg(path: &Path) -> bool {
+    has_extension(path, "svg")
+}
+
+fn has_extension(path: &Path, ext: &str) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+}
+
+fn expand_user(path: &Path) -> PathBuf {
+    let Some(rest) = path.to_str().and_then(|s| s.strip_prefix("~/")) else {
+        return path.to_path_buf();
+    };
+    match std::env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(rest),
+        None => path.to_path_buf(),
+    }
+}
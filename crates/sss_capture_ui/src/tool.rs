@@ -7,6 +7,9 @@ pub enum Tool {
     #[default]
     Pointer,
     Brush(BrushSettings),
+    /// Wide translucent marker, multiply-blended over the capture. Shift
+    /// snaps the stroke to a horizontal bar.
+    Highlighter(BrushSettings),
     Line(BrushSettings),
     Arrow(BrushSettings),
     Rectangle(BrushSettings),
@@ -15,13 +18,42 @@ pub enum Tool {
     BlurRect {
         radius: f32,
     },
+    /// Rectangle redacted with a mosaic, or a solid box in fill mode.
+    Pixelate {
+        block_size: u32,
+    },
+    /// Keeps the dragged area bright and dims everything else.
+    Spotlight {
+        shape: crate::shape::SpotlightShape,
+        amount: u8,
+        desaturate: bool,
+    },
+    /// Drag a source area; an enlarged copy `zoom` times its size is placed
+    /// beside it, bordered with `border`.
+    Magnify {
+        zoom: f32,
+        border: BrushSettings,
+    },
     /// Removes any shape that intersects the eraser radius.
     Eraser {
         radius: f32,
     },
     Step(StepSettings),
     Text(crate::shape::TextStyle),
+    /// Drag out a speech bubble, then type into it; `brush` outlines and
+    /// fills the bubble.
+    Callout {
+        brush: BrushSettings,
+        text: crate::shape::TextStyle,
+    },
     Polygon(BrushSettings),
+    /// Drag a dimension line labelled with its length, or with `boxed` a
+    /// box labelled W × H. Only `bake`d measurements end up in the output.
+    Measure {
+        color: Color,
+        boxed: bool,
+        bake: bool,
+    },
 }
 
 impl Tool {
@@ -29,15 +61,21 @@ impl Tool {
         match self {
             Tool::Pointer => "Pointer",
             Tool::Brush(_) => "Brush",
+            Tool::Highlighter(_) => "Highlighter",
             Tool::Line(_) => "Line",
             Tool::Arrow(_) => "Arrow",
             Tool::Rectangle(_) => "Rectangle",
             Tool::Ellipse(_) => "Ellipse",
             Tool::BlurRect { .. } => "Blur",
+            Tool::Pixelate { .. } => "Pixelate",
+            Tool::Spotlight { .. } => "Spotlight",
+            Tool::Magnify { .. } => "Magnify",
             Tool::Eraser { .. } => "Eraser",
             Tool::Step(_) => "Step",
             Tool::Text(_) => "Text",
+            Tool::Callout { .. } => "Callout",
             Tool::Polygon(_) => "Polygon",
+            Tool::Measure { .. } => "Measure",
         }
     }
 
@@ -45,17 +83,36 @@ impl Tool {
         match self {
             Tool::Pointer => "↖",
             Tool::Brush(_) => "✎",
+            Tool::Highlighter(_) => "▬",
             Tool::Line(_) => "／",
             Tool::Arrow(_) => "➜",
             Tool::Rectangle(_) => "▭",
             Tool::Ellipse(_) => "◯",
             Tool::BlurRect { .. } => "▓",
+            Tool::Pixelate { .. } => "▦",
+            Tool::Spotlight { .. } => "◎",
+            Tool::Magnify { .. } => "⊕",
             Tool::Eraser { .. } => "⌫",
             Tool::Step(_) => "①",
             Tool::Text(_) => "T",
+            Tool::Callout { .. } => "❝",
             Tool::Polygon(_) => "⬠",
+            Tool::Measure { .. } => "↔",
         }
     }
+
+    /// Whether shapes drawn with this tool take the canvas line style.
+    pub fn uses_line_style(&self) -> bool {
+        matches!(
+            self,
+            Tool::Brush(_)
+                | Tool::Line(_)
+                | Tool::Arrow(_)
+                | Tool::Rectangle(_)
+                | Tool::Ellipse(_)
+                | Tool::Polygon(_)
+        )
+    }
 }
 
 /// Settings shared by stroke-drawing tools.
@@ -120,14 +177,34 @@ impl Default for ToolPalette {
             tools: vec![
                 Tool::Pointer,
                 Tool::Brush(BrushSettings::default()),
+                Tool::Highlighter(BrushSettings::solid(Color::YELLOW, 18.0)),
                 Tool::Line(BrushSettings::default()),
                 Tool::Arrow(BrushSettings::default()),
                 Tool::Rectangle(BrushSettings::default()),
                 Tool::Ellipse(BrushSettings::default()),
                 Tool::Polygon(BrushSettings::default()),
                 Tool::BlurRect { radius: 12.0 },
+                Tool::Pixelate { block_size: 12 },
+                Tool::Spotlight {
+                    shape: Default::default(),
+                    amount: 140,
+                    desaturate: false,
+                },
+                Tool::Magnify {
+                    zoom: 3.0,
+                    border: BrushSettings::default(),
+                },
                 Tool::Eraser { radius: 18.0 },
                 Tool::Step(StepSettings::default()),
+                Tool::Callout {
+                    brush: BrushSettings::default(),
+                    text: crate::shape::TextStyle::default(),
+                },
+                Tool::Measure {
+                    color: Color::RED,
+                    boxed: false,
+                    bake: false,
+                },
             ],
             color_palette: Color::palette().to_vec(),
             initial: Tool::Pointer,
@@ -1,10 +1,15 @@
 //! Capture timing.
 
+use std::time::Duration;
+
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub enum CaptureTrigger {
     /// Capture the screenshot before showing the overlay.
     #[default]
     Eager,
+    /// Like [`CaptureTrigger::Eager`], after a [`crate::countdown`] of the
+    /// given length. Gives the user time to open menus or hover tooltips.
+    Delayed(Duration),
     /// Show the overlay over the live desktop and capture on confirm.
     Lazy {
         confirm: KeyChord,
@@ -60,6 +65,10 @@ pub enum KeyBind {
     Tab,
     Delete,
     Backspace,
+    Left,
+    Right,
+    Up,
+    Down,
     /// A printable character, stored lowercase.
     Char(char),
     F(u8),
@@ -1,11 +1,14 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use merge2::{bool::overwrite_false, option::recursive, Merge};
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use sss_capture::image::Rgba;
+use sss_capture::regex::{self, Regex};
+use sss_capture::{MaskStyle, PrivacyMask, WindowMatcher};
 use sss_capture_ui::UiConfig;
 use sss_lib::config_loader::{load_with_imports, HasImports, LoadError};
-use sss_lib::{default_bool, swap_option, RootArgs};
+use sss_lib::{default_bool, parse_hex, swap_option, RootArgs};
 #[cfg(feature = "ocr")]
 use sss_ocr::{GpuMode, Language, Tier};
 
@@ -126,6 +129,10 @@ struct ClapConfig {
     #[clap(flatten)]
     #[serde(flatten)]
     pub root: RootArgs,
+    #[clap(subcommand)]
+    #[serde(skip)]
+    #[merge(strategy = swap_option)]
+    pub command: Option<Command>,
     #[clap(flatten)]
     #[serde(default)]
     #[merge(strategy = recursive)]
@@ -141,6 +148,12 @@ struct ClapConfig {
     #[serde(default, rename = "capture-ui")]
     #[merge(strategy = swap_option)]
     pub capture_ui: Option<UiConfig>,
+    /// Windows to hide in desktop, monitor and region captures. Loaded
+    /// from `[privacy-mask]` in `config.toml`; config file only.
+    #[clap(skip)]
+    #[serde(default, rename = "privacy-mask")]
+    #[merge(strategy = swap_option)]
+    pub privacy_mask: Option<PrivacyMaskConfig>,
     #[cfg(feature = "ocr")]
     #[clap(flatten)]
     #[serde(default, rename = "ocr")]
@@ -148,6 +161,73 @@ struct ClapConfig {
     pub ocr: Option<OcrConfig>,
 }
 
+/// Modes other than "take one screenshot".
+#[derive(Clone, Debug, Subcommand)]
+pub enum Command {
+    /// Record a click-by-click how-to guide (X11 only). Every mouse click
+    /// captures the monitor under the pointer and appends a numbered step
+    /// to a Markdown or HTML document. Stop with Ctrl+C.
+    Steps(StepsArgs),
+    /// Annotate an existing image in the editor window, then render it like
+    /// a fresh capture (`--output`, `--copy`, border, shadow, ...).
+    Edit(EditArgs),
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct EditArgs {
+    #[clap(help = "Image or .sss project to annotate; `-` reads it from stdin")]
+    pub file: PathBuf,
+}
+
+impl EditArgs {
+    #[inline]
+    pub fn is_stdin(&self) -> bool {
+        self.file.as_os_str() == "-"
+    }
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct StepsArgs {
+    #[clap(
+        long,
+        short = 'o',
+        help = "Directory for the document and its screenshots [default: ~/Pictures/sss-steps-<timestamp>]"
+    )]
+    pub output: Option<PathBuf>,
+
+    #[clap(
+        long,
+        default_value = "markdown",
+        value_parser = parse_steps_format,
+        help = "Document format: markdown | html"
+    )]
+    pub format: StepsFormat,
+
+    #[clap(
+        long,
+        default_value = "Steps",
+        help = "Heading of the generated document"
+    )]
+    pub title: String,
+
+    #[clap(long, help = "Stop after this many clicks")]
+    pub max_steps: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepsFormat {
+    Markdown,
+    Html,
+}
+
+fn parse_steps_format(s: &str) -> Result<StepsFormat, String> {
+    match s.to_lowercase().as_str() {
+        "markdown" | "md" => Ok(StepsFormat::Markdown),
+        "html" | "htm" => Ok(StepsFormat::Html),
+        other => Err(format!("unknown format {other:?}; expected markdown|html")),
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Merge, Parser, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct CliConfig {
@@ -229,15 +309,40 @@ pub struct CliConfig {
 
     /// Force a specific capture backend. Useful for diagnosing why
     /// auto-detection picked the wrong one (e.g. `--capture-backend wayland`
-    /// to skip the portal fallback on wlroots compositors).
+    /// to skip the portal fallback on wlroots compositors), or to capture a
+    /// remote desktop with `--capture-backend vnc://host:5901`.
     #[clap(
         long,
         value_parser = parse_backend,
-        help = "Force a capture backend: auto | wayland | portal | x11 | windows | macos"
+        help = "Force a capture backend: auto | wayland | portal | x11 | framebuffer | windows | macos | vnc://host[:port]"
     )]
     #[merge(strategy = swap_option)]
     pub capture_backend: Option<BackendChoice>,
 
+    /// Password for `--capture-backend vnc://…`. Prefer the environment
+    /// variable over the flag so it doesn't show up in `ps`. Never read
+    /// from or written to the config file.
+    #[clap(
+        long,
+        env = "SSS_VNC_PASSWORD",
+        hide_env_values = true,
+        help = "Password for the VNC capture backend."
+    )]
+    #[merge(strategy = swap_option)]
+    #[serde(skip)]
+    pub vnc_password: Option<Password>,
+
+    /// Wait this long before capturing, with an on-screen countdown, so
+    /// menus, tooltips and other transient UI can be opened first. Applies
+    /// to the interactive selector and to direct targets alike.
+    #[clap(
+        long,
+        value_name = "SECS",
+        help = "Wait SECS seconds (with a countdown) before capturing"
+    )]
+    #[merge(strategy = swap_option)]
+    pub delay: Option<u64>,
+
     /// Bump the default log level to `info` (warnings + backend info).
     #[clap(long, short = 'v')]
     #[merge(strategy = overwrite_false)]
@@ -251,42 +356,99 @@ pub struct CliConfig {
     #[merge(strategy = overwrite_false)]
     #[serde(default = "default_bool")]
     pub remember_last_selection: bool,
+
+    /// After an interactive capture or `sss edit`, also write the
+    /// unannotated image and its shapes as `<output>.sss`, so
+    /// `sss edit <output>.sss` can reopen them for editing.
+    #[clap(long, help = "Also save an editable .sss project next to the output")]
+    #[merge(strategy = overwrite_false)]
+    #[serde(default = "default_bool")]
+    pub project: bool,
+
+    /// How a multi-region selection (Shift+drag in the area selector) is
+    /// saved: side by side in one image, or one numbered file per region.
+    #[clap(
+        long,
+        value_parser = parse_multi_region,
+        help = "Save a multi-region selection as: collage | files"
+    )]
+    #[merge(strategy = swap_option)]
+    pub multi_region: Option<MultiRegion>,
+
+    /// Transparent space between the regions of a collage.
+    #[clap(
+        long,
+        value_name = "PX",
+        help = "Gap in pixels between the regions of a collage (default 16)"
+    )]
+    #[merge(strategy = swap_option)]
+    pub collage_gap: Option<u32>,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MultiRegion {
+    /// Every region left to right in one image.
+    #[default]
+    Collage,
+    /// `<output>-1.png`, `<output>-2.png`, …
+    Files,
+}
+
+fn parse_multi_region(s: &str) -> Result<MultiRegion, String> {
+    match s.to_lowercase().as_str() {
+        "collage" => Ok(MultiRegion::Collage),
+        "files" => Ok(MultiRegion::Files),
+        other => Err(format!("unknown layout {other:?}; expected collage|files")),
+    }
+}
+
+#[derive(Clone, Debug)]
 pub enum BackendChoice {
     Auto,
     Wayland,
     Portal,
     X11,
+    Framebuffer,
     Windows,
     MacOs,
+    /// `vnc://host[:port]`
+    Vnc(String),
 }
 
 fn parse_backend(s: &str) -> Result<BackendChoice, String> {
+    if let Some(addr) = s.strip_prefix("vnc://").or_else(|| s.strip_prefix("vnc:")) {
+        if addr.is_empty() {
+            return Err("vnc backend needs an address: vnc://host[:port]".to_string());
+        }
+        return Ok(BackendChoice::Vnc(addr.trim_end_matches('/').to_string()));
+    }
     match s.to_lowercase().as_str() {
         "auto" => Ok(BackendChoice::Auto),
         "wayland" | "wayland-wlr" | "wlr" => Ok(BackendChoice::Wayland),
         "portal" | "wayland-portal" => Ok(BackendChoice::Portal),
         "x11" | "xorg" => Ok(BackendChoice::X11),
+        "framebuffer" | "fbdev" | "fb" => Ok(BackendChoice::Framebuffer),
         "windows" | "win32" | "win" => Ok(BackendChoice::Windows),
         "macos" | "mac" => Ok(BackendChoice::MacOs),
         other => Err(format!(
-            "unknown backend {other:?}; expected auto|wayland|portal|x11|windows|macos"
+            "unknown backend {other:?}; expected auto|wayland|portal|x11|framebuffer|windows|macos|vnc://host"
         )),
     }
 }
 
 impl BackendChoice {
-    pub fn to_kind(self) -> sss_capture::BackendKind {
+    pub fn to_kind(&self) -> sss_capture::BackendKind {
         use sss_capture::BackendKind as K;
         match self {
             BackendChoice::Auto => K::Auto,
             BackendChoice::Wayland => K::Wayland,
             BackendChoice::Portal => K::WaylandPortal,
             BackendChoice::X11 => K::X11,
+            BackendChoice::Framebuffer => K::Framebuffer,
             BackendChoice::Windows => K::WindowsGdi,
             BackendChoice::MacOs => K::MacOS,
+            BackendChoice::Vnc(_) => K::Vnc,
         }
     }
 }
@@ -303,14 +465,74 @@ impl Serialize for BackendChoice {
             BackendChoice::Wayland => "wayland",
             BackendChoice::Portal => "portal",
             BackendChoice::X11 => "x11",
+            BackendChoice::Framebuffer => "framebuffer",
             BackendChoice::Windows => "windows",
             BackendChoice::MacOs => "macos",
+            BackendChoice::Vnc(addr) => return format!("vnc://{addr}").serialize(s),
         };
         name.serialize(s)
     }
 }
 
+/// A secret that prints as `***` in `Debug` output, so config dumps and
+/// logs never show it.
+#[derive(Clone)]
+pub struct Password(String);
+
+impl std::fmt::Debug for Password {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl std::str::FromStr for Password {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_string()))
+    }
+}
+
 impl CliConfig {
+    /// Capture backend requested through `--capture-backend`, `Auto` otherwise.
+    pub fn backend_kind(&self) -> sss_capture::BackendKind {
+        self.capture_backend
+            .as_ref()
+            .map_or(sss_capture::BackendKind::Auto, BackendChoice::to_kind)
+    }
+
+    /// Server and password for `--capture-backend vnc://…`.
+    pub fn vnc_options(&self) -> Option<sss_capture::VncOptions> {
+        match &self.capture_backend {
+            Some(BackendChoice::Vnc(addr)) => Some(sss_capture::VncOptions {
+                addr: addr.clone(),
+                password: self.vnc_password.as_ref().map(|p| p.0.clone()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// A capturer builder for the requested backend.
+    pub fn capturer(&self) -> sss_capture::CapturerBuilder {
+        let builder = sss_capture::Capturer::builder().backend(self.backend_kind());
+        match self.vnc_options() {
+            Some(server) => builder.vnc(server),
+            None => builder,
+        }
+    }
+
+    /// `--delay` as a duration; `None` when unset or zero.
+    pub fn delay(&self) -> Option<std::time::Duration> {
+        self.delay
+            .filter(|&s| s > 0)
+            .map(std::time::Duration::from_secs)
+    }
+
+    /// `--collage-gap`, defaulting to 16 px.
+    pub fn collage_gap(&self) -> u32 {
+        self.collage_gap.unwrap_or(16)
+    }
+
     /// Is the user asking for a direct (non-interactive) capture?
     ///
     /// Returns the [`SelectorMode`]-equivalent target only if every targeting
@@ -338,6 +560,88 @@ impl CliConfig {
     }
 }
 
+// --------------------------------------------------------------------------
+// [privacy-mask] section
+// --------------------------------------------------------------------------
+
+/// Windows that must never show up in a shared capture.
+///
+/// ```toml
+/// [privacy-mask]
+/// style = "pixelate"
+/// windows = [
+///   { app-id = "org.keepassxc.KeePassXC" },
+///   { app-id = "Slack", title = "(?i)direct message" },
+/// ]
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PrivacyMaskConfig {
+    #[serde(default)]
+    pub windows: Vec<WindowMatcherConfig>,
+    #[serde(default)]
+    pub style: MaskStyleChoice,
+    /// Fill colour for `style = "fill"`. Defaults to opaque black.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Blur sigma or pixelate block size, in pixels.
+    #[serde(default)]
+    pub strength: Option<u32>,
+}
+
+/// One `[privacy-mask].windows` entry. Both fields must match when both
+/// are set.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct WindowMatcherConfig {
+    /// Exact application id (Wayland `app_id`, X11 `WM_CLASS`), any case.
+    pub app_id: Option<String>,
+    /// Regular expression searched for in the window title.
+    pub title: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MaskStyleChoice {
+    #[default]
+    Fill,
+    Blur,
+    Pixelate,
+}
+
+impl PrivacyMaskConfig {
+    pub fn to_mask(&self) -> Result<PrivacyMask, ConfigurationError> {
+        let windows = self
+            .windows
+            .iter()
+            .map(|w| {
+                Ok(WindowMatcher {
+                    app_id: w.app_id.clone(),
+                    title: w.title.as_deref().map(Regex::new).transpose()?,
+                })
+            })
+            .collect::<Result<Vec<_>, regex::Error>>()?;
+        let style = match self.style {
+            MaskStyleChoice::Fill => {
+                let color = match &self.color {
+                    Some(c) => parse_hex(c).map_err(|e| {
+                        ConfigurationError::PrivacyMask(format!("invalid color {c:?}: {e}"))
+                    })?,
+                    None => [0, 0, 0, 255],
+                };
+                MaskStyle::Fill(Rgba(color))
+            }
+            MaskStyleChoice::Blur => MaskStyle::Blur {
+                sigma: self.strength.unwrap_or(20) as f32,
+            },
+            MaskStyleChoice::Pixelate => MaskStyle::Pixelate {
+                block: self.strength.unwrap_or(16),
+            },
+        };
+        Ok(PrivacyMask::new(windows, style))
+    }
+}
+
 // --------------------------------------------------------------------------
 // [ocr] section
 // --------------------------------------------------------------------------
@@ -522,9 +826,11 @@ impl HasImports for ClapConfig {
 
 /// Fully-resolved CLI + config bundle returned by [`get_config`].
 pub struct ResolvedConfig {
+    pub command: Option<Command>,
     pub cli: CliConfig,
     pub lib: sss_lib::GenerationSettings,
     pub ui: UiConfig,
+    pub privacy_mask: PrivacyMask,
     #[cfg(feature = "ocr")]
     pub ocr: OcrConfig,
 }
@@ -563,9 +869,11 @@ pub fn get_config() -> Result<ResolvedConfig, ConfigurationError> {
     };
 
     Ok(ResolvedConfig {
+        command: merged.command,
         cli: merged.cli.unwrap_or_default(),
         lib: merged.lib_config.into(),
         ui: merged.capture_ui.unwrap_or_default(),
+        privacy_mask: merged.privacy_mask.unwrap_or_default().to_mask()?,
         #[cfg(feature = "ocr")]
         ocr: merged.ocr.unwrap_or_default(),
     })
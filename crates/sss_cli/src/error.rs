@@ -18,4 +18,8 @@ pub enum Configuration {
     Io(#[from] std::io::Error),
     #[error("Invalid Home directory path from operating system")]
     InvalidHome,
+    #[error("Invalid [privacy-mask] title pattern: {0}")]
+    PrivacyPattern(#[from] sss_capture::regex::Error),
+    #[error("Invalid [privacy-mask]: {0}")]
+    PrivacyMask(String),
 }
@@ -1,4 +1,5 @@
 use mouse_position::{Mouse, MouseExt};
+use sss_capture::{BackendKind, PrivacyMask, VncOptions};
 use sss_lib::error::ImagenGeneration as ImagenGenerationError;
 use sss_lib::image::RgbaImage;
 use sss_lib::DynImageContent;
@@ -13,15 +14,27 @@ pub enum Screenshot {
     Direct {
         target: DirectTarget,
         show_cursor: bool,
+        backend: BackendKind,
+        vnc: Option<VncOptions>,
+        privacy_mask: PrivacyMask,
     },
     PreRendered(RgbaImage),
 }
 
 impl Screenshot {
-    pub fn from_target(target: DirectTarget, show_cursor: bool) -> Self {
+    pub fn from_target(
+        target: DirectTarget,
+        show_cursor: bool,
+        backend: BackendKind,
+        vnc: Option<VncOptions>,
+        privacy_mask: PrivacyMask,
+    ) -> Self {
         Self::Direct {
             target,
             show_cursor,
+            backend,
+            vnc,
+            privacy_mask,
         }
     }
     pub fn pre_rendered(image: RgbaImage) -> Self {
@@ -36,9 +49,12 @@ impl DynImageContent for Screenshot {
             Screenshot::Direct {
                 target,
                 show_cursor,
+                backend,
+                vnc,
+                privacy_mask,
             } => {
                 tracing::trace!("Generating Image: {target:?}");
-                let shot = ShotImpl::new(*show_cursor)
+                let shot = ShotImpl::new(*show_cursor, *backend, vnc.clone(), privacy_mask.clone())
                     .map_err(|e| ImagenGenerationError::Custom(e.to_string()))?;
 
                 match target {
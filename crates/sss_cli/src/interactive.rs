@@ -6,27 +6,36 @@
 //! through `sss_lib::generate_image` to apply the rounded corners, shadow
 //! and author footer that make `sss` recognisable.
 
-use std::path::PathBuf;
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
 
 use color_eyre::eyre::{eyre, Report};
 use sss_capture_ui::{
-    sss_capture::{BackendKind, CaptureOptions, Capturer},
-    CaptureTrigger, OcrPipeline, Outcome, PostAction, SelectorBuilder, SelectorMode, TextClipboard,
-    ToolKind, UiConfig,
+    sss_capture::{CaptureOptions, PrivacyMask},
+    Canvas, CaptureTrigger, Image, ImageClipboard, OcrPipeline, Outcome, PostAction, Project, Rect,
+    SelectorBuilder, SelectorMode, TextClipboard, ToolKind, UiConfig,
 };
 use sss_lib::image::RgbaImage;
 use sss_lib::GenerationSettings;
 use std::sync::Arc;
 
-use crate::config::CliConfig;
+use crate::config::{CliConfig, EditArgs, MultiRegion};
 use crate::persist;
 
 /// What the interactive selector produced. Consumed by `main`.
 pub struct PreRendered {
-    pub image: RgbaImage,
+    /// The capture, or one image per region when a multi-region
+    /// selection is saved as numbered files.
+    pub images: Vec<RgbaImage>,
     pub action: PostAction,
     /// Default save path the CLI computed; the GUI's hint takes precedence.
     pub default_output: Option<PathBuf>,
+    /// Unflattened pixels and shapes of the session, for `--project` and
+    /// SVG output.
+    pub session: Option<Project>,
+    /// Several regions were confirmed together. Their crops come flattened
+    /// one by one, with no single session behind them.
+    pub multi_region: bool,
 }
 
 /// Run the interactive selector. Returns:
@@ -41,6 +50,7 @@ pub fn run(
     config: &CliConfig,
     g: &GenerationSettings,
     ui: &UiConfig,
+    privacy_mask: &PrivacyMask,
     mode: SelectorMode,
     ocr_pipeline: Option<OcrPipeline>,
 ) -> Result<Option<PreRendered>, Report> {
@@ -50,15 +60,10 @@ pub fn run(
         Some(PathBuf::from(&g.output))
     };
 
-    let toolbar = !config.no_toolbar;
-    let backend_kind = config
-        .capture_backend
-        .map(|c| c.to_kind())
-        .unwrap_or(BackendKind::Auto);
-
-    let capturer = Capturer::builder()
-        .backend(backend_kind)
+    let capturer = config
+        .capturer()
         .show_cursor(config.show_cursor)
+        .privacy_mask(privacy_mask.clone())
         .build()
         .map_err(|e| eyre!("capturer build: {e}"))?;
     let capturer = Arc::new(capturer);
@@ -67,47 +72,22 @@ pub fn run(
         capturer.backend_name()
     );
 
-    let mut ui_config = ui.clone();
-    if !toolbar {
-        ui_config.tools = ToolKind::default_list();
-        ui_config.initial_tool = ToolKind::Brush;
-    }
-    // Seed the in-session Border toggle from the CLI-resolved border
-    // setting so `--no-border` opens the overlay with the button already
-    // off and the user can flip it back on for just this capture.
-    ui_config.border_enabled = g.border;
-
-    let mut builder = SelectorBuilder::default()
+    let mut builder = selector(config, g, ui, default_output.as_ref(), ocr_pipeline)
         .mode(mode)
-        .with_toolbar(toolbar)
-        .ui(ui_config)
-        .capture_trigger(CaptureTrigger::Eager)
+        .capture_trigger(match config.delay() {
+            Some(delay) => CaptureTrigger::Delayed(delay),
+            None => CaptureTrigger::Eager,
+        })
         .capturer(capturer)
         .capture_options(CaptureOptions {
             show_cursor: config.show_cursor,
             ..Default::default()
-        })
-        .show_copy(!g.copy)
-        .show_save(g.output.trim().is_empty() || g.output == "out.png");
-    if let Some(path) = default_output.clone() {
-        builder = builder.save_path_hint(path);
-    }
+        });
     if config.remember_last_selection {
         if let Some(rect) = persist::load_last_area() {
             builder = builder.initial_area(rect);
         }
     }
-    if let Some(pipeline) = ocr_pipeline {
-        builder = builder.ocr_pipeline(pipeline);
-        // Pair the OCR pipeline with the inline text-copy hook so Ctrl+C
-        // (or the Copy toolbar icon) on an active OCR selection writes
-        // the joined text to the system clipboard instead of confirming
-        // an image copy + closing the overlay.
-        let clip: TextClipboard = Arc::new(|text: &str| {
-            sss_lib::copy_text_to_clipboard(text).map_err(|e| e.to_string())
-        });
-        builder = builder.text_clipboard(clip);
-    }
 
     let selection = builder
         .build()
@@ -115,17 +95,32 @@ pub fn run(
         .run()
         .map_err(|e| eyre!("selector run: {e}"))?;
 
-    let (image, last_region) = match selection.outcome {
+    let rect = selection.outcome.rect();
+    let multi_region = matches!(selection.outcome, Outcome::Regions { .. });
+    let (images, last_region) = match selection.outcome {
         Outcome::Region {
             rect,
             image: Some(img),
-        } => (img.into_rgba(), Some(rect)),
+        } => (vec![img.into_rgba()], Some(rect)),
         Outcome::Monitor {
             image: Some(img), ..
         }
         | Outcome::Window {
             image: Some(img), ..
-        } => (img.into_rgba(), None),
+        } => (vec![img.into_rgba()], None),
+        Outcome::Regions { regions } => {
+            let images = regions
+                .into_iter()
+                .filter_map(|(_, img)| img.map(Image::into_rgba))
+                .collect::<Vec<_>>();
+            if images.is_empty() {
+                return Err(eyre!("selector returned without an image"));
+            }
+            match config.multi_region.unwrap_or_default() {
+                MultiRegion::Files => (images, None),
+                MultiRegion::Collage => (vec![collage(&images, config.collage_gap())], None),
+            }
+        }
         // Cancellation is the user's explicit choice (Esc / Cancel
         // button). Surface it as `Ok(None)` so the CLI can exit with a
         // non-zero status without color_eyre's full error chrome.
@@ -140,12 +135,182 @@ pub fn run(
     }
 
     Ok(Some(PreRendered {
-        image,
+        images,
+        action: selection.action,
+        default_output,
+        session: to_project(selection.background, selection.canvas, rect),
+        multi_region,
+    }))
+}
+
+/// `sss edit`: open `args.file` (or stdin) in the editor window. Same
+/// return contract as [`run`]; the image is annotated rather than captured,
+/// so the privacy mask and the remembered selection do not apply.
+pub fn edit(
+    args: &EditArgs,
+    config: &CliConfig,
+    g: &GenerationSettings,
+    ui: &UiConfig,
+    ocr_pipeline: Option<OcrPipeline>,
+) -> Result<Option<PreRendered>, Report> {
+    let (name, bytes) = if args.is_stdin() {
+        let mut bytes = Vec::new();
+        std::io::stdin().read_to_end(&mut bytes)?;
+        ("stdin".to_string(), bytes)
+    } else {
+        (args.file.display().to_string(), std::fs::read(&args.file)?)
+    };
+    // A project brings its shapes back as live objects; anything else is
+    // a plain image to draw on.
+    let is_project = Project::is_project(&bytes);
+    let (image, canvas) = if is_project {
+        let project = Project::from_bytes(&bytes).map_err(|e| eyre!("cannot open {name}: {e}"))?;
+        (project.image, Some(project.canvas))
+    } else {
+        let image = sss_lib::image::load_from_memory(&bytes)
+            .map_err(|e| eyre!("cannot decode {name}: {e}"))?;
+        (image.into_rgba8(), None)
+    };
+
+    let default_output = if g.output.trim().is_empty() || g.output == "out.png" {
+        Some(if args.is_stdin() {
+            default_screenshot_path()
+        } else if is_project {
+            args.file.with_extension("png")
+        } else {
+            edited_path(&args.file)
+        })
+    } else {
+        Some(PathBuf::from(&g.output))
+    };
+
+    let mut builder = selector(config, g, ui, default_output.as_ref(), ocr_pipeline).image(image);
+    if let Some(canvas) = canvas {
+        builder = builder.canvas(canvas);
+    }
+    let selection = builder
+        .build()
+        .map_err(|e| eyre!("selector build: {e}"))?
+        .run()
+        .map_err(|e| eyre!("selector run: {e}"))?;
+
+    let rect = selection.outcome.rect();
+    let image = match selection.outcome {
+        Outcome::Region {
+            image: Some(img), ..
+        } => img.into_rgba(),
+        Outcome::Cancelled => return Ok(None),
+        _ => return Err(eyre!("editor returned without an image")),
+    };
+    Ok(Some(PreRendered {
+        images: vec![image],
         action: selection.action,
         default_output,
+        session: to_project(selection.background, selection.canvas, rect),
+        multi_region: false,
     }))
 }
 
+/// Lay `images` out left to right, top-aligned, with `gap` transparent
+/// pixels between them.
+fn collage(images: &[RgbaImage], gap: u32) -> RgbaImage {
+    let gaps = gap * images.len().saturating_sub(1) as u32;
+    let width = images.iter().map(RgbaImage::width).sum::<u32>() + gaps;
+    let height = images.iter().map(RgbaImage::height).max().unwrap_or(0);
+    let mut out = RgbaImage::new(width, height);
+    let mut x = 0;
+    for image in images {
+        sss_lib::image::imageops::replace(&mut out, image, x as i64, 0);
+        x += image.width() + gap;
+    }
+    out
+}
+
+/// Rebase the session onto the confirmed region's own pixels, so the
+/// project reopens as exactly what was saved.
+fn to_project(
+    background: Option<Image>,
+    mut canvas: Canvas,
+    rect: Option<Rect>,
+) -> Option<Project> {
+    let image = background?.into_rgba();
+    let rect = rect?;
+    canvas.translate(-rect.x(), -rect.y());
+    canvas.set_region(None);
+    Some(Project::new(image, canvas))
+}
+
+/// Builder settings shared by [`run`] and [`edit`].
+fn selector(
+    config: &CliConfig,
+    g: &GenerationSettings,
+    ui: &UiConfig,
+    default_output: Option<&PathBuf>,
+    ocr_pipeline: Option<OcrPipeline>,
+) -> SelectorBuilder {
+    let toolbar = !config.no_toolbar;
+    let mut ui_config = ui.clone();
+    if !toolbar {
+        ui_config.tools = ToolKind::default_list();
+        ui_config.initial_tool = ToolKind::Brush;
+    }
+    // Seed the in-session Border toggle from the CLI-resolved border
+    // setting so `--no-border` opens the overlay with the button already
+    // off and the user can flip it back on for just this capture.
+    ui_config.border_enabled = g.border;
+
+    let mut builder = SelectorBuilder::default()
+        .with_toolbar(toolbar)
+        .ui(ui_config)
+        .show_copy(!g.copy)
+        .show_save(g.output.trim().is_empty() || g.output == "out.png");
+    if let Some(path) = default_output {
+        builder = builder.save_path_hint(path.clone());
+    }
+    if let Some(pipeline) = ocr_pipeline {
+        builder = builder.ocr_pipeline(pipeline);
+        // Pair the OCR pipeline with the inline text-copy hook so Ctrl+C
+        // (or the Copy toolbar icon) on an active OCR selection writes
+        // the joined text to the system clipboard instead of confirming
+        // an image copy + closing the overlay.
+        let clip: TextClipboard = Arc::new(|text: &str| {
+            sss_lib::copy_text_to_clipboard(text).map_err(|e| e.to_string())
+        });
+        builder = builder.text_clipboard(clip);
+    }
+    let paste: ImageClipboard = Arc::new(|| {
+        sss_lib::image_from_clipboard()
+            .inspect_err(|e| tracing::warn!(error = %e, "cannot read clipboard image"))
+            .ok()
+            .flatten()
+    });
+    builder.image_clipboard(paste)
+}
+
+/// `shot.png` → `shot-edited.png`, next to the original so an accidental
+/// save never overwrites it.
+fn edited_path(file: &Path) -> PathBuf {
+    let stem = file
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "image".to_string());
+    file.with_file_name(format!("{stem}-edited.png"))
+}
+
+/// `shot.png` → `shot-2.png`: where region `n` (from 1) of a
+/// multi-region selection goes when the regions are saved one per file.
+pub fn numbered_path(path: &Path, n: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "region".to_string());
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}-{n}.{}", ext.to_string_lossy()),
+        None => format!("{stem}-{n}"),
+    };
+    path.with_file_name(name)
+}
+
 fn default_screenshot_path() -> PathBuf {
     // Mirrors `grim` / GNOME Screenshot defaults: ~/Pictures with a timestamp.
     let stamp = std::time::SystemTime::now()
@@ -157,3 +322,75 @@ fn default_screenshot_path() -> PathBuf {
         .unwrap_or_else(std::env::temp_dir);
     base.join(format!("sss-{stamp}.png"))
 }
+
+#[cfg(test)]
+mod tests {
+    use sss_lib::image::Rgba;
+
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+    const CLEAR: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+    #[test]
+    fn collage_lays_regions_out_left_to_right_with_gaps() {
+        let images = [
+            RgbaImage::from_pixel(2, 3, RED),
+            RgbaImage::from_pixel(4, 1, BLUE),
+        ];
+        let out = collage(&images, 2);
+        assert_eq!(out.dimensions(), (8, 3));
+        assert_eq!(*out.get_pixel(1, 2), RED);
+        assert_eq!(*out.get_pixel(2, 0), CLEAR);
+        assert_eq!(*out.get_pixel(3, 0), CLEAR);
+        assert_eq!(*out.get_pixel(4, 0), BLUE);
+        assert_eq!(*out.get_pixel(7, 0), BLUE);
+        // Top-aligned: below the shorter region stays transparent.
+        assert_eq!(*out.get_pixel(4, 1), CLEAR);
+    }
+
+    #[test]
+    fn collage_of_one_region_is_that_region() {
+        let image = RgbaImage::from_fn(3, 2, |x, y| Rgba([x as u8, y as u8, 7, 255]));
+        assert_eq!(collage(std::slice::from_ref(&image), 16), image);
+    }
+
+    #[test]
+    fn collage_without_gap_or_regions() {
+        let images = [
+            RgbaImage::from_pixel(1, 1, RED),
+            RgbaImage::from_pixel(1, 1, BLUE),
+        ];
+        let out = collage(&images, 0);
+        assert_eq!(out.dimensions(), (2, 1));
+        assert_eq!((*out.get_pixel(0, 0), *out.get_pixel(1, 0)), (RED, BLUE));
+        assert_eq!(collage(&[], 16).dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn numbered_path_numbers_the_stem() {
+        assert_eq!(
+            numbered_path(Path::new("shots/shot.png"), 2),
+            Path::new("shots/shot-2.png")
+        );
+        assert_eq!(
+            numbered_path(Path::new("archive.tar.gz"), 1),
+            Path::new("archive.tar-1.gz")
+        );
+        assert_eq!(numbered_path(Path::new("shot"), 3), Path::new("shot-3"));
+        assert_eq!(
+            numbered_path(Path::new(".hidden"), 1),
+            Path::new(".hidden-1")
+        );
+    }
+
+    #[test]
+    fn numbered_path_without_a_file_name_falls_back_to_region() {
+        assert_eq!(numbered_path(Path::new(""), 1), Path::new("region-1"));
+        assert_eq!(
+            numbered_path(Path::new("shots/.."), 2),
+            Path::new("shots/../region-2")
+        );
+    }
+}
@@ -1,13 +1,14 @@
+use std::path::{Path, PathBuf};
 #[cfg(feature = "ocr")]
 use std::sync::{Arc, Mutex};
 
-use color_eyre::eyre::Report;
+use color_eyre::eyre::{eyre, Report};
 use config::get_config;
 #[cfg(feature = "ocr")]
 use config::OcrConfig;
 use img::Screenshot;
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
-use sss_capture_ui::SelectorMode;
+use sss_capture_ui::{export_ora, export_svg, Project, SelectorMode};
 #[cfg(feature = "ocr")]
 use sss_capture_ui::OcrPipeline;
 use sss_lib::generate_image;
@@ -23,6 +24,7 @@ mod img;
 mod interactive;
 mod persist;
 mod shot;
+mod steps;
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Area {
@@ -58,12 +60,21 @@ fn main() -> Result<(), Report> {
         .install()?;
 
     let config::ResolvedConfig {
+        command,
         cli: config,
         lib: mut g_config,
         ui: ui_config,
+        privacy_mask,
         #[cfg(feature = "ocr")]
         ocr: ocr_config,
     } = get_config()?;
+    let edit = match command {
+        Some(config::Command::Steps(args)) => {
+            return steps::run(&args, &config, g_config, privacy_mask);
+        }
+        Some(config::Command::Edit(args)) => Some(args),
+        None => None,
+    };
     #[cfg(feature = "ocr")]
     tracing::info!(
         enabled = ocr_config.is_enabled(),
@@ -98,23 +109,30 @@ fn main() -> Result<(), Report> {
     //   * `--screen` alone                → selector in Monitor mode
     //   * `--current` alone               → direct (monitor under cursor)
     //   * (none of the above)             → selector in AnyOf mode
+    //   * `sss edit <file>`               → editor window over the file
     let direct = config.direct_target();
     let want_interactive = config.interactive || direct.is_none();
 
-    if want_interactive {
-        let mode = pick_initial_mode(&config);
+    if want_interactive || edit.is_some() {
         // `interactive::run` returns `Ok(None)` for user cancellation
         // (Esc / Cancel button). That's not a real error — it's just
         // "user changed their mind". We exit 1 directly so scripts can
         // detect the cancel, but without color_eyre's big error chrome
         // which would otherwise present cancellation as a crash.
-        let pre = match interactive::run(
-            &config,
-            &g_config,
-            &ui_config,
-            mode,
-            ocr_pipeline.clone(),
-        )? {
+        let pre = match &edit {
+            Some(args) => {
+                interactive::edit(args, &config, &g_config, &ui_config, ocr_pipeline.clone())?
+            }
+            None => interactive::run(
+                &config,
+                &g_config,
+                &ui_config,
+                &privacy_mask,
+                pick_initial_mode(&config),
+                ocr_pipeline.clone(),
+            )?,
+        };
+        let pre = match pre {
             Some(pre) => pre,
             None => {
                 // User pressed Esc / Cancel. Honour the "the download
@@ -145,21 +163,128 @@ fn main() -> Result<(), Report> {
                 g_config.output = path.to_string_lossy().into_owned();
             }
         }
-        let result = generate_image(g_config, Screenshot::pre_rendered(pre.image));
+        let project_path = (!g_config.output.is_empty())
+            .then(|| Path::new(&g_config.output).with_extension(Project::EXTENSION));
+        // `.svg` and `.ora` outputs keep the shapes apart from the capture
+        // (vectors / layers); the PNG decorations don't apply, so the
+        // pipeline only runs for --copy.
+        let mut extension = layered_extension(&g_config.output);
+        // A multi-region selection has no single session whose shapes
+        // could be kept apart; its regions go out as PNG instead.
+        if extension.is_some() && pre.multi_region {
+            let png = Path::new(&g_config.output).with_extension("png");
+            tracing::warn!(
+                "a multi-region selection can't be saved as {}; writing {} instead",
+                g_config.output,
+                png.display()
+            );
+            g_config.output = png.to_string_lossy().into_owned();
+            extension = None;
+        }
+        let layered = extension.is_some();
+        if layered {
+            let session = pre
+                .session
+                .as_ref()
+                .ok_or_else(|| eyre!("nothing to export to {}", g_config.output))?;
+            if extension == Some("svg") {
+                std::fs::write(
+                    &g_config.output,
+                    export_svg(&session.image, &session.canvas, (0, 0)),
+                )?;
+            } else {
+                let file = std::io::BufWriter::new(std::fs::File::create(&g_config.output)?);
+                export_ora(&session.image, &session.canvas, (0, 0), file)?;
+            }
+            g_config.output.clear();
+        }
+        let result = if layered && !g_config.copy {
+            Ok(())
+        } else if pre.images.len() > 1 {
+            // Numbered files of a multi-region selection; only the first
+            // goes to the clipboard.
+            let output = PathBuf::from(&g_config.output);
+            let mut result = Ok(());
+            for (i, image) in pre.images.into_iter().enumerate() {
+                let mut g = g_config.clone();
+                g.copy &= i == 0;
+                if !output.as_os_str().is_empty() {
+                    g.output = interactive::numbered_path(&output, i + 1)
+                        .to_string_lossy()
+                        .into_owned();
+                }
+                result = generate_image(g, Screenshot::pre_rendered(image));
+                if result.is_err() {
+                    break;
+                }
+            }
+            result
+        } else {
+            let image = pre.images.into_iter().next().unwrap_or_default();
+            generate_image(g_config, Screenshot::pre_rendered(image))
+        };
         #[cfg(feature = "ocr")]
         finish_prewarm(prewarm);
-        return Ok(result?);
+        result?;
+        // Its regions were flattened one by one; no single image and canvas
+        // could be reopened as a project. The images are out already, so
+        // only the project is refused.
+        if config.project && pre.multi_region {
+            return Err(eyre!(
+                "--project can't save a multi-region selection; its regions \
+                 were written as images but no project was saved"
+            ));
+        }
+        if let Some(project) = pre.session.filter(|_| config.project) {
+            match project_path {
+                Some(path) => project.save(&path)?,
+                None => tracing::warn!("--project needs an output file; project not saved"),
+            }
+        }
+        return Ok(());
     }
 
+    // Nothing was drawn on a direct capture, and the PNG pipeline can't
+    // write vectors or layers; refuse before the countdown and capture.
+    if layered_extension(&g_config.output).is_some() {
+        return Err(eyre!(
+            "{} keeps annotations apart from the capture, which needs the \
+             selector; drop the target flag or save to a raster format",
+            g_config.output
+        ));
+    }
+    if let Some(delay) = config.delay() {
+        sss_capture_ui::countdown(delay);
+    }
     let result = generate_image(
         g_config,
-        Screenshot::from_target(direct.unwrap(), config.show_cursor),
+        Screenshot::from_target(
+            direct.unwrap(),
+            config.show_cursor,
+            config.backend_kind(),
+            config.vnc_options(),
+            privacy_mask,
+        ),
     );
     #[cfg(feature = "ocr")]
     finish_prewarm(prewarm);
     Ok(result?)
 }
 
+/// `svg` or `ora` when `output` names a file that keeps the annotations
+/// apart from the capture (vectors / layers).
+fn layered_extension(output: &str) -> Option<&'static str> {
+    let ext = Path::new(output)
+        .extension()?
+        .to_string_lossy()
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "svg" => Some("svg"),
+        "ora" => Some("ora"),
+        _ => None,
+    }
+}
+
 /// Spawns the OCR model-prewarm worker when OCR is enabled, otherwise
 /// returns `None`.
 ///
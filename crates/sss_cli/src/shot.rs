@@ -5,7 +5,9 @@
 //! in `sss_capture`. This file just maps CLI flags into the right
 //! `Capturer::capture_*` call.
 
-use sss_capture::{BackendKind, CaptureOptions, Capturer, Point, Rect as CRect};
+use sss_capture::{
+    BackendKind, CaptureOptions, Capturer, Point, PrivacyMask, Rect as CRect, VncOptions,
+};
 use sss_lib::error::ImagenGeneration;
 use sss_lib::image::RgbaImage;
 
@@ -23,9 +25,18 @@ pub struct ShotImpl {
 }
 
 impl ShotImpl {
-    pub fn new(show_cursor: bool) -> Result<Self, SSScreenshot> {
-        let capturer = Capturer::builder()
-            .backend(BackendKind::Auto)
+    pub fn new(
+        show_cursor: bool,
+        backend: BackendKind,
+        vnc: Option<VncOptions>,
+        privacy_mask: PrivacyMask,
+    ) -> Result<Self, SSScreenshot> {
+        let mut builder = Capturer::builder().backend(backend);
+        if let Some(server) = vnc {
+            builder = builder.vnc(server);
+        }
+        let capturer = builder
+            .privacy_mask(privacy_mask)
             .options(CaptureOptions {
                 show_cursor,
                 ..Default::default()
@@ -0,0 +1,331 @@
+//! `sss steps`: record a click-by-click how-to guide.
+//!
+//! Every global mouse click captures the monitor under the pointer, rings
+//! the click point and frames the shot with the usual `sss_lib`
+//! decorations (the clicked window's title goes in the window bar). Each
+//! step is appended to a Markdown or HTML document that is rewritten after
+//! every click, so stopping with Ctrl+C never loses a recorded step.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Report};
+use sss_capture::{Point, PrivacyMask};
+use sss_lib::image::{Rgba, RgbaImage};
+use sss_lib::imageproc::drawing::draw_hollow_circle_mut;
+use sss_lib::{generate_image, GenerationSettings};
+
+use crate::config::{CliConfig, StepsArgs, StepsFormat};
+use crate::img::Screenshot;
+
+#[cfg(target_os = "linux")]
+mod x11;
+
+/// Ring drawn around the click point, in logical pixels.
+const RING_RADIUS: f32 = 22.0;
+const RING_WIDTH: f32 = 4.0;
+const RING_COLOR: Rgba<u8> = Rgba([255, 59, 48, 255]);
+
+/// One global mouse click as reported by the platform listener.
+pub(crate) struct Click {
+    /// Pointer position in desktop coordinates.
+    pub point: Point,
+    /// X11 button number: 1 left, 2 middle, 3 right, 8/9 back/forward.
+    pub button: u32,
+    /// Title of the top-level window under the pointer, if any.
+    pub window_title: Option<String>,
+}
+
+struct Step {
+    file: String,
+    click: Click,
+}
+
+pub fn run(
+    args: &StepsArgs,
+    config: &CliConfig,
+    g: GenerationSettings,
+    privacy_mask: PrivacyMask,
+) -> Result<(), Report> {
+    let dir = args.output.clone().unwrap_or_else(default_steps_dir);
+    std::fs::create_dir_all(&dir)?;
+
+    let capturer = config
+        .capturer()
+        .show_cursor(config.show_cursor)
+        .privacy_mask(privacy_mask)
+        .build()
+        .map_err(|e| eyre!("capturer build: {e}"))?;
+    let mut clicks = listen()?;
+
+    let doc = dir.join(doc_name(args.format));
+    eprintln!(
+        "Recording steps into {}; press Ctrl+C to stop.",
+        doc.display()
+    );
+
+    let mut steps: Vec<Step> = Vec::new();
+    while args.max_steps.is_none_or(|max| steps.len() < max) {
+        let click = clicks.next_click()?;
+        let monitor = capturer
+            .monitor_at(click.point)
+            .map_err(|e| eyre!("no monitor under the click: {e}"))?;
+        let mut shot = capturer
+            .capture_monitor(&monitor)
+            .map_err(|e| eyre!("capture failed: {e}"))?
+            .into_rgba();
+
+        let bounds = monitor.bounds();
+        let scale = shot.width() as f32 / bounds.width() as f32;
+        draw_ring(
+            &mut shot,
+            (click.point.x - bounds.x()) as f32 * scale,
+            (click.point.y - bounds.y()) as f32 * scale,
+            scale,
+        );
+
+        let file = step_file(steps.len() + 1);
+        let mut settings = g.clone();
+        settings.output = dir.join(&file).to_string_lossy().into_owned();
+        settings.save_format = Some("png".to_string());
+        settings.copy = false;
+        settings.show_notify = false;
+        if click.window_title.is_some() {
+            settings.window_controls.title = click.window_title.clone();
+        }
+        generate_image(settings, Screenshot::pre_rendered(shot))?;
+        tracing::info!(step = steps.len() + 1, point = ?click.point, "step recorded");
+
+        steps.push(Step { file, click });
+        write_doc(&doc, args.format, &args.title, &steps)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn listen() -> Result<x11::ClickListener, Report> {
+    x11::ClickListener::connect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn listen() -> Result<Unsupported, Report> {
+    Err(eyre!("`sss steps` needs an X11 session"))
+}
+
+#[cfg(not(target_os = "linux"))]
+enum Unsupported {}
+
+#[cfg(not(target_os = "linux"))]
+impl Unsupported {
+    fn next_click(&mut self) -> Result<Click, Report> {
+        match *self {}
+    }
+}
+
+fn draw_ring(img: &mut RgbaImage, cx: f32, cy: f32, scale: f32) {
+    let center = (cx.round() as i32, cy.round() as i32);
+    let inner = (RING_RADIUS * scale).round() as i32;
+    let width = (RING_WIDTH * scale).round().max(1.0) as i32;
+    for r in inner..inner + width {
+        draw_hollow_circle_mut(img, center, r, RING_COLOR);
+    }
+}
+
+fn doc_name(format: StepsFormat) -> &'static str {
+    match format {
+        StepsFormat::Markdown => "steps.md",
+        StepsFormat::Html => "steps.html",
+    }
+}
+
+/// Image file name of the `n`th (1-based) step.
+fn step_file(n: usize) -> String {
+    format!("step-{n:02}.png")
+}
+
+/// Rewrite the whole document with every step recorded so far.
+fn write_doc(doc: &Path, format: StepsFormat, title: &str, steps: &[Step]) -> std::io::Result<()> {
+    let body = match format {
+        StepsFormat::Markdown => markdown(title, steps),
+        StepsFormat::Html => html(title, steps),
+    };
+    std::fs::write(doc, body)
+}
+
+fn action(click: &Click) -> String {
+    let button = match click.button {
+        1 => "Click",
+        2 => "Middle-click",
+        3 => "Right-click",
+        8 => "Back button",
+        9 => "Forward button",
+        _ => "Press a mouse button",
+    };
+    format!("{button} at ({}, {})", click.point.x, click.point.y)
+}
+
+fn markdown(title: &str, steps: &[Step]) -> String {
+    let mut out = format!("# {title}\n");
+    for (i, step) in steps.iter().enumerate() {
+        let n = i + 1;
+        let _ = write!(out, "\n## Step {n}\n\n{}", action(&step.click));
+        if let Some(window) = &step.click.window_title {
+            let _ = write!(out, " in **{}**", window.replace('*', "\\*"));
+        }
+        let _ = write!(out, ".\n\n![Step {n}]({})\n", step.file);
+    }
+    out
+}
+
+fn html(title: &str, steps: &[Step]) -> String {
+    let title = escape_html(title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>body{{font-family:sans-serif;max-width:60rem;margin:2rem auto}}\
+         img{{max-width:100%}}li{{margin-bottom:2rem}}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n<ol>\n"
+    );
+    for (i, step) in steps.iter().enumerate() {
+        let _ = write!(out, "<li>\n<p>{}", action(&step.click));
+        if let Some(window) = &step.click.window_title {
+            let _ = write!(out, " in <strong>{}</strong>", escape_html(window));
+        }
+        let _ = writeln!(
+            out,
+            ".</p>\n<img src=\"{}\" alt=\"Step {}\">\n</li>",
+            step.file,
+            i + 1
+        );
+    }
+    out.push_str("</ol>\n</body>\n</html>\n");
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn default_steps_dir() -> PathBuf {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let base = directories::UserDirs::new()
+        .and_then(|d| d.picture_dir().map(Path::to_path_buf))
+        .unwrap_or_else(std::env::temp_dir);
+    base.join(format!("sss-steps-{stamp}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn fake_steps() -> Vec<Step> {
+        let clicks = [
+            (1, 10, 20, Some("Files")),
+            (3, -5, 7, None),
+            (2, 0, 0, Some("<Tom & \"Jerry\">")),
+            (8, 1, 2, Some("*bold*")),
+        ];
+        clicks
+            .into_iter()
+            .enumerate()
+            .map(|(i, (button, x, y, title))| Step {
+                file: step_file(i + 1),
+                click: Click {
+                    point: Point { x, y },
+                    button,
+                    window_title: title.map(str::to_string),
+                },
+            })
+            .collect()
+    }
+
+    /// Write `steps` into a fresh temp directory and read the document back.
+    fn render(format: StepsFormat, title: &str, steps: &[Step]) -> String {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "sss-steps-test-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let doc = dir.join(doc_name(format));
+        write_doc(&doc, format, title, steps).unwrap();
+        let body = std::fs::read_to_string(&doc).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        body
+    }
+
+    #[test]
+    fn step_files_are_numbered_from_one_and_zero_padded() {
+        assert_eq!(step_file(1), "step-01.png");
+        assert_eq!(step_file(9), "step-09.png");
+        assert_eq!(step_file(10), "step-10.png");
+        assert_eq!(step_file(123), "step-123.png");
+    }
+
+    #[test]
+    fn markdown_document_lists_every_step_in_order() {
+        let body = render(StepsFormat::Markdown, "Open a file", &fake_steps());
+        assert_eq!(
+            body,
+            "# Open a file\n\
+             \n## Step 1\n\nClick at (10, 20) in **Files**.\n\n![Step 1](step-01.png)\n\
+             \n## Step 2\n\nRight-click at (-5, 7).\n\n![Step 2](step-02.png)\n\
+             \n## Step 3\n\nMiddle-click at (0, 0) in **<Tom & \"Jerry\">**.\n\n\
+             ![Step 3](step-03.png)\n\
+             \n## Step 4\n\nBack button at (1, 2) in **\\*bold\\***.\n\n\
+             ![Step 4](step-04.png)\n"
+        );
+    }
+
+    #[test]
+    fn html_document_lists_every_step_in_order_and_escapes_text() {
+        let body = render(StepsFormat::Html, "A <b>guide</b>", &fake_steps());
+        assert!(body.starts_with("<!DOCTYPE html>\n"));
+        assert!(body.contains("<title>A &lt;b&gt;guide&lt;/b&gt;</title>"));
+        assert!(body.contains("<h1>A &lt;b&gt;guide&lt;/b&gt;</h1>\n<ol>\n"));
+        assert!(body.ends_with("</ol>\n</body>\n</html>\n"));
+
+        let items: Vec<&str> = body
+            .split("<li>\n")
+            .skip(1)
+            .map(|item| item.split("</li>").next().unwrap())
+            .collect();
+        assert_eq!(
+            items,
+            [
+                "<p>Click at (10, 20) in <strong>Files</strong>.</p>\n\
+                 <img src=\"step-01.png\" alt=\"Step 1\">\n",
+                "<p>Right-click at (-5, 7).</p>\n<img src=\"step-02.png\" alt=\"Step 2\">\n",
+                "<p>Middle-click at (0, 0) in \
+                 <strong>&lt;Tom &amp; &quot;Jerry&quot;&gt;</strong>.</p>\n\
+                 <img src=\"step-03.png\" alt=\"Step 3\">\n",
+                "<p>Back button at (1, 2) in <strong>*bold*</strong>.</p>\n\
+                 <img src=\"step-04.png\" alt=\"Step 4\">\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn documents_without_steps_keep_their_frame() {
+        assert_eq!(render(StepsFormat::Markdown, "Empty", &[]), "# Empty\n");
+        let html = render(StepsFormat::Html, "Empty", &[]);
+        assert!(html.contains("<h1>Empty</h1>\n<ol>\n</ol>\n"));
+    }
+
+    #[test]
+    fn rewriting_the_document_keeps_earlier_steps() {
+        let steps = fake_steps();
+        let first = render(StepsFormat::Markdown, "Steps", &steps[..1]);
+        let all = render(StepsFormat::Markdown, "Steps", &steps);
+        assert!(all.starts_with(&first));
+        assert_eq!(all.matches("## Step ").count(), steps.len());
+    }
+}
@@ -0,0 +1,132 @@
+//! Global click listener for X11, built on XInput2 raw events.
+//!
+//! Raw events are delivered to the root window regardless of which client
+//! owns the pointer, without grabbing anything, so the session being
+//! recorded behaves exactly as it would without `sss steps` running.
+
+use color_eyre::eyre::{eyre, Report};
+use sss_capture::Point;
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xinput::{self, ConnectionExt as _, EventMask, XIEventMask};
+use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt as _, Window};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+
+use super::Click;
+
+/// `XIAllMasterDevices`: one event per physical click, not one per slave.
+const XI_ALL_MASTER_DEVICES: u16 = 1;
+
+pub(crate) struct ClickListener {
+    conn: RustConnection,
+    root: Window,
+    wm_state: Atom,
+    net_wm_name: Atom,
+    utf8_string: Atom,
+}
+
+impl ClickListener {
+    pub fn connect() -> Result<Self, Report> {
+        let (conn, screen) =
+            x11rb::connect(None).map_err(|e| eyre!("`sss steps` needs an X11 session: {e}"))?;
+        let root = conn.setup().roots[screen].root;
+
+        if conn
+            .extension_information(xinput::X11_EXTENSION_NAME)?
+            .is_none()
+        {
+            return Err(eyre!("the X server has no XInput extension"));
+        }
+        let version = conn.xinput_xi_query_version(2, 2)?.reply()?;
+        if (version.major_version, version.minor_version) < (2, 2) {
+            return Err(eyre!(
+                "XInput {}.{} is too old; raw events need 2.2",
+                version.major_version,
+                version.minor_version
+            ));
+        }
+        conn.xinput_xi_select_events(
+            root,
+            &[EventMask {
+                deviceid: XI_ALL_MASTER_DEVICES,
+                mask: vec![XIEventMask::RAW_BUTTON_PRESS],
+            }],
+        )?
+        .check()?;
+
+        let atom = |name: &[u8]| -> Result<Atom, Report> {
+            Ok(conn.intern_atom(false, name)?.reply()?.atom)
+        };
+        let wm_state = atom(b"WM_STATE")?;
+        let net_wm_name = atom(b"_NET_WM_NAME")?;
+        let utf8_string = atom(b"UTF8_STRING")?;
+
+        Ok(Self {
+            conn,
+            root,
+            wm_state,
+            net_wm_name,
+            utf8_string,
+        })
+    }
+
+    /// Block until the next button press. Scroll wheel "buttons" (4–7) are
+    /// not steps and are skipped.
+    pub fn next_click(&mut self) -> Result<Click, Report> {
+        loop {
+            let Event::XinputRawButtonPress(ev) = self.conn.wait_for_event()? else {
+                continue;
+            };
+            if (4..=7).contains(&ev.detail) {
+                continue;
+            }
+            let pointer = self.conn.query_pointer(self.root)?.reply()?;
+            return Ok(Click {
+                point: Point::new(pointer.root_x.into(), pointer.root_y.into()),
+                button: ev.detail,
+                window_title: self.title_of(pointer.child),
+            });
+        }
+    }
+
+    /// Title of the client behind `frame`, the root's child under the
+    /// pointer. Reparenting window managers put their decoration window
+    /// there, so look for the descendant carrying `WM_STATE` first.
+    fn title_of(&self, frame: Window) -> Option<String> {
+        if frame == x11rb::NONE {
+            return None;
+        }
+        let client = self.client_window(frame, 3).unwrap_or(frame);
+        self.property(client, self.net_wm_name, self.utf8_string)
+            .or_else(|| self.property(client, AtomEnum::WM_NAME.into(), AtomEnum::ANY.into()))
+            .map(|raw| String::from_utf8_lossy(&raw).into_owned())
+            .filter(|t| !t.is_empty())
+    }
+
+    fn client_window(&self, win: Window, depth: u32) -> Option<Window> {
+        if self
+            .property(win, self.wm_state, AtomEnum::ANY.into())
+            .is_some()
+        {
+            return Some(win);
+        }
+        if depth == 0 {
+            return None;
+        }
+        let tree = self.conn.query_tree(win).ok()?.reply().ok()?;
+        tree.children
+            .into_iter()
+            .rev()
+            .find_map(|child| self.client_window(child, depth - 1))
+    }
+
+    fn property(&self, win: Window, prop: Atom, ty: Atom) -> Option<Vec<u8>> {
+        let reply = self
+            .conn
+            .get_property(false, win, prop, ty, 0, 1024)
+            .ok()?
+            .reply()
+            .ok()?;
+        (reply.type_ != x11rb::NONE).then_some(reply.value)
+    }
+}
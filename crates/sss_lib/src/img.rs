@@ -287,3 +287,17 @@ pub fn copy_text_to_clipboard(text: &str) -> Result<(), ImagenGeneration> {
     set.text(text.to_owned())?;
     Ok(())
 }
+
+/// Read an image from the system clipboard; `None` when it holds none.
+pub fn image_from_clipboard() -> Result<Option<RgbaImage>, ImagenGeneration> {
+    let data = match arboard::Clipboard::new()?.get_image() {
+        Ok(data) => data,
+        Err(arboard::Error::ContentNotAvailable) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(RgbaImage::from_raw(
+        data.width as u32,
+        data.height as u32,
+        data.bytes.into_owned(),
+    ))
+}
@@ -5,27 +5,40 @@ with lib; {
     type = types.listOf (types.enum [
       "pointer"
       "brush"
+      "highlighter"
       "line"
       "arrow"
       "rectangle"
       "ellipse"
       "polygon"
       "blur-rect"
+      "pixelate"
+      "spotlight"
+      "magnify"
       "eraser"
       "step"
       "text"
+      "callout"
+      "measure"
     ]);
     default = [
       "pointer"
       "brush"
+      "highlighter"
       "line"
       "arrow"
       "rectangle"
       "ellipse"
       "polygon"
       "blur-rect"
+      "pixelate"
+      "spotlight"
+      "magnify"
       "eraser"
       "step"
+      "text"
+      "callout"
+      "measure"
     ];
     example = [ "pointer" "brush" "arrow" "blur-rect" ];
     description = "Tools shown in the toolbar (and their order).";
@@ -35,15 +48,21 @@ with lib; {
     type = types.enum [
       "pointer"
       "brush"
+      "highlighter"
       "line"
       "arrow"
       "rectangle"
       "ellipse"
       "polygon"
       "blur-rect"
+      "pixelate"
+      "spotlight"
+      "magnify"
       "eraser"
       "step"
       "text"
+      "callout"
+      "measure"
     ];
     default = "pointer";
     description = "Tool the overlay opens with. Must be present in `tools`.";
@@ -102,6 +121,15 @@ with lib; {
     description = "Default Gaussian blur radius for the Blur Rectangle tool.";
   };
 
+  default-pixelate-block = mkOption {
+    type = types.ints.positive;
+    default = 12;
+    description = ''
+      Mosaic cell size in pixels for the Pixelate tool. Its filled variant
+      draws an opaque box instead.
+    '';
+  };
+
   default-eraser-radius = mkOption {
     type = types.float;
     default = 18.0;
@@ -120,12 +148,41 @@ with lib; {
     description = "Default text size for the Text tool (logical pixels).";
   };
 
+  text-fonts = mkOption {
+    type = types.listOf types.str;
+    default = [ "Hack" "sans-serif" "serif" ];
+    example = [ "Inter" "JetBrains Mono" ];
+    description = ''
+      Font families the Text tool's font chip cycles through; the first is
+      the default. Any installed family name works, as do `sans-serif`,
+      `serif` and `monospace`; `Hack` is embedded. Unknown families fall
+      back to Hack.
+    '';
+  };
+
   snap-step = mkOption {
     type = types.float;
     default = 10.0;
     description = "Snap-grid step in pixels (toggled at runtime with `G`).";
   };
 
+  region-presets = mkOption {
+    type = types.listOf types.str;
+    default = [
+      "16:9"
+      "4:3"
+      "1:1"
+      "1280x720"
+      "1200x630"
+    ];
+    example = [ "16:9" "1920x1080" ];
+    description = ''
+      Shapes the area selection can be held to, as `W:H` ratios or exact
+      `WxH` sizes. A chip under the region cycles Free and these while
+      the Pointer tool is active; an empty list hides it.
+    '';
+  };
+
   region-outline-color = mkOption {
     type = types.str;
     default = "#ffffff";
@@ -142,6 +199,80 @@ with lib; {
     '';
   };
 
+  spotlight-dim = mkOption {
+    type = types.ints.between 0 255;
+    default = 140;
+    description = ''
+      Amount the Spotlight tool darkens everything outside its areas
+      (0 = no dim, 255 = black).
+    '';
+  };
+
+  spotlight-desaturate = mkOption {
+    type = types.bool;
+    default = false;
+    description = "Turn the area outside spotlights grey before darkening it.";
+  };
+
+  sticker-dir = mkOption {
+    type = types.nullOr types.str;
+    default = null;
+    example = "~/Pictures/stickers";
+    description = ''
+      Directory of PNG / SVG images offered by the toolbar's sticker
+      picker. `null` hides the picker; pasting (Ctrl+V) and dropping image
+      files onto the overlay work either way.
+    '';
+  };
+
+  keybindings = mkOption {
+    type = types.attrsOf types.str;
+    default = { };
+    example = {
+      "h" = "tool-1";
+      "space" = "confirm";
+      "q" = "cancel";
+      "ctrl+z" = "none";
+    };
+    description = ''
+      Key chords of the overlay, laid over the defaults. Keys are chords
+      such as `ctrl+shift+z`, `alt+f4`, `h` or `space`; values are
+      `tool-N` (the N-th tool of `tools`), `undo`, `redo`, `copy`, `save`,
+      `confirm`, `cancel`, `paste`, `delete`, `select-all`, `duplicate`,
+      `copy-shapes`, `paste-shapes`, `group`, `align-left` / `-center` / `-right` / `-top` / `-middle` /
+      `-bottom`, `distribute-horizontal` / `-vertical`, `toggle-fill`,
+      `toggle-border`, `mode-area`, `mode-monitor`, `mode-window`,
+      `nudge-left` / `-right` / `-up` / `-down` and `resize-…` with an
+      optional `-N` step, `snap-…` / `stretch-…` (to the next monitor or
+      window edge), `edit-region`, `next-target`, `prev-target`, or `none`
+      to remove a default binding.
+    '';
+  };
+
+  default-magnify-zoom = mkOption {
+    type = types.float;
+    default = 3.0;
+    description = "Scale factor of the copy drawn by the Magnify tool.";
+  };
+
+  measure-logical = mkOption {
+    type = types.bool;
+    default = false;
+    description = "Measure in logical points (pixels divided by the monitor scale factor) instead of physical pixels.";
+  };
+
+  bake-measurements = mkOption {
+    type = types.bool;
+    default = false;
+    description = "Draw measurements into the saved image instead of only showing them in the editor.";
+  };
+
+  show-readout = mkOption {
+    type = types.bool;
+    default = false;
+    description = "Show the pointer coordinates and selection size next to the pointer.";
+  };
+
   chrome = mkOption {
     default = { };
     type = types.submodule {
@@ -36,4 +36,21 @@ with lib; {
     with it next time `--area` is opened without a value. Stored at
     `''${XDG_CONFIG_HOME}/sss/last_selection.toml`.
   '';
+  project = mkEnableOption ''
+    Also write an editable `.sss` project (unannotated image plus shapes)
+    next to the output of interactive captures and `sss edit`
+  '';
+  multi-region = mkOption {
+    type = types.enum [ "collage" "files" ];
+    default = "collage";
+    description = ''
+      How a multi-region selection (Shift+drag) is saved: side by side in
+      one image, or as numbered files next to the output.
+    '';
+  };
+  collage-gap = mkOption {
+    type = types.int;
+    default = 16;
+    description = "Transparent gap in pixels between the regions of a collage.";
+  };
 }
@@ -25,6 +25,7 @@ with lib; let
   sharedConfig = import ./sharedConfig.nix { inherit lib; };
   captureUiConfig = import ./captureUiConfig.nix { inherit lib; };
   ocrConfig = import ./ocrConfig.nix { inherit lib; };
+  privacyMaskConfig = import ./privacyMaskConfig.nix { inherit lib; };
   sssPackage = lists.optional cfgSSS.enable sss.packages.default;
   codePackage = lists.optional cfgSSS.code.enable sss.packages.code;
   # Emit only the keys the user actually set. We can't use the evaluated
@@ -51,6 +52,7 @@ with lib; let
       general = mergeDefs optSSS.general;
       capture-ui = mergeDefs optSSS.capture-ui;
       ocr = mergeDefs optSSS.ocr;
+      privacy-mask = mergeDefs optSSS.privacy-mask;
     })
     // (optionalAttrs (cfgSSS.imports != [ ]) { inherit (cfgSSS) imports; });
 in
@@ -109,6 +111,15 @@ in
         default = { };
         type = types.submodule { options = ocrConfig; };
       };
+
+      privacy-mask = mkOption {
+        description = ''
+          Windows to hide in shared captures (password managers, chat apps,
+          notification popups) and how to paint over them.
+        '';
+        default = { };
+        type = types.submodule { options = privacyMaskConfig; };
+      };
     };
   };
 
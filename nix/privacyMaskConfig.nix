@@ -0,0 +1,50 @@
+{ lib, ... }:
+with lib; {
+  windows = mkOption {
+    type = types.listOf (types.submodule {
+      options = {
+        app-id = mkOption {
+          type = types.nullOr types.str;
+          default = null;
+          example = "org.keepassxc.KeePassXC";
+          description = "Application id (Wayland `app_id`, X11 `WM_CLASS`), compared case-insensitively.";
+        };
+        title = mkOption {
+          type = types.nullOr types.str;
+          default = null;
+          example = "(?i)direct message";
+          description = "Regular expression searched for in the window title.";
+        };
+      };
+    });
+    default = [ ];
+    description = ''
+      Windows hidden in every desktop, monitor and region capture. When an
+      entry sets both `app-id` and `title`, both must match. Window
+      captures (`--window`) are never masked.
+    '';
+  };
+
+  style = mkOption {
+    type = types.enum [ "fill" "blur" "pixelate" ];
+    default = "fill";
+    description = ''
+      How masked windows are painted over. `fill` is the only style that
+      cannot leak anything; `blur` and `pixelate` keep the layout
+      recognisable.
+    '';
+  };
+
+  color = mkOption {
+    type = types.nullOr types.str;
+    default = null;
+    example = "#1e1e2e";
+    description = "Fill colour for `style = \"fill\"`. Defaults to opaque black.";
+  };
+
+  strength = mkOption {
+    type = types.nullOr types.ints.positive;
+    default = null;
+    description = "Blur sigma (default 20) or pixelate block size (default 16), in pixels.";
+  };
+}